@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WsServer;
 
@@ -28,6 +29,7 @@ pub struct ArchiveClientExtraComponents {
     pub rpc_tcp_server: Option<TcpServer>,
     pub debug_rpc_ws_server: Option<WsServer>,
     pub rpc_ws_server: Option<WsServer>,
+    pub rpc_ipc_server: Option<IpcServer>,
     pub runtime: Runtime,
     pub sync: Arc<SynchronizationService>,
     pub txpool: Arc<TransactionPool>,
@@ -68,6 +70,7 @@ impl ArchiveClient {
             rpc_tcp_server,
             debug_rpc_ws_server,
             rpc_ws_server,
+            rpc_ipc_server,
             runtime,
         ) = initialize_not_light_node_modules(&conf, exit, NodeType::Archive)?;
         Ok(Box::new(ClientComponents {
@@ -81,6 +84,7 @@ impl ArchiveClient {
                 rpc_tcp_server,
                 debug_rpc_ws_server,
                 rpc_ws_server,
+                rpc_ipc_server,
                 runtime,
                 sync,
                 txpool,
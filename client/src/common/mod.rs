@@ -134,6 +134,7 @@ pub fn initialize_common_modules(
         Arc<CommonRpcImpl>,
         Arc<AccountProvider>,
         Arc<Notifications>,
+        Option<Arc<LightQueryService>>,
         PubSubClient,
         Runtime,
     ),
@@ -230,15 +231,16 @@ pub fn initialize_common_modules(
     ));
 
     let verification_config = conf.verification_config(machine.clone());
+    let notifications = Notifications::init();
     let txpool = Arc::new(TransactionPool::new(
         conf.txpool_config(),
         verification_config.clone(),
         data_man.clone(),
         machine.clone(),
+        notifications.clone(),
     ));
 
     let statistics = Arc::new(Statistics::new());
-    let notifications = Notifications::init();
 
     let consensus = Arc::new(ConsensusGraph::new(
         consensus_conf,
@@ -291,13 +293,39 @@ pub fn initialize_common_modules(
         accounts.clone(),
     ));
 
+    let light = match node_type {
+        NodeType::Light => {
+            let light = Arc::new(LightQueryService::new(
+                consensus.clone(),
+                sync_graph.clone(),
+                network.clone(),
+                conf.raw_conf.throttling_conf.clone(),
+                notifications.clone(),
+                conf.light_node_config(),
+            ));
+            light.register().unwrap();
+            Some(light)
+        }
+        _ => None,
+    };
+
     let runtime = Runtime::with_default_thread_count();
-    let pubsub = PubSubClient::new(
-        runtime.executor(),
-        consensus.clone(),
-        notifications.clone(),
-        *network.get_network_type(),
-    );
+    let pubsub = match light {
+        // light nodes publish logs and executed epochs as they are verified
+        Some(ref light) => PubSubClient::new_light(
+            runtime.executor(),
+            consensus.clone(),
+            light.clone(),
+            notifications.clone(),
+            *network.get_network_type(),
+        ),
+        None => PubSubClient::new(
+            runtime.executor(),
+            consensus.clone(),
+            notifications.clone(),
+            *network.get_network_type(),
+        ),
+    };
     Ok((
         machine,
         secret_store,
@@ -311,6 +339,7 @@ pub fn initialize_common_modules(
         common_impl,
         accounts,
         notifications,
+        light,
         pubsub,
         runtime,
     ))
@@ -333,6 +362,7 @@ pub fn initialize_not_light_node_modules(
         Option<TcpServer>,
         Option<WSServer>,
         Option<WSServer>,
+        Option<IpcServer>,
         Runtime,
     ),
     String,
@@ -350,7 +380,8 @@ pub fn initialize_not_light_node_modules(
         network,
         common_impl,
         accounts,
-        _notifications,
+        notifications,
+        _light,
         pubsub,
         runtime,
     ) = initialize_common_modules(&conf, exit.clone(), node_type)?;
@@ -362,6 +393,8 @@ pub fn initialize_not_light_node_modules(
         txpool.clone(),
         conf.raw_conf.throttling_conf.clone(),
         node_type,
+        conf.raw_conf.light_flow_control_buffer_limit,
+        conf.raw_conf.light_flow_control_recharge_rate,
     ));
     light_provider.register(network.clone()).unwrap();
 
@@ -373,6 +406,7 @@ pub fn initialize_not_light_node_modules(
         conf.state_sync_config(),
         SyncPhaseType::CatchUpRecoverBlockHeaderFromDB,
         light_provider,
+        notifications.clone(),
     ));
     sync.register().unwrap();
 
@@ -482,12 +516,21 @@ pub fn initialize_not_light_node_modules(
         accounts,
     ));
 
+    let filter = CfxFilterClient::new(
+        runtime.executor(),
+        consensus.clone(),
+        notifications,
+        conf.rpc_impl_config(),
+        *network.get_network_type(),
+    );
+
     let debug_rpc_http_server = super::rpc::start_http(
         conf.local_http_config(),
         setup_debug_rpc_apis(
             common_impl.clone(),
             rpc_impl.clone(),
             pubsub.clone(),
+            filter.clone(),
             &conf,
         ),
     )?;
@@ -498,6 +541,7 @@ pub fn initialize_not_light_node_modules(
             common_impl.clone(),
             rpc_impl.clone(),
             pubsub.clone(),
+            filter.clone(),
             &conf,
         ),
         RpcExtractor,
@@ -509,6 +553,7 @@ pub fn initialize_not_light_node_modules(
             common_impl.clone(),
             rpc_impl.clone(),
             pubsub.clone(),
+            filter.clone(),
             &conf,
         ),
         RpcExtractor,
@@ -520,6 +565,7 @@ pub fn initialize_not_light_node_modules(
             common_impl.clone(),
             rpc_impl.clone(),
             pubsub.clone(),
+            filter.clone(),
             &conf,
         ),
         RpcExtractor,
@@ -531,6 +577,19 @@ pub fn initialize_not_light_node_modules(
             common_impl.clone(),
             rpc_impl.clone(),
             pubsub.clone(),
+            filter.clone(),
+            &conf,
+        ),
+        RpcExtractor,
+    )?;
+
+    let rpc_ipc_server = super::rpc::start_ipc(
+        conf.ipc_config(),
+        setup_debug_rpc_apis(
+            common_impl.clone(),
+            rpc_impl.clone(),
+            pubsub.clone(),
+            filter.clone(),
             &conf,
         ),
         RpcExtractor,
@@ -538,7 +597,7 @@ pub fn initialize_not_light_node_modules(
 
     let rpc_http_server = super::rpc::start_http(
         conf.http_config(),
-        setup_public_rpc_apis(common_impl, rpc_impl, pubsub, &conf),
+        setup_public_rpc_apis(common_impl, rpc_impl, pubsub, filter, &conf),
     )?;
 
     Ok((
@@ -554,6 +613,7 @@ pub fn initialize_not_light_node_modules(
         rpc_tcp_server,
         debug_rpc_ws_server,
         rpc_ws_server,
+        rpc_ipc_server,
         runtime,
     ))
 }
@@ -731,8 +791,8 @@ use crate::{
     rpc::{
         extractor::RpcExtractor,
         impls::{
-            cfx::RpcImpl, common::RpcImpl as CommonRpcImpl,
-            pubsub::PubSubClient,
+            cfx::RpcImpl, cfx_filter::CfxFilterClient,
+            common::RpcImpl as CommonRpcImpl, pubsub::PubSubClient,
         },
         setup_debug_rpc_apis, setup_public_rpc_apis,
     },
@@ -749,13 +809,14 @@ use cfxcore::{
     statistics::Statistics,
     sync::SyncPhaseType,
     vm_factory::VmFactory,
-    ConsensusGraph, LightProvider, NodeType, Notifications, Stopable,
-    SynchronizationGraph, SynchronizationService, TransactionPool,
+    ConsensusGraph, LightProvider, LightQueryService, NodeType, Notifications,
+    Stopable, SynchronizationGraph, SynchronizationService, TransactionPool,
     WORKER_COMPUTATION_PARALLELISM,
 };
 use cfxcore_accounts::AccountProvider;
 use cfxkey::public_to_address;
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WSServer;
 use keylib::KeyPair;
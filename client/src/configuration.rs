@@ -4,11 +4,14 @@
 
 use crate::rpc::{
     impls::RpcImplConfiguration, rpc_apis::ApiSet, HttpConfiguration,
-    TcpConfiguration, WsConfiguration,
+    IpcConfiguration, TcpConfiguration, WsConfiguration,
 };
 use cfx_addr::{cfx_addr_decode, Network};
 use cfx_internal_common::{ChainIdParams, ChainIdParamsInner};
-use cfx_parameters::block::DEFAULT_TARGET_BLOCK_GAS_LIMIT;
+use cfx_parameters::{
+    block::DEFAULT_TARGET_BLOCK_GAS_LIMIT,
+    light::{FLOW_CONTROL_BUFFER_LIMIT, FLOW_CONTROL_RECHARGE_RATE},
+};
 use cfx_storage::{
     defaults::DEFAULT_DEBUG_SNAPSHOT_CHECKER_THREADS, storage_dir,
     ConsensusParam, ProvideExtraSnapshotSyncConfig, StorageConfiguration,
@@ -24,7 +27,7 @@ use cfxcore::{
     },
     consensus::{
         consensus_inner::consensus_executor::ConsensusExecutionConfiguration,
-        ConsensusConfig, ConsensusInnerConfig,
+        ConsensusConfig, ConsensusInnerConfig, GasPriceOracleConfig,
     },
     consensus_internal_parameters::*,
     consensus_parameters::*,
@@ -41,7 +44,10 @@ use metrics::MetricsConfiguration;
 use network::DiscoveryConfiguration;
 use parking_lot::RwLock;
 use rand::Rng;
-use std::{collections::BTreeMap, convert::TryInto, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap, convert::TryInto, net::IpAddr, path::PathBuf,
+    sync::Arc,
+};
 use txgen::TransactionGeneratorConfig;
 
 lazy_static! {
@@ -163,6 +169,9 @@ build_config! {
         (jsonrpc_cors, (Option<String>), None)
         (jsonrpc_http_keep_alive, (bool), false)
         (jsonrpc_ws_max_payload_bytes, (usize), 30 * 1024 * 1024)
+        (jsonrpc_ipc_path, (Option<String>), None)
+        (rpc_auth_api_keys_file, (Option<String>), None)
+        (rpc_auth_jwt_secret_file, (Option<String>), None)
         // The network_id, if unset, defaults to the chain_id.
         // Only override the network_id for local experiments,
         // when user would like to keep the existing blockchain data
@@ -187,6 +196,8 @@ build_config! {
         (heartbeat_period_interval_ms, (u64), 30_000)
         (heartbeat_timeout_ms, (u64), 180_000)
         (inflight_pending_tx_index_maintain_timeout_ms, (u64), 30_000)
+        (light_flow_control_buffer_limit, (u64), FLOW_CONTROL_BUFFER_LIMIT)
+        (light_flow_control_recharge_rate, (u64), FLOW_CONTROL_RECHARGE_RATE)
         (max_allowed_timeout_in_observing_period, (u64), 10)
         (max_chunk_number_in_manifest, (usize), 500)
         (max_downloading_chunks, (usize), 8)
@@ -273,11 +284,23 @@ build_config! {
         (check_phase_change_period_ms, (u64), 1000)
         (enable_optimistic_execution, (bool), true)
         (future_block_buffer_capacity, (usize), 32768)
+        (gas_price_oracle, (bool), false)
+        (gas_price_oracle_percentile, (u8), 60)
+        (gas_price_oracle_epoch_window, (u64), 20)
+        // `None` means `tx_pool_min_tx_gas_price` is used.
+        (gas_price_oracle_min_price, (Option<u64>), None)
         (get_logs_filter_max_limit, (Option<usize>), None)
         (get_logs_filter_max_epoch_range, (Option<u64>), None)
         (get_logs_epoch_batch_size, (usize), 32)
         (max_trans_count_received_in_catch_up, (u64), 60_000)
         (persist_tx_index, (bool), false)
+        (persist_address_tx_index, (bool), false)
+        // `None` means the address transaction index is never garbage collected.
+        (address_tx_index_maintained_epoch_count, (Option<usize>), None)
+        (persist_log_index, (bool), false)
+        (cache_epoch_summary, (bool), false)
+        (poll_lifetime_in_seconds, (u32), 60)
+        (call_many_max_requests, (usize), 100)
         (print_memory_usage_period_s, (Option<u64>), None)
         (target_block_gas_limit, (u64), DEFAULT_TARGET_BLOCK_GAS_LIMIT)
         (executive_trace, (bool), false)
@@ -326,6 +349,8 @@ build_config! {
             ProvideExtraSnapshotSyncConfig::parse_config_list)
         (node_type, (Option<NodeType>), None, NodeType::from_str)
         (public_rpc_apis, (ApiSet), ApiSet::Safe, ApiSet::from_str)
+        (jsonrpc_ipc_permissions, (u32), 0o600, parse_file_permissions)
+        (rpc_trusted_proxies, (Vec<IpAddr>), vec![], parse_ip_list)
     }
 }
 
@@ -552,6 +577,19 @@ impl Configuration {
             referee_bound: self.raw_conf.referee_bound,
             get_logs_epoch_batch_size: self.raw_conf.get_logs_epoch_batch_size,
             get_logs_filter_max_epoch_range: self.raw_conf.get_logs_filter_max_epoch_range,
+            gas_price_oracle: if self.raw_conf.gas_price_oracle {
+                Some(GasPriceOracleConfig {
+                    percentile: self.raw_conf.gas_price_oracle_percentile.min(100),
+                    epoch_window: self.raw_conf.gas_price_oracle_epoch_window,
+                    min_price: self
+                        .raw_conf
+                        .gas_price_oracle_min_price
+                        .unwrap_or(self.raw_conf.tx_pool_min_tx_gas_price)
+                        .into(),
+                })
+            } else {
+                None
+            },
             sync_state_starting_epoch: self.raw_conf.sync_state_starting_epoch,
             sync_state_epoch_gap: self.raw_conf.sync_state_epoch_gap,
         };
@@ -809,6 +847,12 @@ impl Configuration {
                 * self.raw_conf.era_epoch_count as f64)
                 as usize,
             strict_tx_index_gc: self.raw_conf.strict_tx_index_gc,
+            persist_address_tx_index: self.raw_conf.persist_address_tx_index,
+            address_tx_index_maintained_epoch_count: self
+                .raw_conf
+                .address_tx_index_maintained_epoch_count,
+            persist_log_index: self.raw_conf.persist_log_index,
+            cache_epoch_summary: self.raw_conf.cache_epoch_summary,
         };
 
         // By default, we do not keep the block data for additional period,
@@ -897,6 +941,8 @@ impl Configuration {
             dev_pack_tx_immediately: self.is_dev_mode()
                 && self.raw_conf.dev_block_interval_ms.is_none(),
             max_payload_bytes: self.raw_conf.jsonrpc_ws_max_payload_bytes,
+            poll_lifetime_in_seconds: self.raw_conf.poll_lifetime_in_seconds,
+            call_many_max_requests: self.raw_conf.call_many_max_requests,
         }
     }
 
@@ -947,6 +993,13 @@ impl Configuration {
         )
     }
 
+    pub fn ipc_config(&self) -> IpcConfiguration {
+        IpcConfiguration::new(
+            self.raw_conf.jsonrpc_ipc_path.clone(),
+            self.raw_conf.jsonrpc_ipc_permissions,
+        )
+    }
+
     pub fn execution_config(&self) -> ConsensusExecutionConfiguration {
         ConsensusExecutionConfiguration {
             executive_trace: self.raw_conf.executive_trace,
@@ -1122,6 +1175,26 @@ pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
     }
 }
 
+/// Parse Unix file permissions in octal, e.g. "600".
+pub fn parse_file_permissions(s: &str) -> Result<u32, String> {
+    match u32::from_str_radix(s.strip_prefix("0o").unwrap_or(s), 8) {
+        Ok(mode) if mode <= 0o777 => Ok(mode),
+        _ => Err(format!("Invalid file permissions: {}", s)),
+    }
+}
+
+/// Parse a comma-separated list of IP addresses.
+pub fn parse_ip_list(s: &str) -> Result<Vec<IpAddr>, String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(|ip| {
+            ip.parse()
+                .map_err(|_| format!("Invalid IP address: {}", ip))
+        })
+        .collect()
+}
+
 pub fn parse_hex_string<F: FromStr>(hex_str: &str) -> Result<F, F::Err> {
     hex_str.strip_prefix("0x").unwrap_or(hex_str).parse()
 }
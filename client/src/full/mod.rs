@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WsServer;
 
@@ -28,6 +29,7 @@ pub struct FullClientExtraComponents {
     pub rpc_tcp_server: Option<TcpServer>,
     pub debug_rpc_ws_server: Option<WsServer>,
     pub rpc_ws_server: Option<WsServer>,
+    pub rpc_ipc_server: Option<IpcServer>,
     pub runtime: Runtime,
     pub sync: Arc<SynchronizationService>,
     pub txpool: Arc<TransactionPool>,
@@ -61,6 +63,7 @@ impl FullClient {
             rpc_tcp_server,
             debug_rpc_ws_server,
             rpc_ws_server,
+            rpc_ipc_server,
             runtime,
         ) = initialize_not_light_node_modules(&conf, exit, NodeType::Full)?;
         Ok(Box::new(ClientComponents {
@@ -74,6 +77,7 @@ impl FullClient {
                 rpc_tcp_server,
                 debug_rpc_ws_server,
                 rpc_ws_server,
+                rpc_ipc_server,
                 runtime,
                 sync,
                 txpool,
@@ -8,6 +8,7 @@ use parking_lot::{Condvar, Mutex};
 use secret_store::SecretStore;
 
 use jsonrpc_http_server::Server as HttpServer;
+use jsonrpc_ipc_server::Server as IpcServer;
 use jsonrpc_tcp_server::Server as TcpServer;
 use jsonrpc_ws_server::Server as WsServer;
 
@@ -34,6 +35,7 @@ pub struct LightClientExtraComponents {
     pub debug_rpc_ws_server: Option<WsServer>,
     pub light: Arc<LightQueryService>,
     pub rpc_http_server: Option<HttpServer>,
+    pub rpc_ipc_server: Option<IpcServer>,
     pub rpc_tcp_server: Option<TcpServer>,
     pub rpc_ws_server: Option<WsServer>,
     pub runtime: Runtime,
@@ -64,24 +66,17 @@ impl LightClient {
             pow,
             txpool,
             consensus,
-            sync_graph,
-            network,
+            _sync_graph,
+            _network,
             common_impl,
             accounts,
-            notifications,
+            _notifications,
+            light,
             pubsub,
             runtime,
         ) = initialize_common_modules(&conf, exit.clone(), NodeType::Light)?;
 
-        let light = Arc::new(LightQueryService::new(
-            consensus.clone(),
-            sync_graph,
-            network.clone(),
-            conf.raw_conf.throttling_conf.clone(),
-            notifications,
-            conf.light_node_config(),
-        ));
-        light.register().unwrap();
+        let light = light.expect("light node");
 
         let rpc_impl = Arc::new(RpcImpl::new(
             conf.rpc_impl_config(),
@@ -145,6 +140,17 @@ impl LightClient {
             RpcExtractor,
         )?;
 
+        let rpc_ipc_server = super::rpc::start_ipc(
+            conf.ipc_config(),
+            setup_debug_rpc_apis_light(
+                common_impl.clone(),
+                rpc_impl.clone(),
+                pubsub.clone(),
+                &conf,
+            ),
+            RpcExtractor,
+        )?;
+
         let rpc_http_server = super::rpc::start_http(
             conf.http_config(),
             setup_public_rpc_apis_light(
@@ -165,6 +171,7 @@ impl LightClient {
                 debug_rpc_ws_server,
                 light,
                 rpc_http_server,
+                rpc_ipc_server,
                 rpc_tcp_server,
                 rpc_ws_server,
                 runtime,
@@ -2,11 +2,15 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use jsonrpc_core::{MetaIoHandler, Result as JsonRpcResult};
+use jsonrpc_core::{MetaIoHandler, RemoteProcedure, Result as JsonRpcResult};
 use jsonrpc_http_server::{
     AccessControlAllowOrigin, DomainsValidation, Server as HttpServer,
     ServerBuilder as HttpServerBuilder,
 };
+use jsonrpc_ipc_server::{
+    MetaExtractor as IpcMetaExtractor, Server as IpcServer,
+    ServerBuilder as IpcServerBuilder,
+};
 use jsonrpc_tcp_server::{
     MetaExtractor as TpcMetaExtractor, Server as TcpServer,
     ServerBuilder as TcpServerBuilder,
@@ -16,10 +20,11 @@ use jsonrpc_ws_server::{
     ServerBuilder as WsServerBuilder,
 };
 use std::{
-    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
     sync::Arc,
 };
 
+pub mod auth;
 mod authcodes;
 pub mod error_codes;
 pub mod extractor;
@@ -29,6 +34,7 @@ pub mod impls;
 pub mod informant;
 mod interceptor;
 pub mod metadata;
+mod openrpc;
 pub mod rpc_apis;
 mod traits;
 pub mod types;
@@ -41,16 +47,20 @@ pub use cfxcore::rpc_errors::{
 use self::{
     impls::{
         cfx::{CfxHandler, LocalRpcImpl, RpcImpl, TestRpcImpl},
+        cfx_filter::CfxFilterClient,
         common::RpcImpl as CommonImpl,
+        eth::EthHandler,
         light::{
             CfxHandler as LightCfxHandler, DebugRpcImpl as LightDebugRpcImpl,
             RpcImpl as LightImpl, TestRpcImpl as LightTestRpcImpl,
+            UNSUPPORTED_METHODS as LIGHT_UNSUPPORTED_METHODS,
         },
         pubsub::PubSubClient,
         trace::TraceHandler,
     },
     traits::{
-        cfx::Cfx, debug::LocalRpc, pubsub::PubSub, test::TestRpc, trace::Trace,
+        cfx::Cfx, cfx_filter::CfxFilter, debug::LocalRpc, eth::Eth,
+        pubsub::PubSub, test::TestRpc, trace::Trace,
     },
 };
 
@@ -58,14 +68,27 @@ pub use self::types::{Block as RpcBlock, Origin};
 use crate::{
     configuration::Configuration,
     rpc::{
-        error_codes::request_rejected_too_many_request_error,
+        auth::{credential, AuthInterceptor, RpcAuth},
+        error_codes::{
+            request_rejected_too_many_request_error,
+            request_rejected_too_many_request_retry_after,
+            request_rejected_unauthorized,
+        },
+        extractor::RpcExtractor,
+        http_common::MetaExtractor as HttpMetaExtractor,
         interceptor::{RpcInterceptor, RpcProxy},
         rpc_apis::{Api, ApiSet},
     },
 };
+use lazy_static::lazy_static;
 pub use metadata::Metadata;
-use std::collections::HashSet;
-use throttling::token_bucket::{ThrottleResult, TokenBucketManager};
+use metrics::{register_meter_with_group, Meter};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use throttling::token_bucket::{
+    KeyedTokenBucketManager, ThrottleResult, TokenBucketManager,
+};
 
 #[derive(Debug, PartialEq)]
 pub struct TcpConfiguration {
@@ -153,43 +176,71 @@ impl WsConfiguration {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct IpcConfiguration {
+    pub enabled: bool,
+    pub path: String,
+    // The Unix file permissions of the socket file
+    pub permissions: u32,
+}
+
+impl IpcConfiguration {
+    pub fn new(path: Option<String>, permissions: u32) -> Self {
+        IpcConfiguration {
+            enabled: path.is_some(),
+            path: path.unwrap_or_default(),
+            permissions,
+        }
+    }
+}
+
 pub fn setup_public_rpc_apis(
     common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
-    conf: &Configuration,
+    filter: CfxFilterClient, conf: &Configuration,
 ) -> MetaIoHandler<Metadata>
 {
+    let (apis, auth) =
+        public_rpc_apis_with_auth(conf, ApiSet::All.list_apis());
     setup_rpc_apis(
         common,
         rpc,
         pubsub,
+        filter,
         &conf.raw_conf.throttling_conf,
         "rpc",
-        conf.raw_conf.public_rpc_apis.list_apis(),
+        &conf.raw_conf.rpc_trusted_proxies,
+        apis,
+        auth,
     )
 }
 
 pub fn setup_debug_rpc_apis(
     common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
-    conf: &Configuration,
+    filter: CfxFilterClient, conf: &Configuration,
 ) -> MetaIoHandler<Metadata>
 {
     setup_rpc_apis(
         common,
         rpc,
         pubsub,
+        filter,
         &conf.raw_conf.throttling_conf,
         "rpc_local",
+        &conf.raw_conf.rpc_trusted_proxies,
         ApiSet::All.list_apis(),
+        None,
     )
 }
 
 fn setup_rpc_apis(
     common: Arc<CommonImpl>, rpc: Arc<RpcImpl>, pubsub: PubSubClient,
-    throttling_conf: &Option<String>, throttling_section: &str,
-    apis: HashSet<Api>,
+    filter: CfxFilterClient, throttling_conf: &Option<String>,
+    throttling_section: &str, trusted_proxies: &[IpAddr], apis: HashSet<Api>,
+    auth: Option<Arc<RpcAuth>>,
 ) -> MetaIoHandler<Metadata>
 {
     let mut handler = MetaIoHandler::default();
+    add_discover_method(&mut handler, apis.clone(), &[], &auth);
     for api in apis {
         match api {
             Api::Cfx => {
@@ -198,19 +249,66 @@ fn setup_rpc_apis(
                 let interceptor = ThrottleInterceptor::new(
                     throttling_conf,
                     throttling_section,
+                    auth.is_some(),
+                    trusted_proxies,
+                );
+                extend_with_auth(
+                    &mut handler,
+                    RpcProxy::new(cfx, interceptor),
+                    Api::Cfx,
+                    &auth,
+                );
+
+                let filter = filter.clone().to_delegate();
+                let interceptor = ThrottleInterceptor::new(
+                    throttling_conf,
+                    throttling_section,
+                    auth.is_some(),
+                    trusted_proxies,
+                );
+                extend_with_auth(
+                    &mut handler,
+                    RpcProxy::new(filter, interceptor),
+                    Api::Cfx,
+                    &auth,
                 );
-                handler.extend_with(RpcProxy::new(cfx, interceptor));
             }
             Api::Debug => {
-                handler.extend_with(
+                extend_with_auth(
+                    &mut handler,
                     LocalRpcImpl::new(common.clone(), rpc.clone())
                         .to_delegate(),
+                    Api::Debug,
+                    &auth,
+                );
+            }
+            Api::Eth => {
+                let eth = EthHandler::new(rpc.clone()).to_delegate();
+                let interceptor = ThrottleInterceptor::new(
+                    throttling_conf,
+                    throttling_section,
+                    auth.is_some(),
+                    trusted_proxies,
+                );
+                extend_with_auth(
+                    &mut handler,
+                    RpcProxy::new(eth, interceptor),
+                    Api::Eth,
+                    &auth,
                 );
             }
-            Api::Pubsub => handler.extend_with(pubsub.clone().to_delegate()),
+            Api::Pubsub => extend_with_auth(
+                &mut handler,
+                pubsub.clone().to_delegate(),
+                Api::Pubsub,
+                &auth,
+            ),
             Api::Test => {
-                handler.extend_with(
+                extend_with_auth(
+                    &mut handler,
                     TestRpcImpl::new(common.clone(), rpc.clone()).to_delegate(),
+                    Api::Test,
+                    &auth,
                 );
             }
             Api::Trace => {
@@ -223,8 +321,15 @@ fn setup_rpc_apis(
                 let interceptor = ThrottleInterceptor::new(
                     throttling_conf,
                     throttling_section,
+                    auth.is_some(),
+                    trusted_proxies,
+                );
+                extend_with_auth(
+                    &mut handler,
+                    RpcProxy::new(trace, interceptor),
+                    Api::Trace,
+                    &auth,
                 );
-                handler.extend_with(RpcProxy::new(trace, interceptor));
             }
         }
     }
@@ -236,13 +341,16 @@ pub fn setup_public_rpc_apis_light(
     conf: &Configuration,
 ) -> MetaIoHandler<Metadata>
 {
+    let (apis, auth) = public_rpc_apis_with_auth(conf, light_supported_apis());
     setup_rpc_apis_light(
         common,
         rpc,
         pubsub,
         &conf.raw_conf.throttling_conf,
         "rpc",
-        conf.raw_conf.public_rpc_apis.list_apis(),
+        &conf.raw_conf.rpc_trusted_proxies,
+        apis,
+        auth,
     )
 }
 
@@ -251,25 +359,33 @@ pub fn setup_debug_rpc_apis_light(
     conf: &Configuration,
 ) -> MetaIoHandler<Metadata>
 {
-    let mut light_debug_apis = ApiSet::All.list_apis();
-    light_debug_apis.remove(&Api::Trace);
     setup_rpc_apis_light(
         common,
         rpc,
         pubsub,
         &conf.raw_conf.throttling_conf,
         "rpc_local",
-        light_debug_apis,
+        &conf.raw_conf.rpc_trusted_proxies,
+        light_supported_apis(),
+        None,
     )
 }
 
 fn setup_rpc_apis_light(
     common: Arc<CommonImpl>, rpc: Arc<LightImpl>, pubsub: PubSubClient,
     throttling_conf: &Option<String>, throttling_section: &str,
-    apis: HashSet<Api>,
+    trusted_proxies: &[IpAddr], apis: HashSet<Api>, auth: Option<Arc<RpcAuth>>,
 ) -> MetaIoHandler<Metadata>
 {
     let mut handler = MetaIoHandler::default();
+    add_discover_method(
+        &mut handler,
+        apis.intersection(&light_supported_apis())
+            .cloned()
+            .collect(),
+        LIGHT_UNSUPPORTED_METHODS,
+        &auth,
+    );
     for api in apis {
         match api {
             Api::Cfx => {
@@ -278,20 +394,41 @@ fn setup_rpc_apis_light(
                 let interceptor = ThrottleInterceptor::new(
                     throttling_conf,
                     throttling_section,
+                    auth.is_some(),
+                    trusted_proxies,
+                );
+                extend_with_auth(
+                    &mut handler,
+                    RpcProxy::new(cfx, interceptor),
+                    Api::Cfx,
+                    &auth,
                 );
-                handler.extend_with(RpcProxy::new(cfx, interceptor));
             }
             Api::Debug => {
-                handler.extend_with(
+                extend_with_auth(
+                    &mut handler,
                     LightDebugRpcImpl::new(common.clone(), rpc.clone())
                         .to_delegate(),
+                    Api::Debug,
+                    &auth,
                 );
             }
-            Api::Pubsub => handler.extend_with(pubsub.clone().to_delegate()),
+            Api::Eth => {
+                warn!("Light nodes do not support eth RPC");
+            }
+            Api::Pubsub => extend_with_auth(
+                &mut handler,
+                pubsub.clone().to_delegate(),
+                Api::Pubsub,
+                &auth,
+            ),
             Api::Test => {
-                handler.extend_with(
+                extend_with_auth(
+                    &mut handler,
                     LightTestRpcImpl::new(common.clone(), rpc.clone())
                         .to_delegate(),
+                    Api::Test,
+                    &auth,
                 );
             }
             Api::Trace => {
@@ -302,6 +439,80 @@ fn setup_rpc_apis_light(
     handler
 }
 
+fn light_supported_apis() -> HashSet<Api> {
+    let mut apis = ApiSet::All.list_apis();
+    apis.remove(&Api::Eth);
+    apis.remove(&Api::Trace);
+    apis
+}
+
+/// Return the APIs to set up for the public RPC servers, and the
+/// authentication of the requests if it is configured. With authentication,
+/// all the `supported` APIs are set up, and each request can only use the
+/// APIs allowed for its credential.
+fn public_rpc_apis_with_auth(
+    conf: &Configuration, supported: HashSet<Api>,
+) -> (HashSet<Api>, Option<Arc<RpcAuth>>) {
+    let public_apis = conf.raw_conf.public_rpc_apis.list_apis();
+    match RpcAuth::load(
+        public_apis.clone(),
+        &conf.raw_conf.rpc_auth_api_keys_file,
+        &conf.raw_conf.rpc_auth_jwt_secret_file,
+    )
+    .expect("invalid RPC authentication configuration")
+    {
+        Some(auth) => (supported, Some(Arc::new(auth))),
+        None => (public_apis, None),
+    }
+}
+
+/// Add the methods of `api` to `handler`. If `auth` is set, the requests
+/// that are not allowed to use `api` are rejected.
+fn extend_with_auth<T>(
+    handler: &mut MetaIoHandler<Metadata>, methods: T, api: Api,
+    auth: &Option<Arc<RpcAuth>>,
+) where
+    T: IntoIterator<Item = (String, RemoteProcedure<Metadata>)>,
+{
+    match auth {
+        Some(auth) => handler.extend_with(RpcProxy::new(
+            methods,
+            AuthInterceptor::new(api, auth.clone()),
+        )),
+        None => handler.extend_with(methods),
+    }
+}
+
+/// Add `rpc.discover`, which returns the OpenRPC document of the methods of
+/// `apis` except `unsupported`. If `auth` is set, only the APIs allowed for
+/// the request are listed.
+fn add_discover_method(
+    handler: &mut MetaIoHandler<Metadata>, apis: HashSet<Api>,
+    unsupported: &'static [&'static str], auth: &Option<Arc<RpcAuth>>,
+) {
+    let auth = auth.clone();
+    handler.add_method_with_meta(
+        "rpc.discover",
+        move |_params, meta: Metadata| -> JsonRpcResult<Value> {
+            let apis = match &auth {
+                Some(auth) => {
+                    let mut allowed = HashSet::new();
+                    for api in &apis {
+                        if auth.is_allowed(&meta, api).map_err(|e| {
+                            request_rejected_unauthorized(Some(e))
+                        })? {
+                            allowed.insert(api.clone());
+                        }
+                    }
+                    allowed
+                }
+                None => apis.clone(),
+            };
+            Ok(openrpc::document(&apis, unsupported))
+        },
+    );
+}
+
 pub fn start_tcp<H, T>(
     conf: TcpConfiguration, handler: H, extractor: T,
 ) -> Result<Option<TcpServer>, String>
@@ -329,7 +540,10 @@ pub fn start_http(
     if !conf.enabled {
         return Ok(None);
     }
-    let mut builder = HttpServerBuilder::new(handler);
+    let mut builder = HttpServerBuilder::with_meta_extractor(
+        handler,
+        HttpMetaExtractor::new(RpcExtractor),
+    );
     if let Some(threads) = conf.threads {
         builder = builder.threads(threads);
     }
@@ -369,45 +583,215 @@ where
     }
 }
 
+lazy_static! {
+    static ref THROTTLED_METERS: Mutex<HashMap<String, Arc<dyn Meter>>> =
+        Default::default();
+}
+
+/// Return the meter of the throttled requests of an RPC method.
+fn throttled_meter(name: &str) -> Arc<dyn Meter> {
+    THROTTLED_METERS
+        .lock()
+        .entry(name.into())
+        .or_insert_with(|| register_meter_with_group("rpc_throttled", name))
+        .clone()
+}
+
+pub fn start_ipc<H, T>(
+    conf: IpcConfiguration, handler: H, extractor: T,
+) -> Result<Option<IpcServer>, String>
+where
+    H: Into<MetaIoHandler<Metadata>>,
+    T: IpcMetaExtractor<Metadata> + 'static,
+{
+    if !conf.enabled {
+        return Ok(None);
+    }
+
+    remove_stale_ipc_socket(&conf.path)?;
+    let server = IpcServerBuilder::with_meta_extractor(handler, extractor)
+        .start(&conf.path)
+        .map_err(|io_error| {
+            format!("IPC error: {} (path = {})", io_error, conf.path)
+        })?;
+    // The socket is bound before `start` returns.
+    if let Err(e) = set_ipc_permissions(&conf.path, conf.permissions) {
+        server.close();
+        return Err(e);
+    }
+    Ok(Some(server))
+}
+
+/// Remove the socket file left by a previous process, so that the path can be
+/// bound again. Other types of files are not removed.
+#[cfg(unix)]
+fn remove_stale_ipc_socket(path: &str) -> Result<(), String> {
+    use std::os::unix::fs::FileTypeExt;
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            std::fs::remove_file(path)
+                .map_err(|e| format!("IPC error: {} (path = {})", e, path))
+        }
+        Ok(_) => Err(format!(
+            "IPC error: the path exists and is not a socket (path = {})",
+            path
+        )),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn remove_stale_ipc_socket(_path: &str) -> Result<(), String> { Ok(()) }
+
+/// Set the permissions of the socket file.
+#[cfg(unix)]
+fn set_ipc_permissions(path: &str, permissions: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(
+        path,
+        std::fs::Permissions::from_mode(permissions & 0o777),
+    )
+    .map_err(|e| format!("IPC error: {} (path = {})", e, path))
+}
+
+#[cfg(not(unix))]
+fn set_ipc_permissions(_path: &str, _permissions: u32) -> Result<(), String> {
+    Ok(())
+}
+
+/// Throttles the requests of each method with the buckets in the section
+/// `section` of the throttling configuration file, and the requests of each
+/// method from each client with the buckets in the section
+/// `<section>_client`.
 struct ThrottleInterceptor {
     manager: TokenBucketManager,
+    client_manager: KeyedTokenBucketManager<String>,
+    // Whether the clients with credentials are throttled by credentials.
+    // Otherwise, the clients are throttled by IP addresses.
+    by_credential: bool,
+    // The proxies whose forwarding headers give the addresses of clients.
+    trusted_proxies: HashSet<IpAddr>,
 }
 
 impl ThrottleInterceptor {
-    fn new(file: &Option<String>, section: &str) -> Self {
-        let manager = match file {
-            Some(file) => TokenBucketManager::load(file, Some(section))
+    fn new(
+        file: &Option<String>, section: &str, by_credential: bool,
+        trusted_proxies: &[IpAddr],
+    ) -> Self
+    {
+        let (manager, client_manager) = match file {
+            Some(file) => (
+                TokenBucketManager::load(file, Some(section))
+                    .expect("invalid throttling configuration file"),
+                KeyedTokenBucketManager::load(
+                    file,
+                    Some(&format!("{}_client", section)),
+                )
                 .expect("invalid throttling configuration file"),
-            None => TokenBucketManager::default(),
+            ),
+            None => Default::default(),
         };
 
-        ThrottleInterceptor { manager }
+        ThrottleInterceptor {
+            manager,
+            client_manager,
+            by_credential,
+            trusted_proxies: trusted_proxies.iter().cloned().collect(),
+        }
     }
-}
 
-impl RpcInterceptor for ThrottleInterceptor {
-    fn before(&self, name: &String) -> JsonRpcResult<()> {
-        let bucket = match self.manager.get(name) {
-            Some(bucket) => bucket,
-            None => return Ok(()),
+    /// Return the key of the client to throttle its requests. Clients are
+    /// identified by their IP addresses, and the `X-Real-IP` or the last
+    /// `X-Forwarded-For` address is only used for the requests from trusted
+    /// proxies. IPv6 clients are identified by their /64 prefixes, which are
+    /// usually assigned to a single host. The clients without addresses, i.e.
+    /// IPC clients, share one key.
+    fn client_key(&self, meta: &Metadata) -> String {
+        if self.by_credential {
+            if let Some(credential) = credential(meta) {
+                return format!("key:{}", credential);
+            }
+        }
+        let ip = match meta.peer_addr {
+            Some(addr) => addr.ip(),
+            None => return "local".into(),
         };
+        if self.trusted_proxies.contains(&ip) {
+            let forwarded = meta
+                .headers
+                .get("x-real-ip")
+                .or_else(|| meta.headers.get("x-forwarded-for"));
+            if let Some(forwarded) = forwarded {
+                // The last address is set by the nearest proxy.
+                let forwarded =
+                    forwarded.rsplit(',').next().unwrap_or_default().trim();
+                return match forwarded.parse() {
+                    Ok(ip) => ip_key(ip),
+                    Err(_) => format!("ip:{}", forwarded),
+                };
+            }
+        }
+        ip_key(ip)
+    }
+}
 
-        let result = bucket.lock().throttle_default();
+/// Return the throttling key of an IP address, i.e. the address itself for
+/// IPv4 and its /64 prefix for IPv6.
+fn ip_key(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => format!("ip:{}", ip),
+        IpAddr::V6(ip) => match ip.to_ipv4() {
+            Some(ip) => format!("ip:{}", ip),
+            None => {
+                let segments = ip.segments();
+                format!(
+                    "ip:{:x}:{:x}:{:x}:{:x}::/64",
+                    segments[0], segments[1], segments[2], segments[3]
+                )
+            }
+        },
+    }
+}
+
+impl RpcInterceptor<Metadata> for ThrottleInterceptor {
+    fn before(&self, name: &String, meta: &Metadata) -> JsonRpcResult<()> {
+        let mut result = match self.manager.get(name) {
+            Some(bucket) => bucket.lock().throttle_default(),
+            None => ThrottleResult::Success,
+        };
+        let mut by_client = false;
+        if result == ThrottleResult::Success {
+            if let Some(client_result) = self
+                .client_manager
+                .throttle_default(self.client_key(meta), name)
+            {
+                result = client_result;
+                by_client = true;
+            }
+        }
+        let throttled = if by_client {
+            "throttled for the client"
+        } else {
+            "throttled"
+        };
 
         match result {
             ThrottleResult::Success => Ok(()),
             ThrottleResult::Throttled(wait_time) => {
-                debug!("RPC {} throttled in {:?}", name, wait_time);
-                bail!(request_rejected_too_many_request_error(Some(format!(
-                    "throttled in {:?}",
+                debug!("RPC {} {} in {:?}", name, throttled, wait_time);
+                throttled_meter(name).mark(1);
+                bail!(request_rejected_too_many_request_retry_after(
+                    format!("{} in {:?}", throttled, wait_time),
                     wait_time
-                ))))
+                ))
             }
             ThrottleResult::AlreadyThrottled => {
-                debug!("RPC {} already throttled", name);
-                bail!(request_rejected_too_many_request_error(Some(
-                    "already throttled, please try again later".into()
-                )))
+                debug!("RPC {} already {}", name, throttled);
+                throttled_meter(name).mark(1);
+                bail!(request_rejected_too_many_request_error(Some(format!(
+                    "already {}, please try again later",
+                    throttled
+                ))))
             }
         }
     }
@@ -0,0 +1,313 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Authentication of the public RPC requests with API keys or JWTs.
+//!
+//! The credential is read from the `X-Api-Key` header, from the
+//! `Authorization: Bearer <credential>` header, or for WebSocket connections,
+//! from the first subprotocol of the handshake. A credential that is not a
+//! configured API key is validated as a JWT signed with HS256. The requests
+//! without credentials can only use `public_rpc_apis`.
+
+use crate::rpc::{
+    error_codes::request_rejected_unauthorized,
+    interceptor::RpcInterceptor,
+    rpc_apis::{Api, ApiSet},
+    Metadata,
+};
+use jsonrpc_core::Result as JsonRpcResult;
+use parity_crypto::hmac;
+use rustc_hex::FromHex;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub struct RpcAuth {
+    /// The APIs allowed for the requests without credentials.
+    public_apis: HashSet<Api>,
+    /// The APIs allowed for each API key.
+    api_keys: HashMap<String, HashSet<Api>>,
+    /// The secret to validate JWTs. JWTs are not accepted if it is not set.
+    jwt_secret: Option<Vec<u8>>,
+}
+
+impl RpcAuth {
+    /// Load the API keys and the JWT secret. Return `None` if neither of them
+    /// is configured, in which case authentication is disabled.
+    ///
+    /// The API keys file is in TOML format, where each entry maps an API key
+    /// to an API set like `public_rpc_apis`, e.g. `"my-key" = "safe,debug"`.
+    /// The JWT secret file contains the secret in hex.
+    pub fn load(
+        public_apis: HashSet<Api>, api_keys_file: &Option<String>,
+        jwt_secret_file: &Option<String>,
+    ) -> Result<Option<Self>, String>
+    {
+        if api_keys_file.is_none() && jwt_secret_file.is_none() {
+            return Ok(None);
+        }
+
+        let mut api_keys = HashMap::new();
+        if let Some(file) = api_keys_file {
+            let content = fs::read_to_string(file).map_err(|e| {
+                format!("failed to read API keys file {}: {}", file, e)
+            })?;
+            let entries: HashMap<String, String> = toml::from_str(&content)
+                .map_err(|e| {
+                    format!("invalid API keys file {}: {}", file, e)
+                })?;
+            for (key, apis) in entries {
+                let apis = ApiSet::from_str(&apis).map_err(|e| {
+                    format!("invalid API set of key {}: {}", key, e)
+                })?;
+                api_keys.insert(key, apis.list_apis());
+            }
+        }
+
+        let jwt_secret = match jwt_secret_file {
+            Some(file) => {
+                let content = fs::read_to_string(file).map_err(|e| {
+                    format!("failed to read JWT secret file {}: {}", file, e)
+                })?;
+                let content = content.trim();
+                let secret: Vec<u8> = content
+                    .strip_prefix("0x")
+                    .unwrap_or(content)
+                    .from_hex()
+                    .map_err(|e| {
+                        format!("invalid JWT secret file {}: {}", file, e)
+                    })?;
+                if secret.is_empty() {
+                    return Err(format!("empty JWT secret file {}", file));
+                }
+                Some(secret)
+            }
+            None => None,
+        };
+
+        Ok(Some(RpcAuth {
+            public_apis,
+            api_keys,
+            jwt_secret,
+        }))
+    }
+
+    /// Return whether `api` is allowed for the request, or an error if its
+    /// credential is invalid.
+    pub fn is_allowed(
+        &self, meta: &Metadata, api: &Api,
+    ) -> Result<bool, String> {
+        let credential = match credential(meta) {
+            Some(credential) => credential,
+            None => return Ok(self.public_apis.contains(api)),
+        };
+        if let Some(apis) = self.api_keys.get(credential) {
+            return Ok(apis.contains(api));
+        }
+        match &self.jwt_secret {
+            Some(secret) => match validate_jwt(secret, credential)? {
+                Some(apis) => Ok(apis.list_apis().contains(api)),
+                None => Ok(self.public_apis.contains(api)),
+            },
+            None => Err("invalid API key".into()),
+        }
+    }
+}
+
+/// Return the credential of a request if it has one.
+pub(super) fn credential(meta: &Metadata) -> Option<&str> {
+    if let Some(key) = meta.headers.get("x-api-key") {
+        return Some(key.trim());
+    }
+    if let Some(authorization) = meta.headers.get("authorization") {
+        let authorization = authorization.trim();
+        return match authorization.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("bearer ") => {
+                Some(authorization[7..].trim())
+            }
+            _ => Some(authorization),
+        };
+    }
+    meta.headers
+        .get("sec-websocket-protocol")
+        .and_then(|protocols| protocols.split(',').next())
+        .map(str::trim)
+}
+
+/// Validate a JWT signed with HS256 and with an `exp` claim, and return the
+/// APIs in its `apis` claim, or `None` if it has no such claim, in which case
+/// only the public APIs are allowed.
+fn validate_jwt(secret: &[u8], token: &str) -> Result<Option<ApiSet>, String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err("invalid JWT format".into());
+    }
+    let decode = |part: &str| {
+        base64::decode_config(part, base64::URL_SAFE_NO_PAD)
+            .map_err(|_| "invalid JWT encoding".to_string())
+    };
+    let parse = |part: &str| -> Result<Value, String> {
+        serde_json::from_slice(&decode(part)?)
+            .map_err(|_| "invalid JWT encoding".to_string())
+    };
+
+    let header = parse(parts[0])?;
+    if header.get("alg").and_then(Value::as_str) != Some("HS256") {
+        return Err("unsupported JWT algorithm, HS256 is expected".into());
+    }
+    let signed = &token[..parts[0].len() + 1 + parts[1].len()];
+    let key = hmac::VerifyKey::sha256(secret);
+    if !hmac::verify(&key, signed.as_bytes(), &decode(parts[2])?) {
+        return Err("invalid JWT signature".into());
+    }
+
+    let claims = parse(parts[1])?;
+    let exp = claims.get("exp").ok_or("JWT has no exp claim")?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is after the Unix epoch")
+        .as_secs();
+    match exp.as_u64() {
+        Some(exp) if exp > now => {}
+        _ => return Err("JWT expired".into()),
+    }
+    match claims.get("apis") {
+        None => Ok(None),
+        Some(apis) => apis
+            .as_str()
+            .ok_or_else(|| "invalid JWT apis claim".to_string())
+            .and_then(ApiSet::from_str)
+            .map(Some),
+    }
+}
+
+/// Rejects the requests that are not allowed to use the API of the
+/// intercepted methods.
+pub struct AuthInterceptor {
+    api: Api,
+    auth: Arc<RpcAuth>,
+}
+
+impl AuthInterceptor {
+    pub fn new(api: Api, auth: Arc<RpcAuth>) -> Self {
+        AuthInterceptor { api, auth }
+    }
+}
+
+impl RpcInterceptor<Metadata> for AuthInterceptor {
+    fn before(&self, name: &String, meta: &Metadata) -> JsonRpcResult<()> {
+        match self.auth.is_allowed(meta, &self.api) {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                debug!(
+                    "RPC {} from {:?} rejected: API not allowed",
+                    name, meta.peer_addr
+                );
+                bail!(request_rejected_unauthorized(Some(format!(
+                    "{:?} API is not allowed",
+                    self.api
+                ))))
+            }
+            Err(e) => {
+                debug!(
+                    "RPC {} from {:?} rejected: {}",
+                    name, meta.peer_addr, e
+                );
+                bail!(request_rejected_unauthorized(Some(e)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_jwt, RpcAuth};
+    use crate::rpc::{
+        rpc_apis::{Api, ApiSet},
+        Metadata,
+    };
+    use parity_crypto::hmac;
+    use std::collections::HashMap;
+
+    const SECRET: &[u8] = b"secret";
+
+    fn jwt(claims: &str) -> String {
+        let encode =
+            |s: &[u8]| base64::encode_config(s, base64::URL_SAFE_NO_PAD);
+        let signed = format!(
+            "{}.{}",
+            encode(br#"{"alg":"HS256","typ":"JWT"}"#),
+            encode(claims.as_bytes())
+        );
+        let signature =
+            hmac::sign(&hmac::SigKey::sha256(SECRET), signed.as_bytes());
+        format!("{}.{}", signed, encode(&signature))
+    }
+
+    fn meta(name: &str, value: &str) -> Metadata {
+        let mut meta = Metadata::default();
+        meta.headers.insert(name.into(), value.into());
+        meta
+    }
+
+    #[test]
+    fn test_validate_jwt() {
+        assert_eq!(
+            validate_jwt(SECRET, &jwt(r#"{"exp":4102444800}"#)),
+            Ok(None)
+        );
+        assert_eq!(
+            validate_jwt(SECRET, &jwt(r#"{"apis":"debug","exp":4102444800}"#)),
+            Ok(Some(ApiSet::List(vec![Api::Debug].into_iter().collect())))
+        );
+        assert!(validate_jwt(b"other", &jwt(r#"{"exp":4102444800}"#)).is_err());
+        assert!(validate_jwt(SECRET, &jwt("{}")).is_err());
+        assert!(validate_jwt(SECRET, &jwt(r#"{"exp":1}"#)).is_err());
+        assert!(validate_jwt(SECRET, "invalid").is_err());
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let mut api_keys = HashMap::new();
+        api_keys
+            .insert("key".to_string(), vec![Api::Test].into_iter().collect());
+        let auth = RpcAuth {
+            public_apis: vec![Api::Cfx].into_iter().collect(),
+            api_keys,
+            jwt_secret: Some(SECRET.to_vec()),
+        };
+
+        assert_eq!(auth.is_allowed(&Metadata::default(), &Api::Cfx), Ok(true));
+        assert_eq!(
+            auth.is_allowed(&Metadata::default(), &Api::Test),
+            Ok(false)
+        );
+
+        let with_key = meta("x-api-key", "key");
+        assert_eq!(auth.is_allowed(&with_key, &Api::Test), Ok(true));
+        assert_eq!(auth.is_allowed(&with_key, &Api::Cfx), Ok(false));
+        let with_bearer_key = meta("authorization", "Bearer key");
+        assert_eq!(auth.is_allowed(&with_bearer_key, &Api::Test), Ok(true));
+
+        let with_jwt = meta(
+            "sec-websocket-protocol",
+            &jwt(r#"{"apis":"safe,debug","exp":4102444800}"#),
+        );
+        assert_eq!(auth.is_allowed(&with_jwt, &Api::Debug), Ok(true));
+        assert_eq!(auth.is_allowed(&with_jwt, &Api::Test), Ok(false));
+
+        // JWTs without the apis claim only grant the public APIs
+        let with_jwt = meta("authorization", &jwt(r#"{"exp":4102444800}"#));
+        assert_eq!(auth.is_allowed(&with_jwt, &Api::Cfx), Ok(true));
+        assert_eq!(auth.is_allowed(&with_jwt, &Api::Debug), Ok(false));
+
+        let with_invalid = meta("authorization", "Bearer unknown");
+        assert!(auth.is_allowed(&with_invalid, &Api::Cfx).is_err());
+    }
+}
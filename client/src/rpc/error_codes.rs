@@ -20,7 +20,7 @@
 use cfx_types::H256;
 use jsonrpc_core::{Error, ErrorCode, Value};
 use rustc_hex::ToHex;
-use std::fmt;
+use std::{fmt, time::Duration};
 
 pub mod codes {
     /// JsonRPC spec reserved from and including -32768 to -32000 for
@@ -41,7 +41,7 @@ pub mod codes {
     /// by 1.
     ///
     /// Do not recycle deprecated error codes.
-    const NEXT_SERVER_ERROR_CODE: i64 = -32078;
+    const NEXT_SERVER_ERROR_CODE: i64 = -32079;
     /// When the above number is equal to -32100, take the number below on the
     /// right for new error code, then increase it by 1.
     const CFX_EXTRA_SERVER_ERROR_CODE: i64 = -31999;
@@ -73,6 +73,9 @@ pub mod codes {
     /// This is mostly an application error but it's generic enough to define it
     /// here.
     pub const REQUEST_REJECTED_LIMIT_DATA: i64 = -32041;
+    /// When the credential of the request is invalid, or the requested API is
+    /// not allowed for it.
+    pub const REQUEST_REJECTED_UNAUTHORIZED: i64 = -32078;
 
     /* Conflux node status related error codes
      *
@@ -166,6 +169,26 @@ pub fn request_rejected_too_many_request_error(
     }
 }
 
+/// The request is throttled and can be retried after `wait_time`, which is
+/// appended to `details` as "retry after <milliseconds>ms".
+pub fn request_rejected_too_many_request_retry_after(
+    details: String, wait_time: Duration,
+) -> Error {
+    request_rejected_too_many_request_error(Some(format!(
+        "{}, retry after {}ms",
+        details,
+        wait_time.as_millis()
+    )))
+}
+
+pub fn request_rejected_unauthorized(details: Option<String>) -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::REQUEST_REJECTED_UNAUTHORIZED),
+        message: "Request rejected due to unauthorized access.".into(),
+        data: details.map(Value::String),
+    }
+}
+
 pub fn request_rejected_in_catch_up_mode(details: Option<String>) -> Error {
     Error {
         code: ErrorCode::ServerError(codes::REQUEST_REJECTED_IN_CATCH_UP),
@@ -184,3 +207,11 @@ pub fn pivot_assumption_failed(expected: H256, got: H256) -> Error {
         ))),
     }
 }
+
+pub fn subscription_closed(details: String) -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::EXCEPTION_ERROR),
+        message: "Subscription closed.".into(),
+        data: Some(Value::String(details)),
+    }
+}
@@ -18,10 +18,11 @@
 
 use crate::rpc::{http_common::HttpMetaExtractor, Metadata, Origin};
 use cfx_types::H256;
+use jsonrpc_ipc_server as ipc;
 use jsonrpc_pubsub::Session;
 use jsonrpc_tcp_server as tcp;
 use jsonrpc_ws_server as ws;
-use std::sync::Arc;
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 //use ws;
 
 /// Common HTTP & IPC metadata extractor.
@@ -32,7 +33,9 @@ impl HttpMetaExtractor for RpcExtractor {
 
     fn read_metadata(
         &self, origin: Option<String>, user_agent: Option<String>,
-    ) -> Metadata {
+        headers: HashMap<String, String>, peer_addr: Option<SocketAddr>,
+    ) -> Metadata
+    {
         Metadata {
             origin: Origin::Rpc(format!(
                 "{} / {}",
@@ -40,6 +43,8 @@ impl HttpMetaExtractor for RpcExtractor {
                 user_agent.unwrap_or_else(|| "unknown agent".to_string())
             )),
             session: None,
+            headers,
+            peer_addr,
         }
     }
 }
@@ -49,17 +54,43 @@ impl tcp::MetaExtractor<Metadata> for RpcExtractor {
         Metadata {
             origin: Origin::Tcp(req.peer_addr),
             session: Some(Arc::new(Session::new(req.sender.clone()))),
+            headers: HashMap::new(),
+            peer_addr: Some(req.peer_addr),
+        }
+    }
+}
+
+impl ipc::MetaExtractor<Metadata> for RpcExtractor {
+    fn extract(&self, req: &ipc::RequestContext) -> Metadata {
+        Metadata {
+            origin: Origin::Ipc {
+                session: H256::from_low_u64_be(req.session_id),
+            },
+            session: Some(Arc::new(Session::new(req.sender.clone()))),
+            headers: HashMap::new(),
+            peer_addr: None,
         }
     }
 }
 
 impl ws::MetaExtractor<Metadata> for RpcExtractor {
     fn extract(&self, req: &ws::RequestContext) -> Metadata {
+        // Browsers cannot set other headers in the WebSocket handshake, so
+        // the credentials are sent as the subprotocol.
+        let mut headers = HashMap::new();
+        if !req.protocols.is_empty() {
+            headers.insert(
+                "sec-websocket-protocol".to_string(),
+                req.protocols.join(", "),
+            );
+        }
         Metadata {
             origin: Origin::Ws {
                 session: H256::from_low_u64_be(req.session_id),
             },
             session: Some(Arc::new(Session::new(req.sender()))),
+            headers,
+            peer_addr: req.peer_addr,
         }
     }
 }
@@ -256,6 +287,7 @@ impl ws::MetaExtractor<Metadata> for RpcExtractor {
 #[cfg(test)]
 mod tests {
     use super::{HttpMetaExtractor, Origin, RpcExtractor};
+    use std::collections::HashMap;
 
     #[test]
     fn should_extract_rpc_origin() {
@@ -263,11 +295,20 @@ mod tests {
         let extractor = RpcExtractor;
 
         // when
-        let meta1 = extractor.read_metadata(None, None);
-        let meta2 = extractor
-            .read_metadata(None, Some("https://conflux-chain.org".to_owned()));
-        let meta3 = extractor
-            .read_metadata(None, Some("https://conflux-chain.org".to_owned()));
+        let peer_addr = "127.0.0.1:12537".parse().unwrap();
+        let meta1 = extractor.read_metadata(None, None, HashMap::new(), None);
+        let meta2 = extractor.read_metadata(
+            None,
+            Some("https://conflux-chain.org".to_owned()),
+            HashMap::new(),
+            None,
+        );
+        let meta3 = extractor.read_metadata(
+            None,
+            Some("https://conflux-chain.org".to_owned()),
+            HashMap::new(),
+            Some(peer_addr),
+        );
 
         // then
         assert_eq!(
@@ -282,5 +323,7 @@ mod tests {
             meta3.origin,
             Origin::Rpc("unknown origin / https://conflux-chain.org".into())
         );
+        assert_eq!(meta1.peer_addr, None);
+        assert_eq!(meta3.peer_addr, Some(peer_addr));
     }
 }
@@ -8,5 +8,6 @@ mod subscribers;
 mod variadic_value;
 
 pub use epoch_queue::EpochQueue;
+pub use poll_manager::{PollId, PollManager};
 pub use subscribers::{Id as SubscriberId, Subscribers};
 pub use variadic_value::{maybe_vec_into, VariadicValue};
@@ -74,6 +74,9 @@ where T: Timer
         self.polls.get_mut(id)
     }
 
+    /// Removes the polls that have expired.
+    pub fn prune(&mut self) { self.polls.prune(); }
+
     /// Removes poll info.
     pub fn remove_poll(&mut self, id: &PollId) -> bool {
         self.polls.remove(id).is_some()
@@ -1,8 +1,9 @@
+use crate::rpc::openrpc::{one_of, RpcSchema, SchemaComponents};
 use serde::{
     de::{DeserializeOwned, Error},
     Deserialize, Deserializer, Serialize, Serializer,
 };
-use serde_json::{from_value, Value};
+use serde_json::{from_value, json, Value};
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum VariadicValue<T> {
@@ -70,6 +71,16 @@ where T: DeserializeOwned
     }
 }
 
+impl<T: RpcSchema> RpcSchema for VariadicValue<T> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        one_of(vec![
+            json!({ "type": "null" }),
+            T::schema(components),
+            Vec::<T>::schema(components),
+        ])
+    }
+}
+
 // helper implementing automatic Option<Vec<A>> -> Option<Vec<B>> conversion
 pub fn maybe_vec_into<A, B>(src: &Option<Vec<A>>) -> Option<Vec<B>>
 where A: Clone + Into<B> {
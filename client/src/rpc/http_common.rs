@@ -19,14 +19,16 @@
 
 use jsonrpc_core;
 use jsonrpc_http_server::{self as http, hyper};
+use std::{collections::HashMap, net::SocketAddr};
 
 /// HTTP RPC server impl-independent metadata extractor
 pub trait HttpMetaExtractor: Send + Sync + 'static {
     /// Type of Metadata
     type Metadata: jsonrpc_core::Metadata;
-    /// Extracts metadata from given params.
+    /// Extracts metadata from given params. `headers` have lower-case names.
     fn read_metadata(
         &self, origin: Option<String>, user_agent: Option<String>,
+        headers: HashMap<String, String>, peer_addr: Option<SocketAddr>,
     ) -> Self::Metadata;
 }
 
@@ -50,6 +52,16 @@ where
 
         let origin = as_string(req.headers().get("origin"));
         let user_agent = as_string(req.headers().get("user-agent"));
-        self.extractor.read_metadata(origin, user_agent)
+        let headers = req
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                as_string(Some(value)).map(|value| (name.to_string(), value))
+            })
+            .collect();
+        // the address of the peer, if the server attaches it to the request
+        let peer_addr = req.extensions().get::<SocketAddr>().cloned();
+        self.extractor
+            .read_metadata(origin, user_agent, headers, peer_addr)
     }
 }
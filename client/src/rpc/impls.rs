@@ -14,10 +14,19 @@ pub struct RpcImplConfiguration {
     // note: currently we only handle this for `cfx_getEpochReceipts`,
     // other APIs will disconnect on oversized response
     pub max_payload_bytes: usize,
+
+    /// Filters created through `cfx_newFilter` and the like are removed if
+    /// they are not polled for this many seconds.
+    pub poll_lifetime_in_seconds: u32,
+
+    /// The maximal number of calls in one `cfx_callMany` request.
+    pub call_many_max_requests: usize,
 }
 
 pub mod cfx;
+pub mod cfx_filter;
 pub mod common;
+pub mod eth;
 pub mod light;
 pub mod pubsub;
 pub mod trace;
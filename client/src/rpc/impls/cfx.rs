@@ -4,7 +4,7 @@
 
 use crate::rpc::types::{
     call_request::rpc_call_request_network, errors::check_rpc_address_network,
-    RpcAddress, SponsorInfo, TokenSupplyInfo, MAX_GAS_CALL_REQUEST,
+    state_override_into_primitive, RpcAddress, SponsorInfo, TokenSupplyInfo,
 };
 use blockgen::BlockGenerator;
 use cfx_state::state_trait::StateOpsTrait;
@@ -13,10 +13,10 @@ use cfx_types::{
     address_util::AddressUtil, BigEndianHash, H256, H520, U128, U256, U64,
 };
 use cfxcore::{
-    executive::{ExecutionError, ExecutionOutcome, TxDropError},
+    executive::ExecutionOutcome,
     rpc_errors::{account_result_to_rpc_result, invalid_params_check},
     state_exposer::STATE_EXPOSER,
-    vm, ConsensusGraph, ConsensusGraphTrait, PeerInfo, SharedConsensusGraph,
+    ConsensusGraph, ConsensusGraphTrait, PeerInfo, SharedConsensusGraph,
     SharedSynchronizationService, SharedTransactionPool,
 };
 use cfxcore_accounts::AccountProvider;
@@ -29,9 +29,10 @@ use network::{
 };
 use parking_lot::Mutex;
 use primitives::{
-    filter::LogFilter, transaction::Action::Call, Account, Block,
-    BlockReceipts, DepositInfo, SignedTransaction, StorageKey, StorageRoot,
-    StorageValue, TransactionIndex, TransactionWithSignature, VoteStakeInfo,
+    filter::LogFilter, log_entry::LocalizedLogEntry, transaction::Action::Call,
+    Account, Block, BlockReceipts, DepositInfo, SignedTransaction, StateRoot,
+    StorageKey, StorageRoot, StorageValue, TransactionIndex,
+    TransactionWithSignature, VoteStakeInfo,
 };
 use random_crash::*;
 use rlp::Rlp;
@@ -53,14 +54,17 @@ use crate::{
         traits::{cfx::Cfx, debug::LocalRpc, test::TestRpc},
         types::{
             sign_call, Account as RpcAccount, AccountPendingInfo,
-            AccountPendingTransactions, BlameInfo, Block as RpcBlock,
-            BlockHashOrEpochNumber, Bytes, CallRequest,
-            CheckBalanceAgainstTransactionResponse, ConsensusGraphStates,
-            EpochNumber, EstimateGasAndCollateralResponse, Log as RpcLog,
+            AccountPendingTransactions, AccountProof, AddressTransactions,
+            BlameInfo, Block as RpcBlock, BlockHashOrEpochNumber, Bytes,
+            CallRequest, CfxLogs, CheckBalanceAgainstTransactionResponse,
+            ConsensusGraphStates, EpochNumber, EpochSummary,
+            EstimateGasAndCollateralResponse, FeeHistory, Log as RpcLog,
             LogFilter as RpcFilter, PackedOrExecuted, Receipt as RpcReceipt,
-            RewardInfo as RpcRewardInfo, SendTxRequest, Status as RpcStatus,
-            SyncGraphStates, Transaction as RpcTransaction, TxPoolPendingInfo,
-            TxWithPoolInfo,
+            RewardInfo as RpcRewardInfo, SendTxRequest, StateOverride,
+            Status as RpcStatus, StorageEntryProof, StructLogOptions,
+            SyncGraphStates, Transaction as RpcTransaction,
+            TransactionStructLogs, TxPoolPendingInfo, TxState, TxStatus,
+            TxWithPoolInfo, VirtualCallResult,
         },
         RpcResult,
     },
@@ -69,11 +73,11 @@ use cfx_addr::Network;
 use cfxcore::{
     consensus::{MaybeExecutedTxExtraInfo, TransactionInfo},
     consensus_parameters::DEFERRED_STATE_EPOCH_COUNT,
-    executive::revert_reason_decode,
     spec::genesis::{
         genesis_contract_address_four_year, genesis_contract_address_two_year,
     },
-    trace::ErrorUnwind,
+    trace::{StructLogConfig, StructLogTracer},
+    transaction_pool::TransactionStatus,
 };
 use lazy_static::lazy_static;
 use metrics::{register_timer_with_group, ScopeTimer, Timer};
@@ -87,6 +91,14 @@ lazy_static! {
         register_timer_with_group("rpc", "rpc:getLogs");
 }
 
+/// The maximum number of transactions returned by
+/// `cfx_getTransactionsByAddress`.
+const MAX_ADDRESS_TRANSACTIONS_LIMIT: u64 = 1000;
+
+/// The maximum number of logs returned in a page by `cfx_getLogs` if
+/// `get_logs_filter_max_limit` is not set.
+const DEFAULT_LOGS_PAGE_SIZE: usize = 10000;
+
 #[derive(Debug)]
 struct BlockExecInfo {
     block_receipts: Arc<BlockReceipts>,
@@ -382,7 +394,7 @@ impl RpcImpl {
         Ok(state_db.get_accumulate_interest_rate()?.into())
     }
 
-    fn send_raw_transaction(&self, raw: Bytes) -> RpcResult<H256> {
+    pub fn send_raw_transaction(&self, raw: Bytes) -> RpcResult<H256> {
         let _timer = ScopeTimer::time_scope(SEND_RAW_TX_TIMER.as_ref());
         info!("RPC Request: cfx_sendRawTransaction len={:?}", raw.0.len());
         debug!("RawTransaction bytes={:?}", raw);
@@ -551,6 +563,91 @@ impl RpcImpl {
         Ok(Some(root))
     }
 
+    fn get_proof(
+        &self, address: RpcAddress, storage_keys: Vec<H256>,
+        epoch_num: Option<EpochNumber>,
+    ) -> RpcResult<AccountProof>
+    {
+        self.check_address_network(address.network)?;
+        let epoch_num = epoch_num.unwrap_or(EpochNumber::LatestState).into();
+
+        info!(
+            "RPC Request: cfx_getProof address={:?} storage_keys={:?} epoch={:?}",
+            address, storage_keys, epoch_num
+        );
+
+        // Resolve the epoch number first so that the state and the reported
+        // state roots are guaranteed to belong to the same epoch.
+        let epoch = invalid_params_check(
+            "epoch_num",
+            self.consensus_graph()
+                .get_height_from_epoch_number(epoch_num),
+        )?;
+
+        let state_db = self.consensus.get_state_db_by_epoch_number(
+            primitives::EpochNumber::Number(epoch),
+            "epoch_num",
+        )?;
+
+        let state_root_of = |epoch: u64| -> RpcResult<StateRoot> {
+            let pivot = self.consensus.get_hash_from_epoch_number(
+                primitives::EpochNumber::Number(epoch),
+            )?;
+            let commitment = self
+                .consensus
+                .get_data_manager()
+                .get_epoch_execution_commitment_with_db(&pivot)
+                // FIXME: server error, client should request another server.
+                .ok_or("Inconsistent state")?;
+            Ok(commitment.state_root_with_aux_info.state_root)
+        };
+
+        let state_root = state_root_of(epoch)?;
+
+        // The padding of the intermediate delta trie keys is derived from the
+        // state root one snapshot period earlier.
+        let snapshot_epoch_count =
+            self.consensus.get_data_manager().get_snapshot_epoch_count() as u64;
+        let prev_snapshot_state_root = match epoch {
+            e if e <= snapshot_epoch_count => None,
+            e => Some(state_root_of(e - snapshot_epoch_count)?),
+        };
+
+        let hex_address = &address.hex_address;
+
+        let (account, account_proof) = state_db.get_original_raw_with_proof(
+            StorageKey::new_account_key(hex_address),
+        )?;
+
+        let (storage_root, storage_root_proof) =
+            state_db.get_original_storage_root_with_proof(hex_address)?;
+
+        let mut storage_proof = Vec::with_capacity(storage_keys.len());
+        for key in storage_keys {
+            let (value, proof) = state_db.get_original_raw_with_proof(
+                StorageKey::new_storage_key(hex_address, key.as_ref()),
+            )?;
+
+            storage_proof.push(StorageEntryProof {
+                key,
+                value: value.map(|v| Bytes::new(v.into_vec())),
+                proof: (&proof).into(),
+            });
+        }
+
+        Ok(AccountProof {
+            address,
+            epoch_number: epoch.into(),
+            state_root,
+            prev_snapshot_state_root,
+            account: account.map(|v| Bytes::new(v.into_vec())),
+            account_proof: (&account_proof).into(),
+            storage_root,
+            storage_root_proof: (&storage_root_proof).into(),
+            storage_proof,
+        })
+    }
+
     fn send_usable_genesis_accounts(
         &self, account_start_index: usize,
     ) -> RpcResult<Bytes> {
@@ -625,6 +722,86 @@ impl RpcImpl {
         })
     }
 
+    pub fn fee_history(
+        &self, epoch_count: U64, newest_epoch: EpochNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistory>
+    {
+        info!(
+            "RPC Request: cfx_feeHistory(epoch_count={:?}, newest_epoch={:?}, reward_percentiles={:?})",
+            epoch_count, newest_epoch, reward_percentiles
+        );
+
+        let history = invalid_params_check(
+            "params",
+            self.consensus_graph().fee_history(
+                newest_epoch.into(),
+                epoch_count.as_u64(),
+                reward_percentiles.as_deref().unwrap_or(&[]),
+            ),
+        )?;
+        Ok(FeeHistory::new(history, reward_percentiles.is_some()))
+    }
+
+    pub fn epoch_summary(
+        &self, epoch_number: EpochNumber,
+    ) -> RpcResult<EpochSummary> {
+        info!("RPC Request: cfx_getEpochSummary({:?})", epoch_number);
+        let epoch_number: primitives::EpochNumber = epoch_number.into();
+        let summaries = invalid_params_check(
+            "epoch_number",
+            self.consensus_graph()
+                .epoch_summaries(epoch_number.clone(), epoch_number),
+        )?;
+        Ok(summaries.into_iter().next().expect("one epoch").into())
+    }
+
+    pub fn epoch_summaries(
+        &self, from_epoch: EpochNumber, to_epoch: EpochNumber,
+    ) -> RpcResult<Vec<EpochSummary>> {
+        info!(
+            "RPC Request: cfx_getEpochSummaries(from_epoch={:?}, to_epoch={:?})",
+            from_epoch, to_epoch
+        );
+        let summaries = invalid_params_check(
+            "params",
+            self.consensus_graph()
+                .epoch_summaries(from_epoch.into(), to_epoch.into()),
+        )?;
+        Ok(summaries.into_iter().map(Into::into).collect())
+    }
+
+    pub fn transactions_by_address(
+        &self, address: RpcAddress, maybe_skip: Option<U64>,
+        maybe_limit: Option<U64>,
+    ) -> RpcResult<AddressTransactions>
+    {
+        info!("RPC Request: cfx_getTransactionsByAddress(addr={:?}, skip={:?}, limit={:?})",
+              address, maybe_skip, maybe_limit);
+        self.check_address_network(address.network)?;
+
+        let skip = maybe_skip.map_or(0, |skip| skip.as_u64());
+        let limit = maybe_limit
+            .map_or(MAX_ADDRESS_TRANSACTIONS_LIMIT, |limit| {
+                limit.as_u64().min(MAX_ADDRESS_TRANSACTIONS_LIMIT)
+            });
+        let (total_count, transactions) = match self
+            .consensus
+            .get_data_manager()
+            .address_transactions(&address.into(), skip, limit)
+        {
+            Some(result) => result,
+            None => bail!(
+                "The address transaction index is not enabled, \
+                 set persist_address_tx_index to enable it"
+            ),
+        };
+        Ok(AddressTransactions {
+            transactions: transactions.into_iter().map(Into::into).collect(),
+            total_count: total_count.into(),
+        })
+    }
+
     pub fn transaction_by_hash(
         &self, hash: H256,
     ) -> RpcResult<Option<RpcTransaction>> {
@@ -690,6 +867,91 @@ impl RpcImpl {
         Ok(None)
     }
 
+    pub fn transaction_status(&self, hash: H256) -> RpcResult<TxStatus> {
+        info!("RPC Request: cfx_getTransactionStatus({:?})", hash);
+
+        if let Some((
+            _,
+            TransactionInfo {
+                tx_index,
+                maybe_executed_extra_info,
+            },
+        )) = self.consensus.get_transaction_info_by_hash(&hash)
+        {
+            let mut status = match maybe_executed_extra_info {
+                None => TxStatus::new(TxState::Packed),
+                Some(MaybeExecutedTxExtraInfo {
+                    receipt,
+                    tx_exec_error_msg,
+                    ..
+                }) => {
+                    let mut status = TxStatus::new(TxState::Executed);
+                    status.outcome_status =
+                        Some(U64::from(receipt.outcome_status));
+                    status.tx_exec_error_msg = tx_exec_error_msg;
+                    status
+                }
+            };
+            let epoch_number =
+                self.consensus.get_block_epoch_number(&tx_index.block_hash);
+            self.fill_tx_status_block(
+                &mut status,
+                tx_index.block_hash,
+                epoch_number,
+            );
+            return Ok(status);
+        }
+
+        match self.tx_pool.get_transaction_status(&hash) {
+            Some(TransactionStatus::Packed) => {
+                return Ok(TxStatus::new(TxState::Packed));
+            }
+            Some(TransactionStatus::Ready) => {
+                return Ok(TxStatus::new(TxState::Ready));
+            }
+            Some(TransactionStatus::Pending(reason)) => {
+                let mut status = TxStatus::new(TxState::Pending);
+                status.pending_reason = Some(reason);
+                return Ok(status);
+            }
+            None => {}
+        }
+
+        if let Some(skipped) = self.tx_pool.get_skipped_transaction(&hash) {
+            let mut status = TxStatus::new(TxState::Skipped);
+            status.skip_reason = Some(skipped.reason);
+            self.fill_tx_status_block(
+                &mut status,
+                skipped.block_hash,
+                Some(skipped.epoch_number),
+            );
+            return Ok(status);
+        }
+
+        Ok(if self.tx_pool.is_garbage_collected(&hash) {
+            TxStatus::new(TxState::GarbageCollected)
+        } else {
+            TxStatus::new(TxState::Unknown)
+        })
+    }
+
+    fn fill_tx_status_block(
+        &self, status: &mut TxStatus, block_hash: H256,
+        epoch_number: Option<u64>,
+    )
+    {
+        let consensus_graph = self.consensus_graph();
+        let risk = consensus_graph
+            .confirmation_meter
+            .confirmation_risk_by_hash(
+                &*consensus_graph.inner.read(),
+                block_hash,
+            );
+        status.block_hash = Some(block_hash);
+        status.epoch_number = epoch_number.map(Into::into);
+        status.confirmation_risk = risk.map(common::scaled_confirmation_risk);
+    }
+
     fn get_block_execution_info(
         &self, block_hash: &H256,
     ) -> RpcResult<Option<BlockExecInfo>> {
@@ -1004,7 +1266,7 @@ impl RpcImpl {
         ))
     }
 
-    fn get_logs(&self, filter: RpcFilter) -> RpcResult<Vec<RpcLog>> {
+    fn get_logs(&self, filter: RpcFilter) -> RpcResult<CfxLogs> {
         // all addresses specified should be for the correct network
         if let Some(addresses) = &filter.address {
             for address in addresses.iter() {
@@ -1018,11 +1280,43 @@ impl RpcImpl {
             }
         }
 
-        let _timer = ScopeTimer::time_scope(GET_LOGS_TIMER.as_ref());
-        let consensus_graph = self.consensus_graph();
-
         info!("RPC Request: cfx_getLogs({:?})", filter);
-        let mut filter: LogFilter = filter.into_primitive()?;
+
+        let network = *self.sync.network.get_network_type();
+        let max_page_size = self
+            .config
+            .get_logs_filter_max_limit
+            .unwrap_or(DEFAULT_LOGS_PAGE_SIZE);
+        if let Some((cursor, page_size)) = filter.page(max_page_size)? {
+            let _timer = ScopeTimer::time_scope(GET_LOGS_TIMER.as_ref());
+            let (logs, next) = self.consensus_graph().logs_page(
+                filter.into_primitive()?,
+                cursor,
+                page_size,
+            )?;
+            return Ok(CfxLogs::Page {
+                logs: logs
+                    .into_iter()
+                    .map(|l| RpcLog::try_from_localized(l, network))
+                    .collect::<Result<_, _>>()?,
+                cursor: next.map(|cursor| Bytes::new(cursor.to_bytes())),
+            });
+        }
+
+        Ok(CfxLogs::Logs(
+            self.logs(filter.into_primitive()?)?
+                .into_iter()
+                .map(|l| RpcLog::try_from_localized(l, network))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    /// Return the logs matching `filter`, with its limit capped by
+    /// `get_logs_filter_max_limit`.
+    pub fn logs(
+        &self, mut filter: LogFilter,
+    ) -> RpcResult<Vec<LocalizedLogEntry>> {
+        let _timer = ScopeTimer::time_scope(GET_LOGS_TIMER.as_ref());
 
         // If max_limit is set, the value in `filter` will be modified to
         // satisfy this limitation to avoid loading too many blocks
@@ -1033,17 +1327,7 @@ impl RpcImpl {
             }
         }
 
-        Ok(consensus_graph
-            .logs(filter)?
-            .iter()
-            .cloned()
-            .map(|l| {
-                RpcLog::try_from_localized(
-                    l,
-                    *self.sync.network.get_network_type(),
-                )
-            })
-            .collect::<Result<_, _>>()?)
+        Ok(self.consensus_graph().logs(filter)?)
     }
 
     fn get_block_reward_info(
@@ -1078,149 +1362,24 @@ impl RpcImpl {
         Ok(ret)
     }
 
-    fn call(
+    pub fn call(
         &self, request: CallRequest, epoch: Option<EpochNumber>,
-    ) -> RpcResult<Bytes> {
-        match self.exec_transaction(request, epoch)? {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(expected, got)) => {
-                bail!(call_execution_error(
-                    "Transaction can not be executed".into(),
-                    format! {"nonce is too old expected {:?} got {:?}", expected, got}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::NotExecutedDrop(TxDropError::InvalidRecipientAddress(recipient)) => {
-                bail!(call_execution_error(
-                    "Transaction can not be executed".into(),
-                    format! {"invalid recipient address {:?}", recipient}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Transaction can not be executed".into(),
-                    format! {"{:?}", e}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(vm::Error::Reverted),
-                executed,
-            ) => bail!(call_execution_error(
-                "Transaction reverted".into(),
-                executed.output
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    "Transaction execution failed".into(),
-                    format! {"{:?}", e}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
-        }
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Bytes>
+    {
+        common::call_output(self.exec_transaction(request, epoch, state_override)?)
     }
 
-    fn estimate_gas_and_collateral(
+    pub fn estimate_gas_and_collateral(
         &self, request: CallRequest, epoch: Option<EpochNumber>,
-    ) -> RpcResult<EstimateGasAndCollateralResponse> {
-        let executed = match self.exec_transaction(request, epoch)? {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(expected, got)) => {
-                bail!(call_execution_error(
-                    "Can not estimate: transaction can not be executed".into(),
-                    format! {"nonce is too old expected {:?} got {:?}", expected, got}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::NotExecutedDrop(TxDropError::InvalidRecipientAddress(recipient)) => {
-                bail!(call_execution_error(
-                    "Can not estimate: transaction can not be executed".into(),
-                    format! {"invalid recipient address {:?}", recipient}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Can not estimate: transaction can not be executed".into(),
-                    format! {"{:?}", e}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(vm::Error::Reverted),
-                executed,
-            ) => {
-                let network_type = *self.sync.network.get_network_type();
-
-                // When a revert exception happens, there is usually an error in the sub-calls.
-                // So we return the trace information for debugging contract.
-                let errors = ErrorUnwind::from_traces(executed.trace).errors.iter()
-                    .map(|(addr,error)| {
-                        let cip37_addr = RpcAddress::try_from_h160(addr.clone(),network_type).unwrap().base32_address;
-                        format!("{}: {}", cip37_addr, error)
-                    })
-                    .collect::<Vec<String>>();
-
-                // Decode revert error
-                let revert_error = revert_reason_decode(&executed.output);
-                let revert_error = if !revert_error.is_empty() {
-                    format!(": {}.",revert_error)
-                }else{
-                    format!(".")
-                };
-
-                // Try to fetch the innermost error.
-                let innermost_error = if errors.len()>0{
-                    format!(" Innermost error is at {}.", errors[0])
-                }else{
-                    String::default()
-                };
-
-                bail!(call_execution_error(
-                    format!("Estimation isn't accurate: transaction is reverted{}{}",
-                        revert_error, innermost_error),
-                    errors.join("\n").into_bytes(),
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    format! {"Can not estimate: transaction execution failed, \
-                    all gas will be charged (execution error: {:?})", e}
-                    .into(),
-                    format! {"{:?}", e}.into_bytes()
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => executed,
-        };
-        let mut storage_collateralized = U64::from(0);
-        for storage_change in &executed.storage_collateralized {
-            storage_collateralized += storage_change.collaterals;
-        }
-        // In case of unlimited full gas charge at some VM call, or if there are
-        // infinite loops, the total estimated gas used is very close to
-        // MAX_GAS_CALL_REQUEST, 0.8 is chosen to check if it's close.
-        const TOO_MUCH_GAS_USED: u64 =
-            (0.8 * (MAX_GAS_CALL_REQUEST as f32)) as u64;
-        if executed.gas_used >= U256::from(TOO_MUCH_GAS_USED) {
-            bail!(call_execution_error(
-                format!(
-                    "Gas too high. Most likely there are problems within the contract code. \
-                    gas {}, storage_limit {}",
-                    executed.gas_used, storage_collateralized
-                ),
-                format!(
-                    "gas {}, storage_limit {}", executed.gas_used, storage_collateralized
-                )
-                .into_bytes(),
-            ));
-        }
-        let response = EstimateGasAndCollateralResponse {
-            // We multiply the gas_used for 2 reasons:
-            // 1. In each EVM call, the gas passed is at most 63/64 of the
-            // remaining gas, so the gas_limit should be multiplied a factor so
-            // that the gas passed into the sub-call is sufficient. The 4 / 3
-            // factor is sufficient for 18 level of calls.
-            // 2. In Conflux, we recommend setting the gas_limit to (gas_used *
-            // 4) / 3, because the extra gas will be refunded up to
-            // 1/4 of the gas limit.
-            gas_limit: executed.gas_used * 4 / 3,
-            gas_used: executed.gas_used,
-            storage_collateralized,
-        };
-        Ok(response)
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<EstimateGasAndCollateralResponse>
+    {
+        let outcome = self.exec_transaction(request, epoch, state_override)?;
+        common::estimate_gas_and_collateral_response(
+            outcome,
+            *self.sync.network.get_network_type(),
+        )
     }
 
     fn check_balance_against_transaction(
@@ -1269,9 +1428,86 @@ impl RpcImpl {
         ))
     }
 
+    fn call_many(
+        &self, requests: Vec<CallRequest>, epoch: Option<EpochNumber>,
+        include_trace: Option<bool>,
+    ) -> RpcResult<Vec<VirtualCallResult>>
+    {
+        let consensus_graph = self.consensus_graph();
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState);
+        let network = *self.sync.network.get_network_type();
+        let include_trace = include_trace.unwrap_or(false);
+
+        if requests.len() > self.config.call_many_max_requests {
+            bail!(JsonRpcError::invalid_params(format!(
+                "too many calls: {} > {}",
+                requests.len(),
+                self.config.call_many_max_requests
+            )));
+        }
+
+        // The calls without a nonce use the nonce left by the previous calls
+        // from the same sender.
+        let carry_nonce = requests
+            .iter()
+            .map(|request| request.nonce.is_none())
+            .collect::<Vec<_>>();
+        let signed_txs = requests
+            .into_iter()
+            .map(|request| self.sign_call_request(request))
+            .collect::<RpcResult<Vec<_>>>()?;
+        trace!("call txs {:?}", signed_txs);
+        let outcomes = consensus_graph.call_virtual_many(
+            &signed_txs,
+            &carry_nonce,
+            epoch.into(),
+            None,
+        )?;
+
+        let results = outcomes
+            .into_iter()
+            .map(|outcome| {
+                VirtualCallResult::try_from(outcome, network, include_trace)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.check_response_size(&results)?;
+        Ok(results)
+    }
+
     fn exec_transaction(
         &self, request: CallRequest, epoch: Option<EpochNumber>,
-    ) -> RpcResult<ExecutionOutcome> {
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        let signed_tx = self.sign_call_request(request)?;
+
+        let state_override = match state_override {
+            Some(state_override) => Some(invalid_params_check(
+                "state_override",
+                state_override_into_primitive(
+                    state_override,
+                    *self.sync.network.get_network_type(),
+                ),
+            )?),
+            None => None,
+        };
+
+        let consensus_graph = self.consensus_graph();
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState);
+
+        trace!("call tx {:?}", signed_tx);
+        consensus_graph.call_virtual(
+            &signed_tx,
+            epoch.into(),
+            state_override.as_ref(),
+        )
+    }
+
+    /// Check the addresses of `request` and turn it into a fake-signed
+    /// transaction for virtual execution.
+    fn sign_call_request(
+        &self, request: CallRequest,
+    ) -> RpcResult<SignedTransaction> {
         let rpc_request_network = invalid_params_check(
             "request",
             rpc_call_request_network(
@@ -1288,13 +1524,9 @@ impl RpcImpl {
         )?;
 
         let consensus_graph = self.consensus_graph();
-        let epoch = epoch.unwrap_or(EpochNumber::LatestState);
-
         let best_epoch_height = consensus_graph.best_epoch_number();
         let chain_id = consensus_graph.best_chain_id();
-        let signed_tx = sign_call(best_epoch_height, chain_id, request)?;
-        trace!("call tx {:?}", signed_tx);
-        consensus_graph.call_virtual(&signed_tx, epoch.into())
+        sign_call(best_epoch_height, chain_id, request)
     }
 
     fn current_sync_phase(&self) -> RpcResult<String> {
@@ -1501,6 +1733,50 @@ impl RpcImpl {
 
         Ok(Some(epoch_receipts))
     }
+
+    fn trace_transaction_struct_logs(
+        &self, tx_hash: H256, options: Option<StructLogOptions>,
+    ) -> RpcResult<TransactionStructLogs> {
+        info!(
+            "RPC Request: debug_traceTransaction tx_hash={:?} options={:?}",
+            tx_hash, options
+        );
+
+        let config: StructLogConfig = options.unwrap_or_default().into();
+        let mut struct_logs = Vec::new();
+        let (outcome, _) = self.consensus_graph().replay_transaction(
+            &tx_hash,
+            StructLogTracer::new(config, &mut struct_logs),
+            false, /* collect_state_diff */
+        )?;
+        let (failed, executed) = match outcome {
+            ExecutionOutcome::Finished(executed) => (false, executed),
+            ExecutionOutcome::ExecutionErrorBumpNonce(_, executed) => {
+                (true, executed)
+            }
+            ExecutionOutcome::NotExecutedDrop(e) => {
+                bail!(call_execution_error(
+                    "Transaction can not be executed".into(),
+                    format! {"{:?}", e}.into_bytes()
+                ))
+            }
+            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+                bail!(call_execution_error(
+                    "Transaction can not be executed".into(),
+                    format! {"{:?}", e}.into_bytes()
+                ))
+            }
+        };
+
+        let response = TransactionStructLogs {
+            gas: executed.gas_used,
+            failed,
+            return_value: executed.output.into(),
+            struct_logs: struct_logs.into_iter().map(Into::into).collect(),
+        };
+        self.check_response_size(&response)?;
+        Ok(response)
+    }
 }
 
 #[allow(dead_code)]
@@ -1553,25 +1829,33 @@ impl Cfx for CfxHandler {
             fn vote_list(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<Vec<VoteStakeInfo>>;
             fn collateral_for_storage(&self, address: RpcAddress, num: Option<EpochNumber>)
                 -> BoxFuture<U256>;
-            fn call(&self, request: CallRequest, epoch: Option<EpochNumber>)
-                -> JsonRpcResult<Bytes>;
+            fn call(&self, request: CallRequest, epoch: Option<EpochNumber>, state_override: Option<StateOverride>)
+                -> BoxFuture<Bytes>;
+            fn call_many(&self, requests: Vec<CallRequest>, epoch: Option<EpochNumber>, include_trace: Option<bool>)
+                -> JsonRpcResult<Vec<VirtualCallResult>>;
             fn estimate_gas_and_collateral(
-                &self, request: CallRequest, epoch_number: Option<EpochNumber>)
-                -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+                &self, request: CallRequest, epoch_number: Option<EpochNumber>, state_override: Option<StateOverride>)
+                -> BoxFuture<EstimateGasAndCollateralResponse>;
             fn check_balance_against_transaction(
                 &self, account_addr: RpcAddress, contract_addr: RpcAddress, gas_limit: U256, gas_price: U256, storage_limit: U256, epoch: Option<EpochNumber>,
             ) -> BoxFuture<CheckBalanceAgainstTransactionResponse>;
-            fn get_logs(&self, filter: RpcFilter) -> BoxFuture<Vec<RpcLog>>;
-            fn get_block_reward_info(&self, num: EpochNumber) -> JsonRpcResult<Vec<RpcRewardInfo>>;
+            fn get_logs(&self, filter: RpcFilter) -> BoxFuture<CfxLogs>;
+            fn get_block_reward_info(&self, num: EpochNumber) -> BoxFuture<Vec<RpcRewardInfo>>;
             fn send_raw_transaction(&self, raw: Bytes) -> JsonRpcResult<H256>;
             fn storage_at(&self, addr: RpcAddress, pos: H256, epoch_number: Option<EpochNumber>)
                 -> BoxFuture<Option<H256>>;
             fn transaction_by_hash(&self, hash: H256) -> BoxFuture<Option<RpcTransaction>>;
+            fn transaction_status(&self, tx_hash: H256) -> BoxFuture<TxStatus>;
             fn account_pending_info(&self, addr: RpcAddress) -> BoxFuture<Option<AccountPendingInfo>>;
             fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<AccountPendingTransactions>;
+            fn fee_history(&self, epoch_count: U64, newest_epoch: EpochNumber, reward_percentiles: Option<Vec<f64>>) -> BoxFuture<FeeHistory>;
+            fn epoch_summary(&self, epoch_number: EpochNumber) -> BoxFuture<EpochSummary>;
+            fn epoch_summaries(&self, from_epoch: EpochNumber, to_epoch: EpochNumber) -> BoxFuture<Vec<EpochSummary>>;
+            fn transactions_by_address(&self, address: RpcAddress, maybe_skip: Option<U64>, maybe_limit: Option<U64>) -> BoxFuture<AddressTransactions>;
             fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<RpcReceipt>>;
             fn storage_root(&self, address: RpcAddress, epoch_num: Option<EpochNumber>) -> BoxFuture<Option<StorageRoot>>;
-            fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<TokenSupplyInfo>;
+            fn get_proof(&self, address: RpcAddress, storage_keys: Vec<H256>, epoch_num: Option<EpochNumber>) -> BoxFuture<AccountProof>;
+            fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<TokenSupplyInfo>;
         }
     }
 }
@@ -1681,6 +1965,7 @@ impl LocalRpc for LocalRpcImpl {
             fn send_transaction(
                 &self, tx: SendTxRequest, password: Option<String>) -> BoxFuture<H256>;
             fn sign_transaction(&self, tx: SendTxRequest, password: Option<String>) -> JsonRpcResult<String>;
+            fn trace_transaction_struct_logs(&self, tx_hash: H256, options: Option<StructLogOptions>) -> JsonRpcResult<TransactionStructLogs>;
         }
     }
 }
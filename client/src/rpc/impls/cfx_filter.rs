@@ -0,0 +1,479 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    common::delegate_convert::into_jsonrpc_result,
+    rpc::{
+        error_codes::invalid_params,
+        helpers::{PollId, PollManager},
+        impls::RpcImplConfiguration,
+        traits::cfx_filter::CfxFilter,
+        types::{
+            errors::check_rpc_address_network, CfxFilterBlock,
+            CfxFilterChanges, CfxFilterLog, Log as RpcLog,
+            LogFilter as RpcFilter,
+        },
+        RpcResult,
+    },
+};
+use cfx_addr::Network;
+use cfx_types::{H256, U256};
+use cfxcore::{
+    channel::{Channel, Receiver},
+    rpc_errors::invalid_params_check,
+    ConsensusGraph, Notifications, SharedConsensusGraph,
+};
+use futures::{
+    compat::Future01CompatExt,
+    future::{FutureExt, TryFutureExt},
+};
+use jsonrpc_core::Result as JsonRpcResult;
+use parking_lot::Mutex;
+use primitives::{epoch::EpochNumber, filter::LogFilter, SignedTransaction};
+use runtime::Executor;
+use std::{
+    cmp::{max, min},
+    collections::VecDeque,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use tokio_timer::sleep;
+
+/// Maximum number of reported epochs a filter remembers for detecting pivot
+/// chain reorgs.
+const MAX_TRACKED_EPOCHS: usize = 256;
+
+type PendingTransactions = Vec<Arc<SignedTransaction>>;
+
+/// Receives the transactions accepted into the transaction pool for a pending
+/// transaction filter. The subscription is dropped together with the filter,
+/// which happens at the latest `poll_lifetime_in_seconds` after it expires.
+struct PendingTransactionReceiver {
+    channel: Arc<Channel<PendingTransactions>>,
+    receiver: Receiver<PendingTransactions>,
+}
+
+impl Drop for PendingTransactionReceiver {
+    fn drop(&mut self) { self.channel.unsubscribe(self.receiver.id); }
+}
+
+/// Pivot hashes of the epochs recently reported by a filter.
+struct ReportedEpochs {
+    /// The last epoch reported.
+    last_epoch: u64,
+
+    /// (epoch number, pivot hash) pairs, oldest first.
+    pivots: VecDeque<(u64, H256)>,
+}
+
+impl ReportedEpochs {
+    fn new(epoch: u64, pivot: H256) -> Self {
+        let mut pivots = VecDeque::with_capacity(MAX_TRACKED_EPOCHS);
+        pivots.push_back((epoch, pivot));
+
+        ReportedEpochs {
+            last_epoch: epoch,
+            pivots,
+        }
+    }
+
+    fn push(&mut self, epoch: u64, pivot: H256) {
+        self.pivots.push_back((epoch, pivot));
+
+        if self.pivots.len() > MAX_TRACKED_EPOCHS {
+            self.pivots.pop_front();
+        }
+    }
+
+    // Forget the reported epochs that are no longer on the pivot chain and
+    // return the last reported epoch that still is.
+    fn fork_point(&mut self, consensus: &SharedConsensusGraph) -> u64 {
+        let oldest = match self.pivots.front() {
+            Some((epoch, _)) => epoch.saturating_sub(1),
+            None => self.last_epoch,
+        };
+
+        while let Some(&(epoch, pivot)) = self.pivots.back() {
+            match consensus
+                .get_hash_from_epoch_number(EpochNumber::Number(epoch))
+            {
+                Ok(hash) if hash == pivot => return epoch,
+                _ => self.pivots.pop_back(),
+            };
+        }
+
+        // all tracked epochs have been reverted
+        oldest
+    }
+}
+
+enum PollFilter {
+    /// Reports the blocks of new epochs.
+    Block(ReportedEpochs),
+
+    /// Reports the transactions accepted into the transaction pool.
+    PendingTransaction(PendingTransactionReceiver),
+
+    /// Reports the logs of newly executed epochs.
+    Logs {
+        epochs: ReportedEpochs,
+        filter: LogFilter,
+    },
+}
+
+/// Cfx filter rpc implementation.
+#[derive(Clone)]
+pub struct CfxFilterClient {
+    consensus: SharedConsensusGraph,
+    new_pending_transactions: Arc<Channel<PendingTransactions>>,
+    polls: Arc<Mutex<PollManager<PollFilter>>>,
+    get_logs_filter_max_limit: Option<usize>,
+    network: Network,
+}
+
+impl CfxFilterClient {
+    /// Creates new `CfxFilterClient`. Filters not polled for
+    /// `config.poll_lifetime_in_seconds` are removed.
+    pub fn new(
+        executor: Executor, consensus: SharedConsensusGraph,
+        notifications: Arc<Notifications>, config: RpcImplConfiguration,
+        network: Network,
+    ) -> Self
+    {
+        let lifetime = config.poll_lifetime_in_seconds;
+        let polls = Arc::new(Mutex::new(PollManager::new(lifetime)));
+
+        // remove expired filters even if no filter requests are received, so
+        // that abandoned pending transaction filters stop buffering
+        let weak_polls = Arc::downgrade(&polls);
+        let interval = Duration::from_secs(max(lifetime, 1) as u64);
+        let fut = Self::prune_loop(weak_polls, interval);
+
+        // run futures@0.3 future on tokio@0.1 executor
+        executor.spawn(fut.unit_error().boxed().compat());
+
+        CfxFilterClient {
+            consensus,
+            new_pending_transactions: notifications
+                .new_pending_transactions
+                .clone(),
+            polls,
+            get_logs_filter_max_limit: config.get_logs_filter_max_limit,
+            network,
+        }
+    }
+
+    async fn prune_loop(
+        polls: Weak<Mutex<PollManager<PollFilter>>>, interval: Duration,
+    ) {
+        loop {
+            let _ = sleep(interval).compat().await;
+
+            match polls.upgrade() {
+                Some(polls) => polls.lock().prune(),
+                None => return,
+            }
+        }
+    }
+
+    fn consensus_graph(&self) -> &ConsensusGraph {
+        self.consensus
+            .as_any()
+            .downcast_ref::<ConsensusGraph>()
+            .expect("downcast should succeed")
+    }
+
+    fn poll_id(filter_id: U256) -> RpcResult<PollId> {
+        if filter_id > U256::from(usize::max_value()) {
+            bail!(invalid_params("filter_id", "Filter not found"));
+        }
+
+        Ok(filter_id.as_usize())
+    }
+
+    fn reported_epochs(&self, epoch: u64) -> RpcResult<ReportedEpochs> {
+        let pivot = self
+            .consensus
+            .get_hash_from_epoch_number(EpochNumber::Number(epoch))?;
+
+        Ok(ReportedEpochs::new(epoch, pivot))
+    }
+
+    // Bring `epochs` up to date with the pivot chain until `latest`. Returns
+    // the epoch to revert to if some reported epochs have been reverted, and
+    // the block hashes of the epochs to report.
+    fn update_epochs(
+        &self, epochs: &mut ReportedEpochs, latest: u64,
+    ) -> RpcResult<(Option<u64>, Vec<(u64, Vec<H256>)>)> {
+        let fork = epochs.fork_point(&self.consensus);
+
+        let revert_to = if fork < epochs.last_epoch {
+            debug!("pivot chain reorg: {} -> {}", epochs.last_epoch, fork);
+            Some(fork)
+        } else {
+            None
+        };
+
+        let mut new_epochs = vec![];
+
+        for epoch in (fork + 1)..=latest {
+            let hashes = self
+                .consensus
+                .get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+            let pivot = *hashes.last().expect("pivot block always exist");
+
+            epochs.push(epoch, pivot);
+            new_epochs.push((epoch, hashes));
+        }
+
+        epochs.last_epoch = max(fork, latest);
+        Ok((revert_to, new_epochs))
+    }
+
+    // Resolve the epoch range bound `epoch` of a log filter to its number.
+    fn epoch_bound(&self, epoch: &EpochNumber) -> RpcResult<u64> {
+        match epoch {
+            EpochNumber::Number(n) => Ok(*n),
+            _ => Ok(self
+                .consensus_graph()
+                .get_height_from_epoch_number(epoch.clone())?),
+        }
+    }
+
+    // Bring `epochs` up to date with the executed epochs. Returns the epoch to
+    // revert to if some reported epochs have been reverted, and the filter to
+    // query the logs of the new epochs within the range of `filter`, if any.
+    fn logs_query(
+        &self, epochs: &mut ReportedEpochs, filter: &LogFilter,
+    ) -> RpcResult<(Option<u64>, Option<LogFilter>)> {
+        let latest = self.consensus_graph().best_executed_state_epoch_number();
+        let (revert_to, new_epochs) = self.update_epochs(epochs, latest)?;
+
+        let (from, to) = match (new_epochs.first(), new_epochs.last()) {
+            (Some((from, _)), Some((to, _))) => (*from, *to),
+            _ => return Ok((revert_to, None)),
+        };
+
+        // only report the epochs within the range of the filter
+        let from = max(from, self.epoch_bound(&filter.from_epoch)?);
+        let to = min(to, self.epoch_bound(&filter.to_epoch)?);
+
+        if from > to {
+            return Ok((revert_to, None));
+        }
+
+        let mut filter = filter.clone();
+        filter.from_epoch = EpochNumber::Number(from);
+        filter.to_epoch = EpochNumber::Number(to);
+        filter.offset = None;
+        filter.limit = None;
+
+        Ok((revert_to, Some(filter)))
+    }
+
+    fn logs_changes(
+        &self, revert_to: Option<u64>, query: Option<LogFilter>,
+    ) -> RpcResult<Vec<CfxFilterLog>> {
+        let mut changes = vec![];
+
+        if let Some(epoch) = revert_to {
+            changes.push(CfxFilterLog::ChainReorg {
+                revert_to: epoch.into(),
+            });
+        }
+
+        let filter = match query {
+            Some(filter) => filter,
+            None => return Ok(changes),
+        };
+
+        for log in self.consensus_graph().logs(filter)? {
+            let log = RpcLog::try_from_localized(log, self.network)?;
+            changes.push(CfxFilterLog::Log(log));
+        }
+
+        Ok(changes)
+    }
+
+    fn new_filter_impl(&self, filter: RpcFilter) -> RpcResult<U256> {
+        info!("RPC Request: cfx_newFilter({:?})", filter);
+
+        // all addresses specified should be for the correct network
+        if let Some(addresses) = &filter.address {
+            for address in addresses.iter() {
+                invalid_params_check(
+                    "filter.address",
+                    check_rpc_address_network(
+                        Some(address.network),
+                        &self.network,
+                    ),
+                )?;
+            }
+        }
+
+        if filter.block_hashes.is_some() {
+            bail!(invalid_params(
+                "filter.block_hashes",
+                "Filtering by block hashes is not supported by cfx_newFilter, use cfx_getLogs instead",
+            ));
+        }
+
+        if filter.cursor.is_some() || filter.page_size.is_some() {
+            bail!(invalid_params(
+                "filter",
+                "Pagination is not supported by cfx_newFilter, use cfx_getLogs instead",
+            ));
+        }
+
+        let filter = filter.into_primitive()?;
+        let latest = self.consensus_graph().best_executed_state_epoch_number();
+
+        let poll = PollFilter::Logs {
+            epochs: self.reported_epochs(latest)?,
+            filter,
+        };
+
+        Ok(self.polls.lock().create_poll(poll).into())
+    }
+
+    fn new_block_filter_impl(&self) -> RpcResult<U256> {
+        info!("RPC Request: cfx_newBlockFilter()");
+
+        let latest = self.consensus.best_epoch_number();
+        let poll = PollFilter::Block(self.reported_epochs(latest)?);
+
+        Ok(self.polls.lock().create_poll(poll).into())
+    }
+
+    fn new_pending_transaction_filter_impl(&self) -> RpcResult<U256> {
+        info!("RPC Request: cfx_newPendingTransactionFilter()");
+
+        let poll = PollFilter::PendingTransaction(PendingTransactionReceiver {
+            channel: self.new_pending_transactions.clone(),
+            receiver: self.new_pending_transactions.subscribe(),
+        });
+
+        Ok(self.polls.lock().create_poll(poll).into())
+    }
+
+    fn filter_changes_impl(
+        &self, filter_id: U256,
+    ) -> RpcResult<CfxFilterChanges> {
+        info!("RPC Request: cfx_getFilterChanges({:?})", filter_id);
+
+        let id = Self::poll_id(filter_id)?;
+        let mut polls = self.polls.lock();
+
+        let poll = match polls.poll_mut(&id) {
+            Some(poll) => poll,
+            None => bail!(invalid_params("filter_id", "Filter not found")),
+        };
+
+        match poll {
+            PollFilter::Block(epochs) => {
+                let latest = self.consensus.best_epoch_number();
+                let (revert_to, new_epochs) =
+                    self.update_epochs(epochs, latest)?;
+
+                let mut changes = vec![];
+
+                if let Some(epoch) = revert_to {
+                    changes.push(CfxFilterBlock::ChainReorg {
+                        revert_to: epoch.into(),
+                    });
+                }
+
+                for (_, hashes) in new_epochs {
+                    changes
+                        .extend(hashes.into_iter().map(CfxFilterBlock::Hash));
+                }
+
+                Ok(CfxFilterChanges::Blocks(changes))
+            }
+            PollFilter::PendingTransaction(pending) => {
+                let mut hashes = vec![];
+
+                while let Ok(txs) = pending.receiver.try_recv() {
+                    hashes.extend(txs.iter().map(|tx| tx.hash()));
+                }
+
+                Ok(CfxFilterChanges::Hashes(hashes))
+            }
+            PollFilter::Logs { epochs, filter } => {
+                let (revert_to, query) = self.logs_query(epochs, filter)?;
+
+                // do not block the other filters while querying the logs
+                drop(polls);
+
+                let changes = self.logs_changes(revert_to, query)?;
+                Ok(CfxFilterChanges::Logs(changes))
+            }
+        }
+    }
+
+    fn filter_logs_impl(&self, filter_id: U256) -> RpcResult<Vec<RpcLog>> {
+        info!("RPC Request: cfx_getFilterLogs({:?})", filter_id);
+
+        let id = Self::poll_id(filter_id)?;
+
+        let mut filter = match self.polls.lock().poll(&id) {
+            Some(PollFilter::Logs { filter, .. }) => filter.clone(),
+            Some(_) => bail!(invalid_params("filter_id", "Not a log filter")),
+            None => bail!(invalid_params("filter_id", "Filter not found")),
+        };
+
+        // apply the same limitation as `cfx_getLogs`
+        if let Some(max_limit) = self.get_logs_filter_max_limit {
+            if filter.limit.is_none() || filter.limit.unwrap() > max_limit {
+                filter.limit = Some(max_limit);
+            }
+        }
+
+        Ok(self
+            .consensus_graph()
+            .logs(filter)?
+            .into_iter()
+            .map(|l| RpcLog::try_from_localized(l, self.network))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn uninstall_filter_impl(&self, filter_id: U256) -> RpcResult<bool> {
+        info!("RPC Request: cfx_uninstallFilter({:?})", filter_id);
+
+        let id = match Self::poll_id(filter_id) {
+            Ok(id) => id,
+            Err(_) => return Ok(false),
+        };
+
+        Ok(self.polls.lock().remove_poll(&id))
+    }
+}
+
+impl CfxFilter for CfxFilterClient {
+    fn new_filter(&self, filter: RpcFilter) -> JsonRpcResult<U256> {
+        into_jsonrpc_result(self.new_filter_impl(filter))
+    }
+
+    fn new_block_filter(&self) -> JsonRpcResult<U256> {
+        into_jsonrpc_result(self.new_block_filter_impl())
+    }
+
+    fn new_pending_transaction_filter(&self) -> JsonRpcResult<U256> {
+        into_jsonrpc_result(self.new_pending_transaction_filter_impl())
+    }
+
+    fn filter_changes(
+        &self, filter_id: U256,
+    ) -> JsonRpcResult<CfxFilterChanges> {
+        into_jsonrpc_result(self.filter_changes_impl(filter_id))
+    }
+
+    fn filter_logs(&self, filter_id: U256) -> JsonRpcResult<Vec<RpcLog>> {
+        into_jsonrpc_result(self.filter_logs_impl(filter_id))
+    }
+
+    fn uninstall_filter(&self, filter_id: U256) -> JsonRpcResult<bool> {
+        into_jsonrpc_result(self.uninstall_filter_impl(filter_id))
+    }
+}
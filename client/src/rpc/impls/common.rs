@@ -3,11 +3,13 @@
 // See http://www.gnu.org/licenses/
 
 use crate::rpc::{
+    error_codes::call_execution_error,
     types::{
         errors::check_rpc_address_network, Block as RpcBlock,
         BlockHashOrEpochNumber, Bytes, CheckBalanceAgainstTransactionResponse,
-        EpochNumber, RpcAddress, Status as RpcStatus,
-        Transaction as RpcTransaction, TxPoolPendingInfo, TxWithPoolInfo,
+        EpochNumber, EstimateGasAndCollateralResponse, RpcAddress,
+        Status as RpcStatus, Transaction as RpcTransaction, TxPoolPendingInfo,
+        TxWithPoolInfo, MAX_GAS_CALL_REQUEST,
     },
     RpcResult,
 };
@@ -16,8 +18,13 @@ use cfx_addr::Network;
 use cfx_parameters::staking::DRIPS_PER_STORAGE_COLLATERAL_UNIT;
 use cfx_types::{Address, H160, H256, H520, U128, U256, U512, U64};
 use cfxcore::{
-    rpc_errors::invalid_params_check, BlockDataManager, ConsensusGraph,
-    ConsensusGraphTrait, PeerInfo, SharedConsensusGraph, SharedTransactionPool,
+    executive::{
+        revert_reason_decode, ExecutionError, ExecutionOutcome, TxDropError,
+    },
+    rpc_errors::invalid_params_check,
+    trace::ErrorUnwind,
+    vm, BlockDataManager, ConsensusGraph, ConsensusGraphTrait, PeerInfo,
+    SharedConsensusGraph, SharedTransactionPool,
 };
 use cfxcore_accounts::AccountProvider;
 use cfxkey::Password;
@@ -244,22 +251,7 @@ impl RpcImpl {
         let result = consensus_graph
             .confirmation_meter
             .confirmation_risk_by_hash(inner, block_hash.into());
-        if result.is_none() {
-            Ok(None)
-        } else {
-            let risk: BigDecimal = result.unwrap().into();
-            let scale = BigInt::parse_bytes(b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF", 16).expect("failed to unwrap U256::max into bigInt");
-
-            //TODO: there's a precision problem here, it should be fine under a
-            // (2^256 - 1) scale
-            let scaled_risk: BigInt = (risk * scale)
-                .to_bigint()
-                .expect("failed to convert scaled risk to bigInt");
-            let (sign, big_endian_bytes) = scaled_risk.to_bytes_be();
-            assert_ne!(sign, num_bigint::Sign::Minus);
-            let rpc_result = U256::from(big_endian_bytes.as_slice());
-            Ok(Some(rpc_result.into()))
-        }
+        Ok(result.map(scaled_confirmation_risk))
     }
 
     pub fn block_by_hash(
@@ -927,6 +919,25 @@ impl RpcImpl {
     }
 }
 
+/// Scale a confirmation risk in `[0, 1]` to `[0, 2^256 - 1]`.
+pub fn scaled_confirmation_risk(risk: f64) -> U256 {
+    let risk: BigDecimal = risk.into();
+    let scale = BigInt::parse_bytes(
+        b"FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+        16,
+    )
+    .expect("failed to unwrap U256::max into bigInt");
+
+    //TODO: there's a precision problem here, it should be fine under a
+    // (2^256 - 1) scale
+    let scaled_risk: BigInt = (risk * scale)
+        .to_bigint()
+        .expect("failed to convert scaled risk to bigInt");
+    let (sign, big_endian_bytes) = scaled_risk.to_bytes_be();
+    assert_ne!(sign, num_bigint::Sign::Minus);
+    U256::from(big_endian_bytes.as_slice())
+}
+
 /// Returns a eth_sign-compatible hash of data to sign.
 /// The data is prepended with special message to prevent
 /// malicious DApps from using the function to sign forged transactions.
@@ -936,3 +947,166 @@ fn eth_data_hash(mut data: Vec<u8>) -> H256 {
     message_data.append(&mut data);
     keccak(message_data)
 }
+
+/// Turn the outcome of a virtual call into the output of `cfx_call`.
+pub fn call_output(outcome: ExecutionOutcome) -> RpcResult<Bytes> {
+    match outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => {
+            bail!(call_execution_error(
+                "Transaction can not be executed".into(),
+                format! {"nonce is too old expected {:?} got {:?}", expected, got}.into_bytes()
+            ))
+        }
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => {
+            bail!(call_execution_error(
+                "Transaction can not be executed".into(),
+                format! {"invalid recipient address {:?}", recipient}
+                    .into_bytes()
+            ))
+        }
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Transaction can not be executed".into(),
+                format! {"{:?}", e}.into_bytes()
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(vm::Error::Reverted),
+            executed,
+        ) => bail!(call_execution_error(
+            "Transaction reverted".into(),
+            executed.output
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                "Transaction execution failed".into(),
+                format! {"{:?}", e}.into_bytes()
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
+    }
+}
+
+/// Turn the outcome of a virtual call into the estimation of
+/// `cfx_estimateGasAndCollateral`.
+pub fn estimate_gas_and_collateral_response(
+    outcome: ExecutionOutcome, network_type: Network,
+) -> RpcResult<EstimateGasAndCollateralResponse> {
+    let executed = match outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => {
+            bail!(call_execution_error(
+                "Can not estimate: transaction can not be executed".into(),
+                format! {"nonce is too old expected {:?} got {:?}", expected, got}.into_bytes()
+            ))
+        }
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => {
+            bail!(call_execution_error(
+                "Can not estimate: transaction can not be executed".into(),
+                format! {"invalid recipient address {:?}", recipient}
+                    .into_bytes()
+            ))
+        }
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Can not estimate: transaction can not be executed".into(),
+                format! {"{:?}", e}.into_bytes()
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(vm::Error::Reverted),
+            executed,
+        ) => {
+            // When a revert exception happens, there is usually an error in the
+            // sub-calls. So we return the trace information for
+            // debugging contract.
+            let errors = ErrorUnwind::from_traces(executed.trace)
+                .errors
+                .iter()
+                .map(|(addr, error)| {
+                    let cip37_addr =
+                        RpcAddress::try_from_h160(addr.clone(), network_type)
+                            .unwrap()
+                            .base32_address;
+                    format!("{}: {}", cip37_addr, error)
+                })
+                .collect::<Vec<String>>();
+
+            // Decode revert error
+            let revert_error = revert_reason_decode(&executed.output);
+            let revert_error = if !revert_error.is_empty() {
+                format!(": {}.", revert_error)
+            } else {
+                format!(".")
+            };
+
+            // Try to fetch the innermost error.
+            let innermost_error = if errors.len() > 0 {
+                format!(" Innermost error is at {}.", errors[0])
+            } else {
+                String::default()
+            };
+
+            bail!(call_execution_error(
+                format!(
+                    "Estimation isn't accurate: transaction is reverted{}{}",
+                    revert_error, innermost_error
+                ),
+                errors.join("\n").into_bytes(),
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                format! {"Can not estimate: transaction execution failed, \
+                all gas will be charged (execution error: {:?})", e}
+                .into(),
+                format! {"{:?}", e}.into_bytes()
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => executed,
+    };
+    let mut storage_collateralized = U64::from(0);
+    for storage_change in &executed.storage_collateralized {
+        storage_collateralized += storage_change.collaterals;
+    }
+    // In case of unlimited full gas charge at some VM call, or if there are
+    // infinite loops, the total estimated gas used is very close to
+    // MAX_GAS_CALL_REQUEST, 0.8 is chosen to check if it's close.
+    const TOO_MUCH_GAS_USED: u64 = (0.8 * (MAX_GAS_CALL_REQUEST as f32)) as u64;
+    if executed.gas_used >= U256::from(TOO_MUCH_GAS_USED) {
+        bail!(call_execution_error(
+            format!(
+                "Gas too high. Most likely there are problems within the contract code. \
+                gas {}, storage_limit {}",
+                executed.gas_used, storage_collateralized
+            ),
+            format!(
+                "gas {}, storage_limit {}", executed.gas_used, storage_collateralized
+            )
+            .into_bytes(),
+        ));
+    }
+    let response = EstimateGasAndCollateralResponse {
+        // We multiply the gas_used for 2 reasons:
+        // 1. In each EVM call, the gas passed is at most 63/64 of the
+        // remaining gas, so the gas_limit should be multiplied a factor so
+        // that the gas passed into the sub-call is sufficient. The 4 / 3
+        // factor is sufficient for 18 level of calls.
+        // 2. In Conflux, we recommend setting the gas_limit to (gas_used *
+        // 4) / 3, because the extra gas will be refunded up to
+        // 1/4 of the gas limit.
+        gas_limit: executed.gas_used * 4 / 3,
+        gas_used: executed.gas_used,
+        storage_collateralized,
+    };
+    Ok(response)
+}
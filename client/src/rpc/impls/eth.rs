@@ -0,0 +1,894 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{
+    common::delegate_convert::into_jsonrpc_result,
+    rpc::{
+        error_codes::invalid_params,
+        impls::cfx::RpcImpl,
+        traits::eth::Eth,
+        types::{
+            eth::{
+                Block, BlockNumber, BlockTransactions, CallRequest, Filter,
+                Log, Receipt, Transaction, TransactionLocation,
+            },
+            Bytes,
+        },
+        RpcResult,
+    },
+};
+use cfx_addr::Network;
+use cfx_statedb::StateDbExt;
+use cfx_types::{BigEndianHash, Bloom, H160, H256, H64, U256, U64};
+use cfxcore::{
+    consensus_parameters::ONE_GDRIP_IN_DRIP, executive::contract_address,
+    rpc_errors::invalid_params_check, vm::CreateContractAddress,
+    ConsensusGraph, ConsensusGraphTrait, SharedConsensusGraph,
+};
+use cfxkey::Signature;
+use jsonrpc_core::{Error as JsonRpcError, Result as JsonRpcResult};
+use keccak_hash::{keccak, KECCAK_EMPTY_LIST_RLP};
+use parking_lot::Mutex;
+use primitives::{
+    log_entry::LocalizedLogEntry,
+    receipt::TRANSACTION_OUTCOME_SUCCESS,
+    transaction::{Action, Transaction as PrimitiveTransaction},
+    BlockHashOrEpochNumber, EpochNumber, StorageKey, StorageValue,
+    TransactionIndex, TransactionWithSignature,
+};
+use rlp::Rlp;
+use std::{collections::HashMap, sync::Arc};
+use transient_hashmap::TransientHashMap;
+
+/// Decode a signed legacy Ethereum transaction `[nonce, gasPrice, gas, to,
+/// value, data, v, r, s]` into an Ethereum like Conflux transaction. Only
+/// EIP-155 signatures are accepted, as the signed data of Ethereum like
+/// transactions always includes the chain id.
+fn decode_eip155_transaction(
+    raw: &[u8],
+) -> Result<TransactionWithSignature, String> {
+    let rlp = Rlp::new(raw);
+    let decode_error = |e| format!("invalid rlp: {:?}", e);
+
+    if rlp.item_count().map_err(decode_error)? != 9 {
+        return Err("invalid rlp: expected a list of 9 items".into());
+    }
+
+    let v: u64 = rlp.val_at(6).map_err(decode_error)?;
+    if v < 35 {
+        return Err(format!(
+            "unsupported v {}: only EIP-155 transactions are accepted",
+            v
+        ));
+    }
+    let chain_id = (v - 35) / 2;
+    if chain_id > u32::MAX as u64 {
+        return Err(format!("unsupported chain id {}", chain_id));
+    }
+
+    let unsigned = PrimitiveTransaction {
+        nonce: rlp.val_at(0).map_err(decode_error)?,
+        gas_price: rlp.val_at(1).map_err(decode_error)?,
+        gas: rlp.val_at(2).map_err(decode_error)?,
+        action: rlp.val_at(3).map_err(decode_error)?,
+        value: rlp.val_at(4).map_err(decode_error)?,
+        storage_limit: u64::MAX,
+        epoch_height: u64::MAX,
+        chain_id: chain_id as u32,
+        data: rlp.val_at(5).map_err(decode_error)?,
+    };
+
+    let r: U256 = rlp.val_at(7).map_err(decode_error)?;
+    let s: U256 = rlp.val_at(8).map_err(decode_error)?;
+    let signature = Signature::from_rsv(
+        &BigEndianHash::from_uint(&r),
+        &BigEndianHash::from_uint(&s),
+        ((v - 35) % 2) as u8,
+    );
+
+    Ok(unsigned.with_signature(signature))
+}
+
+/// How long the Ethereum hashes of sent transactions are kept for looking
+/// them up in the transaction pool.
+const PENDING_TX_LIFETIME_IN_SECONDS: u32 = 3600;
+
+/// The hash by which the `eth` RPCs identify `tx`.
+fn eth_tx_hash(tx: &TransactionWithSignature) -> H256 {
+    tx.eth_hash().unwrap_or_else(|| tx.hash())
+}
+
+/// The number of transactions and logs, and the gas used, in an epoch before
+/// a given block.
+#[derive(Clone, Copy, Default)]
+struct BlockOffsets {
+    transactions: usize,
+    logs: usize,
+    gas_used: U256,
+}
+
+pub struct EthHandler {
+    rpc_impl: Arc<RpcImpl>,
+
+    /// The Conflux hashes of the transactions sent through
+    /// `eth_sendRawTransaction`, by Ethereum hash, so that they can be found
+    /// in the transaction pool before they are executed and indexed.
+    pending_txs: Mutex<TransientHashMap<H256, H256>>,
+}
+
+impl EthHandler {
+    pub fn new(rpc_impl: Arc<RpcImpl>) -> Self {
+        EthHandler {
+            rpc_impl,
+            pending_txs: Mutex::new(TransientHashMap::new(
+                PENDING_TX_LIFETIME_IN_SECONDS,
+            )),
+        }
+    }
+
+    fn consensus(&self) -> &SharedConsensusGraph { &self.rpc_impl.consensus }
+
+    fn consensus_graph(&self) -> &ConsensusGraph {
+        self.consensus()
+            .as_any()
+            .downcast_ref::<ConsensusGraph>()
+            .expect("downcast should succeed")
+    }
+
+    fn network(&self) -> Network {
+        *self.rpc_impl.sync.network.get_network_type()
+    }
+
+    fn block_epoch_number(&self, hash: &H256) -> Option<u64> {
+        // try to get from memory
+        if let Some(e) = self.consensus().get_block_epoch_number(hash) {
+            return Some(e);
+        }
+
+        // try to get from db
+        self.consensus().get_data_manager().block_epoch_number(hash)
+    }
+
+    /// Return the epoch number of `hash` if it is a pivot block.
+    fn pivot_epoch(&self, hash: &H256) -> Option<u64> {
+        let epoch = self.block_epoch_number(hash)?;
+        match self
+            .consensus()
+            .get_hash_from_epoch_number(EpochNumber::Number(epoch))
+        {
+            Ok(pivot_hash) if pivot_hash == *hash => Some(epoch),
+            _ => None,
+        }
+    }
+
+    /// Return the pivot block hash of `epoch` and, for each block in the
+    /// epoch, the number of transactions in the epoch before it.
+    fn epoch_tx_offsets(
+        &self, epoch: u64,
+    ) -> RpcResult<(H256, HashMap<H256, usize>)> {
+        let data_man = self.consensus().get_data_manager();
+        let hashes = self
+            .consensus()
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+        let pivot_hash = *hashes.last().ok_or("Inconsistent state")?;
+
+        let mut offsets = HashMap::new();
+        let mut offset = 0;
+        for h in hashes {
+            let block = data_man
+                .block_by_hash(&h, false /* update_cache */)
+                .ok_or("Inconsistent state")?;
+            offsets.insert(h, offset);
+            offset += block.transactions.len();
+        }
+
+        Ok((pivot_hash, offsets))
+    }
+
+    /// Return the pivot block hash of the executed `epoch` and the offsets of
+    /// each block in the epoch.
+    fn executed_epoch_offsets(
+        &self, epoch: u64,
+    ) -> RpcResult<(H256, HashMap<H256, BlockOffsets>)> {
+        let data_man = self.consensus().get_data_manager();
+        let hashes = self
+            .consensus()
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+        let pivot_hash = *hashes.last().ok_or("Inconsistent state")?;
+
+        let mut offsets = HashMap::new();
+        let mut offset = BlockOffsets::default();
+        for h in hashes {
+            let receipts = data_man
+                .block_execution_result_by_hash_with_epoch(
+                    &h,
+                    &pivot_hash,
+                    false, /* update_pivot_assumption */
+                    false, /* update_cache */
+                )
+                .ok_or("Inconsistent state")?
+                .block_receipts;
+            offsets.insert(h, offset);
+
+            offset.transactions += receipts.receipts.len();
+            offset.logs += receipts
+                .receipts
+                .iter()
+                .map(|r| r.logs.len())
+                .sum::<usize>();
+            if let Some(r) = receipts.receipts.last() {
+                offset.gas_used += r.accumulated_gas_used;
+            }
+        }
+
+        Ok((pivot_hash, offsets))
+    }
+
+    fn transaction_location(
+        &self, tx_index: &TransactionIndex,
+    ) -> RpcResult<Option<TransactionLocation>> {
+        let epoch = match self.block_epoch_number(&tx_index.block_hash) {
+            None => return Ok(None),
+            Some(epoch) => epoch,
+        };
+
+        let (pivot_hash, offsets) = self.epoch_tx_offsets(epoch)?;
+        Ok(offsets.get(&tx_index.block_hash).map(|offset| {
+            TransactionLocation {
+                block_hash: pivot_hash,
+                block_number: epoch,
+                transaction_index: offset + tx_index.index,
+            }
+        }))
+    }
+
+    fn balance_impl(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> RpcResult<U256> {
+        let epoch_num = block.unwrap_or_default().into_primitive();
+
+        info!(
+            "RPC Request: eth_getBalance address={:?} epoch_num={:?}",
+            address, epoch_num
+        );
+
+        let state_db = self
+            .consensus()
+            .get_state_db_by_epoch_number(epoch_num, "block")?;
+        let acc = state_db.get_account(&address)?;
+
+        Ok(acc.map_or(U256::zero(), |acc| acc.balance))
+    }
+
+    fn storage_at_impl(
+        &self, address: H160, position: U256, block: Option<BlockNumber>,
+    ) -> RpcResult<H256> {
+        let epoch_num = block.unwrap_or_default().into_primitive();
+
+        info!(
+            "RPC Request: eth_getStorageAt address={:?}, position={:?}, epoch_num={:?})",
+            address, position, epoch_num
+        );
+
+        let state_db = self
+            .consensus()
+            .get_state_db_by_epoch_number(epoch_num, "block")?;
+
+        let position: H256 = BigEndianHash::from_uint(&position);
+        let key = StorageKey::new_storage_key(&address, position.as_ref());
+
+        Ok(match state_db.get::<StorageValue>(key)? {
+            Some(entry) => H256::from_uint(&entry.value),
+            None => H256::zero(),
+        })
+    }
+
+    fn transaction_count_impl(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> RpcResult<U256> {
+        let block = block.unwrap_or_default();
+
+        info!(
+            "RPC Request: eth_getTransactionCount address={:?} block={:?}",
+            address, block
+        );
+
+        let nonce = self.consensus_graph().next_nonce(
+            address,
+            BlockHashOrEpochNumber::EpochNumber(block.clone().into_primitive()),
+            "block",
+        )?;
+
+        if block != BlockNumber::Pending {
+            return Ok(nonce);
+        }
+
+        // include the transactions from this sender in the tx pool
+        let (pool_nonce, _) = self
+            .consensus()
+            .get_tx_pool()
+            .get_local_account_info(&address);
+        Ok(nonce.max(pool_nonce))
+    }
+
+    fn code_impl(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> RpcResult<Bytes> {
+        let epoch_num = block.unwrap_or_default().into_primitive();
+
+        info!(
+            "RPC Request: eth_getCode address={:?} epoch_num={:?}",
+            address, epoch_num
+        );
+
+        let state_db = self
+            .consensus()
+            .get_state_db_by_epoch_number(epoch_num, "block")?;
+
+        let acc = match state_db.get_account(&address)? {
+            None => return Ok(Bytes::default()),
+            Some(acc) => acc,
+        };
+
+        Ok(Bytes::new(
+            match state_db.get_code(&address, &acc.code_hash) {
+                Ok(Some(code)) => (*code.code).clone(),
+                _ => vec![],
+            },
+        ))
+    }
+
+    fn send_raw_transaction_impl(&self, raw: Bytes) -> RpcResult<H256> {
+        info!("RPC Request: eth_sendRawTransaction len={:?}", raw.0.len());
+
+        let tx =
+            invalid_params_check("raw", decode_eip155_transaction(&raw.0))?;
+
+        // the transaction is identified by `eth_hash` once executed, so make
+        // sure that it matches the hash of `raw`
+        let eth_hash = keccak(&raw.0);
+        if tx.eth_hash() != Some(eth_hash) {
+            bail!(invalid_params("raw", "invalid rlp: non-canonical encoding"));
+        }
+
+        let hash = self
+            .rpc_impl
+            .send_raw_transaction(rlp::encode(&tx).into())?;
+        self.pending_txs.lock().insert(eth_hash, hash);
+        Ok(eth_hash)
+    }
+
+    fn call_impl(
+        &self, request: CallRequest, block: Option<BlockNumber>,
+    ) -> RpcResult<Bytes> {
+        info!(
+            "RPC Request: eth_call request={:?} block={:?}",
+            request, block
+        );
+
+        let request =
+            invalid_params_check("request", request.into_cfx(self.network()))?;
+        self.rpc_impl.call(
+            request,
+            Some(block.unwrap_or_default().into()),
+            None, /* state_override */
+        )
+    }
+
+    fn estimate_gas_impl(
+        &self, request: CallRequest, block: Option<BlockNumber>,
+    ) -> RpcResult<U256> {
+        info!(
+            "RPC Request: eth_estimateGas request={:?} block={:?}",
+            request, block
+        );
+
+        let request =
+            invalid_params_check("request", request.into_cfx(self.network()))?;
+        let response = self.rpc_impl.estimate_gas_and_collateral(
+            request,
+            Some(block.unwrap_or_default().into()),
+            None, /* state_override */
+        )?;
+        Ok(response.gas_limit)
+    }
+
+    /// Build the Ethereum block of `epoch`. Gas used and logs bloom are only
+    /// filled in once the epoch is executed.
+    fn build_block(
+        &self, epoch: u64, hashes: Vec<H256>, include_txs: bool,
+    ) -> RpcResult<Block> {
+        let data_man = self.consensus().get_data_manager();
+        let pivot_hash = *hashes.last().ok_or("Inconsistent state")?;
+        let pivot_header = data_man
+            .block_header_by_hash(&pivot_hash)
+            .ok_or("Inconsistent state")?;
+        let executed =
+            epoch <= self.consensus_graph().best_executed_state_epoch_number();
+
+        let mut gas_used = U256::zero();
+        let mut logs_bloom = Bloom::zero();
+        let mut transactions = vec![];
+
+        for h in &hashes {
+            let block = data_man
+                .block_by_hash(h, false /* update_cache */)
+                .ok_or("Inconsistent state")?;
+
+            if executed {
+                let exec_result = data_man
+                    .block_execution_result_by_hash_with_epoch(
+                        h,
+                        &pivot_hash,
+                        false, /* update_pivot_assumption */
+                        false, /* update_cache */
+                    )
+                    .ok_or("Inconsistent state")?;
+
+                if let Some(r) = exec_result.block_receipts.receipts.last() {
+                    gas_used += r.accumulated_gas_used;
+                }
+                logs_bloom.accrue_bloom(&exec_result.bloom);
+            }
+
+            transactions.extend(block.transactions.iter().cloned());
+        }
+
+        let transactions = if include_txs {
+            BlockTransactions::Full(
+                transactions
+                    .iter()
+                    .enumerate()
+                    .map(|(transaction_index, tx)| {
+                        Transaction::from_signed(
+                            tx,
+                            Some(TransactionLocation {
+                                block_hash: pivot_hash,
+                                block_number: epoch,
+                                transaction_index,
+                            }),
+                        )
+                    })
+                    .collect(),
+            )
+        } else {
+            BlockTransactions::Hashes(
+                transactions.iter().map(|tx| eth_tx_hash(tx)).collect(),
+            )
+        };
+
+        Ok(Block {
+            hash: pivot_hash,
+            parent_hash: *pivot_header.parent_hash(),
+            sha3_uncles: KECCAK_EMPTY_LIST_RLP,
+            miner: *pivot_header.author(),
+            state_root: *pivot_header.deferred_state_root(),
+            transactions_root: *pivot_header.transactions_root(),
+            receipts_root: *pivot_header.deferred_receipts_root(),
+            logs_bloom,
+            difficulty: *pivot_header.difficulty(),
+            number: epoch.into(),
+            gas_limit: *pivot_header.gas_limit(),
+            gas_used,
+            timestamp: pivot_header.timestamp().into(),
+            extra_data: Bytes::default(),
+            mix_hash: H256::zero(),
+            nonce: H64::from_low_u64_be(pivot_header.nonce().low_u64()),
+            uncles: vec![],
+            transactions,
+        })
+    }
+
+    /// Return the epoch number and the block hashes of `block`, or None if
+    /// the block does not exist yet.
+    fn epoch_by_number(
+        &self, block: BlockNumber,
+    ) -> RpcResult<Option<(u64, Vec<H256>)>> {
+        let epoch = match block {
+            BlockNumber::Num(num) => num.as_u64(),
+            BlockNumber::Earliest => 0,
+            BlockNumber::Latest | BlockNumber::Pending => {
+                self.consensus_graph().best_executed_state_epoch_number()
+            }
+        };
+
+        if epoch > self.consensus().best_epoch_number() {
+            return Ok(None);
+        }
+
+        let hashes = self
+            .consensus()
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+        Ok(Some((epoch, hashes)))
+    }
+
+    /// Return the epoch number and the block hashes of the epoch with pivot
+    /// block `hash`, or None if `hash` is not a pivot block.
+    fn epoch_by_hash(&self, hash: H256) -> RpcResult<Option<(u64, Vec<H256>)>> {
+        let epoch = match self.pivot_epoch(&hash) {
+            None => return Ok(None),
+            Some(epoch) => epoch,
+        };
+
+        let hashes = self
+            .consensus()
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+        Ok(Some((epoch, hashes)))
+    }
+
+    fn block_by_hash_impl(
+        &self, hash: H256, include_txs: bool,
+    ) -> RpcResult<Option<Block>> {
+        info!(
+            "RPC Request: eth_getBlockByHash hash={:?} include_txs={:?}",
+            hash, include_txs
+        );
+
+        match self.epoch_by_hash(hash)? {
+            None => Ok(None),
+            Some((epoch, hashes)) => {
+                Ok(Some(self.build_block(epoch, hashes, include_txs)?))
+            }
+        }
+    }
+
+    fn block_by_number_impl(
+        &self, block: BlockNumber, include_txs: bool,
+    ) -> RpcResult<Option<Block>> {
+        info!(
+            "RPC Request: eth_getBlockByNumber block={:?} include_txs={:?}",
+            block, include_txs
+        );
+
+        match self.epoch_by_number(block)? {
+            None => Ok(None),
+            Some((epoch, hashes)) => {
+                Ok(Some(self.build_block(epoch, hashes, include_txs)?))
+            }
+        }
+    }
+
+    fn transaction_count_in_epoch(
+        &self, epoch: Option<(u64, Vec<H256>)>,
+    ) -> RpcResult<Option<U256>> {
+        let hashes = match epoch {
+            None => return Ok(None),
+            Some((_, hashes)) => hashes,
+        };
+
+        let mut count = 0;
+        for h in hashes {
+            count += self
+                .consensus()
+                .get_data_manager()
+                .block_by_hash(&h, false /* update_cache */)
+                .ok_or("Inconsistent state")?
+                .transactions
+                .len();
+        }
+
+        Ok(Some(count.into()))
+    }
+
+    fn transaction_by_hash_impl(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Transaction>> {
+        info!("RPC Request: eth_getTransactionByHash({:?})", hash);
+
+        if let Some((tx, info)) =
+            self.consensus().get_transaction_info_by_hash(&hash)
+        {
+            let location = self.transaction_location(&info.tx_index)?;
+            return Ok(Some(Transaction::from_signed(&tx, location)));
+        }
+
+        let pool_hash = {
+            let mut pending_txs = self.pending_txs.lock();
+            pending_txs.prune();
+            pending_txs.get(&hash).cloned().unwrap_or(hash)
+        };
+
+        if let Some(tx) =
+            self.consensus().get_tx_pool().get_transaction(&pool_hash)
+        {
+            return Ok(Some(Transaction::from_signed(&tx, None)));
+        }
+
+        Ok(None)
+    }
+
+    fn transaction_receipt_impl(
+        &self, hash: H256,
+    ) -> RpcResult<Option<Receipt>> {
+        info!("RPC Request: eth_getTransactionReceipt({:?})", hash);
+
+        let data_man = self.consensus().get_data_manager();
+        let consensus_graph = self.consensus_graph();
+
+        // See `RpcImpl::prepare_receipt` on outdated transaction indices.
+        let tx_index = match data_man
+            .transaction_index_by_hash(&hash, false /* update_cache */)
+        {
+            None => return Ok(None),
+            Some(tx_index) => tx_index,
+        };
+
+        let (pivot_hash, block_receipts) = match consensus_graph
+            .get_block_execution_info(&tx_index.block_hash)
+        {
+            None => return Ok(None),
+            Some((exec_res, _)) => (exec_res.0, exec_res.1.block_receipts),
+        };
+
+        let epoch = data_man
+            .block_header_by_hash(&pivot_hash)
+            .ok_or("Inconsistent state")?
+            .height();
+
+        if epoch > consensus_graph.best_executed_state_epoch_number() {
+            // The receipt is only visible to optimistic execution.
+            return Ok(None);
+        }
+
+        let offset = match self.executed_epoch_offsets(epoch)? {
+            (epoch_pivot_hash, offsets) if epoch_pivot_hash == pivot_hash => {
+                *offsets
+                    .get(&tx_index.block_hash)
+                    .ok_or("Inconsistent state")?
+            }
+            // pivot chain reorg
+            _ => return Ok(None),
+        };
+
+        let block = data_man
+            .block_by_hash(&tx_index.block_hash, false /* update_cache */)
+            .ok_or("Inconsistent state")?;
+        let id = tx_index.index;
+
+        if id >= block.transactions.len() || id >= block_receipts.receipts.len()
+        {
+            bail!("Inconsistent state");
+        }
+
+        let tx = &block.transactions[id];
+        let receipt = &block_receipts.receipts[id];
+        let hash = eth_tx_hash(tx);
+        let transaction_index = offset.transactions + id;
+
+        let gas_used = match id {
+            0 => receipt.accumulated_gas_used,
+            id => {
+                receipt.accumulated_gas_used
+                    - block_receipts.receipts[id - 1].accumulated_gas_used
+            }
+        };
+
+        let success = receipt.outcome_status == TRANSACTION_OUTCOME_SUCCESS;
+        let contract_address = match tx.action {
+            Action::Create if success => Some(
+                contract_address(
+                    CreateContractAddress::FromSenderNonceAndCodeHash,
+                    block_receipts.block_number.into(),
+                    &tx.sender,
+                    &tx.nonce,
+                    &tx.data,
+                )
+                .0,
+            ),
+            _ => None,
+        };
+
+        let log_index = offset.logs
+            + block_receipts.receipts[..id]
+                .iter()
+                .map(|r| r.logs.len())
+                .sum::<usize>();
+
+        let logs = receipt
+            .logs
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, entry)| {
+                Log::from_localized(
+                    LocalizedLogEntry {
+                        entry,
+                        block_hash: tx_index.block_hash,
+                        epoch_number: epoch,
+                        transaction_hash: hash,
+                        transaction_index: id,
+                        log_index: i,
+                        transaction_log_index: i,
+                    },
+                    pivot_hash,
+                    transaction_index,
+                    log_index + i,
+                )
+            })
+            .collect();
+
+        Ok(Some(Receipt {
+            transaction_hash: hash,
+            transaction_index: transaction_index.into(),
+            block_hash: pivot_hash,
+            block_number: epoch.into(),
+            from: tx.sender,
+            to: match tx.action {
+                Action::Create => None,
+                Action::Call(ref address) => Some(*address),
+            },
+            cumulative_gas_used: offset.gas_used + receipt.accumulated_gas_used,
+            gas_used,
+            contract_address,
+            logs,
+            logs_bloom: receipt.log_bloom,
+            status: U64::from(success as u8),
+        }))
+    }
+
+    fn logs_impl(&self, filter: Filter) -> RpcResult<Vec<Log>> {
+        info!("RPC Request: eth_getLogs({:?})", filter);
+
+        let filter = filter.into_primitive(|hash| {
+            self.pivot_epoch(&hash).ok_or_else(|| {
+                JsonRpcError::invalid_params(
+                    "filter.blockHash is not a pivot block",
+                )
+            })
+        })?;
+
+        let data_man = self.consensus().get_data_manager();
+        let mut epochs = HashMap::new();
+        let mut logs = vec![];
+
+        for mut l in self.rpc_impl.logs(filter)? {
+            if !epochs.contains_key(&l.epoch_number) {
+                epochs.insert(
+                    l.epoch_number,
+                    self.executed_epoch_offsets(l.epoch_number)?,
+                );
+            }
+
+            let (pivot_hash, offsets) = &epochs[&l.epoch_number];
+            let offset =
+                offsets.get(&l.block_hash).ok_or("Inconsistent state")?;
+            let transaction_index = offset.transactions + l.transaction_index;
+            let log_index = offset.logs + l.log_index;
+
+            let block = data_man
+                .block_by_hash(&l.block_hash, false /* update_cache */)
+                .ok_or("Inconsistent state")?;
+            let tx = block
+                .transactions
+                .get(l.transaction_index)
+                .ok_or("Inconsistent state")?;
+            l.transaction_hash = eth_tx_hash(tx);
+
+            logs.push(Log::from_localized(
+                l,
+                *pivot_hash,
+                transaction_index,
+                log_index,
+            ));
+        }
+
+        Ok(logs)
+    }
+}
+
+impl Eth for EthHandler {
+    fn net_version(&self) -> JsonRpcResult<String> {
+        Ok(self.rpc_impl.sync.network.network_id().to_string())
+    }
+
+    fn chain_id(&self) -> JsonRpcResult<U64> {
+        Ok(self.consensus().best_chain_id().into())
+    }
+
+    fn syncing(&self) -> JsonRpcResult<bool> {
+        Ok(self.rpc_impl.sync.catch_up_mode())
+    }
+
+    fn gas_price(&self) -> JsonRpcResult<U256> {
+        Ok(self
+            .consensus_graph()
+            .gas_price()
+            .unwrap_or(ONE_GDRIP_IN_DRIP.into()))
+    }
+
+    fn accounts(&self) -> JsonRpcResult<Vec<H160>> { Ok(vec![]) }
+
+    fn block_number(&self) -> JsonRpcResult<U256> {
+        Ok(self
+            .consensus_graph()
+            .best_executed_state_epoch_number()
+            .into())
+    }
+
+    fn balance(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<U256> {
+        into_jsonrpc_result(self.balance_impl(address, block))
+    }
+
+    fn storage_at(
+        &self, address: H160, position: U256, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<H256> {
+        into_jsonrpc_result(self.storage_at_impl(address, position, block))
+    }
+
+    fn transaction_count(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<U256> {
+        into_jsonrpc_result(self.transaction_count_impl(address, block))
+    }
+
+    fn code(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<Bytes> {
+        into_jsonrpc_result(self.code_impl(address, block))
+    }
+
+    fn send_raw_transaction(&self, raw: Bytes) -> JsonRpcResult<H256> {
+        into_jsonrpc_result(self.send_raw_transaction_impl(raw))
+    }
+
+    fn call(
+        &self, request: CallRequest, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<Bytes> {
+        into_jsonrpc_result(self.call_impl(request, block))
+    }
+
+    fn estimate_gas(
+        &self, request: CallRequest, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<U256> {
+        into_jsonrpc_result(self.estimate_gas_impl(request, block))
+    }
+
+    fn block_by_hash(
+        &self, hash: H256, include_txs: bool,
+    ) -> JsonRpcResult<Option<Block>> {
+        into_jsonrpc_result(self.block_by_hash_impl(hash, include_txs))
+    }
+
+    fn block_by_number(
+        &self, block: BlockNumber, include_txs: bool,
+    ) -> JsonRpcResult<Option<Block>> {
+        into_jsonrpc_result(self.block_by_number_impl(block, include_txs))
+    }
+
+    fn block_transaction_count_by_hash(
+        &self, hash: H256,
+    ) -> JsonRpcResult<Option<U256>> {
+        into_jsonrpc_result(
+            self.epoch_by_hash(hash)
+                .and_then(|epoch| self.transaction_count_in_epoch(epoch)),
+        )
+    }
+
+    fn block_transaction_count_by_number(
+        &self, block: BlockNumber,
+    ) -> JsonRpcResult<Option<U256>> {
+        into_jsonrpc_result(
+            self.epoch_by_number(block)
+                .and_then(|epoch| self.transaction_count_in_epoch(epoch)),
+        )
+    }
+
+    fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> JsonRpcResult<Option<Transaction>> {
+        into_jsonrpc_result(self.transaction_by_hash_impl(hash))
+    }
+
+    fn transaction_receipt(
+        &self, hash: H256,
+    ) -> JsonRpcResult<Option<Receipt>> {
+        into_jsonrpc_result(self.transaction_receipt_impl(hash))
+    }
+
+    fn logs(&self, filter: Filter) -> JsonRpcResult<Vec<Log>> {
+        into_jsonrpc_result(self.logs_impl(filter))
+    }
+}
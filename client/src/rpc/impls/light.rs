@@ -2,11 +2,15 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::rpc::types::errors::check_rpc_address_network;
+use crate::rpc::types::{
+    call_request::rpc_call_request_network, errors::check_rpc_address_network,
+    sign_call, state_override_into_primitive,
+};
 use cfx_types::{H160, H256, H520, U128, U256, U64};
 use cfxcore::{
     block_data_manager::BlockDataManager,
     consensus_parameters::ONE_GDRIP_IN_DRIP,
+    executive::ExecutionOutcome,
     light_protocol::{
         self, query_service::TxInfo, Error as LightError, ErrorKind,
     },
@@ -38,14 +42,17 @@ use crate::{
         traits::{cfx::Cfx, debug::LocalRpc, test::TestRpc},
         types::{
             Account as RpcAccount, AccountPendingInfo,
-            AccountPendingTransactions, BlameInfo, Block as RpcBlock,
-            BlockHashOrEpochNumber, Bytes, CallRequest,
-            CheckBalanceAgainstTransactionResponse, ConsensusGraphStates,
-            EpochNumber, EstimateGasAndCollateralResponse, Log as RpcLog,
+            AccountPendingTransactions, AccountProof, AddressTransactions,
+            BlameInfo, Block as RpcBlock, BlockHashOrEpochNumber, Bytes,
+            CallRequest, CfxLogs, CheckBalanceAgainstTransactionResponse,
+            ConsensusGraphStates, EpochNumber, EpochSummary,
+            EstimateGasAndCollateralResponse, FeeHistory, Log as RpcLog,
             LogFilter as RpcFilter, Receipt as RpcReceipt,
             RewardInfo as RpcRewardInfo, RpcAddress, SendTxRequest,
-            SponsorInfo, Status as RpcStatus, SyncGraphStates, TokenSupplyInfo,
-            Transaction as RpcTransaction, TxPoolPendingInfo, TxWithPoolInfo,
+            SponsorInfo, StateOverride, Status as RpcStatus, StructLogOptions,
+            SyncGraphStates, TokenSupplyInfo, Transaction as RpcTransaction,
+            TransactionStructLogs, TxPoolPendingInfo, TxStatus, TxWithPoolInfo,
+            VirtualCallResult,
         },
         RpcBoxFuture, RpcResult,
     },
@@ -80,6 +87,48 @@ macro_rules! not_supported {
     };
 }
 
+/// The methods that light nodes do not serve, i.e. those not set up or stubbed
+/// with `not_supported!`. They are not listed by `rpc.discover`.
+pub const UNSUPPORTED_METHODS: &[&str] = &[
+    // cfx
+    "cfx_callMany",
+    "cfx_feeHistory",
+    "cfx_getEpochSummary",
+    "cfx_getEpochSummaries",
+    "cfx_getTransactionsByAddress",
+    "cfx_getProof",
+    "cfx_getTransactionStatus",
+    // cfx filters
+    "cfx_newFilter",
+    "cfx_newBlockFilter",
+    "cfx_newPendingTransactionFilter",
+    "cfx_getFilterChanges",
+    "cfx_getFilterLogs",
+    "cfx_uninstallFilter",
+    // test
+    "expireblockgc",
+    "test_generateblockwithblameinfo",
+    "test_generateblockwithfaketxs",
+    "test_generate_block_with_nonce_and_timestamp",
+    "test_generatecustomblock",
+    "generate_empty_blocks",
+    "generatefixedblock",
+    "generate_one_block_with_direct_txgen",
+    "generateoneblock",
+    "get_block_status",
+    "getExecutedInfo",
+    "getPivotChainAndWeight",
+    "test_sendUsableGenesisAccounts",
+    "set_db_crash",
+    // debug
+    "consensus_graph_state",
+    "current_sync_phase",
+    "cfx_getEpochReceipts",
+    "cfx_signTransaction",
+    "sync_graph_state",
+    "debug_traceTransaction",
+];
+
 pub struct RpcImpl {
     // account provider used for signing transactions
     accounts: Arc<AccountProvider>,
@@ -416,7 +465,7 @@ impl RpcImpl {
         Box::new(fut.boxed().compat())
     }
 
-    fn get_logs(&self, filter: RpcFilter) -> RpcBoxFuture<Vec<RpcLog>> {
+    fn get_logs(&self, filter: RpcFilter) -> RpcBoxFuture<CfxLogs> {
         info!("RPC Request: cfx_getLogs filter={:?}", filter);
 
         // clone `self.light` to avoid lifetime issues due to capturing `self`
@@ -437,6 +486,13 @@ impl RpcImpl {
                 }
             }
 
+            if filter.page(usize::MAX)?.is_some() {
+                return Err(error_codes::unimplemented(Some(
+                    "Pagination is not supported by light nodes".to_string(),
+                ))
+                .into());
+            }
+
             let mut filter = filter.into_primitive()?;
 
             // If max_limit is set, the value in `filter` will be modified to
@@ -454,12 +510,13 @@ impl RpcImpl {
                 .map_err(|e| e.to_string()) // TODO(thegaram): return meaningful error
                 .map_err(RpcError::invalid_params)?;
 
-            Ok(logs
-                .into_iter()
-                .map(|l| {
-                    RpcLog::try_from_localized(l, *light.get_network_type())
-                })
-                .collect::<Result<_, _>>()?)
+            Ok(CfxLogs::Logs(
+                logs.into_iter()
+                    .map(|l| {
+                        RpcLog::try_from_localized(l, *light.get_network_type())
+                    })
+                    .collect::<Result<_, _>>()?,
+            ))
         };
 
         Box::new(fut.boxed().compat())
@@ -1015,6 +1072,231 @@ impl RpcImpl {
 
         Box::new(fut.boxed().compat())
     }
+
+    fn call(
+        &self, request: CallRequest, epoch: Option<EpochNumber>,
+        state_override: Option<StateOverride>,
+    ) -> RpcBoxFuture<Bytes>
+    {
+        info!(
+            "RPC Request: cfx_call request={:?} epoch={:?}",
+            request, epoch
+        );
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let consensus_graph = self.consensus.clone();
+        let light = self.light.clone();
+
+        let fut = async move {
+            let outcome = Self::exec_transaction(
+                consensus_graph,
+                light,
+                request,
+                epoch,
+                state_override,
+            )
+            .await?;
+
+            common::call_output(outcome)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn estimate_gas_and_collateral(
+        &self, request: CallRequest, epoch: Option<EpochNumber>,
+        state_override: Option<StateOverride>,
+    ) -> RpcBoxFuture<EstimateGasAndCollateralResponse>
+    {
+        info!(
+            "RPC Request: cfx_estimateGasAndCollateral request={:?} epoch={:?}",
+            request, epoch
+        );
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let consensus_graph = self.consensus.clone();
+        let light = self.light.clone();
+
+        let fut = async move {
+            let network = *light.get_network_type();
+
+            let outcome = Self::exec_transaction(
+                consensus_graph,
+                light,
+                request,
+                epoch,
+                state_override,
+            )
+            .await?;
+
+            common::estimate_gas_and_collateral_response(outcome, network)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn get_block_reward_info(
+        &self, epoch: EpochNumber,
+    ) -> RpcBoxFuture<Vec<RpcRewardInfo>> {
+        info!(
+            "RPC Request: cfx_getBlockRewardInfo epoch_number={:?}",
+            epoch
+        );
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let data_man = self.data_man.clone();
+        let light = self.light.clone();
+
+        let fut = async move {
+            let network = *light.get_network_type();
+
+            let rewards = light
+                .get_block_rewards(epoch.into())
+                .await
+                .map_err(|e| e.to_string())
+                .map_err(RpcError::invalid_params)?;
+
+            let mut ret = Vec::new();
+            for (hash, reward) in rewards {
+                let header =
+                    data_man.block_header_by_hash(&hash).ok_or_else(|| {
+                        RpcError::invalid_params(format!(
+                            "Header of block {:?} not found",
+                            hash
+                        ))
+                    })?;
+                let author =
+                    RpcAddress::try_from_h160(*header.author(), network)?;
+
+                ret.push(RpcRewardInfo::new(hash, author, reward));
+            }
+            Ok(ret)
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn get_supply_info(
+        &self, epoch: Option<EpochNumber>,
+    ) -> RpcBoxFuture<TokenSupplyInfo> {
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+
+        info!("RPC Request: cfx_getSupplyInfo epoch={:?}", epoch);
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            let info = light
+                .get_supply_info(epoch)
+                .await
+                .map_err(|e| e.to_string())
+                .map_err(RpcError::invalid_params)?;
+
+            Ok(TokenSupplyInfo {
+                total_circulating: info.total_circulating,
+                total_issued: info.total_issued,
+                total_staking: info.total_staking,
+                total_collateral: info.total_collateral,
+            })
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    fn account_pending_transactions(
+        &self, address: RpcAddress, maybe_start_nonce: Option<U256>,
+        maybe_limit: Option<U64>,
+    ) -> RpcBoxFuture<AccountPendingTransactions>
+    {
+        info!("RPC Request: cfx_getAccountPendingTransactions(addr={:?}, start_nonce={:?}, limit={:?})",
+              address, maybe_start_nonce, maybe_limit);
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+
+        let fut = async move {
+            Self::check_address_network(address.network, &light)?;
+            let network = *light.get_network_type();
+            let address: H160 = address.into();
+
+            // full nodes do not relay the pending txs of arbitrary accounts,
+            // so we only serve the accounts that sent txs through this node
+            if !light.is_tx_sender(&address) {
+                bail!(RpcError::invalid_params(format!(
+                    "No transaction from {:?} was sent through this node",
+                    address
+                )));
+            }
+
+            let txs = light
+                .get_account_pending_txs(
+                    address,
+                    maybe_start_nonce,
+                    maybe_limit.map(|limit| limit.as_u64()),
+                )
+                .await
+                .map_err(|e| e.to_string())
+                .map_err(RpcError::invalid_params)?;
+
+            Ok(AccountPendingTransactions {
+                pending_transactions: txs
+                    .pending_txs
+                    .iter()
+                    .map(|tx| RpcTransaction::from_signed(tx, None, network))
+                    .collect::<Result<Vec<RpcTransaction>, String>>()?,
+                first_tx_status: txs.first_tx_status,
+                pending_count: txs.pending_count.into(),
+            })
+        };
+
+        Box::new(fut.boxed().compat())
+    }
+
+    /// Turn `request` into a fake-signed transaction and execute it on the
+    /// state of `epoch`, retrieved from peers and verified.
+    async fn exec_transaction(
+        consensus_graph: SharedConsensusGraph, light: Arc<LightQueryService>,
+        request: CallRequest, epoch: Option<EpochNumber>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        let rpc_request_network = invalid_params_check(
+            "request",
+            rpc_call_request_network(
+                request.from.as_ref(),
+                request.to.as_ref(),
+            ),
+        )?;
+        invalid_params_check(
+            "request",
+            check_rpc_address_network(
+                rpc_request_network,
+                light.get_network_type(),
+            ),
+        )?;
+
+        let state_override = match state_override {
+            Some(state_override) => Some(invalid_params_check(
+                "state_override",
+                state_override_into_primitive(
+                    state_override,
+                    *light.get_network_type(),
+                ),
+            )?),
+            None => None,
+        };
+
+        let signed_tx = sign_call(
+            consensus_graph.best_epoch_number(),
+            consensus_graph.best_chain_id(),
+            request,
+        )?;
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+
+        trace!("call tx {:?}", signed_tx);
+        light.call_virtual(signed_tx, epoch, state_override).await
+    }
 }
 
 pub struct CfxHandler {
@@ -1047,13 +1329,17 @@ impl Cfx for CfxHandler {
             fn block_by_hash_with_pivot_assumption(&self, block_hash: H256, pivot_hash: H256, epoch_number: U64) -> BoxFuture<RpcBlock>;
             fn block_by_hash(&self, hash: H256, include_txs: bool) -> BoxFuture<Option<RpcBlock>>;
             fn blocks_by_epoch(&self, num: EpochNumber) -> JsonRpcResult<Vec<H256>>;
+            fn call(&self, request: CallRequest, epoch: Option<EpochNumber>, state_override: Option<StateOverride>) -> BoxFuture<Bytes>;
             fn check_balance_against_transaction(&self, account_addr: RpcAddress, contract_addr: RpcAddress, gas_limit: U256, gas_price: U256, storage_limit: U256, epoch: Option<EpochNumber>) -> BoxFuture<CheckBalanceAgainstTransactionResponse>;
             fn code(&self, address: RpcAddress, epoch_num: Option<EpochNumber>) -> BoxFuture<Bytes>;
             fn collateral_for_storage(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<U256>;
             fn deposit_list(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<Vec<DepositInfo>>;
             fn epoch_number(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<U256>;
+            fn estimate_gas_and_collateral(&self, request: CallRequest, epoch_num: Option<EpochNumber>, state_override: Option<StateOverride>) -> BoxFuture<EstimateGasAndCollateralResponse>;
             fn gas_price(&self) -> BoxFuture<U256>;
-            fn get_logs(&self, filter: RpcFilter) -> BoxFuture<Vec<RpcLog>>;
+            fn get_block_reward_info(&self, num: EpochNumber) -> BoxFuture<Vec<RpcRewardInfo>>;
+            fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<TokenSupplyInfo>;
+            fn get_logs(&self, filter: RpcFilter) -> BoxFuture<CfxLogs>;
             fn interest_rate(&self, num: Option<EpochNumber>) -> BoxFuture<U256>;
             fn next_nonce(&self, address: RpcAddress, num: Option<BlockHashOrEpochNumber>) -> BoxFuture<U256>;
             fn send_raw_transaction(&self, raw: Bytes) -> JsonRpcResult<H256>;
@@ -1065,16 +1351,19 @@ impl Cfx for CfxHandler {
             fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<RpcReceipt>>;
             fn vote_list(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<Vec<VoteStakeInfo>>;
             fn account_pending_info(&self, addr: RpcAddress) -> BoxFuture<Option<AccountPendingInfo>>;
+            fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<AccountPendingTransactions>;
         }
     }
 
     // TODO(thegaram): add support for these
     not_supported! {
-        fn call(&self, request: CallRequest, epoch: Option<EpochNumber>) -> JsonRpcResult<Bytes>;
-        fn estimate_gas_and_collateral(&self, request: CallRequest, epoch_num: Option<EpochNumber>) -> JsonRpcResult<EstimateGasAndCollateralResponse>;
-        fn get_block_reward_info(&self, num: EpochNumber) -> JsonRpcResult<Vec<RpcRewardInfo>>;
-        fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<TokenSupplyInfo>;
-        fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<AccountPendingTransactions>;
+        fn call_many(&self, requests: Vec<CallRequest>, epoch: Option<EpochNumber>, include_trace: Option<bool>) -> JsonRpcResult<Vec<VirtualCallResult>>;
+        fn fee_history(&self, epoch_count: U64, newest_epoch: EpochNumber, reward_percentiles: Option<Vec<f64>>) -> BoxFuture<FeeHistory>;
+        fn epoch_summary(&self, epoch_number: EpochNumber) -> BoxFuture<EpochSummary>;
+        fn epoch_summaries(&self, from_epoch: EpochNumber, to_epoch: EpochNumber) -> BoxFuture<Vec<EpochSummary>>;
+        fn transactions_by_address(&self, address: RpcAddress, maybe_skip: Option<U64>, maybe_limit: Option<U64>) -> BoxFuture<AddressTransactions>;
+        fn get_proof(&self, address: RpcAddress, storage_keys: Vec<H256>, epoch_num: Option<EpochNumber>) -> BoxFuture<AccountProof>;
+        fn transaction_status(&self, tx_hash: H256) -> BoxFuture<TxStatus>;
     }
 }
 
@@ -1169,5 +1458,6 @@ impl LocalRpc for DebugRpcImpl {
         fn epoch_receipts(&self, epoch: BlockHashOrEpochNumber) -> JsonRpcResult<Option<Vec<Vec<RpcReceipt>>>>;
         fn sign_transaction(&self, tx: SendTxRequest, password: Option<String>) -> JsonRpcResult<String>;
         fn sync_graph_state(&self) -> JsonRpcResult<SyncGraphStates>;
+        fn trace_transaction_struct_logs(&self, tx_hash: H256, options: Option<StructLogOptions>) -> JsonRpcResult<TransactionStructLogs>;
     }
 }
@@ -8,32 +8,38 @@ use crate::rpc::{
     metadata::Metadata,
     traits::PubSub,
     types::{
-        pubsub::{self, SubscriptionEpoch},
-        Header as RpcHeader, Log as RpcLog,
+        errors::check_rpc_address_network,
+        pubsub::{self, PendingTransactionFilter, SubscriptionEpoch},
+        Header as RpcHeader, LocalizedTrace as RpcLocalizedTrace,
+        Log as RpcLog, Transaction as RpcTransaction,
     },
 };
 use cfx_addr::Network;
 use cfx_parameters::consensus::DEFERRED_STATE_EPOCH_COUNT;
 use cfx_types::H256;
 use cfxcore::{
-    channel::Channel, BlockDataManager, Notifications, SharedConsensusGraph,
+    channel::Channel, sync::SyncPhaseProgress,
+    trace::trace_filter::TraceFilter, BlockDataManager, ConsensusGraph,
+    LightQueryService, Notifications, SharedConsensusGraph,
 };
 use futures::{
     compat::Future01CompatExt,
     future::{join_all, FutureExt, TryFutureExt},
 };
 use itertools::zip;
-use jsonrpc_core::{futures::Future, Result as RpcResult};
+use jsonrpc_core::{futures::Future, Error, Result as RpcResult};
 use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
     SubscriptionId,
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use primitives::{
     filter::LogFilter, log_entry::LocalizedLogEntry, BlockReceipts,
+    EpochNumber, SignedTransaction,
 };
 use runtime::Executor;
 use std::{
+    ops::RangeInclusive,
     sync::{Arc, Weak},
     time::Duration,
 };
@@ -48,7 +54,12 @@ pub struct PubSubClient {
     heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
     epochs_subscribers: Arc<RwLock<Subscribers<Client>>>,
     logs_subscribers: Arc<RwLock<Subscribers<(Client, LogFilter)>>>,
+    pending_txs_subscribers:
+        Arc<RwLock<Subscribers<(Client, PendingTransactionFilter)>>>,
+    syncing_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    traces_subscribers: Arc<RwLock<Subscribers<(Client, TraceFilter)>>>,
     epochs_ordered: Arc<Channel<(u64, Vec<H256>)>>,
+    verified_header_heights: Arc<Channel<u64>>,
 }
 
 impl PubSubClient {
@@ -57,28 +68,111 @@ impl PubSubClient {
         executor: Executor, consensus: SharedConsensusGraph,
         notifications: Arc<Notifications>, network: Network,
     ) -> Self
+    {
+        Self::with_light(executor, consensus, None, notifications, network)
+    }
+
+    /// Creates new `PubSubClient` for light nodes. Executed epochs and logs
+    /// are published once the light sync handler verifies the corresponding
+    /// headers, and logs are retrieved and verified through `light`.
+    pub fn new_light(
+        executor: Executor, consensus: SharedConsensusGraph,
+        light: Arc<LightQueryService>, notifications: Arc<Notifications>,
+        network: Network,
+    ) -> Self
+    {
+        Self::with_light(
+            executor,
+            consensus,
+            Some(light),
+            notifications,
+            network,
+        )
+    }
+
+    fn with_light(
+        executor: Executor, consensus: SharedConsensusGraph,
+        light: Option<Arc<LightQueryService>>,
+        notifications: Arc<Notifications>, network: Network,
+    ) -> Self
     {
         let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
         let epochs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
         let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+        let pending_txs_subscribers =
+            Arc::new(RwLock::new(Subscribers::default()));
+        let syncing_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+        let traces_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 
         let handler = Arc::new(ChainNotificationHandler {
             executor,
             consensus: consensus.clone(),
             data_man: consensus.get_data_manager().clone(),
+            light,
             heads_subscribers: heads_subscribers.clone(),
+            pending_txs_subscribers: pending_txs_subscribers.clone(),
+            syncing_subscribers: syncing_subscribers.clone(),
+            latest_sync_progress: RwLock::new(None),
+            last_verified_header_height: Mutex::new(None),
             network,
         });
 
         // --------- newHeads ---------
-        // subscribe to the `new_block_hashes` channel
-        let receiver = notifications.new_block_hashes.subscribe();
+        // loop asynchronously
+        let handler_clone = handler.clone();
+
+        let fut = match handler.light {
+            // light nodes only publish the headers they have verified, so
+            // subscribe to the `verified_header_heights` channel
+            Some(_) => {
+                let receiver =
+                    notifications.verified_header_heights.subscribe();
+
+                receiver
+                    .for_each(move |height| {
+                        handler_clone.notify_verified_headers(height);
+                    })
+                    .boxed()
+            }
+
+            // subscribe to the `new_block_hashes` channel
+            None => {
+                let receiver = notifications.new_block_hashes.subscribe();
+
+                receiver
+                    .for_each(move |hash| {
+                        handler_clone.notify_header(&hash);
+                    })
+                    .boxed()
+            }
+        };
+
+        // run futures@0.3 future on tokio@0.1 executor
+        handler.executor.spawn(fut.unit_error().boxed().compat());
+
+        // --------- newPendingTransactions ---------
+        // subscribe to the `new_pending_transactions` channel
+        let receiver = notifications.new_pending_transactions.subscribe();
+
+        // loop asynchronously
+        let handler_clone = handler.clone();
+
+        let fut = receiver.for_each(move |txs| {
+            handler_clone.notify_pending_transactions(&txs);
+        });
+
+        // run futures@0.3 future on tokio@0.1 executor
+        handler.executor.spawn(fut.unit_error().boxed().compat());
+
+        // --------- syncing ---------
+        // subscribe to the `sync_progress` channel
+        let receiver = notifications.sync_progress.subscribe();
 
         // loop asynchronously
         let handler_clone = handler.clone();
 
-        let fut = receiver.for_each(move |hash| {
-            handler_clone.notify_header(&hash);
+        let fut = receiver.for_each(move |progress| {
+            handler_clone.notify_sync_progress(progress);
         });
 
         // run futures@0.3 future on tokio@0.1 executor
@@ -89,7 +183,13 @@ impl PubSubClient {
             heads_subscribers,
             epochs_subscribers,
             logs_subscribers,
+            pending_txs_subscribers,
+            syncing_subscribers,
+            traces_subscribers,
             epochs_ordered: notifications.epochs_ordered.clone(),
+            verified_header_heights: notifications
+                .verified_header_heights
+                .clone(),
         }
     }
 
@@ -104,6 +204,13 @@ impl PubSubClient {
     fn start_epoch_loop(&self, id: SubscriberId, sub_epoch: SubscriptionEpoch) {
         trace!("start_epoch_loop({:?})", id);
 
+        // light nodes do not execute epochs
+        if sub_epoch == SubscriptionEpoch::LatestState
+            && self.handler.light.is_some()
+        {
+            return self.start_light_epoch_loop(id);
+        }
+
         // clone everything we use in our async loop
         let subscribers = self.epochs_subscribers.clone();
         let epochs_ordered = self.epochs_ordered.clone();
@@ -164,6 +271,11 @@ impl PubSubClient {
     fn start_logs_loop(&self, id: SubscriberId) {
         trace!("start_logs_loop({:?})", id);
 
+        // light nodes do not execute epochs
+        if self.handler.light.is_some() {
+            return self.start_light_logs_loop(id);
+        }
+
         // clone everything we use in our async loop
         let subscribers = self.logs_subscribers.clone();
         let epochs_ordered = self.epochs_ordered.clone();
@@ -218,6 +330,254 @@ impl PubSubClient {
         let fut = fut.unit_error().boxed().compat();
         self.handler.executor.spawn(fut);
     }
+
+    // Start an async loop that continuously receives epoch notifications and
+    // publishes the corresponding traces to subscriber `id`, keeping their
+    // original order. The loop terminates when subscriber `id` unsubscribes.
+    fn start_traces_loop(&self, id: SubscriberId) {
+        trace!("start_traces_loop({:?})", id);
+
+        // clone everything we use in our async loop
+        let subscribers = self.traces_subscribers.clone();
+        let epochs_ordered = self.epochs_ordered.clone();
+        let handler = self.handler.clone();
+
+        // subscribe to the `epochs_ordered` channel
+        let mut receiver = epochs_ordered.subscribe();
+
+        // use a queue to make sure we only process an epoch once it has been
+        // executed for sure
+        let mut queue = EpochQueue::<Vec<H256>>::with_capacity(
+            (DEFERRED_STATE_EPOCH_COUNT - 1) as usize,
+        );
+
+        // loop asynchronously
+        let fut = async move {
+            let mut last_epoch = 0;
+
+            while let Some(epoch) = receiver.recv().await {
+                trace!("traces_loop({:?}): {:?}", id, epoch);
+
+                // retrieve subscriber
+                let (sub, filter) = match subscribers.read().get(&id) {
+                    Some(sub) => sub.clone(),
+                    None => {
+                        // unsubscribed, terminate loop
+                        epochs_ordered.unsubscribe(receiver.id);
+                        return;
+                    }
+                };
+
+                let epoch = match queue.push(epoch) {
+                    None => continue,
+                    Some(e) => e,
+                };
+
+                // publish pivot chain reorg if necessary
+                if epoch.0 <= last_epoch {
+                    debug!("pivot chain reorg: {} -> {}", last_epoch, epoch.0);
+                    assert!(epoch.0 > 0, "Unexpected epoch number received.");
+                    handler.notify_revert(&sub, epoch.0 - 1).await;
+                }
+
+                last_epoch = epoch.0;
+
+                // publish matching traces
+                handler.notify_traces(&sub, &filter, epoch).await;
+            }
+        };
+
+        // run futures@0.3 future on tokio@0.1 executor
+        let fut = fut.unit_error().boxed().compat();
+        self.handler.executor.spawn(fut);
+    }
+
+    // Start an async loop that continuously receives the heights of headers
+    // verified by the light sync handler and publishes the epochs that become
+    // verifiable to subscriber `id`, keeping their original order. The loop
+    // terminates when subscriber `id` unsubscribes.
+    fn start_light_epoch_loop(&self, id: SubscriberId) {
+        trace!("start_light_epoch_loop({:?})", id);
+
+        // clone everything we use in our async loop
+        let subscribers = self.epochs_subscribers.clone();
+        let verified_header_heights = self.verified_header_heights.clone();
+        let handler = self.handler.clone();
+
+        // subscribe to the `verified_header_heights` channel
+        let mut receiver = verified_header_heights.subscribe();
+
+        // loop asynchronously
+        let fut = async move {
+            let mut last_height = None;
+
+            while let Some(height) = receiver.recv().await {
+                trace!("light_epoch_loop({:?}): {:?}", id, height);
+
+                // retrieve subscriber
+                let sub = match subscribers.read().get(&id) {
+                    Some(sub) => sub.clone(),
+                    None => {
+                        // unsubscribed, terminate loop
+                        verified_header_heights.unsubscribe(receiver.id);
+                        return;
+                    }
+                };
+
+                for epoch in newly_verified_epochs(&mut last_height, height) {
+                    let hashes = match handler.epoch_hashes(epoch) {
+                        Some(hashes) => hashes,
+                        None => continue,
+                    };
+
+                    // publish epochs
+                    handler.notify_epoch(sub.clone(), (epoch, hashes)).await;
+                }
+            }
+        };
+
+        // run futures@0.3 future on tokio@0.1 executor
+        let fut = fut.unit_error().boxed().compat();
+        self.handler.executor.spawn(fut);
+    }
+
+    // Start an async loop that continuously receives the heights of headers
+    // verified by the light sync handler and publishes the logs of the epochs
+    // that become verifiable to subscriber `id`, keeping their original order.
+    // The loop terminates when subscriber `id` unsubscribes.
+    fn start_light_logs_loop(&self, id: SubscriberId) {
+        trace!("start_light_logs_loop({:?})", id);
+
+        // maximum number of epochs whose logs are retrieved concurrently
+        const MAX_LIGHT_LOGS_EPOCHS: usize = 32;
+
+        // clone everything we use in our async loop
+        let subscribers = self.logs_subscribers.clone();
+        let verified_header_heights = self.verified_header_heights.clone();
+        let handler = self.handler.clone();
+
+        // subscribe to the `verified_header_heights` channel
+        let mut receiver = verified_header_heights.subscribe();
+
+        // loop asynchronously
+        let fut = async move {
+            let mut last_height = None;
+            let mut last_epoch = 0;
+
+            while let Some(height) = receiver.recv().await {
+                trace!("light_logs_loop({:?}): {:?}", id, height);
+
+                // retrieve subscriber
+                let (sub, filter) = match subscribers.read().get(&id) {
+                    Some(sub) => sub.clone(),
+                    None => {
+                        // unsubscribed, terminate loop
+                        verified_header_heights.unsubscribe(receiver.id);
+                        return;
+                    }
+                };
+
+                // take the heights verified while the previous round was
+                // being processed, so that they do not queue up
+                let mut epochs =
+                    newly_verified_epochs(&mut last_height, height);
+
+                while let Ok(height) = receiver.try_recv() {
+                    epochs.extend(newly_verified_epochs(
+                        &mut last_height,
+                        height,
+                    ));
+                }
+
+                // pivot chain reorg to publish before the next logs, if any
+                let mut revert_to: Option<u64> = None;
+
+                // retrieve the logs of up to `MAX_LIGHT_LOGS_EPOCHS` epochs
+                // concurrently, so that a large backlog does not flood peers
+                for chunk in epochs.chunks(MAX_LIGHT_LOGS_EPOCHS) {
+                    let logs = join_all(chunk.iter().map(|epoch| {
+                        handler
+                            .retrieve_light_epoch_logs(filter.clone(), *epoch)
+                    }))
+                    .await;
+
+                    for (epoch, logs) in zip(chunk.iter().cloned(), logs) {
+                        // publish pivot chain reorg if necessary
+                        if epoch <= last_epoch && epoch > 0 {
+                            debug!(
+                                "pivot chain reorg: {} -> {}",
+                                last_epoch, epoch
+                            );
+                            revert_to = Some(
+                                revert_to
+                                    .map_or(epoch - 1, |e| e.min(epoch - 1)),
+                            );
+                        }
+
+                        last_epoch = epoch;
+
+                        if let Some(e) = revert_to.take() {
+                            handler.notify_revert(&sub, e).await;
+                        }
+
+                        // publish matching logs
+                        let logs = match logs {
+                            Some(logs) => logs,
+                            None => {
+                                // do not skip the logs of `epoch` silently,
+                                // notify the subscriber and terminate loop
+                                let error = error_codes::subscription_closed(
+                                    format!(
+                                        "Unable to retrieve the logs of epoch {}",
+                                        epoch
+                                    ),
+                                );
+
+                                handler.notify_error(&sub, error).await;
+                                subscribers.write().remove(&id);
+                                verified_header_heights
+                                    .unsubscribe(receiver.id);
+                                return;
+                            }
+                        };
+
+                        handler.notify_light_logs(&sub, logs).await;
+                    }
+                }
+            }
+        };
+
+        // run futures@0.3 future on tokio@0.1 executor
+        let fut = fut.unit_error().boxed().compat();
+        self.handler.executor.spawn(fut);
+    }
+}
+
+// Return the heights verified since `last_height` after the light sync handler
+// verifies the header at `height`. The handler skips the heights of blamed
+// headers, and it verifies lower heights again after pivot chain reorgs.
+fn newly_verified_heights(
+    last_height: &mut Option<u64>, height: u64,
+) -> RangeInclusive<u64> {
+    let from = match *last_height {
+        Some(last) if last < height => last + 1,
+        _ => height,
+    };
+
+    *last_height = Some(height);
+    from..=height
+}
+
+// Return the epochs that can be verified after the light sync handler verifies
+// the header at `height`, i.e. the epochs whose state, receipts and blooms are
+// committed to by the headers verified since `last_height`.
+fn newly_verified_epochs(
+    last_height: &mut Option<u64>, height: u64,
+) -> Vec<u64> {
+    // each header commits to the epoch `DEFERRED_STATE_EPOCH_COUNT` below it
+    newly_verified_heights(last_height, height)
+        .filter_map(|h| h.checked_sub(DEFERRED_STATE_EPOCH_COUNT))
+        .collect()
 }
 
 /// PubSub notification handler.
@@ -225,7 +585,15 @@ pub struct ChainNotificationHandler {
     pub executor: Executor,
     consensus: SharedConsensusGraph,
     data_man: Arc<BlockDataManager>,
+    light: Option<Arc<LightQueryService>>,
     heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    pending_txs_subscribers:
+        Arc<RwLock<Subscribers<(Client, PendingTransactionFilter)>>>,
+    syncing_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    /// The last published sync progress, sent to new `syncing` subscribers.
+    latest_sync_progress: RwLock<Option<pubsub::SyncProgress>>,
+    /// The last header height verified by the light sync handler.
+    last_verified_header_height: Mutex<Option<u64>>,
     network: Network,
 }
 
@@ -249,6 +617,16 @@ impl ChainNotificationHandler {
         let _ = fut.compat().await;
     }
 
+    // notify `subscriber` about `error` asynchronously
+    async fn notify_error(&self, subscriber: &Client, error: Error) {
+        let fut = subscriber.notify(Err(error)).map(|_| ()).map_err(
+            |e| warn!(target: "rpc", "Unable to send notification: {}", e),
+        );
+
+        // convert futures01::Future into std::Future so that we can await
+        let _ = fut.compat().await;
+    }
+
     // notify each subscriber about header `hash` concurrently
     // NOTE: multiple calls to this method will result in concurrent
     // notifications, so the headers published might be reordered.
@@ -289,6 +667,88 @@ impl ChainNotificationHandler {
         }
     }
 
+    // notify each subscriber about the headers of the epochs whose pivot
+    // headers the light sync handler has verified up to `height`
+    fn notify_verified_headers(&self, height: u64) {
+        trace!("notify_verified_headers({:?})", height);
+
+        let heights = newly_verified_heights(
+            &mut *self.last_verified_header_height.lock(),
+            height,
+        );
+
+        // do not retrieve anything unnecessarily
+        if self.heads_subscribers.read().is_empty() {
+            return;
+        }
+
+        for epoch in heights {
+            for hash in self.epoch_hashes(epoch).unwrap_or_default() {
+                self.notify_header(&hash);
+            }
+        }
+    }
+
+    // notify each subscriber about the matching transactions in `txs`
+    // NOTE: as with headers, notifications of consecutive batches might be
+    // reordered.
+    fn notify_pending_transactions(&self, txs: &Vec<Arc<SignedTransaction>>) {
+        trace!("notify_pending_transactions({:?})", txs.len());
+
+        let subscribers = self.pending_txs_subscribers.read();
+
+        // do not convert anything unnecessarily
+        if subscribers.is_empty() {
+            return;
+        }
+
+        for (subscriber, filter) in subscribers.values() {
+            for tx in txs.iter().filter(|tx| filter.matches(tx)) {
+                let result = if filter.full_transactions {
+                    match RpcTransaction::from_signed(
+                        tx,
+                        None, /* maybe_packed_or_executed */
+                        self.network,
+                    ) {
+                        Ok(tx) => pubsub::Result::Transaction(tx),
+                        Err(e) => {
+                            error!(
+                                "Unexpected error while constructing RpcTransaction: {:?}",
+                                e
+                            );
+                            continue;
+                        }
+                    }
+                } else {
+                    pubsub::Result::TransactionHash(tx.hash())
+                };
+
+                Self::notify(&self.executor, subscriber, result);
+            }
+        }
+    }
+
+    // notify each subscriber about the sync progress
+    // NOTE: phase changes are infrequent, so they are not expected to be
+    // reordered in practice.
+    fn notify_sync_progress(&self, progress: SyncPhaseProgress) {
+        trace!("notify_sync_progress({:?})", progress);
+
+        // hold the lock while updating the latest progress, so that new
+        // subscribers get either this notification or the updated progress
+        let subscribers = self.syncing_subscribers.read();
+        let progress = pubsub::SyncProgress::from(progress);
+        *self.latest_sync_progress.write() = Some(progress.clone());
+
+        for subscriber in subscribers.values() {
+            Self::notify(
+                &self.executor,
+                subscriber,
+                pubsub::Result::SyncProgress(progress.clone()),
+            );
+        }
+    }
+
     async fn notify_epoch(&self, subscriber: Client, epoch: (u64, Vec<H256>)) {
         trace!("notify_epoch({:?})", epoch);
 
@@ -355,6 +815,79 @@ impl ChainNotificationHandler {
         }
     }
 
+    async fn notify_light_logs(
+        &self, subscriber: &Client, logs: Vec<LocalizedLogEntry>,
+    ) {
+        trace!("notify_light_logs({:?})", logs.len());
+
+        // send logs in order
+        for log in logs {
+            match RpcLog::try_from_localized(log, self.network) {
+                Ok(l) => {
+                    Self::notify_async(subscriber, pubsub::Result::Log(l)).await
+                }
+                Err(e) => {
+                    error!(
+                        "Unexpected error while constructing RpcLog: {:?}",
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    async fn notify_traces(
+        &self, subscriber: &Client, filter: &TraceFilter,
+        epoch: (u64, Vec<H256>),
+    )
+    {
+        trace!("notify_traces({:?})", epoch);
+
+        let (epoch_number, hashes) = epoch;
+        let pivot = hashes.last().cloned().expect("epoch should not be empty");
+
+        // make sure the epoch has been executed
+        self.wait_for_epoch(&pivot).await;
+
+        let traces = match self.consensus_graph().filter_epoch_traces(
+            filter,
+            epoch_number,
+            pivot,
+        ) {
+            Ok(traces) => traces,
+            Err(e) => {
+                warn!(
+                    "Unable to retrieve traces of epoch {}: {}",
+                    epoch_number, e
+                );
+                return;
+            }
+        };
+
+        // send traces in order
+        for trace in traces {
+            match RpcLocalizedTrace::from(trace, self.network) {
+                Ok(t) => {
+                    Self::notify_async(subscriber, pubsub::Result::Trace(t))
+                        .await
+                }
+                Err(e) => {
+                    error!(
+                        "Unexpected error while constructing RpcLocalizedTrace: {:?}",
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    fn consensus_graph(&self) -> &ConsensusGraph {
+        self.consensus
+            .as_any()
+            .downcast_ref::<ConsensusGraph>()
+            .expect("downcast should succeed")
+    }
+
     // attempt to retrieve block receipts from BlockDataManager
     // on failure, wait and retry a few times, then fail
     // NOTE: we do this because we might get epoch notifications
@@ -399,6 +932,64 @@ impl ChainNotificationHandler {
         let _ = self.retrieve_block_receipts(&pivot, &pivot).await;
     }
 
+    // attempt to retrieve and verify the matching logs of `epoch` from peers
+    // on failure, wait and retry a few times with increasing intervals, then
+    // fail
+    // NOTE: we do this because the roots of blamed headers might be received
+    // after their heights are verified
+    async fn retrieve_light_epoch_logs(
+        &self, filter: LogFilter, epoch: u64,
+    ) -> Option<Vec<LocalizedLogEntry>> {
+        const MAX_ATTEMPTS: u32 = 6;
+        const INITIAL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+        let light = self.light.as_ref().expect("light node");
+
+        // restrict the subscription filter to `epoch`
+        let filter = LogFilter {
+            from_epoch: EpochNumber::Number(epoch),
+            to_epoch: EpochNumber::Number(epoch),
+            block_hashes: None,
+            offset: None,
+            limit: None,
+            ..filter
+        };
+
+        let mut poll_interval = INITIAL_POLL_INTERVAL;
+
+        for _ in 1..MAX_ATTEMPTS {
+            match light.get_logs(filter.clone()).await {
+                Ok(logs) => return Some(logs),
+                Err(e) => {
+                    trace!("Cannot retrieve logs of epoch {}: {}", epoch, e);
+                    let _ = sleep(poll_interval).compat().await;
+                    poll_interval *= 2;
+                }
+            }
+        }
+
+        match light.get_logs(filter).await {
+            Ok(logs) => Some(logs),
+            Err(e) => {
+                warn!("Cannot retrieve logs of epoch {}: {}", epoch, e);
+                None
+            }
+        }
+    }
+
+    fn epoch_hashes(&self, epoch: u64) -> Option<Vec<H256>> {
+        match self
+            .consensus
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))
+        {
+            Ok(hashes) => Some(hashes),
+            Err(e) => {
+                warn!("Unable to retrieve epoch {}: {}", epoch, e);
+                None
+            }
+        }
+    }
+
     async fn retrieve_epoch_logs(
         &self, epoch: (u64, Vec<H256>),
     ) -> Option<Vec<LocalizedLogEntry>> {
@@ -466,6 +1057,9 @@ impl PubSub for PubSubClient {
         kind: pubsub::Kind, params: Option<pubsub::Params>,
     )
     {
+        // an empty filter (`{}`) is the same as no filter
+        let params = params.filter(|params| *params != pubsub::Params::None);
+
         let error = match (kind, params) {
             // --------- newHeads ---------
             (pubsub::Kind::NewHeads, None) => {
@@ -519,7 +1113,90 @@ impl PubSub for PubSubClient {
                 "logs",
                 "Expected filter parameter.",
             ),
-            _ => error_codes::unimplemented(None),
+            // --------- newPendingTransactions ---------
+            (pubsub::Kind::NewPendingTransactions, None) => {
+                self.pending_txs_subscribers
+                    .write()
+                    .push(subscriber, PendingTransactionFilter::default());
+                return;
+            }
+            (
+                pubsub::Kind::NewPendingTransactions,
+                Some(pubsub::Params::PendingTransactions(filter)),
+            ) => {
+                let network = self.handler.network;
+                match filter.addresses().try_for_each(|address| {
+                    check_rpc_address_network(Some(address.network), &network)
+                }) {
+                    Err(e) => {
+                        error_codes::invalid_params("newPendingTransactions", e)
+                    }
+                    Ok(()) => {
+                        self.pending_txs_subscribers
+                            .write()
+                            .push(subscriber, filter);
+                        return;
+                    }
+                }
+            }
+            (pubsub::Kind::NewPendingTransactions, _) => {
+                error_codes::invalid_params(
+                    "newPendingTransactions",
+                    "Expected pending transactions filter parameter.",
+                )
+            }
+            // --------- syncing ---------
+            (pubsub::Kind::Syncing, None) => {
+                let mut subscribers = self.syncing_subscribers.write();
+                let id = subscribers.push(subscriber);
+
+                // send the current progress, if any, when the subscription
+                // starts
+                let progress = self.handler.latest_sync_progress.read().clone();
+                if let (Some(sub), Some(progress)) =
+                    (subscribers.get(&id), progress)
+                {
+                    ChainNotificationHandler::notify(
+                        &self.handler.executor,
+                        sub,
+                        pubsub::Result::SyncProgress(progress),
+                    );
+                }
+                return;
+            }
+            (pubsub::Kind::Syncing, _) => error_codes::invalid_params(
+                "syncing",
+                "Expected no parameters.",
+            ),
+            // --------- traces ---------
+            (pubsub::Kind::Traces, None) => {
+                let filter = pubsub::TraceSubscriptionFilter::default()
+                    .into_primitive()
+                    .expect("default trace filter is valid");
+                let id =
+                    self.traces_subscribers.write().push(subscriber, filter);
+
+                self.start_traces_loop(id);
+                return;
+            }
+            (pubsub::Kind::Traces, Some(pubsub::Params::Traces(filter))) => {
+                match filter.into_primitive() {
+                    Err(e) => e,
+                    Ok(filter) => {
+                        let id = self
+                            .traces_subscribers
+                            .write()
+                            .push(subscriber, filter);
+
+                        self.start_traces_loop(id);
+                        return;
+                    }
+                }
+            }
+            (pubsub::Kind::Traces, _) => error_codes::invalid_params(
+                "traces",
+                "Expected traces filter parameter.",
+            ),
         };
 
         let _ = subscriber.reject(error);
@@ -531,7 +1208,10 @@ impl PubSub for PubSubClient {
         let res0 = self.heads_subscribers.write().remove(&id).is_some();
         let res1 = self.epochs_subscribers.write().remove(&id).is_some();
         let res2 = self.logs_subscribers.write().remove(&id).is_some();
+        let res3 = self.pending_txs_subscribers.write().remove(&id).is_some();
+        let res4 = self.syncing_subscribers.write().remove(&id).is_some();
+        let res5 = self.traces_subscribers.write().remove(&id).is_some();
 
-        Ok(res0 || res1 || res2)
+        Ok(res0 || res1 || res2 || res3 || res4 || res5)
     }
 }
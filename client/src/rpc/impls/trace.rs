@@ -6,10 +6,15 @@ use super::super::types::LocalizedBlockTrace;
 use crate::{
     common::delegate_convert::into_jsonrpc_result,
     rpc::{
+        error_codes::call_execution_error,
         traits::trace::Trace,
         types::{
-            Action as RpcAction, LocalizedTrace as RpcLocalizedTrace,
-            LocalizedTrace, TraceFilter as RpcTraceFilter, TraceFilter,
+            call_request::rpc_call_request_network,
+            errors::check_rpc_address_network, sign_call, Action as RpcAction,
+            Bytes, CallRequest, EpochNumber,
+            LocalizedTrace as RpcLocalizedTrace, LocalizedTrace,
+            TraceFilter as RpcTraceFilter, TraceFilter, TraceFilterResults,
+            TraceResults, TraceType,
         },
         RpcResult,
     },
@@ -17,12 +22,19 @@ use crate::{
 use cfx_addr::Network;
 use cfx_types::H256;
 use cfxcore::{
-    block_data_manager::DataVersionTuple, trace::trace::ExecTrace,
-    BlockDataManager, ConsensusGraph, SharedConsensusGraph,
+    block_data_manager::DataVersionTuple,
+    executive::{Executed, ExecutionOutcome},
+    rpc_errors::invalid_params_check,
+    trace::{trace::ExecTrace, ExecutiveTracer},
+    BlockDataManager, ConsensusGraph, ConsensusGraphTrait,
+    SharedConsensusGraph,
 };
 use jsonrpc_core::Result as JsonRpcResult;
 use std::sync::Arc;
 
+/// The maximum number of traces returned in a page by `trace_filter`.
+const MAX_TRACE_FILTER_PAGE_SIZE: usize = 10000;
+
 pub struct TraceHandler {
     data_man: Arc<BlockDataManager>,
     consensus: SharedConsensusGraph,
@@ -90,21 +102,38 @@ impl TraceHandler {
 
     fn filter_traces_impl(
         &self, rpc_filter: RpcTraceFilter,
-    ) -> RpcResult<Option<Vec<RpcLocalizedTrace>>> {
-        let filter = rpc_filter.into_primitive()?;
+    ) -> RpcResult<Option<TraceFilterResults>> {
         let consensus_graph = self.consensus_graph();
+        let to_rpc_trace = |trace| {
+            RpcLocalizedTrace::from(trace, self.network)
+                .expect("Local address conversion should succeed")
+        };
+
+        // A page is returned even if it is empty.
+        if let Some((cursor, page_size)) =
+            rpc_filter.page(MAX_TRACE_FILTER_PAGE_SIZE)?
+        {
+            let (traces, next) = consensus_graph.filter_traces_page(
+                rpc_filter.into_primitive()?,
+                cursor,
+                page_size,
+            )?;
+            return Ok(Some(TraceFilterResults::Page {
+                traces: traces.into_iter().map(to_rpc_trace).collect(),
+                cursor: next.map(|cursor| Bytes::new(cursor.to_bytes())),
+            }));
+        }
+
+        let filter = rpc_filter.into_primitive()?;
         let traces: Vec<_> = consensus_graph
             .filter_traces(filter)?
             .into_iter()
-            .map(|trace| {
-                RpcLocalizedTrace::from(trace, self.network)
-                    .expect("Local address conversion should succeed")
-            })
+            .map(to_rpc_trace)
             .collect();
         if traces.is_empty() {
             Ok(None)
         } else {
-            Ok(Some(traces))
+            Ok(Some(TraceFilterResults::Traces(traces)))
         }
     }
 
@@ -151,6 +180,82 @@ impl TraceHandler {
                     })
             }))
     }
+
+    fn call_impl(
+        &self, request: CallRequest, trace_types: Vec<TraceType>,
+        epoch: Option<EpochNumber>,
+    ) -> RpcResult<TraceResults>
+    {
+        let rpc_request_network = invalid_params_check(
+            "request",
+            rpc_call_request_network(
+                request.from.as_ref(),
+                request.to.as_ref(),
+            ),
+        )?;
+        invalid_params_check(
+            "request",
+            check_rpc_address_network(rpc_request_network, &self.network),
+        )?;
+
+        let consensus_graph = self.consensus_graph();
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState);
+        let signed_tx = sign_call(
+            consensus_graph.best_epoch_number(),
+            consensus_graph.best_chain_id(),
+            request,
+        )?;
+        trace!("trace call tx {:?}", signed_tx);
+        let (outcome, state_diff) = consensus_graph
+            .call_virtual_with_state_diff(&signed_tx, epoch.into())?;
+
+        Ok(TraceResults::try_from(
+            Self::executed(outcome)?,
+            Some(state_diff),
+            &trace_types,
+            self.network,
+        )?)
+    }
+
+    fn replay_transaction_impl(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> RpcResult<TraceResults> {
+        let (outcome, state_diff) = self.consensus_graph().replay_transaction(
+            &tx_hash,
+            ExecutiveTracer::default(),
+            trace_types.contains(&TraceType::StateDiff),
+        )?;
+
+        let mut results = TraceResults::try_from(
+            Self::executed(outcome)?,
+            state_diff,
+            &trace_types,
+            self.network,
+        )?;
+        results.transaction_hash = Some(tx_hash);
+        Ok(results)
+    }
+
+    fn executed(outcome: ExecutionOutcome) -> RpcResult<Executed> {
+        match outcome {
+            ExecutionOutcome::Finished(executed)
+            | ExecutionOutcome::ExecutionErrorBumpNonce(_, executed) => {
+                Ok(executed)
+            }
+            ExecutionOutcome::NotExecutedDrop(e) => {
+                bail!(call_execution_error(
+                    "Transaction can not be executed".into(),
+                    format! {"{:?}", e}.into_bytes()
+                ))
+            }
+            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+                bail!(call_execution_error(
+                    "Transaction can not be executed".into(),
+                    format! {"{:?}", e}.into_bytes()
+                ))
+            }
+        }
+    }
 }
 
 impl Trace for TraceHandler {
@@ -162,7 +267,7 @@ impl Trace for TraceHandler {
 
     fn filter_traces(
         &self, filter: TraceFilter,
-    ) -> JsonRpcResult<Option<Vec<LocalizedTrace>>> {
+    ) -> JsonRpcResult<Option<TraceFilterResults>> {
         into_jsonrpc_result(self.filter_traces_impl(filter))
     }
 
@@ -171,4 +276,18 @@ impl Trace for TraceHandler {
     ) -> JsonRpcResult<Option<Vec<LocalizedTrace>>> {
         into_jsonrpc_result(self.transaction_trace_impl(&tx_hash))
     }
+
+    fn call(
+        &self, request: CallRequest, trace_types: Vec<TraceType>,
+        epoch: Option<EpochNumber>,
+    ) -> JsonRpcResult<TraceResults>
+    {
+        into_jsonrpc_result(self.call_impl(request, trace_types, epoch))
+    }
+
+    fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> JsonRpcResult<TraceResults> {
+        into_jsonrpc_result(self.replay_transaction_impl(tx_hash, trace_types))
+    }
 }
@@ -18,7 +18,7 @@
 use super::types::Origin;
 use jsonrpc_core;
 use jsonrpc_pubsub::{PubSubMetadata, Session};
-use std::sync::Arc;
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 /// RPC methods metadata.
 #[derive(Clone, Default, Debug)]
@@ -27,6 +27,11 @@ pub struct Metadata {
     pub origin: Origin,
     /// Request PubSub Session
     pub session: Option<Arc<Session>>,
+    /// Request headers with lower-case names. WebSocket requests only have
+    /// the `sec-websocket-protocol` header of the handshake.
+    pub headers: HashMap<String, String>,
+    /// Request peer address, if the transport provides it.
+    pub peer_addr: Option<SocketAddr>,
 }
 
 impl jsonrpc_core::Metadata for Metadata {}
@@ -0,0 +1,479 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! The OpenRPC document served by `rpc.discover`.
+//!
+//! The JSON schemas of the RPC types are derived from their serde attributes
+//! with `#[derive(RpcSchema)]`, and the method descriptions are generated
+//! from the RPC traits with `#[openrpc]`, so the document follows the code.
+//! The types defined in other crates implement `RpcSchema` here.
+
+use super::{
+    rpc_apis::Api,
+    traits::{cfx, cfx_filter, debug, eth, pubsub, test, trace},
+};
+use cfx_types::{Bloom, H160, H256, H512, H520, H64, U128, U256, U64};
+use cfxcore::{
+    trace::trace::Outcome,
+    transaction_pool::{PendingReason, SkipReason, TransactionStatus},
+    vm::CallType,
+};
+use clap::crate_version;
+use jsonrpc_pubsub::SubscriptionId;
+use primitives::{DepositInfo, StateRoot, StorageRoot, VoteStakeInfo};
+pub use rpc_schema_derive::{openrpc, RpcSchema};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    net::SocketAddr,
+};
+
+/// The OpenRPC specification version of the document.
+const OPENRPC_VERSION: &str = "1.2.6";
+
+/// The APIs in the order they are listed in the document.
+const APIS: [(Api, &str); 6] = [
+    (Api::Cfx, "cfx"),
+    (Api::Debug, "debug"),
+    (Api::Eth, "eth"),
+    (Api::Pubsub, "pubsub"),
+    (Api::Test, "test"),
+    (Api::Trace, "trace"),
+];
+
+/// A type whose JSON representation can be described with a JSON schema.
+pub trait RpcSchema {
+    /// Returns the JSON schema of the type. The schemas of the named types
+    /// are added to `components` and referenced.
+    fn schema(components: &mut SchemaComponents) -> Value;
+}
+
+/// The named schemas in the `components` of an OpenRPC document.
+#[derive(Default)]
+pub struct SchemaComponents {
+    schemas: BTreeMap<String, Value>,
+}
+
+impl SchemaComponents {
+    /// Returns a reference to the schema `name`, and adds the schema built by
+    /// `build` if it is not added yet.
+    pub fn reference<F>(&mut self, name: &str, build: F) -> Value
+    where F: FnOnce(&mut Self) -> Value {
+        if !self.schemas.contains_key(name) {
+            // Add a placeholder first so that recursive types terminate.
+            self.schemas.insert(name.to_string(), Value::Null);
+            let schema = build(self);
+            self.schemas.insert(name.to_string(), schema);
+        }
+        json!({ "$ref": format!("#/components/schemas/{}", name) })
+    }
+}
+
+/// A property of an object schema.
+pub struct Field {
+    pub name: &'static str,
+    pub schema: Value,
+    pub required: bool,
+    pub description: Option<&'static str>,
+}
+
+/// A parameter of an RPC method.
+pub struct Param {
+    pub name: &'static str,
+    pub schema: Value,
+    pub required: bool,
+}
+
+/// Returns the OpenRPC document of the methods of `apis`, except the methods
+/// in `unsupported`.
+pub fn document(apis: &HashSet<Api>, unsupported: &[&str]) -> Value {
+    let mut components = SchemaComponents::default();
+    let mut methods = Vec::new();
+    let mut enabled_apis = Vec::new();
+    for (api, name) in APIS.iter() {
+        if !apis.contains(api) {
+            continue;
+        }
+        enabled_apis.push(*name);
+        methods.extend(api_methods(api, &mut components));
+    }
+    methods.retain(|method| {
+        !unsupported.contains(&method["name"].as_str().unwrap_or_default())
+    });
+    methods.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "Conflux JSON-RPC",
+            "version": crate_version!(),
+            "x-enabled-apis": enabled_apis,
+        },
+        "methods": methods,
+        "components": { "schemas": components.schemas },
+    })
+}
+
+fn api_methods(api: &Api, components: &mut SchemaComponents) -> Vec<Value> {
+    match api {
+        Api::Cfx => {
+            let mut methods = cfx::method_schemas(components);
+            methods.extend(cfx_filter::method_schemas(components));
+            methods
+        }
+        Api::Debug => debug::method_schemas(components),
+        Api::Eth => eth::method_schemas(components),
+        Api::Pubsub => pubsub::method_schemas(components),
+        Api::Test => test::method_schemas(components),
+        Api::Trace => trace::method_schemas(components),
+    }
+}
+
+pub fn method(
+    name: &str, summary: &str, params: Vec<Param>, result: Value,
+) -> Value {
+    let params: Vec<Value> = params
+        .into_iter()
+        .map(|param| {
+            json!({
+                "name": param.name,
+                "required": param.required,
+                "schema": param.schema,
+            })
+        })
+        .collect();
+    json!({
+        "name": name,
+        "summary": summary,
+        "params": params,
+        "result": { "name": "result", "schema": result },
+    })
+}
+
+pub fn object(fields: Vec<Field>, description: Option<&str>) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let mut schema = field.schema;
+        if let (Some(description), Some(schema)) =
+            (field.description, schema.as_object_mut())
+        {
+            schema.insert("description".into(), description.into());
+        }
+        properties.insert(field.name.into(), schema);
+        if field.required {
+            required.push(field.name);
+        }
+    }
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    });
+    if let Some(description) = description {
+        schema["description"] = description.into();
+    }
+    schema
+}
+
+pub fn one_of(schemas: Vec<Value>) -> Value { json!({ "oneOf": schemas }) }
+
+pub fn string_enum(values: Vec<&str>) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// The schema of an externally tagged enum variant.
+pub fn tagged(name: &str, schema: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { name: schema },
+        "required": [name],
+    })
+}
+
+pub fn tuple(items: Vec<Value>) -> Value {
+    json!({
+        "type": "array",
+        "items": items,
+        "minItems": items.len(),
+        "maxItems": items.len(),
+    })
+}
+
+pub fn hex_string(pattern: &str, description: &str) -> Value {
+    json!({
+        "type": "string",
+        "pattern": pattern,
+        "description": description,
+    })
+}
+
+/// The schema of a hex encoded unsigned integer.
+pub fn quantity() -> Value {
+    hex_string("^0x(0|[1-9a-f][0-9a-f]*)$", "hex encoded unsigned integer")
+}
+
+fn fixed_bytes(len: usize) -> Value {
+    hex_string(
+        &format!("^0x[0-9a-f]{{{}}}$", len * 2),
+        &format!("hex encoded {} bytes", len),
+    )
+}
+
+macro_rules! impl_rpc_schema {
+    ($($ty:ty => $schema:expr;)*) => {
+        $(
+            impl RpcSchema for $ty {
+                fn schema(_: &mut SchemaComponents) -> Value { $schema }
+            }
+        )*
+    };
+}
+
+impl_rpc_schema! {
+    () => json!({ "type": "null" });
+    bool => json!({ "type": "boolean" });
+    String => json!({ "type": "string" });
+    &'static str => json!({ "type": "string" });
+    u8 => json!({ "type": "integer", "minimum": 0, "maximum": 255 });
+    u32 => json!({ "type": "integer", "minimum": 0 });
+    u64 => json!({ "type": "integer", "minimum": 0 });
+    usize => json!({ "type": "integer", "minimum": 0 });
+    i32 => json!({ "type": "integer" });
+    f64 => json!({ "type": "number" });
+    Value => json!({});
+    SocketAddr => json!({ "type": "string", "description": "IP:port" });
+    H64 => fixed_bytes(8);
+    H160 => fixed_bytes(20);
+    H256 => fixed_bytes(32);
+    H512 => fixed_bytes(64);
+    H520 => fixed_bytes(65);
+    Bloom => fixed_bytes(256);
+    U64 => quantity();
+    U128 => quantity();
+    U256 => quantity();
+    SubscriptionId => one_of(vec![
+        json!({ "type": "string" }),
+        json!({ "type": "integer", "minimum": 0 }),
+    ]);
+}
+
+impl<T: RpcSchema> RpcSchema for Option<T> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        one_of(vec![T::schema(components), json!({ "type": "null" })])
+    }
+}
+
+impl<T: RpcSchema> RpcSchema for Vec<T> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({ "type": "array", "items": T::schema(components) })
+    }
+}
+
+impl<K, V: RpcSchema> RpcSchema for HashMap<K, V> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({
+            "type": "object",
+            "additionalProperties": V::schema(components),
+        })
+    }
+}
+
+impl<K, V: RpcSchema> RpcSchema for BTreeMap<K, V> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({
+            "type": "object",
+            "additionalProperties": V::schema(components),
+        })
+    }
+}
+
+impl<A: RpcSchema, B: RpcSchema> RpcSchema for (A, B) {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        tuple(vec![A::schema(components), B::schema(components)])
+    }
+}
+
+impl RpcSchema for DepositInfo {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("DepositInfo", |components| {
+            object(
+                vec![
+                    Field {
+                        name: "amount",
+                        schema: U256::schema(components),
+                        required: true,
+                        description: None,
+                    },
+                    Field {
+                        name: "depositTime",
+                        schema: u64::schema(components),
+                        required: true,
+                        description: Some("the number of past blocks"),
+                    },
+                    Field {
+                        name: "accumulatedInterestRate",
+                        schema: U256::schema(components),
+                        required: true,
+                        description: None,
+                    },
+                ],
+                None,
+            )
+        })
+    }
+}
+
+impl RpcSchema for VoteStakeInfo {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("VoteStakeInfo", |components| {
+            object(
+                vec![
+                    Field {
+                        name: "amount",
+                        schema: U256::schema(components),
+                        required: true,
+                        description: None,
+                    },
+                    Field {
+                        name: "unlockBlockNumber",
+                        schema: u64::schema(components),
+                        required: true,
+                        description: None,
+                    },
+                ],
+                None,
+            )
+        })
+    }
+}
+
+impl RpcSchema for StateRoot {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("StateRoot", |components| {
+            let field = |name, components: &mut SchemaComponents| Field {
+                name,
+                schema: H256::schema(components),
+                required: true,
+                description: None,
+            };
+            object(
+                vec![
+                    field("snapshotRoot", components),
+                    field("intermediateDeltaRoot", components),
+                    field("deltaRoot", components),
+                ],
+                None,
+            )
+        })
+    }
+}
+
+impl RpcSchema for StorageRoot {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("StorageRoot", |components| {
+            // `MptValue` is serialized as null, a hash or "TOMBSTONE".
+            let mpt_value = one_of(vec![
+                Option::<H256>::schema(components),
+                string_enum(vec!["TOMBSTONE"]),
+            ]);
+            object(
+                vec![
+                    Field {
+                        name: "delta",
+                        schema: mpt_value.clone(),
+                        required: true,
+                        description: None,
+                    },
+                    Field {
+                        name: "intermediate",
+                        schema: mpt_value,
+                        required: true,
+                        description: None,
+                    },
+                    Field {
+                        name: "snapshot",
+                        schema: Option::<H256>::schema(components),
+                        required: true,
+                        description: None,
+                    },
+                ],
+                None,
+            )
+        })
+    }
+}
+
+impl RpcSchema for CallType {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        string_enum(vec![
+            "none",
+            "call",
+            "callcode",
+            "delegatecall",
+            "staticcall",
+        ])
+    }
+}
+
+impl RpcSchema for Outcome {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        string_enum(vec!["success", "reverted", "fail"])
+    }
+}
+
+impl RpcSchema for TransactionStatus {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        one_of(vec![
+            string_enum(vec!["packed", "ready"]),
+            tagged("pending", PendingReason::schema(components)),
+        ])
+    }
+}
+
+impl RpcSchema for PendingReason {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        string_enum(vec!["futureNonce", "notEnoughCash", "oldNonce"])
+    }
+}
+
+impl RpcSchema for SkipReason {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        string_enum(vec![
+            "invalidNonce",
+            "notEnoughCash",
+            "epochHeightOutOfBound",
+            "invalidRecipientAddress",
+        ])
+    }
+}
+
+impl RpcSchema for network::UpdateNodeOperation {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        string_enum(vec!["Failure", "Demotion", "Remove"])
+    }
+}
+
+/// The network types only used in the debug and test APIs are described
+/// as plain objects.
+macro_rules! impl_object_rpc_schema {
+    ($($ty:ty => $name:expr;)*) => {
+        $(
+            impl RpcSchema for $ty {
+                fn schema(components: &mut SchemaComponents) -> Value {
+                    components.reference($name, |_| {
+                        json!({ "type": "object" })
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_object_rpc_schema! {
+    network::node_table::Node => "Node";
+    network::throttling::Service => "ThrottlingService";
+    network::SessionDetails => "SessionDetails";
+    network::PeerInfo => "PeerInfo";
+}
@@ -8,6 +8,7 @@ use std::{collections::HashSet, str::FromStr};
 pub enum Api {
     Cfx,
     Debug,
+    Eth,
     Pubsub,
     Test,
     Trace,
@@ -21,6 +22,7 @@ impl FromStr for Api {
         match s {
             "cfx" => Ok(Cfx),
             "debug" => Ok(Debug),
+            "eth" => Ok(Eth),
             "pubsub" => Ok(Pubsub),
             "test" => Ok(Test),
             "trace" => Ok(Trace),
@@ -40,12 +42,17 @@ impl ApiSet {
     pub fn list_apis(&self) -> HashSet<Api> {
         match *self {
             ApiSet::List(ref apis) => apis.clone(),
-            ApiSet::All => {
-                [Api::Cfx, Api::Debug, Api::Pubsub, Api::Test, Api::Trace]
-                    .iter()
-                    .cloned()
-                    .collect()
-            }
+            ApiSet::All => [
+                Api::Cfx,
+                Api::Debug,
+                Api::Eth,
+                Api::Pubsub,
+                Api::Test,
+                Api::Trace,
+            ]
+            .iter()
+            .cloned()
+            .collect(),
             ApiSet::Safe => [Api::Cfx, Api::Pubsub].iter().cloned().collect(),
         }
     }
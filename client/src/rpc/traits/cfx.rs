@@ -3,14 +3,19 @@
 // See http://www.gnu.org/licenses/
 
 use super::super::types::{
-    Account as RpcAccount, AccountPendingInfo, Block, Bytes, CallRequest,
-    CheckBalanceAgainstTransactionResponse, EpochNumber,
-    EstimateGasAndCollateralResponse, Log as RpcLog, LogFilter as RpcFilter,
-    Receipt as RpcReceipt, RewardInfo as RpcRewardInfo, SponsorInfo,
-    Status as RpcStatus, TokenSupplyInfo, Transaction,
+    Account as RpcAccount, AccountPendingInfo, AccountProof, Block, Bytes,
+    CallRequest, CfxLogs, CheckBalanceAgainstTransactionResponse, EpochNumber,
+    EpochSummary, EstimateGasAndCollateralResponse, FeeHistory,
+    LogFilter as RpcFilter, Receipt as RpcReceipt, RewardInfo as RpcRewardInfo,
+    SponsorInfo, StateOverride, Status as RpcStatus, TokenSupplyInfo,
+    Transaction, TxStatus, VirtualCallResult,
 };
-use crate::rpc::types::{
-    AccountPendingTransactions, BlockHashOrEpochNumber, RpcAddress,
+use crate::rpc::{
+    openrpc::openrpc,
+    types::{
+        AccountPendingTransactions, AddressTransactions,
+        BlockHashOrEpochNumber, RpcAddress,
+    },
 };
 use cfx_types::{H256, U256, U64};
 use jsonrpc_core::{BoxFuture, Result as JsonRpcResult};
@@ -18,6 +23,7 @@ use jsonrpc_derive::rpc;
 use primitives::{DepositInfo, StorageRoot, VoteStakeInfo};
 
 /// Cfx rpc interface.
+#[openrpc]
 #[rpc(server)]
 pub trait Cfx {
     //        /// Returns protocol version encoded as a string (quotes are
@@ -40,6 +46,27 @@ pub trait Cfx {
     #[rpc(name = "cfx_gasPrice")]
     fn gas_price(&self) -> BoxFuture<U256>;
 
+    /// Returns the gas used ratio of each epoch in a range, and the requested
+    /// percentiles of the gas prices of the transactions in each epoch.
+    #[rpc(name = "cfx_feeHistory")]
+    fn fee_history(
+        &self, epoch_count: U64, newest_epoch: EpochNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> BoxFuture<FeeHistory>;
+
+    /// Returns the aggregates of the execution results of an epoch.
+    #[rpc(name = "cfx_getEpochSummary")]
+    fn epoch_summary(
+        &self, epoch_number: EpochNumber,
+    ) -> BoxFuture<EpochSummary>;
+
+    /// Returns the aggregates of the execution results of each epoch in a
+    /// range, at most 1000 epochs.
+    #[rpc(name = "cfx_getEpochSummaries")]
+    fn epoch_summaries(
+        &self, from_epoch: EpochNumber, to_epoch: EpochNumber,
+    ) -> BoxFuture<Vec<EpochSummary>>;
+
     /// Returns highest epoch number.
     #[rpc(name = "cfx_epochNumber")]
     fn epoch_number(
@@ -105,6 +132,14 @@ pub trait Cfx {
         &self, address: RpcAddress, epoch_num: Option<EpochNumber>,
     ) -> BoxFuture<Option<StorageRoot>>;
 
+    /// Returns the account and storage entries of the given address, together
+    /// with their Merkle proofs against the state root of the given epoch.
+    #[rpc(name = "cfx_getProof")]
+    fn get_proof(
+        &self, address: RpcAddress, storage_keys: Vec<H256>,
+        epoch_number: Option<EpochNumber>,
+    ) -> BoxFuture<AccountProof>;
+
     /// Returns block with given hash.
     #[rpc(name = "cfx_getBlockByHash")]
     fn block_by_hash(
@@ -152,15 +187,26 @@ pub trait Cfx {
     //        #[rpc(name = "cfx_submitTransaction")]
     //        fn submit_transaction(&self, Bytes) -> JsonRpcResult<H256>;
 
-    /// Call contract, returning the output data.
+    /// Call contract, returning the output data. The optional state override
+    /// is applied on top of the state of the epoch before the call.
     #[rpc(name = "cfx_call")]
     fn call(
         &self, tx: CallRequest, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<Bytes>;
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<Bytes>;
 
-    /// Returns logs matching the filter provided.
+    /// Execute the calls one after another on the state of the epoch, each
+    /// call sees the effects of the previous ones. Nothing is committed.
+    #[rpc(name = "cfx_callMany")]
+    fn call_many(
+        &self, requests: Vec<CallRequest>, epoch_number: Option<EpochNumber>,
+        include_trace: Option<bool>,
+    ) -> JsonRpcResult<Vec<VirtualCallResult>>;
+
+    /// Returns logs matching the filter provided. If the filter asks for
+    /// pagination, returns a page of logs and the cursor of the next page.
     #[rpc(name = "cfx_getLogs")]
-    fn get_logs(&self, filter: RpcFilter) -> BoxFuture<Vec<RpcLog>>;
+    fn get_logs(&self, filter: RpcFilter) -> BoxFuture<CfxLogs>;
 
     /// Get transaction by its hash.
     #[rpc(name = "cfx_getTransactionByHash")]
@@ -168,6 +214,11 @@ pub trait Cfx {
         &self, tx_hash: H256,
     ) -> BoxFuture<Option<Transaction>>;
 
+    /// Returns the lifecycle status of a transaction: whether it is in the
+    /// pool, packed, executed, skipped, or garbage-collected.
+    #[rpc(name = "cfx_getTransactionStatus")]
+    fn transaction_status(&self, tx_hash: H256) -> BoxFuture<TxStatus>;
+
     /// Get transaction pending info by account address
     #[rpc(name = "cfx_getAccountPendingInfo")]
     fn account_pending_info(
@@ -181,11 +232,21 @@ pub trait Cfx {
         maybe_limit: Option<U64>,
     ) -> BoxFuture<AccountPendingTransactions>;
 
-    /// Return estimated gas and collateral usage.
+    /// Returns the executed transactions sent or received by an address, from
+    /// the newest one. Requires `persist_address_tx_index` to be enabled.
+    #[rpc(name = "cfx_getTransactionsByAddress")]
+    fn transactions_by_address(
+        &self, address: RpcAddress, maybe_skip: Option<U64>,
+        maybe_limit: Option<U64>,
+    ) -> BoxFuture<AddressTransactions>;
+
+    /// Return estimated gas and collateral usage. The optional state override
+    /// is applied on top of the state of the epoch before the estimation.
     #[rpc(name = "cfx_estimateGasAndCollateral")]
     fn estimate_gas_and_collateral(
         &self, request: CallRequest, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+        state_override: Option<StateOverride>,
+    ) -> BoxFuture<EstimateGasAndCollateralResponse>;
 
     /// Check if user balance is enough for the transaction.
     #[rpc(name = "cfx_checkBalanceAgainstTransaction")]
@@ -236,11 +297,13 @@ pub trait Cfx {
     #[rpc(name = "cfx_getStatus")]
     fn get_status(&self) -> JsonRpcResult<RpcStatus>;
 
-    /// Returns block reward information in an epoch
+    /// Returns block reward information in an epoch. On light nodes, the
+    /// rewards are retrieved from a full node peer and are unverified, since
+    /// they are not committed to in block headers.
     #[rpc(name = "cfx_getBlockRewardInfo")]
     fn get_block_reward_info(
         &self, num: EpochNumber,
-    ) -> JsonRpcResult<Vec<RpcRewardInfo>>;
+    ) -> BoxFuture<Vec<RpcRewardInfo>>;
 
     /// Return the client version as a string
     #[rpc(name = "cfx_clientVersion")]
@@ -250,7 +313,7 @@ pub trait Cfx {
     #[rpc(name = "cfx_getSupplyInfo")]
     fn get_supply_info(
         &self, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<TokenSupplyInfo>;
+    ) -> BoxFuture<TokenSupplyInfo>;
 
     //        /// Returns transaction at given block hash and index.
     //        #[rpc(name = "cfx_getTransactionByBlockHashAndIndex")]
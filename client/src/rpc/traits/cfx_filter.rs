@@ -0,0 +1,43 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::{
+    openrpc::openrpc,
+    types::{CfxFilterChanges, Log, LogFilter},
+};
+use cfx_types::U256;
+use jsonrpc_core::Result as JsonRpcResult;
+use jsonrpc_derive::rpc;
+
+/// Cfx filters rpc interface.
+#[openrpc]
+#[rpc(server)]
+pub trait CfxFilter {
+    /// Returns id of new log filter. Only logs in epochs executed after the
+    /// filter is created are reported by `cfx_getFilterChanges`.
+    #[rpc(name = "cfx_newFilter")]
+    fn new_filter(&self, filter: LogFilter) -> JsonRpcResult<U256>;
+
+    /// Returns id of new block filter.
+    #[rpc(name = "cfx_newBlockFilter")]
+    fn new_block_filter(&self) -> JsonRpcResult<U256>;
+
+    /// Returns id of new pending transaction filter.
+    #[rpc(name = "cfx_newPendingTransactionFilter")]
+    fn new_pending_transaction_filter(&self) -> JsonRpcResult<U256>;
+
+    /// Returns filter changes since last poll.
+    #[rpc(name = "cfx_getFilterChanges")]
+    fn filter_changes(
+        &self, filter_id: U256,
+    ) -> JsonRpcResult<CfxFilterChanges>;
+
+    /// Returns all logs matching given filter (in a range 'from' - 'to').
+    #[rpc(name = "cfx_getFilterLogs")]
+    fn filter_logs(&self, filter_id: U256) -> JsonRpcResult<Vec<Log>>;
+
+    /// Uninstalls filter.
+    #[rpc(name = "cfx_uninstallFilter")]
+    fn uninstall_filter(&self, filter_id: U256) -> JsonRpcResult<bool>;
+}
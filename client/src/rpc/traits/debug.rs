@@ -4,10 +4,11 @@
 
 use super::super::types::{
     BlockHashOrEpochNumber, Bytes as RpcBytes, ConsensusGraphStates,
-    Receipt as RpcReceipt, RpcAddress, SyncGraphStates,
-    Transaction as RpcTransaction, TxPoolPendingInfo, TxWithPoolInfo,
+    Receipt as RpcReceipt, RpcAddress, StructLogOptions, SyncGraphStates,
+    Transaction as RpcTransaction, TransactionStructLogs, TxPoolPendingInfo,
+    TxWithPoolInfo,
 };
-use crate::rpc::types::SendTxRequest;
+use crate::rpc::{openrpc::openrpc, types::SendTxRequest};
 use cfx_types::{H256, H520, U128};
 use jsonrpc_core::{BoxFuture, Result as JsonRpcResult};
 use jsonrpc_derive::rpc;
@@ -17,6 +18,7 @@ use network::{
 };
 use std::collections::BTreeMap;
 
+#[openrpc]
 #[rpc(server)]
 pub trait LocalRpc {
     #[rpc(name = "txpool_status")]
@@ -119,4 +121,11 @@ pub trait LocalRpc {
     fn epoch_receipts(
         &self, epoch: BlockHashOrEpochNumber,
     ) -> JsonRpcResult<Option<Vec<Vec<RpcReceipt>>>>;
+
+    /// Re-executes a transaction on the state of its epoch and returns the
+    /// state of the interpreter at every executed opcode.
+    #[rpc(name = "debug_traceTransaction")]
+    fn trace_transaction_struct_logs(
+        &self, tx_hash: H256, options: Option<StructLogOptions>,
+    ) -> JsonRpcResult<TransactionStructLogs>;
 }
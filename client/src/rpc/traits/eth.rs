@@ -0,0 +1,129 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::{
+    openrpc::openrpc,
+    types::{
+        eth::{
+            Block, BlockNumber, CallRequest, Filter, Log, Receipt, Transaction,
+        },
+        Bytes,
+    },
+};
+use cfx_types::{H160, H256, U256, U64};
+use jsonrpc_core::Result as JsonRpcResult;
+use jsonrpc_derive::rpc;
+
+/// Ethereum-compatible rpc interface. Each epoch is presented as one block,
+/// identified by its pivot block hash and numbered by its epoch number.
+#[openrpc]
+#[rpc(server)]
+pub trait Eth {
+    /// Returns the network id.
+    #[rpc(name = "net_version")]
+    fn net_version(&self) -> JsonRpcResult<String>;
+
+    /// Returns the chain id used for signing transactions.
+    #[rpc(name = "eth_chainId")]
+    fn chain_id(&self) -> JsonRpcResult<U64>;
+
+    /// Returns false if the node is fully synced, true otherwise.
+    #[rpc(name = "eth_syncing")]
+    fn syncing(&self) -> JsonRpcResult<bool>;
+
+    /// Returns current gas price.
+    #[rpc(name = "eth_gasPrice")]
+    fn gas_price(&self) -> JsonRpcResult<U256>;
+
+    /// Returns accounts list. Always empty, as `eth` does not manage keys.
+    #[rpc(name = "eth_accounts")]
+    fn accounts(&self) -> JsonRpcResult<Vec<H160>>;
+
+    /// Returns the number of the latest block with state.
+    #[rpc(name = "eth_blockNumber")]
+    fn block_number(&self) -> JsonRpcResult<U256>;
+
+    /// Returns balance of the given account.
+    #[rpc(name = "eth_getBalance")]
+    fn balance(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<U256>;
+
+    /// Returns content of the storage at the given address.
+    #[rpc(name = "eth_getStorageAt")]
+    fn storage_at(
+        &self, address: H160, position: U256, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<H256>;
+
+    /// Returns the number of transactions sent from the given address. For
+    /// `pending`, transactions in the transaction pool are counted as well.
+    #[rpc(name = "eth_getTransactionCount")]
+    fn transaction_count(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<U256>;
+
+    /// Returns the code at the given address.
+    #[rpc(name = "eth_getCode")]
+    fn code(
+        &self, address: H160, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<Bytes>;
+
+    /// Sends a signed EIP-155 transaction and returns its Conflux
+    /// transaction hash.
+    #[rpc(name = "eth_sendRawTransaction")]
+    fn send_raw_transaction(&self, raw: Bytes) -> JsonRpcResult<H256>;
+
+    /// Executes a new message call immediately without creating a
+    /// transaction.
+    #[rpc(name = "eth_call")]
+    fn call(
+        &self, request: CallRequest, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<Bytes>;
+
+    /// Estimates the gas limit needed for the given message call.
+    #[rpc(name = "eth_estimateGas")]
+    fn estimate_gas(
+        &self, request: CallRequest, block: Option<BlockNumber>,
+    ) -> JsonRpcResult<U256>;
+
+    /// Returns the block with the given pivot block hash.
+    #[rpc(name = "eth_getBlockByHash")]
+    fn block_by_hash(
+        &self, hash: H256, include_txs: bool,
+    ) -> JsonRpcResult<Option<Block>>;
+
+    /// Returns the block with the given number.
+    #[rpc(name = "eth_getBlockByNumber")]
+    fn block_by_number(
+        &self, block: BlockNumber, include_txs: bool,
+    ) -> JsonRpcResult<Option<Block>>;
+
+    /// Returns the number of transactions in the block with the given pivot
+    /// block hash.
+    #[rpc(name = "eth_getBlockTransactionCountByHash")]
+    fn block_transaction_count_by_hash(
+        &self, hash: H256,
+    ) -> JsonRpcResult<Option<U256>>;
+
+    /// Returns the number of transactions in the block with the given number.
+    #[rpc(name = "eth_getBlockTransactionCountByNumber")]
+    fn block_transaction_count_by_number(
+        &self, block: BlockNumber,
+    ) -> JsonRpcResult<Option<U256>>;
+
+    /// Returns the transaction with the given hash.
+    #[rpc(name = "eth_getTransactionByHash")]
+    fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> JsonRpcResult<Option<Transaction>>;
+
+    /// Returns the receipt of the transaction with the given hash.
+    #[rpc(name = "eth_getTransactionReceipt")]
+    fn transaction_receipt(&self, hash: H256)
+        -> JsonRpcResult<Option<Receipt>>;
+
+    /// Returns logs matching the given filter.
+    #[rpc(name = "eth_getLogs")]
+    fn logs(&self, filter: Filter) -> JsonRpcResult<Vec<Log>>;
+}
@@ -3,13 +3,17 @@
 // See http://www.gnu.org/licenses/
 
 pub use cfx::Cfx;
+pub use cfx_filter::CfxFilter;
 pub use debug::LocalRpc;
+pub use eth::Eth;
 pub use pubsub::PubSub;
 pub use test::TestRpc;
 pub use trace::Trace;
 
 pub mod cfx;
+pub mod cfx_filter;
 pub mod debug;
+pub mod eth;
 pub mod pubsub;
 pub mod test;
 pub mod trace;
@@ -18,13 +18,14 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::rpc::types::pubsub;
+use crate::rpc::{openrpc::openrpc, types::pubsub};
 ///! Cfx PUB-SUB rpc interface.
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed, SubscriptionId};
 
 /// Cfx PUB-SUB rpc interface.
+#[openrpc]
 #[rpc(server)]
 pub trait PubSub {
     type Metadata;
@@ -3,6 +3,7 @@
 // See http://www.gnu.org/licenses/
 
 use super::super::types::{BlameInfo, Block, Bytes};
+use crate::rpc::openrpc::openrpc;
 use cfx_types::{H256, U256};
 use cfxcore::PeerInfo;
 use jsonrpc_core::Result as RpcResult;
@@ -10,6 +11,7 @@ use jsonrpc_derive::rpc;
 use network::node_table::NodeId;
 use std::net::SocketAddr;
 
+#[openrpc]
 #[rpc(server)]
 pub trait TestRpc {
     #[rpc(name = "sayhello")]
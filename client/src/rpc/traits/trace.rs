@@ -3,12 +3,19 @@
 // See http://www.gnu.org/licenses/
 
 use super::super::types::{LocalizedBlockTrace, LocalizedTrace};
-use crate::rpc::types::TraceFilter;
+use crate::rpc::{
+    openrpc::openrpc,
+    types::{
+        CallRequest, EpochNumber, TraceFilter, TraceFilterResults,
+        TraceResults, TraceType,
+    },
+};
 use cfx_types::H256;
 use jsonrpc_core::Result as JsonRpcResult;
 use jsonrpc_derive::rpc;
 
 /// Trace specific rpc interface.
+#[openrpc]
 #[rpc(server)]
 pub trait Trace {
     /// Returns all traces produced at the given block.
@@ -17,15 +24,32 @@ pub trait Trace {
         &self, block_hash: H256,
     ) -> JsonRpcResult<Option<LocalizedBlockTrace>>;
 
-    /// Returns all traces matching the provided filter.
+    /// Returns all traces matching the provided filter. If the filter asks
+    /// for pagination, returns a page of traces and the cursor of the next
+    /// page.
     #[rpc(name = "trace_filter")]
     fn filter_traces(
         &self, filter: TraceFilter,
-    ) -> JsonRpcResult<Option<Vec<LocalizedTrace>>>;
+    ) -> JsonRpcResult<Option<TraceFilterResults>>;
 
     /// Returns all traces produced at the given transaction.
     #[rpc(name = "trace_transaction")]
     fn transaction_traces(
         &self, tx_hash: H256,
     ) -> JsonRpcResult<Option<Vec<LocalizedTrace>>>;
+
+    /// Executes the given call at the given epoch without creating a
+    /// transaction, and returns the requested trace types.
+    #[rpc(name = "trace_call")]
+    fn call(
+        &self, request: CallRequest, trace_types: Vec<TraceType>,
+        epoch: Option<EpochNumber>,
+    ) -> JsonRpcResult<TraceResults>;
+
+    /// Re-executes the given transaction on the state it was executed on,
+    /// and returns the requested trace types.
+    #[rpc(name = "trace_replayTransaction")]
+    fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> JsonRpcResult<TraceResults>;
 }
@@ -10,21 +10,29 @@ mod bytes;
 pub mod call_request;
 mod consensus_graph_states;
 mod epoch_number;
+mod epoch_summary;
 pub mod errors;
+pub mod eth;
+mod fee_history;
 mod filter;
 mod index;
 mod log;
+mod proof;
 mod provenance;
 pub mod pubsub;
 mod receipt;
 mod reward_info;
 mod sponsor_info;
+mod state_override;
 mod status;
+mod struct_log;
 mod sync_graph_states;
 mod token_supply_info;
 mod trace;
 mod trace_filter;
+mod trace_results;
 mod transaction;
+mod tx_status;
 
 pub use self::{
     account::Account,
@@ -34,26 +42,39 @@ pub use self::{
     bytes::Bytes,
     call_request::{
         sign_call, CallRequest, CheckBalanceAgainstTransactionResponse,
-        EstimateGasAndCollateralResponse, SendTxRequest, MAX_GAS_CALL_REQUEST,
+        EstimateGasAndCollateralResponse, SendTxRequest, VirtualCallResult,
+        MAX_GAS_CALL_REQUEST,
     },
     consensus_graph_states::ConsensusGraphStates,
     epoch_number::{BlockHashOrEpochNumber, EpochNumber},
-    filter::LogFilter,
+    epoch_summary::EpochSummary,
+    fee_history::FeeHistory,
+    filter::{
+        CfxFilterBlock, CfxFilterChanges, CfxFilterLog, CfxLogs, LogFilter,
+    },
     index::Index,
     log::Log,
+    proof::{AccountProof, StorageEntryProof, TrieProofTriplet},
     provenance::Origin,
     receipt::Receipt,
     reward_info::RewardInfo,
     sponsor_info::SponsorInfo,
+    state_override::{
+        state_override_into_primitive, AccountOverride, StateOverride,
+    },
     status::Status,
+    struct_log::{StructLog, StructLogOptions, TransactionStructLogs},
     sync_graph_states::SyncGraphStates,
     token_supply_info::TokenSupplyInfo,
     trace::{
         Action, LocalizedBlockTrace, LocalizedTrace, LocalizedTransactionTrace,
     },
-    trace_filter::TraceFilter,
+    trace_filter::{TraceFilter, TraceFilterResults},
+    trace_results::{TraceResults, TraceType},
     transaction::{
-        AccountPendingInfo, AccountPendingTransactions, PackedOrExecuted,
-        Transaction, TxPoolPendingInfo, TxWithPoolInfo,
+        AccountPendingInfo, AccountPendingTransactions, AddressTransaction,
+        AddressTransactions, PackedOrExecuted, Transaction, TxPoolPendingInfo,
+        TxWithPoolInfo,
     },
+    tx_status::{TxState, TxStatus},
 };
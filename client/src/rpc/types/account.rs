@@ -3,11 +3,12 @@
 // See http://www.gnu.org/licenses/
 
 use super::RpcAddress;
+use crate::rpc::openrpc::RpcSchema;
 use cfx_addr::Network;
 use cfx_types::{H256, U256};
 use primitives::Account as PrimitiveAccount;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     // This field isn't part of Account RLP but is helpful for debugging.
@@ -2,9 +2,11 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use crate::rpc::openrpc::{RpcSchema, SchemaComponents};
 use cfx_addr::{cfx_addr_decode, cfx_addr_encode, EncodingOptions, Network};
 use cfx_types::H160;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
 
 /// This is the address type used in Rpc. It deserializes user's Rpc input, or
 /// it prepares the base32 address for Rpc output.
@@ -71,6 +73,15 @@ impl Serialize for RpcAddress {
     }
 }
 
+impl RpcSchema for RpcAddress {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        json!({
+            "type": "string",
+            "description": "base32 encoded address with a network prefix",
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::RpcAddress;
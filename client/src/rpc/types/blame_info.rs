@@ -2,9 +2,10 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use crate::rpc::openrpc::RpcSchema;
 use cfx_types::{H256, U64};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BlameInfo {
     pub blame: Option<U64>,
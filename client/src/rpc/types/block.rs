@@ -27,7 +27,10 @@ use serde::{
 use serde_json::Value;
 use std::{convert::TryInto, sync::Arc};
 
-use crate::rpc::types::{transaction::PackedOrExecuted, Receipt, Transaction};
+use crate::rpc::{
+    openrpc::{one_of, RpcSchema, SchemaComponents},
+    types::{transaction::PackedOrExecuted, Receipt, Transaction},
+};
 use cfx_bytes::Bytes;
 
 #[derive(PartialEq, Debug)]
@@ -84,7 +87,16 @@ impl<'a> Deserialize<'a> for BlockTransactions {
     }
 }
 
-#[derive(PartialEq, Debug, Serialize, Deserialize)]
+impl RpcSchema for BlockTransactions {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        one_of(vec![
+            Vec::<H256>::schema(components),
+            Vec::<Transaction>::schema(components),
+        ])
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Block {
     /// Hash of the block
@@ -350,7 +362,7 @@ impl Block {
 }
 
 /// Block header representation.
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Header {
     /// Hash of the block
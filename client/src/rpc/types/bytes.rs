@@ -20,11 +20,13 @@
 
 //! Serializable wrapper around vector of bytes
 
+use crate::rpc::openrpc::{hex_string, RpcSchema, SchemaComponents};
 use rustc_hex::{FromHex, ToHex};
 use serde::{
     de::{Error, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
+use serde_json::Value;
 use std::fmt;
 
 /// Wrapper structure around vector of bytes.
@@ -65,6 +67,12 @@ impl<'a> Deserialize<'a> for Bytes {
     }
 }
 
+impl RpcSchema for Bytes {
+    fn schema(_: &mut SchemaComponents) -> Value {
+        hex_string("^0x([0-9a-f]{2})*$", "hex encoded bytes")
+    }
+}
+
 struct BytesVisitor;
 
 impl<'a> Visitor<'a> for BytesVisitor {
@@ -3,16 +3,21 @@
 // See http://www.gnu.org/licenses/
 
 use crate::rpc::{
+    openrpc::RpcSchema,
     types::{
         address::RpcAddress,
         errors::{check_rpc_address_network, RcpAddressNetworkInconsistent},
-        Bytes,
+        Action as RpcAction, Bytes, LocalizedTrace, Log,
     },
     RpcResult,
 };
 use cfx_addr::Network;
 use cfx_types::{address_util::AddressUtil, Address, U256, U64};
-use cfxcore::rpc_errors::invalid_params_check;
+use cfxcore::{
+    executive::{revert_reason_decode, ExecutionError, ExecutionOutcome},
+    rpc_errors::invalid_params_check,
+    vm,
+};
 use cfxcore_accounts::AccountProvider;
 use cfxkey::Password;
 use primitives::{
@@ -27,7 +32,7 @@ use std::{cmp::min, sync::Arc};
 /// not too high that a call_virtual consumes too much resource.
 pub const MAX_GAS_CALL_REQUEST: u64 = 500_000_000;
 
-#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CallRequest {
     /// From
@@ -48,7 +53,7 @@ pub struct CallRequest {
     pub storage_limit: Option<U64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SendTxRequest {
     pub from: RpcAddress,
@@ -63,7 +68,7 @@ pub struct SendTxRequest {
     pub epoch_height: Option<U256>,
 }
 
-#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EstimateGasAndCollateralResponse {
     /// The recommended gas_limit.
@@ -74,7 +79,93 @@ pub struct EstimateGasAndCollateralResponse {
     pub storage_collateralized: U64,
 }
 
-#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+/// Result of a single call in `cfx_callMany`.
+#[derive(Debug, Default, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualCallResult {
+    /// The returned data, or the revert data if the call is reverted.
+    pub output: Bytes,
+    /// The amount of gas used in the execution.
+    pub gas_used: U256,
+    /// The number of bytes collateralized in the execution.
+    pub storage_collateralized: U64,
+    pub logs: Vec<Log>,
+    /// Set if the call is not executed or fails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Set if the traces are requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<LocalizedTrace>>,
+}
+
+impl VirtualCallResult {
+    pub fn try_from(
+        outcome: ExecutionOutcome, network: Network, include_trace: bool,
+    ) -> Result<Self, String> {
+        let (executed, error) = match outcome {
+            ExecutionOutcome::NotExecutedDrop(e) => {
+                return Ok(VirtualCallResult {
+                    error: Some(format!("{:?}", e)),
+                    ..Default::default()
+                });
+            }
+            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+                return Ok(VirtualCallResult {
+                    error: Some(format!("{:?}", e)),
+                    ..Default::default()
+                });
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(
+                ExecutionError::VmError(vm::Error::Reverted),
+                executed,
+            ) => {
+                let reason = revert_reason_decode(&executed.output);
+                (executed, Some(format!("Vm reverted, {}", reason)))
+            }
+            ExecutionOutcome::ExecutionErrorBumpNonce(e, executed) => {
+                (executed, Some(format!("{:?}", e)))
+            }
+            ExecutionOutcome::Finished(executed) => (executed, None),
+        };
+
+        let mut storage_collateralized = U64::from(0);
+        for storage_change in &executed.storage_collateralized {
+            storage_collateralized += storage_change.collaterals;
+        }
+        let logs = executed
+            .logs
+            .into_iter()
+            .map(|log| Log::try_from(log, network))
+            .collect::<Result<_, _>>()?;
+        let trace = if include_trace {
+            let mut traces = Vec::with_capacity(executed.trace.len());
+            for trace in executed.trace {
+                traces.push(LocalizedTrace {
+                    action: RpcAction::try_from(trace.action, network)?,
+                    epoch_hash: None,
+                    epoch_number: None,
+                    block_hash: None,
+                    transaction_position: None,
+                    transaction_hash: None,
+                });
+            }
+            Some(traces)
+        } else {
+            None
+        };
+
+        Ok(VirtualCallResult {
+            output: executed.output.into(),
+            gas_used: executed.gas_used,
+            storage_collateralized,
+            logs,
+            error,
+            trace,
+        })
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CheckBalanceAgainstTransactionResponse {
     /// Whether the account should pay transaction fee by self.
@@ -2,10 +2,11 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use crate::rpc::openrpc::RpcSchema;
 use cfx_types::{H256, U64};
 use cfxcore::state_exposer::ConsensusGraphStates as PrimitiveConsensusGraphStates;
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConsensusGraphBlockState {
     pub block_hash: H256,
@@ -15,7 +16,7 @@ pub struct ConsensusGraphBlockState {
     pub adaptive: bool,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ConsensusGraphBlockExecutionState {
     pub block_hash: H256,
@@ -25,7 +26,7 @@ pub struct ConsensusGraphBlockExecutionState {
     pub state_valid: bool,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 /// This struct maintains some inner state of consensus graph.
 pub struct ConsensusGraphStates {
@@ -2,6 +2,9 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use crate::rpc::openrpc::{
+    hex_string, one_of, quantity, string_enum, RpcSchema, SchemaComponents,
+};
 use cfx_types::{H256, U64};
 use primitives::{
     BlockHashOrEpochNumber as PrimitiveBlockHashOrEpochNumber,
@@ -11,6 +14,7 @@ use serde::{
     de::{Error, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
+use serde_json::Value;
 use std::{fmt, str::FromStr};
 
 /// Represents rpc api epoch number param.
@@ -65,6 +69,23 @@ impl Serialize for EpochNumber {
     }
 }
 
+impl RpcSchema for EpochNumber {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("EpochNumber", |_| {
+            one_of(vec![
+                quantity(),
+                string_enum(vec![
+                    "earliest",
+                    "latest_checkpoint",
+                    "latest_confirmed",
+                    "latest_state",
+                    "latest_mined",
+                ]),
+            ])
+        })
+    }
+}
+
 impl EpochNumber {
     pub fn into_primitive(self) -> PrimitiveEpochNumber {
         match self {
@@ -180,6 +201,17 @@ impl Serialize for BlockHashOrEpochNumber {
     }
 }
 
+impl RpcSchema for BlockHashOrEpochNumber {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("BlockHashOrEpochNumber", |components| {
+            one_of(vec![
+                EpochNumber::schema(components),
+                hex_string("^hash:0x[0-9a-f]{64}$", "hash:<BLOCK_HASH>"),
+            ])
+        })
+    }
+}
+
 struct BlockHashOrEpochNumberVisitor;
 
 impl<'a> Visitor<'a> for BlockHashOrEpochNumberVisitor {
@@ -0,0 +1,51 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::openrpc::RpcSchema;
+use cfx_types::{H256, U256, U64};
+use cfxcore::block_data_manager::EpochSummary as PrimitiveEpochSummary;
+
+/// Result of `cfx_getEpochSummary`.
+#[derive(Debug, Serialize, Clone, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochSummary {
+    pub epoch_number: U64,
+    pub pivot_hash: H256,
+    pub timestamp: U64,
+    pub block_count: U64,
+    /// The number of the executed transactions, including the failed ones
+    /// but not the skipped ones.
+    pub transaction_count: U64,
+    pub gas_used: U256,
+    /// The gas fees charged for the executed transactions.
+    pub total_fee: U256,
+    /// The storage collateral deposited by the transactions.
+    pub storage_collateralized: U256,
+    /// The storage collateral refunded by the transactions.
+    pub storage_released: U256,
+    /// The sum of the rewards of the blocks. The rewards are null until they
+    /// are computed 12 epochs later.
+    pub total_reward: Option<U256>,
+    pub base_reward: Option<U256>,
+    pub tx_fee_reward: Option<U256>,
+}
+
+impl From<PrimitiveEpochSummary> for EpochSummary {
+    fn from(summary: PrimitiveEpochSummary) -> Self {
+        EpochSummary {
+            epoch_number: summary.epoch_number.into(),
+            pivot_hash: summary.pivot_hash,
+            timestamp: summary.timestamp.into(),
+            block_count: summary.block_count.into(),
+            transaction_count: summary.transaction_count.into(),
+            gas_used: summary.gas_used,
+            total_fee: summary.total_fee,
+            storage_collateralized: summary.storage_collateralized,
+            storage_released: summary.storage_released,
+            total_reward: summary.rewards.as_ref().map(|r| r.total_reward),
+            base_reward: summary.rewards.as_ref().map(|r| r.base_reward),
+            tx_fee_reward: summary.rewards.as_ref().map(|r| r.tx_fee),
+        }
+    }
+}
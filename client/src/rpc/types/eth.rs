@@ -0,0 +1,503 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Types of the Ethereum-compatible `eth` namespace. Each epoch is presented
+//! as one Ethereum block identified by its pivot block, and all addresses are
+//! in hex format.
+
+use crate::rpc::{
+    helpers::VariadicValue,
+    openrpc::{one_of, quantity, string_enum, RpcSchema, SchemaComponents},
+    types::{Bytes, CallRequest as CfxCallRequest, EpochNumber, RpcAddress},
+};
+use cfx_addr::Network;
+use cfx_types::{Bloom, H160, H256, H64, U256, U64};
+use jsonrpc_core::Error as RpcError;
+use primitives::{
+    epoch::EpochNumber as PrimitiveEpochNumber,
+    filter::LogFilter as PrimitiveFilter,
+    log_entry::LocalizedLogEntry,
+    transaction::{Action, TransactionType},
+    SignedTransaction,
+};
+use serde::{
+    de::{Error, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value;
+use std::{fmt, str::FromStr};
+
+/// Represents rpc api block number param. Block `n` is the pivot block of
+/// epoch `n`.
+#[derive(Debug, PartialEq, Clone, Hash, Eq)]
+pub enum BlockNumber {
+    /// Number
+    Num(U64),
+    /// Earliest block (true genesis)
+    Earliest,
+    /// Latest block with state.
+    Latest,
+    /// Same as `Latest`, as there are no pending blocks.
+    Pending,
+}
+
+impl Default for BlockNumber {
+    fn default() -> Self { BlockNumber::Latest }
+}
+
+impl<'a> Deserialize<'a> for BlockNumber {
+    fn deserialize<D>(deserializer: D) -> Result<BlockNumber, D::Error>
+    where D: Deserializer<'a> {
+        deserializer.deserialize_any(BlockNumberVisitor)
+    }
+}
+
+impl Serialize for BlockNumber {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        match *self {
+            BlockNumber::Num(ref x) => {
+                serializer.serialize_str(&format!("0x{:x}", x))
+            }
+            BlockNumber::Earliest => serializer.serialize_str("earliest"),
+            BlockNumber::Latest => serializer.serialize_str("latest"),
+            BlockNumber::Pending => serializer.serialize_str("pending"),
+        }
+    }
+}
+
+impl RpcSchema for BlockNumber {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("BlockNumber", |_| {
+            one_of(vec![
+                quantity(),
+                string_enum(vec!["earliest", "latest", "pending"]),
+            ])
+        })
+    }
+}
+
+impl BlockNumber {
+    pub fn into_primitive(self) -> PrimitiveEpochNumber {
+        match self {
+            BlockNumber::Num(num) => PrimitiveEpochNumber::Number(num.as_u64()),
+            BlockNumber::Earliest => PrimitiveEpochNumber::Earliest,
+            BlockNumber::Latest | BlockNumber::Pending => {
+                PrimitiveEpochNumber::LatestState
+            }
+        }
+    }
+}
+
+impl FromStr for BlockNumber {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latest" => Ok(BlockNumber::Latest),
+            "earliest" => Ok(BlockNumber::Earliest),
+            "pending" => Ok(BlockNumber::Pending),
+            _ if s.starts_with("0x") => u64::from_str_radix(&s[2..], 16)
+                .map(U64::from)
+                .map(BlockNumber::Num)
+                .map_err(|e| format!("Invalid block number: {}", e)),
+            _ => Err("Invalid block number: missing 0x prefix".to_string()),
+        }
+    }
+}
+
+impl Into<PrimitiveEpochNumber> for BlockNumber {
+    fn into(self) -> PrimitiveEpochNumber { self.into_primitive() }
+}
+
+impl Into<EpochNumber> for BlockNumber {
+    fn into(self) -> EpochNumber {
+        match self {
+            BlockNumber::Num(num) => EpochNumber::Num(num),
+            BlockNumber::Earliest => EpochNumber::Earliest,
+            BlockNumber::Latest | BlockNumber::Pending => {
+                EpochNumber::LatestState
+            }
+        }
+    }
+}
+
+struct BlockNumberVisitor;
+
+impl<'a> Visitor<'a> for BlockNumberVisitor {
+    type Value = BlockNumber;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a block number or 'latest', 'earliest' or 'pending'"
+        )
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where E: Error {
+        value.parse().map_err(Error::custom)
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+    where E: Error {
+        self.visit_str(value.as_ref())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+#[rpc_schema(name = "EthCallRequest")]
+pub struct CallRequest {
+    /// From
+    pub from: Option<H160>,
+    /// To
+    pub to: Option<H160>,
+    /// Gas Price
+    pub gas_price: Option<U256>,
+    /// Gas
+    pub gas: Option<U256>,
+    /// Value
+    pub value: Option<U256>,
+    /// Data
+    #[serde(alias = "input")]
+    pub data: Option<Bytes>,
+    /// Nonce
+    pub nonce: Option<U256>,
+}
+
+impl CallRequest {
+    /// Convert into a Conflux call request with addresses on `network`. The
+    /// storage limit is left unset, so storage usage is not restricted.
+    pub fn into_cfx(self, network: Network) -> Result<CfxCallRequest, String> {
+        let to_rpc_address = |address: Option<H160>| {
+            address
+                .map(|a| RpcAddress::try_from_h160(a, network))
+                .transpose()
+        };
+
+        Ok(CfxCallRequest {
+            from: to_rpc_address(self.from)?,
+            to: to_rpc_address(self.to)?,
+            gas_price: self.gas_price,
+            gas: self.gas,
+            value: self.value,
+            data: self.data,
+            nonce: self.nonce,
+            storage_limit: None,
+        })
+    }
+}
+
+/// The position of a packed transaction in the Ethereum view: the pivot
+/// block of its epoch and its index among all transactions of the epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransactionLocation {
+    pub block_hash: H256,
+    pub block_number: u64,
+    pub transaction_index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+#[rpc_schema(name = "EthTransaction")]
+pub struct Transaction {
+    pub hash: H256,
+    pub nonce: U256,
+    pub block_hash: Option<H256>,
+    pub block_number: Option<U256>,
+    pub transaction_index: Option<U256>,
+    pub from: H160,
+    pub to: Option<H160>,
+    pub value: U256,
+    pub gas_price: U256,
+    pub gas: U256,
+    pub input: Bytes,
+    pub chain_id: U64,
+    /// The EIP-155 `v` for Ethereum like transactions, the recovery id
+    /// otherwise.
+    pub v: U64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl Transaction {
+    pub fn from_signed(
+        tx: &SignedTransaction, location: Option<TransactionLocation>,
+    ) -> Transaction {
+        let chain_id = tx.chain_id as u64;
+        let v = match tx.transaction_type() {
+            TransactionType::EthereumLike => {
+                tx.transaction.v as u64 + 35 + chain_id * 2
+            }
+            TransactionType::Normal => tx.transaction.v as u64,
+        };
+
+        Transaction {
+            hash: tx.eth_hash().unwrap_or_else(|| tx.hash()),
+            nonce: tx.nonce,
+            block_hash: location.map(|l| l.block_hash),
+            block_number: location.map(|l| l.block_number.into()),
+            transaction_index: location.map(|l| l.transaction_index.into()),
+            from: tx.sender(),
+            to: match tx.action {
+                Action::Create => None,
+                Action::Call(ref address) => Some(*address),
+            },
+            value: tx.value,
+            gas_price: tx.gas_price,
+            gas: tx.gas,
+            input: tx.data.clone().into(),
+            chain_id: chain_id.into(),
+            v: v.into(),
+            r: tx.transaction.r,
+            s: tx.transaction.s,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+#[rpc_schema(name = "EthLog")]
+pub struct Log {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+    pub block_hash: H256,
+    pub block_number: U256,
+    pub transaction_hash: H256,
+    pub transaction_index: U256,
+    /// Log position in the epoch.
+    pub log_index: U256,
+    pub transaction_log_index: U256,
+    /// Always false, as reverted logs are never returned.
+    pub removed: bool,
+}
+
+impl Log {
+    /// Convert a Conflux log into the Ethereum view. `pivot_hash` is the
+    /// pivot block of the log's epoch, `transaction_index` is the index of
+    /// its transaction in the epoch and `log_index` is its position in the
+    /// epoch.
+    pub fn from_localized(
+        l: LocalizedLogEntry, pivot_hash: H256, transaction_index: usize,
+        log_index: usize,
+    ) -> Log {
+        Log {
+            address: l.entry.address,
+            topics: l.entry.topics,
+            data: l.entry.data.into(),
+            block_hash: pivot_hash,
+            block_number: l.epoch_number.into(),
+            transaction_hash: l.transaction_hash,
+            transaction_index: transaction_index.into(),
+            log_index: log_index.into(),
+            transaction_log_index: l.transaction_log_index.into(),
+            removed: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+#[rpc_schema(name = "EthReceipt")]
+pub struct Receipt {
+    pub transaction_hash: H256,
+    pub transaction_index: U256,
+    pub block_hash: H256,
+    pub block_number: U256,
+    pub from: H160,
+    pub to: Option<H160>,
+    /// The total gas used in the epoch up to and including this transaction.
+    pub cumulative_gas_used: U256,
+    pub gas_used: U256,
+    pub contract_address: Option<H160>,
+    pub logs: Vec<Log>,
+    pub logs_bloom: Bloom,
+    /// 1 on success, 0 on failure.
+    pub status: U64,
+}
+
+#[derive(Debug, PartialEq, Serialize, RpcSchema)]
+#[serde(untagged)]
+#[rpc_schema(name = "EthBlockTransactions")]
+pub enum BlockTransactions {
+    /// Only hashes
+    Hashes(Vec<H256>),
+    /// Full transactions
+    Full(Vec<Transaction>),
+}
+
+/// An epoch presented as an Ethereum block. The header fields are those of
+/// the pivot block, while `transactions` contains the transactions of all
+/// blocks in the epoch, in execution order.
+#[derive(Debug, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+#[rpc_schema(name = "EthBlock")]
+pub struct Block {
+    pub hash: H256,
+    pub parent_hash: H256,
+    /// Always the hash of an empty list, as there are no uncles.
+    pub sha3_uncles: H256,
+    pub miner: H160,
+    pub state_root: H256,
+    pub transactions_root: H256,
+    pub receipts_root: H256,
+    pub logs_bloom: Bloom,
+    pub difficulty: U256,
+    pub number: U256,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub timestamp: U256,
+    pub extra_data: Bytes,
+    pub mix_hash: H256,
+    pub nonce: H64,
+    pub uncles: Vec<H256>,
+    pub transactions: BlockTransactions,
+}
+
+#[derive(
+    PartialEq, Debug, Serialize, Deserialize, Eq, Hash, Clone, RpcSchema,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[rpc_schema(name = "EthFilter")]
+pub struct Filter {
+    /// Search will be applied from this block number.
+    pub from_block: Option<BlockNumber>,
+
+    /// Till this block number.
+    pub to_block: Option<BlockNumber>,
+
+    /// Search will be applied in this block if given. This will override
+    /// from/to_block fields.
+    pub block_hash: Option<H256>,
+
+    /// Search addresses.
+    pub address: Option<VariadicValue<H160>>,
+
+    /// Search topics.
+    pub topics: Option<Vec<VariadicValue<H256>>>,
+}
+
+impl Filter {
+    /// Convert into a Conflux log filter. If `block_hash` is set, it is
+    /// resolved into its epoch by `pivot_epoch`, and the filter covers all
+    /// blocks in that epoch.
+    pub fn into_primitive<F>(
+        self, pivot_epoch: F,
+    ) -> Result<PrimitiveFilter, RpcError>
+    where F: FnOnce(H256) -> Result<u64, RpcError> {
+        let (from_epoch, to_epoch) = match self.block_hash {
+            Some(_) if self.from_block.is_some() || self.to_block.is_some() => {
+                return Err(RpcError::invalid_params(
+                    "filter.blockHash cannot be used with fromBlock or toBlock",
+                ))
+            }
+            Some(hash) => {
+                let epoch = pivot_epoch(hash)?;
+                (
+                    PrimitiveEpochNumber::Number(epoch),
+                    PrimitiveEpochNumber::Number(epoch),
+                )
+            }
+            None => (
+                self.from_block.unwrap_or_default().into_primitive(),
+                self.to_block.unwrap_or_default().into_primitive(),
+            ),
+        };
+
+        match self.topics {
+            Some(ref ts) if ts.len() > 4 => {
+                return Err(RpcError::invalid_params(format!(
+                    "filter.topics can contain up to 4 topics; {} were provided.",
+                    ts.len()
+                )))
+            }
+            _ => {}
+        }
+
+        let topics = {
+            let mut iter = self
+                .topics
+                .map_or_else(Vec::new, |topics| {
+                    topics.into_iter().take(4).map(Into::into).collect()
+                })
+                .into_iter();
+
+            vec![
+                iter.next().unwrap_or(None),
+                iter.next().unwrap_or(None),
+                iter.next().unwrap_or(None),
+                iter.next().unwrap_or(None),
+            ]
+        };
+
+        Ok(PrimitiveFilter {
+            from_epoch,
+            to_epoch,
+            block_hashes: None,
+            address: self.address.and_then(Into::into),
+            topics,
+            offset: None,
+            limit: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockNumber, Filter};
+    use cfx_types::U64;
+    use primitives::epoch::EpochNumber as PrimitiveEpochNumber;
+    use serde_json;
+
+    #[test]
+    fn test_deserialize_block_number() {
+        let deserialized: Vec<BlockNumber> =
+            serde_json::from_str(r#"["0xa", "latest", "earliest", "pending"]"#)
+                .unwrap();
+
+        assert_eq!(
+            deserialized,
+            vec![
+                BlockNumber::Num(U64::from(10)),
+                BlockNumber::Latest,
+                BlockNumber::Earliest,
+                BlockNumber::Pending,
+            ]
+        );
+
+        assert!(serde_json::from_str::<BlockNumber>(r#""10""#).is_err());
+    }
+
+    #[test]
+    fn test_filter_into_primitive() {
+        let filter: Filter =
+            serde_json::from_str(r#"{"fromBlock": "0x1"}"#).unwrap();
+        let filter = filter.into_primitive(|_| unreachable!()).unwrap();
+
+        assert_eq!(filter.from_epoch, PrimitiveEpochNumber::Number(1));
+        assert_eq!(filter.to_epoch, PrimitiveEpochNumber::LatestState);
+
+        let block_hash = r#""0x0000000000000000000000000000000000000000000000000000000000000001""#;
+
+        let filter: Filter = serde_json::from_str(&format!(
+            r#"{{"blockHash": {}}}"#,
+            block_hash
+        ))
+        .unwrap();
+        let filter = filter.into_primitive(|_| Ok(5)).unwrap();
+
+        assert_eq!(filter.from_epoch, PrimitiveEpochNumber::Number(5));
+        assert_eq!(filter.to_epoch, PrimitiveEpochNumber::Number(5));
+        assert_eq!(filter.block_hashes, None);
+
+        let filter: Filter = serde_json::from_str(&format!(
+            r#"{{"fromBlock": "0x1", "blockHash": {}}}"#,
+            block_hash
+        ))
+        .unwrap();
+        assert!(filter.into_primitive(|_| Ok(5)).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::openrpc::RpcSchema;
+use cfx_types::{U256, U64};
+use cfxcore::consensus::FeeHistory as PrimitiveFeeHistory;
+
+/// Result of `cfx_feeHistory`.
+#[derive(Debug, Serialize, Clone, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    /// The first epoch of the returned range.
+    pub oldest_epoch: U64,
+    /// The ratio of the gas used to the gas limit of each epoch.
+    pub gas_used_ratio: Vec<f64>,
+    /// The requested gas price percentiles of the transactions in each
+    /// epoch. Not returned if no percentiles are requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+}
+
+impl FeeHistory {
+    pub fn new(history: PrimitiveFeeHistory, include_reward: bool) -> Self {
+        FeeHistory {
+            oldest_epoch: history.oldest_epoch.into(),
+            gas_used_ratio: history.gas_used_ratio,
+            reward: if include_reward {
+                Some(history.reward)
+            } else {
+                None
+            },
+        }
+    }
+}
@@ -2,16 +2,21 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use super::{EpochNumber, RpcAddress};
-use crate::rpc::helpers::{maybe_vec_into, VariadicValue};
-use cfx_types::{H256, U64};
+use super::{Bytes, EpochNumber, Log, RpcAddress};
+use crate::rpc::{
+    helpers::{maybe_vec_into, VariadicValue},
+    openrpc::RpcSchema,
+};
+use cfx_types::{H256, U256, U64};
 use jsonrpc_core::Error as RpcError;
-use primitives::filter::LogFilter as PrimitiveFilter;
+use primitives::filter::{FilterCursor, LogFilter as PrimitiveFilter};
 use serde::{Deserialize, Serialize};
 
 const FILTER_BLOCK_HASH_LIMIT: usize = 128;
 
-#[derive(PartialEq, Debug, Serialize, Deserialize, Eq, Hash, Clone)]
+#[derive(
+    PartialEq, Debug, Serialize, Deserialize, Eq, Hash, Clone, RpcSchema,
+)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct LogFilter {
     /// Search will be applied from this epoch number.
@@ -51,9 +56,33 @@ pub struct LogFilter {
     /// If specified, should only return *last* `n` logs
     /// after the offset has been applied.
     pub limit: Option<U64>,
+
+    /// The cursor returned with the previous page of logs.
+    ///
+    /// If `cursor` or `pageSize` is specified, logs are returned in pages
+    /// from the oldest one, together with the cursor of the next page.
+    pub cursor: Option<Bytes>,
+
+    /// The maximum number of logs in a page.
+    pub page_size: Option<U64>,
 }
 
 impl LogFilter {
+    /// Return the cursor and the size of the page to return if the logs
+    /// should be paginated. The page size is capped by `max_page_size`.
+    pub fn page(
+        &self, max_page_size: usize,
+    ) -> Result<Option<(Option<FilterCursor>, usize)>, RpcError> {
+        if (self.cursor.is_some() || self.page_size.is_some())
+            && (self.offset.is_some() || self.limit.is_some())
+        {
+            return Err(RpcError::invalid_params(
+                "filter.offset and filter.limit cannot be used with pagination",
+            ));
+        }
+        filter_page(&self.cursor, self.page_size, max_page_size)
+    }
+
     pub fn into_primitive(self) -> Result<PrimitiveFilter, RpcError> {
         // from_epoch, to_epoch
         let from_epoch = self
@@ -125,9 +154,95 @@ impl LogFilter {
     }
 }
 
+/// Return the cursor and the page size given by a filter, or `None` if the
+/// filter does not ask for pagination.
+pub(super) fn filter_page(
+    cursor: &Option<Bytes>, page_size: Option<U64>, max_page_size: usize,
+) -> Result<Option<(Option<FilterCursor>, usize)>, RpcError> {
+    if cursor.is_none() && page_size.is_none() {
+        return Ok(None);
+    }
+    let cursor = match cursor {
+        None => None,
+        Some(bytes) => {
+            Some(FilterCursor::from_bytes(&bytes.0).ok_or_else(|| {
+                RpcError::invalid_params("filter.cursor is invalid")
+            })?)
+        }
+    };
+    let page_size = match page_size {
+        None => max_page_size,
+        Some(size) if size.is_zero() => {
+            return Err(RpcError::invalid_params(
+                "filter.pageSize should be larger than 0",
+            ))
+        }
+        Some(size) => max_page_size.min(size.low_u64() as usize),
+    };
+    Ok(Some((cursor, page_size)))
+}
+
+/// Results of `cfx_getLogs`.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(untagged)]
+pub enum CfxLogs {
+    /// Logs of a filter without pagination.
+    Logs(Vec<Log>),
+
+    /// A page of logs and the cursor of the next page. The cursor is null if
+    /// there are no more logs.
+    Page {
+        logs: Vec<Log>,
+        cursor: Option<Bytes>,
+    },
+}
+
+/// An entry returned by `cfx_getFilterChanges` for log filters.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(untagged)]
+pub enum CfxFilterLog {
+    /// Log
+    Log(Log),
+
+    /// Chain reorg: all logs reported for epochs after `revert_to` have been
+    /// reverted.
+    #[serde(rename_all = "camelCase")]
+    ChainReorg { revert_to: U256 },
+}
+
+/// An entry returned by `cfx_getFilterChanges` for block filters.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(untagged)]
+pub enum CfxFilterBlock {
+    /// Block hash
+    Hash(H256),
+
+    /// Chain reorg: all blocks reported for epochs after `revert_to` have
+    /// been reverted.
+    #[serde(rename_all = "camelCase")]
+    ChainReorg { revert_to: U256 },
+}
+
+/// Results of `cfx_getFilterChanges`.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(untagged)]
+pub enum CfxFilterChanges {
+    /// New logs (and reverts) since the last poll.
+    Logs(Vec<CfxFilterLog>),
+
+    /// New block hashes (and reverts) since the last poll.
+    Blocks(Vec<CfxFilterBlock>),
+
+    /// New transaction hashes since the last poll.
+    Hashes(Vec<H256>),
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{super::RpcAddress, EpochNumber, LogFilter, VariadicValue};
+    use super::{
+        super::{Bytes, RpcAddress},
+        EpochNumber, FilterCursor, LogFilter, VariadicValue,
+    };
     use cfx_addr::Network;
     use cfx_types::{H160, H256, U64};
     use primitives::{
@@ -147,6 +262,8 @@ mod tests {
             topics: None,
             offset: None,
             limit: None,
+            cursor: None,
+            page_size: None,
         };
 
         let serialized_filter = serde_json::to_string(&filter).unwrap();
@@ -160,7 +277,9 @@ mod tests {
              \"address\":null,\
              \"topics\":null,\
              \"offset\":null,\
-             \"limit\":null\
+             \"limit\":null,\
+             \"cursor\":null,\
+             \"pageSize\":null\
              }"
         );
 
@@ -184,6 +303,8 @@ mod tests {
             ]),
             offset: Some(U64::from(1)),
             limit: Some(U64::from(2)),
+            cursor: None,
+            page_size: None,
         };
 
         let serialized_filter = serde_json::to_string(&filter).unwrap();
@@ -200,7 +321,9 @@ mod tests {
                 [\"0xd397b3b043d87fcd6fad1291ff0bfd16401c274896d8c63a923727f077b8e0b5\",\"0xd397b3b043d87fcd6fad1291ff0bfd16401c274896d8c63a923727f077b8e0b5\"]\
              ],\
              \"offset\":\"0x1\",\
-             \"limit\":\"0x2\"\
+             \"limit\":\"0x2\",\
+             \"cursor\":null,\
+             \"pageSize\":null\
              }"
         );
     }
@@ -217,6 +340,8 @@ mod tests {
             topics: None,
             offset: None,
             limit: None,
+            cursor: None,
+            page_size: None,
         };
 
         let deserialized_filter: LogFilter =
@@ -256,6 +381,8 @@ mod tests {
             ]),
             offset: Some(U64::from(1)),
             limit: Some(U64::from(2)),
+            cursor: None,
+            page_size: None,
         };
 
         let deserialized_filter: LogFilter =
@@ -285,6 +412,8 @@ mod tests {
             ]),
             offset: Some(U64::from(1)),
             limit: Some(U64::from(2)),
+            cursor: None,
+            page_size: None,
         };
 
         let primitive_filter = PrimitiveFilter {
@@ -313,4 +442,29 @@ mod tests {
 
         assert_eq!(filter.into_primitive(), Ok(primitive_filter));
     }
+
+    #[test]
+    fn test_filter_page() {
+        let mut filter: LogFilter = serde_json::from_str("{}").unwrap();
+        assert_eq!(filter.page(10), Ok(None));
+
+        filter.page_size = Some(U64::from(20));
+        assert_eq!(filter.page(10), Ok(Some((None, 10))));
+
+        let cursor = FilterCursor {
+            epoch_number: 1,
+            epoch_hash: H256::repeat_byte(1),
+            block_index: 2,
+            entry_index: 3,
+        };
+        filter.cursor = Some(Bytes::new(cursor.to_bytes()));
+        assert_eq!(filter.page(10), Ok(Some((Some(cursor), 10))));
+
+        filter.limit = Some(U64::from(1));
+        assert!(filter.page(10).is_err());
+
+        filter.limit = None;
+        filter.cursor = Some(Bytes::new(vec![0x12, 0x34]));
+        assert!(filter.page(10).is_err());
+    }
 }
@@ -2,12 +2,17 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::rpc::types::{Bytes, RpcAddress};
+use crate::rpc::{
+    openrpc::RpcSchema,
+    types::{Bytes, RpcAddress},
+};
 use cfx_addr::Network;
 use cfx_types::{H256, U256};
 use primitives::log_entry::{LocalizedLogEntry, LogEntry};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[derive(
+    Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, RpcSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct Log {
     /// Address
@@ -0,0 +1,97 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{Bytes, RpcAddress};
+use crate::rpc::openrpc::RpcSchema;
+use cfx_storage::{NodeMerkleProof, StateProof, TrieProof};
+use cfx_types::{H256, U64};
+use primitives::{StateRoot, StorageRoot};
+
+/// Merkle proof of a single key against the three tries (delta,
+/// intermediate delta and snapshot) that make up a Conflux state root.
+///
+/// Each trie proof is the list of RLP-encoded trie nodes, starting from the
+/// root node of the corresponding trie. A missing proof means that the
+/// corresponding trie is empty, or that the key was already proven in a
+/// newer trie.
+#[derive(Debug, Default, Serialize, Deserialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrieProofTriplet {
+    pub delta_proof: Option<Vec<Bytes>>,
+    pub intermediate_proof: Option<Vec<Bytes>>,
+    pub snapshot_proof: Option<Vec<Bytes>>,
+}
+
+impl TrieProofTriplet {
+    fn encode_trie_proof(proof: &Option<TrieProof>) -> Option<Vec<Bytes>> {
+        proof.as_ref().map(|proof| {
+            proof
+                .get_proof_nodes()
+                .iter()
+                .map(|node| Bytes::new(rlp::encode(node)))
+                .collect()
+        })
+    }
+}
+
+impl From<&StateProof> for TrieProofTriplet {
+    fn from(proof: &StateProof) -> Self {
+        Self {
+            delta_proof: Self::encode_trie_proof(&proof.delta_proof),
+            intermediate_proof: Self::encode_trie_proof(
+                &proof.intermediate_proof,
+            ),
+            snapshot_proof: Self::encode_trie_proof(&proof.snapshot_proof),
+        }
+    }
+}
+
+impl From<&NodeMerkleProof> for TrieProofTriplet {
+    fn from(proof: &NodeMerkleProof) -> Self {
+        Self {
+            delta_proof: Self::encode_trie_proof(&proof.delta_proof),
+            intermediate_proof: Self::encode_trie_proof(
+                &proof.intermediate_proof,
+            ),
+            snapshot_proof: Self::encode_trie_proof(&proof.snapshot_proof),
+        }
+    }
+}
+
+/// Proof of a single storage slot of a contract.
+#[derive(Debug, Serialize, Deserialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageEntryProof {
+    /// The requested storage position.
+    pub key: H256,
+    /// The raw (RLP-encoded) storage entry, `None` if the slot is empty.
+    pub value: Option<Bytes>,
+    /// Proof of `value` under the storage key of (`address`, `key`).
+    pub proof: TrieProofTriplet,
+}
+
+/// Response of `cfx_getProof`.
+///
+/// To verify the proofs, first check that `stateRoot` hashes to the deferred
+/// state root committed for `epochNumber` (the `state_root` part of the
+/// `StateRootWithAuxInfo` reported by the node). If the intermediate delta
+/// trie is not empty, the intermediate proofs use the key padding derived
+/// from `prevSnapshotStateRoot`, which is the state root of the epoch one
+/// snapshot period earlier.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: RpcAddress,
+    pub epoch_number: U64,
+    pub state_root: StateRoot,
+    pub prev_snapshot_state_root: Option<StateRoot>,
+    /// The raw (RLP-encoded) account, `None` if the account does not exist.
+    pub account: Option<Bytes>,
+    /// Proof of `account` under the account key of `address`.
+    pub account_proof: TrieProofTriplet,
+    pub storage_root: StorageRoot,
+    /// Proof of `storageRoot` under the account key of `address`.
+    pub storage_root_proof: TrieProofTriplet,
+    pub storage_proof: Vec<StorageEntryProof>,
+}
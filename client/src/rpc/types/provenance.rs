@@ -28,6 +28,11 @@ pub enum Origin {
     Rpc(String),
     /// TCP server (includes peer address)
     Tcp(SocketAddr),
+    /// IPC server
+    Ipc {
+        /// Session id
+        session: H256,
+    },
     /// WS server
     Ws {
         /// Session id
@@ -53,6 +58,9 @@ impl fmt::Display for Origin {
         match *self {
             Origin::Rpc(ref origin) => write!(f, "{} via RPC", origin),
             Origin::Tcp(ref address) => write!(f, "TCP (address: {})", address),
+            Origin::Ipc { ref session } => {
+                write!(f, "IPC (session: {})", session)
+            }
             Origin::Ws { ref session } => {
                 write!(f, "WebSocket (session: {})", session)
             }
@@ -20,17 +20,31 @@
 
 //! Pub-Sub types.
 
-use super::{Header, Log, LogFilter};
-use cfx_types::{H256, U256};
+use super::{
+    trace::ActionType, Header, LocalizedTrace, Log, LogFilter, RpcAddress,
+    TraceFilter, Transaction,
+};
+use crate::rpc::{
+    helpers::VariadicValue,
+    openrpc::{one_of, RpcSchema, SchemaComponents},
+};
+use cfx_types::{H256, U256, U64};
+use cfxcore::{
+    sync::SyncPhaseProgress,
+    trace::trace_filter::TraceFilter as PrimitiveTraceFilter,
+};
+use jsonrpc_core::Error as RpcError;
+use primitives::{transaction::Action, SignedTransaction};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
+#[rpc_schema(name = "SubscriptionResult")]
 pub enum Result {
     /// New block header.
     Header(Header),
@@ -38,9 +52,15 @@ pub enum Result {
     /// Log
     Log(Log),
 
+    /// Trace
+    Trace(LocalizedTrace),
+
     /// Transaction hash
     TransactionHash(H256),
 
+    /// Transaction
+    Transaction(Transaction),
+
     /// Epoch
     #[serde(rename_all = "camelCase")]
     Epoch {
@@ -51,12 +71,50 @@ pub enum Result {
     /// Chain reorg
     #[serde(rename_all = "camelCase")]
     ChainReorg { revert_to: U256 },
+
+    /// Sync progress
+    SyncProgress(SyncProgress),
+}
+
+/// Synchronization progress, published on sync phase changes and while
+/// downloading snapshot chunks.
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    /// Name of the current sync phase, `NormalSyncPhase` once caught up.
+    pub phase: String,
+    /// The best epoch reported by peers.
+    pub best_peer_epoch: Option<U64>,
+    pub local_best_epoch: U64,
+    /// Only set during state sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloaded_snapshot_chunks: Option<U64>,
+    /// Only set during state sync.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_snapshot_chunks: Option<U64>,
+}
+
+impl From<SyncPhaseProgress> for SyncProgress {
+    fn from(progress: SyncPhaseProgress) -> Self {
+        SyncProgress {
+            phase: progress.phase.into(),
+            best_peer_epoch: progress.best_peer_epoch.map(Into::into),
+            local_best_epoch: progress.local_best_epoch.into(),
+            downloaded_snapshot_chunks: progress
+                .snapshot_chunks
+                .map(|(downloaded, _)| downloaded.into()),
+            total_snapshot_chunks: progress
+                .snapshot_chunks
+                .map(|(_, total)| total.into()),
+        }
+    }
 }
 
 /// Subscription kind.
-#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone, RpcSchema)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
+#[rpc_schema(name = "SubscriptionKind")]
 pub enum Kind {
     /// New block headers subscription.
     NewHeads,
@@ -68,10 +126,12 @@ pub enum Kind {
     Syncing,
     /// Epoch
     Epochs,
+    /// Traces subscription.
+    Traces,
 }
 
 /// Subscription epoch.
-#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone, Copy, RpcSchema)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
 pub enum SubscriptionEpoch {
@@ -81,6 +141,91 @@ pub enum SubscriptionEpoch {
     LatestState,
 }
 
+/// Pending transactions subscription filter.
+#[derive(
+    Debug, Default, Deserialize, PartialEq, Eq, Hash, Clone, RpcSchema,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PendingTransactionFilter {
+    /// Publish full transactions instead of transaction hashes.
+    #[serde(default)]
+    pub full_transactions: bool,
+
+    /// If specified, the transaction must be sent by one of these addresses.
+    pub from: Option<VariadicValue<RpcAddress>>,
+
+    /// If specified, the transaction must be sent to one of these addresses.
+    /// Contract creations never match.
+    pub to: Option<VariadicValue<RpcAddress>>,
+}
+
+impl PendingTransactionFilter {
+    pub fn addresses(&self) -> impl Iterator<Item = &RpcAddress> {
+        let from = self.from.iter().flat_map(|v| v.iter());
+        let to = self.to.iter().flat_map(|v| v.iter());
+        from.chain(to)
+    }
+
+    pub fn matches(&self, tx: &SignedTransaction) -> bool {
+        if let Some(from) = &self.from {
+            if !from.iter().any(|a| a.hex_address == tx.sender) {
+                return false;
+            }
+        }
+
+        if let Some(to) = &self.to {
+            let recipient = match tx.action {
+                Action::Call(ref address) => address,
+                Action::Create => return false,
+            };
+            if !to.iter().any(|a| a.hex_address == *recipient) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Traces subscription filter. It has the same fields as `TraceFilter` except
+/// for the epoch range, i.e. `fromEpoch`, `toEpoch` and `blockHashes`.
+#[derive(
+    Debug, Default, Deserialize, PartialEq, Eq, Hash, Clone, RpcSchema,
+)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TraceSubscriptionFilter {
+    /// Search action.
+    ///
+    /// If None, match all.
+    /// If specified, trace must match one of these action types.
+    pub action_types: Option<VariadicValue<ActionType>>,
+
+    /// The offset trace number within each epoch.
+    pub after: Option<U64>,
+
+    /// The number of traces to publish for each epoch.
+    pub count: Option<U64>,
+}
+
+impl TraceSubscriptionFilter {
+    /// Returns the corresponding `TraceFilter`. Its epoch range is not used.
+    pub fn into_primitive(
+        self,
+    ) -> ::std::result::Result<PrimitiveTraceFilter, RpcError> {
+        TraceFilter {
+            from_epoch: None,
+            to_epoch: None,
+            block_hashes: None,
+            action_types: self.action_types,
+            after: self.after,
+            count: self.count,
+            cursor: None,
+            page_size: None,
+        }
+        .into_primitive()
+    }
+}
+
 /// Subscription kind.
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Params {
@@ -90,12 +235,29 @@ pub enum Params {
     Logs(LogFilter),
     /// Epoch parameters.
     Epochs(SubscriptionEpoch),
+    /// Pending transactions parameters.
+    PendingTransactions(PendingTransactionFilter),
+    /// Traces parameters.
+    Traces(TraceSubscriptionFilter),
 }
 
 impl Default for Params {
     fn default() -> Self { Params::None }
 }
 
+impl RpcSchema for Params {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("SubscriptionParams", |components| {
+            one_of(vec![
+                LogFilter::schema(components),
+                TraceSubscriptionFilter::schema(components),
+                PendingTransactionFilter::schema(components),
+                SubscriptionEpoch::schema(components),
+            ])
+        })
+    }
+}
+
 impl<'a> Deserialize<'a> for Params {
     fn deserialize<D>(
         deserializer: D,
@@ -103,7 +265,8 @@ impl<'a> Deserialize<'a> for Params {
     where D: Deserializer<'a> {
         let v: Value = Deserialize::deserialize(deserializer)?;
 
-        if v.is_null() {
+        // an empty filter is the same as no filter
+        if v.is_null() || v.as_object().map_or(false, |o| o.is_empty()) {
             return Ok(Params::None);
         }
 
@@ -112,6 +275,16 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(v);
         }
 
+        // try to interpret as a traces filter
+        if let Ok(v) = from_value(v.clone()).map(Params::Traces) {
+            return Ok(v);
+        }
+
+        // try to interpret as a pending transactions filter
+        if let Ok(v) = from_value(v.clone()).map(Params::PendingTransactions) {
+            return Ok(v);
+        }
+
         // otherwise, interpret as epoch
         from_value(v).map(Params::Epochs).map_err(|e| {
             D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e))
@@ -2,7 +2,10 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::rpc::types::{Log, RpcAddress};
+use crate::rpc::{
+    openrpc::RpcSchema,
+    types::{Log, RpcAddress},
+};
 use cfx_addr::Network;
 use cfx_types::{Bloom, H256, U256, U64};
 use cfxcore::{executive::contract_address, vm::CreateContractAddress};
@@ -15,7 +18,7 @@ use primitives::{
 };
 use serde_derive::Serialize;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, RpcSchema)]
 pub struct StorageChange {
     pub address: RpcAddress,
     pub collaterals: U64,
@@ -32,7 +35,7 @@ impl StorageChange {
     }
 }
 
-#[derive(Debug, Serialize, Clone, Deserialize)]
+#[derive(Debug, Serialize, Clone, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Receipt {
     /// Transaction hash.
@@ -1,8 +1,9 @@
 use super::RpcAddress;
+use crate::rpc::openrpc::RpcSchema;
 use cfx_types::{H256, U256};
 use cfxcore::block_data_manager::BlockRewardResult;
 
-#[derive(Debug, Serialize, Clone, Deserialize)]
+#[derive(Debug, Serialize, Clone, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RewardInfo {
     block_hash: H256,
@@ -3,11 +3,12 @@
 // See http://www.gnu.org/licenses/
 
 use super::RpcAddress;
+use crate::rpc::openrpc::RpcSchema;
 use cfx_addr::Network;
 use cfx_types::U256;
 use primitives::SponsorInfo as PrimitiveSponsorInfo;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SponsorInfo {
     /// This is the address of the sponsor for gas cost of the contract.
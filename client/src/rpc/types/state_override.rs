@@ -0,0 +1,58 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{errors::check_rpc_address_network, Bytes, RpcAddress};
+use crate::rpc::openrpc::RpcSchema;
+use cfx_addr::Network;
+use cfx_types::{H256, U256};
+use cfxcore::state::{
+    AccountOverride as PrimitiveAccountOverride,
+    StateOverride as PrimitiveStateOverride,
+};
+use std::collections::HashMap;
+
+/// Overrides of a single account used by `cfx_call` and
+/// `cfx_estimateGasAndCollateral`. At most one of `state` and `stateDiff`
+/// can be set.
+#[derive(Debug, Default, Clone, Deserialize, RpcSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<U256>,
+    pub code: Option<Bytes>,
+    /// Replaces the whole storage of the account.
+    pub state: Option<HashMap<H256, H256>>,
+    /// Replaces the given storage slots and keeps the others.
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+pub type StateOverride = HashMap<RpcAddress, AccountOverride>;
+
+pub fn state_override_into_primitive(
+    state_override: StateOverride, network: Network,
+) -> Result<PrimitiveStateOverride, String> {
+    state_override
+        .into_iter()
+        .map(|(address, account)| {
+            check_rpc_address_network(Some(address.network), &network)
+                .map_err(|e| e.to_string())?;
+            if account.state.is_some() && account.state_diff.is_some() {
+                return Err(format!(
+                    "both state and stateDiff are set for address {}",
+                    address.base32_address
+                ));
+            }
+            Ok((
+                address.hex_address,
+                PrimitiveAccountOverride {
+                    balance: account.balance,
+                    nonce: account.nonce,
+                    code: account.code.map(Into::into),
+                    state: account.state,
+                    state_diff: account.state_diff,
+                },
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,85 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::Bytes;
+use crate::rpc::openrpc::RpcSchema;
+use cfx_types::{H256, U256};
+use cfxcore::trace::{
+    StructLog as PrimitiveStructLog, StructLogConfig as PrimitiveConfig,
+};
+use std::collections::BTreeMap;
+
+/// Options of `debug_traceTransaction`. The stack, memory and storage are
+/// captured unless disabled.
+#[derive(Debug, Default, Clone, Deserialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogOptions {
+    #[serde(default)]
+    pub disable_stack: bool,
+    #[serde(default)]
+    pub disable_memory: bool,
+    #[serde(default)]
+    pub disable_storage: bool,
+}
+
+impl Into<PrimitiveConfig> for StructLogOptions {
+    fn into(self) -> PrimitiveConfig {
+        PrimitiveConfig {
+            disable_stack: self.disable_stack,
+            disable_memory: self.disable_memory,
+            disable_storage: self.disable_storage,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: usize,
+    pub op: &'static str,
+    pub gas: U256,
+    pub gas_cost: U256,
+    pub depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// The memory split into 32-byte words.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<Bytes>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<PrimitiveStructLog> for StructLog {
+    fn from(log: PrimitiveStructLog) -> Self {
+        StructLog {
+            pc: log.pc,
+            op: log.op,
+            gas: log.gas,
+            gas_cost: log.gas_cost,
+            depth: log.depth,
+            stack: log.stack,
+            memory: log.memory.map(|memory| {
+                memory
+                    .chunks(32)
+                    .map(|word| Bytes::new(word.to_vec()))
+                    .collect()
+            }),
+            storage: log.storage,
+            error: log.error,
+        }
+    }
+}
+
+/// Response of `debug_traceTransaction`.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStructLogs {
+    /// Gas used by the transaction.
+    pub gas: U256,
+    pub failed: bool,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
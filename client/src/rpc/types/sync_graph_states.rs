@@ -2,10 +2,11 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use crate::rpc::openrpc::RpcSchema;
 use cfx_types::{H256, U256, U64};
 use cfxcore::state_exposer::SyncGraphStates as PrimitiveSyncGraphStates;
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncGraphBlockState {
     pub block_hash: H256,
@@ -16,7 +17,7 @@ pub struct SyncGraphBlockState {
     pub adaptive: bool,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 /// This struct maintains some inner state of synchronization graph.
 pub struct SyncGraphStates {
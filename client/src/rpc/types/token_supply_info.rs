@@ -1,6 +1,7 @@
+use crate::rpc::openrpc::RpcSchema;
 use cfx_types::U256;
 
-#[derive(Debug, Serialize, Clone, Deserialize)]
+#[derive(Debug, Serialize, Clone, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenSupplyInfo {
     pub total_circulating: U256,
@@ -3,7 +3,12 @@
 // See http://www.gnu.org/licenses/
 
 use super::RpcAddress;
-use crate::rpc::types::Bytes;
+use crate::rpc::{
+    openrpc::{
+        object, one_of, string_enum, Field, RpcSchema, SchemaComponents,
+    },
+    types::Bytes,
+};
 use cfx_addr::Network;
 use cfx_types::{H256, U256, U64};
 use cfxcore::{
@@ -18,6 +23,7 @@ use cfxcore::{
     vm::CallType,
 };
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use serde_json::Value;
 use strum_macros::EnumDiscriminants;
 
 #[derive(Debug, Clone, PartialEq, EnumDiscriminants)]
@@ -68,7 +74,21 @@ impl Into<VmActionType> for ActionType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+impl RpcSchema for ActionType {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("ActionType", |_| {
+            string_enum(vec![
+                "call",
+                "create",
+                "call_result",
+                "create_result",
+                "internal_transfer_action",
+            ])
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Call {
     pub from: RpcAddress,
@@ -92,7 +112,7 @@ impl Call {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CallResult {
     pub outcome: Outcome,
@@ -110,7 +130,7 @@ impl From<VmCallResult> for CallResult {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Create {
     pub from: RpcAddress,
@@ -130,7 +150,7 @@ impl Create {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateResult {
     pub outcome: Outcome,
@@ -152,7 +172,7 @@ impl CreateResult {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct InternalTransferAction {
     pub from: RpcAddress,
@@ -172,7 +192,7 @@ impl InternalTransferAction {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalizedBlockTrace {
     pub transaction_traces: Vec<LocalizedTransactionTrace>,
@@ -184,7 +204,7 @@ pub struct LocalizedBlockTrace {
     pub block_hash: H256,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalizedTransactionTrace {
     pub traces: Vec<LocalizedTrace>,
@@ -194,7 +214,7 @@ pub struct LocalizedTransactionTrace {
     pub transaction_hash: H256,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LocalizedTrace {
     pub action: Action,
     /// Epoch hash.
@@ -264,6 +284,47 @@ impl Serialize for LocalizedTrace {
     }
 }
 
+impl RpcSchema for LocalizedTrace {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("LocalizedTrace", |components| {
+            let optional = |name, schema| Field {
+                name,
+                schema,
+                required: false,
+                description: None,
+            };
+            object(
+                vec![
+                    Field {
+                        name: "type",
+                        schema: ActionType::schema(components),
+                        required: true,
+                        description: None,
+                    },
+                    Field {
+                        name: "action",
+                        schema: one_of(vec![
+                            Call::schema(components),
+                            Create::schema(components),
+                            CallResult::schema(components),
+                            CreateResult::schema(components),
+                            InternalTransferAction::schema(components),
+                        ]),
+                        required: true,
+                        description: Some("the action of the given type"),
+                    },
+                    optional("epochHash", H256::schema(components)),
+                    optional("epochNumber", U256::schema(components)),
+                    optional("blockHash", H256::schema(components)),
+                    optional("transactionPosition", U64::schema(components)),
+                    optional("transactionHash", H256::schema(components)),
+                ],
+                None,
+            )
+        })
+    }
+}
+
 impl LocalizedTrace {
     pub fn from(
         trace: PrimitiveLocalizedTrace, network: Network,
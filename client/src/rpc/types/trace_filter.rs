@@ -2,16 +2,26 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use super::{trace::ActionType, EpochNumber};
-use crate::rpc::helpers::{maybe_vec_into, VariadicValue};
+use super::{
+    filter::filter_page,
+    trace::{ActionType, LocalizedTrace},
+    Bytes, EpochNumber,
+};
+use crate::rpc::{
+    helpers::{maybe_vec_into, VariadicValue},
+    openrpc::RpcSchema,
+};
 use cfx_types::{H256, U64};
 use cfxcore::trace::trace_filter::TraceFilter as PrimitiveTraceFilter;
 use jsonrpc_core::Error as RpcError;
+use primitives::filter::FilterCursor;
 use serde::{Deserialize, Serialize};
 
 const FILTER_BLOCK_HASH_LIMIT: usize = 128;
 
-#[derive(PartialEq, Debug, Serialize, Deserialize, Eq, Hash, Clone)]
+#[derive(
+    PartialEq, Debug, Serialize, Deserialize, Eq, Hash, Clone, RpcSchema,
+)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct TraceFilter {
     /// Search will be applied from this epoch number.
@@ -35,9 +45,33 @@ pub struct TraceFilter {
 
     /// The number of traces to display in a batch.
     pub count: Option<U64>,
+
+    /// The cursor returned with the previous page of traces.
+    ///
+    /// If `cursor` or `pageSize` is specified, traces are returned in pages
+    /// from the oldest one, together with the cursor of the next page.
+    pub cursor: Option<Bytes>,
+
+    /// The maximum number of traces in a page.
+    pub page_size: Option<U64>,
 }
 
 impl TraceFilter {
+    /// Return the cursor and the size of the page to return if the traces
+    /// should be paginated. The page size is capped by `max_page_size`.
+    pub fn page(
+        &self, max_page_size: usize,
+    ) -> Result<Option<(Option<FilterCursor>, usize)>, RpcError> {
+        if (self.cursor.is_some() || self.page_size.is_some())
+            && (self.after.is_some() || self.count.is_some())
+        {
+            return Err(RpcError::invalid_params(
+                "filter.after and filter.count cannot be used with pagination",
+            ));
+        }
+        filter_page(&self.cursor, self.page_size, max_page_size)
+    }
+
     pub fn into_primitive(self) -> Result<PrimitiveTraceFilter, RpcError> {
         // from_epoch, to_epoch
         let from_epoch = self
@@ -78,6 +112,21 @@ impl TraceFilter {
     }
 }
 
+/// Results of `trace_filter`.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(untagged)]
+pub enum TraceFilterResults {
+    /// Traces of a filter without pagination.
+    Traces(Vec<LocalizedTrace>),
+
+    /// A page of traces and the cursor of the next page. The cursor is null
+    /// if there are no more traces.
+    Page {
+        traces: Vec<LocalizedTrace>,
+        cursor: Option<Bytes>,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
@@ -101,6 +150,8 @@ mod tests {
             action_types: None,
             after: None,
             count: None,
+            cursor: None,
+            page_size: None,
         };
 
         let serialized_filter = serde_json::to_string(&filter).unwrap();
@@ -113,7 +164,9 @@ mod tests {
              \"blockHashes\":null,\
              \"actionTypes\":null,\
              \"after\":null,\
-             \"count\":null\
+             \"count\":null,\
+             \"cursor\":null,\
+             \"pageSize\":null\
              }"
         );
 
@@ -130,6 +183,8 @@ mod tests {
             ])),
             after: Some(U64::from(2)),
             count: Some(U64::from(3)),
+            cursor: None,
+            page_size: None,
         };
 
         let serialized_filter = serde_json::to_string(&filter).unwrap();
@@ -142,7 +197,9 @@ mod tests {
              \"blockHashes\":[\"0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470\",\"0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347\"],\
              \"actionTypes\":[\"call\",\"create_result\"],\
              \"after\":\"0x2\",\
-             \"count\":\"0x3\"\
+             \"count\":\"0x3\",\
+             \"cursor\":null,\
+             \"pageSize\":null\
              }"
         );
     }
@@ -158,6 +215,8 @@ mod tests {
             action_types: None,
             after: None,
             count: None,
+            cursor: None,
+            page_size: None,
         };
 
         let deserialized_filter: TraceFilter =
@@ -186,6 +245,8 @@ mod tests {
             ])),
             after: Some(U64::from(2)),
             count: Some(U64::from(3)),
+            cursor: None,
+            page_size: None,
         };
 
         let deserialized_filter: TraceFilter =
@@ -208,6 +269,8 @@ mod tests {
             ])),
             after: Some(U64::from(2)),
             count: Some(U64::from(3)),
+            cursor: None,
+            page_size: None,
         };
 
         let primitive_filter = PrimitiveTraceFilter {
@@ -0,0 +1,190 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::{
+    openrpc::RpcSchema,
+    types::{Action as RpcAction, Bytes, LocalizedTrace, RpcAddress},
+};
+use cfx_addr::Network;
+use cfx_types::{BigEndianHash, H256, U256};
+use cfxcore::{
+    executive::Executed,
+    state::{
+        AccountDiff as PrimitiveAccountDiff, Diff as PrimitiveDiff,
+        StateDiff as PrimitiveStateDiff,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The kinds of results `trace_call` and `trace_replayTransaction` can
+/// return.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, RpcSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceType {
+    /// The execution traces of the transaction.
+    Trace,
+    /// The changes of the accounts touched by the transaction.
+    StateDiff,
+}
+
+/// The old and new values of a changed field.
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+pub struct ChangedType<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// The change of a field, in the format used by the `stateDiff` traces of
+/// other clients.
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+pub enum Diff<T> {
+    #[serde(rename = "=")]
+    Same,
+    #[serde(rename = "+")]
+    Born(T),
+    #[serde(rename = "-")]
+    Died(T),
+    #[serde(rename = "*")]
+    Changed(ChangedType<T>),
+}
+
+impl<T> Diff<T> {
+    fn from_primitive<U, F: Fn(U) -> T>(diff: PrimitiveDiff<U>, f: F) -> Self {
+        match diff {
+            PrimitiveDiff::Same => Diff::Same,
+            PrimitiveDiff::Born(x) => Diff::Born(f(x)),
+            PrimitiveDiff::Died(x) => Diff::Died(f(x)),
+            PrimitiveDiff::Changed(from, to) => Diff::Changed(ChangedType {
+                from: f(from),
+                to: f(to),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, RpcSchema)]
+pub struct AccountDiff {
+    pub balance: Diff<U256>,
+    pub nonce: Diff<U256>,
+    pub code: Diff<Bytes>,
+    pub storage: HashMap<Bytes, Diff<H256>>,
+}
+
+impl From<PrimitiveAccountDiff> for AccountDiff {
+    fn from(diff: PrimitiveAccountDiff) -> Self {
+        AccountDiff {
+            balance: Diff::from_primitive(diff.balance, |x| x),
+            nonce: Diff::from_primitive(diff.nonce, |x| x),
+            code: Diff::from_primitive(diff.code, Bytes::new),
+            storage: diff
+                .storage
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        Bytes::new(key),
+                        Diff::from_primitive(value, |v| H256::from_uint(&v)),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+pub type StateDiff = HashMap<RpcAddress, AccountDiff>;
+
+/// Result of `trace_call` and `trace_replayTransaction`. The results of
+/// the trace types not requested are null.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+    /// The returned data, or the revert data if the transaction is reverted.
+    pub output: Bytes,
+    pub trace: Option<Vec<LocalizedTrace>>,
+    pub state_diff: Option<StateDiff>,
+    /// Only set by `trace_replayTransaction`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<H256>,
+}
+
+impl TraceResults {
+    pub fn try_from(
+        executed: Executed, state_diff: Option<PrimitiveStateDiff>,
+        trace_types: &[TraceType], network: Network,
+    ) -> Result<Self, String> {
+        let trace = if trace_types.contains(&TraceType::Trace) {
+            let mut traces = Vec::with_capacity(executed.trace.len());
+            for trace in executed.trace {
+                traces.push(LocalizedTrace {
+                    action: RpcAction::try_from(trace.action, network)?,
+                    epoch_hash: None,
+                    epoch_number: None,
+                    block_hash: None,
+                    transaction_position: None,
+                    transaction_hash: None,
+                });
+            }
+            Some(traces)
+        } else {
+            None
+        };
+        let state_diff = match state_diff {
+            Some(state_diff) if trace_types.contains(&TraceType::StateDiff) => {
+                let mut diffs = HashMap::with_capacity(state_diff.len());
+                for (address, diff) in state_diff {
+                    diffs.insert(
+                        RpcAddress::try_from_h160(address, network)?,
+                        diff.into(),
+                    );
+                }
+                Some(diffs)
+            }
+            _ => None,
+        };
+
+        Ok(TraceResults {
+            output: executed.output.into(),
+            trace,
+            state_diff,
+            transaction_hash: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangedType, Diff, TraceType};
+    use cfx_types::U256;
+    use serde_json;
+
+    #[test]
+    fn diff_serialize() {
+        assert_eq!(
+            serde_json::to_string(&Diff::<U256>::Same).unwrap(),
+            r#""=""#
+        );
+        assert_eq!(
+            serde_json::to_string(&Diff::Born(U256::from(1))).unwrap(),
+            r#"{"+":"0x1"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Diff::Changed(ChangedType {
+                from: U256::from(1),
+                to: U256::from(2),
+            }))
+            .unwrap(),
+            r#"{"*":{"from":"0x1","to":"0x2"}}"#
+        );
+    }
+
+    #[test]
+    fn trace_type_deserialize() {
+        let types: Vec<TraceType> =
+            serde_json::from_str(r#"["trace","stateDiff"]"#).unwrap();
+        assert_eq!(types, vec![TraceType::Trace, TraceType::StateDiff]);
+        assert!(serde_json::from_str::<TraceType>(r#""vmTrace""#).is_err());
+    }
+}
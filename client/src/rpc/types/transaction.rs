@@ -2,10 +2,16 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::rpc::types::{receipt::Receipt, Bytes, RpcAddress};
+use crate::rpc::{
+    openrpc::RpcSchema,
+    types::{receipt::Receipt, Bytes, RpcAddress},
+};
 use cfx_addr::Network;
 use cfx_types::{H256, U256, U64};
-use cfxcore::transaction_pool::TransactionStatus;
+use cfxcore::{
+    block_data_manager::AddressTransaction as PrimitiveAddressTransaction,
+    transaction_pool::TransactionStatus,
+};
 use cfxkey::Error;
 use primitives::{
     transaction::Action, SignedTransaction,
@@ -13,7 +19,7 @@ use primitives::{
     TransactionWithSignature, TransactionWithSignatureSerializePart,
 };
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
     pub hash: H256,
@@ -148,7 +154,7 @@ impl Transaction {
     }
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, RpcSchema)]
 pub struct TxWithPoolInfo {
     pub exist: bool,
     pub packed: bool,
@@ -160,14 +166,14 @@ pub struct TxWithPoolInfo {
     pub state_balance_enough: bool,
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, RpcSchema)]
 pub struct TxPoolPendingInfo {
     pub pending_count: usize,
     pub min_nonce: U256,
     pub max_nonce: U256,
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountPendingInfo {
     pub local_nonce: U256,
@@ -176,10 +182,39 @@ pub struct AccountPendingInfo {
     pub next_pending_tx: H256,
 }
 
-#[derive(Default, Serialize)]
+#[derive(Default, Serialize, RpcSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountPendingTransactions {
     pub pending_transactions: Vec<Transaction>,
     pub first_tx_status: Option<TransactionStatus>,
     pub pending_count: U64,
 }
+
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransaction {
+    pub transaction_hash: H256,
+    pub block_hash: H256,
+    pub transaction_index: U64,
+    pub epoch_number: U64,
+}
+
+impl From<PrimitiveAddressTransaction> for AddressTransaction {
+    fn from(tx: PrimitiveAddressTransaction) -> Self {
+        AddressTransaction {
+            transaction_hash: tx.transaction_hash,
+            block_hash: tx.block_hash,
+            transaction_index: tx.index.into(),
+            epoch_number: tx.epoch_number.into(),
+        }
+    }
+}
+
+/// The transactions sent or received by an address, from the newest one.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransactions {
+    pub transactions: Vec<AddressTransaction>,
+    /// The number of all indexed transactions of the address.
+    pub total_count: U64,
+}
@@ -0,0 +1,71 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::rpc::openrpc::RpcSchema;
+use cfx_types::{H256, U256, U64};
+use cfxcore::transaction_pool::{PendingReason, SkipReason};
+
+/// The lifecycle state of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TxState {
+    /// The transaction is not known by the node.
+    Unknown,
+    /// The transaction is in the pool and ready to be packed.
+    Ready,
+    /// The transaction is in the pool but cannot be packed yet.
+    Pending,
+    /// The transaction is packed, but its epoch is not executed yet.
+    Packed,
+    /// The transaction is executed, successfully or not.
+    Executed,
+    /// The transaction is skipped in execution without bumping the nonce.
+    Skipped,
+    /// The transaction is removed from the pool before it is packed.
+    GarbageCollected,
+}
+
+/// Result of `cfx_getTransactionStatus`.
+#[derive(Debug, Serialize, RpcSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxStatus {
+    pub status: TxState,
+    /// Why a pending transaction cannot be packed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_reason: Option<PendingReason>,
+    /// Why a transaction is skipped in its last execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<SkipReason>,
+    /// The outcome status of an executed transaction, 0 for success and 1
+    /// for failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome_status: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_exec_error_msg: Option<String>,
+    /// The block where the transaction is packed, executed or skipped, if
+    /// it is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<H256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch_number: Option<U64>,
+    /// The confirmation risk of `block_hash`, in the same format as
+    /// `cfx_getConfirmationRiskByHash`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_risk: Option<U256>,
+}
+
+impl TxStatus {
+    pub fn new(status: TxState) -> Self {
+        TxStatus {
+            status,
+            pending_reason: None,
+            skip_reason: None,
+            outcome_status: None,
+            tx_exec_error_msg: None,
+            block_hash: None,
+            epoch_number: None,
+            confirmation_risk: None,
+        }
+    }
+}
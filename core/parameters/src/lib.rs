@@ -280,6 +280,8 @@ pub mod light {
         pub static ref TX_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
         pub static ref TX_INFO_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
         pub static ref STORAGE_ROOT_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+        pub static ref BLOCK_REWARD_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+        pub static ref ACCOUNT_PENDING_TXS_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
 
         /// Maximum time period we wait for a response for an on-demand query.
         /// After this timeout has been reached, we try another peer or give up.
@@ -307,6 +309,8 @@ pub mod light {
     pub const TX_REQUEST_BATCH_SIZE: usize = 30;
     pub const TX_INFO_REQUEST_BATCH_SIZE: usize = 30;
     pub const STORAGE_ROOT_REQUEST_BATCH_SIZE: usize = 30;
+    pub const BLOCK_REWARD_REQUEST_BATCH_SIZE: usize = 30;
+    pub const ACCOUNT_PENDING_TXS_REQUEST_BATCH_SIZE: usize = 10;
 
     /// Maximum number of in-flight items at any given time.
     /// If we reach this limit, we will not request any more.
@@ -320,6 +324,8 @@ pub mod light {
     pub const MAX_TXS_IN_FLIGHT: usize = 100;
     pub const MAX_TX_INFOS_IN_FLIGHT: usize = 100;
     pub const MAX_STORAGE_ROOTS_IN_FLIGHT: usize = 100;
+    pub const MAX_BLOCK_REWARDS_IN_FLIGHT: usize = 100;
+    pub const MAX_ACCOUNT_PENDING_TXS_IN_FLIGHT: usize = 100;
 
     /// Maximum number of in-flight epoch requests at any given time.
     /// Similar to `MAX_HEADERS_IN_FLIGHT`. However, it is hard to match
@@ -333,6 +339,10 @@ pub mod light {
     /// If we have fewer, we will try to request some more.
     pub const NUM_WAITING_HEADERS_THRESHOLD: usize = 1000;
 
+    /// Number of verified headers after which the header sync progress is
+    /// stored on disk again.
+    pub const SYNC_PROGRESS_PERSIST_INTERVAL: u64 = 100;
+
     /// Max number of epochs/headers/txs to send to a light peer in a response.
     pub const MAX_EPOCHS_TO_SEND: usize = 128;
     pub const MAX_HEADERS_TO_SEND: usize = 512;
@@ -340,6 +350,13 @@ pub mod light {
     pub const MAX_WITNESSES_TO_SEND: usize = 100;
     pub const MAX_ITEMS_TO_SEND: usize = 50;
 
+    /// Credit-based flow control for serving light peers. Each light peer
+    /// starts with a full buffer of credits, which is recharged at a
+    /// constant rate (credits per second) up to the buffer limit. The cost
+    /// of each request type is defined in `FlowControlParams`.
+    pub const FLOW_CONTROL_BUFFER_LIMIT: u64 = 1_000_000;
+    pub const FLOW_CONTROL_RECHARGE_RATE: u64 = 100_000;
+
     /// During syncing, we might transiently have enough malicious blaming
     /// blocks to consider a correct header incorrect. For this reason, we
     /// first wait for enough header to accumulate before checking blaming.
@@ -363,6 +380,23 @@ pub mod light {
 
     // Number of blocks we retrieve in parallel for the gas price sample.
     pub const GAS_PRICE_BATCH_SIZE: usize = 30;
+
+    /// Maximum number of state entries retrieved to execute a virtual call
+    /// (`cfx_call`, `cfx_estimateGasAndCollateral`). As the call is executed
+    /// again after each retrieval, this also bounds the number of executions.
+    pub const MAX_VIRTUAL_CALL_STATE_ENTRIES: usize = 1000;
+
+    /// Number of threads executing virtual calls on a light node.
+    pub const VIRTUAL_CALL_THREADS: usize = 4;
+
+    /// Maximum number of virtual call executions waiting for a thread on a
+    /// light node. The calls beyond it are rejected.
+    pub const MAX_QUEUED_VIRTUAL_CALLS: usize = 64;
+
+    /// Maximum number of the latest senders of the txs relayed through a light
+    /// node, whose pending txs are served by
+    /// `cfx_getAccountPendingTransactions`.
+    pub const MAX_TX_SENDERS: usize = 10_000;
 }
 
 pub const WORKER_COMPUTATION_PARALLELISM: usize = 8;
@@ -0,0 +1,290 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{
+    db_manager::{AddressTransactionBatch, DBManager},
+    AddressTransaction, AddressTransactionEpoch, AddressTransactionRange,
+    BlockDataManager, EpochIndexProgress,
+};
+use crate::trace::trace::Action as TraceAction;
+use cfx_types::{Address, H256};
+use primitives::{
+    receipt::TRANSACTION_OUTCOME_EXCEPTION_WITHOUT_NONCE_BUMPING, Action,
+};
+use std::collections::{HashMap, HashSet};
+
+/// The updates made to the address transaction index when an epoch is
+/// indexed, together with the ranges of the addresses they change.
+struct AddressTransactionUpdates {
+    batch: AddressTransactionBatch,
+    ranges: HashMap<Address, AddressTransactionRange>,
+    /// The last entries appended to each address in this update.
+    last_appended: HashMap<Address, AddressTransaction>,
+}
+
+impl AddressTransactionUpdates {
+    fn range(
+        &mut self, db_manager: &DBManager, address: &Address,
+    ) -> &mut AddressTransactionRange {
+        self.ranges.entry(*address).or_insert_with(|| {
+            db_manager
+                .address_transaction_range_from_db(address)
+                .unwrap_or_default()
+        })
+    }
+}
+
+impl BlockDataManager {
+    /// Index the senders and recipients of the transactions executed in a
+    /// pivot epoch. The recipients include the receivers of the internal
+    /// transfers in the traces if traces are kept.
+    ///
+    /// If the epoch has been indexed with another pivot block, the epochs
+    /// from `epoch_number` are rolled back first. All the updates, including
+    /// the progress, are written in one database transaction.
+    pub fn index_address_transactions(
+        &self, epoch_number: u64, epoch_hash: &H256,
+        epoch_block_hashes: &Vec<H256>,
+    )
+    {
+        if !self.config.persist_address_tx_index {
+            return;
+        }
+        let db_manager = &self.db_manager;
+        let mut progress =
+            match db_manager.address_transaction_index_progress_from_db() {
+                Some(progress) => progress,
+                None => EpochIndexProgress {
+                    first_epoch: epoch_number,
+                    next_epoch: epoch_number,
+                },
+            };
+        let mut updates = AddressTransactionUpdates {
+            batch: AddressTransactionBatch::default(),
+            ranges: HashMap::new(),
+            last_appended: HashMap::new(),
+        };
+
+        if epoch_number < progress.next_epoch {
+            if let Some(indexed) =
+                db_manager.address_transaction_epoch_from_db(epoch_number)
+            {
+                if indexed.epoch_hash == *epoch_hash {
+                    return;
+                }
+            }
+            for epoch in (epoch_number..progress.next_epoch).rev() {
+                self.rollback_address_transaction_epoch(epoch, &mut updates);
+            }
+            progress.next_epoch = epoch_number;
+            if progress.first_epoch > epoch_number {
+                progress.first_epoch = epoch_number;
+            }
+        }
+
+        let mut addresses = Vec::new();
+        for block_hash in epoch_block_hashes {
+            let block = match self.block_by_hash(block_hash, false) {
+                Some(block) => block,
+                None => continue,
+            };
+            let receipts = match self.block_execution_result_by_hash_with_epoch(
+                block_hash, epoch_hash,
+                false, /* update_pivot_assumption */
+                false, /* update_cache */
+            ) {
+                Some(result) => result.block_receipts,
+                None => continue,
+            };
+            let traces = self.block_traces_by_hash_with_epoch(
+                block_hash, epoch_hash,
+                false, /* update_pivot_assumption */
+                false, /* update_cache */
+            );
+            for (index, tx) in block.transactions.iter().enumerate() {
+                let outcome_status = match receipts.receipts.get(index) {
+                    Some(receipt) => receipt.outcome_status,
+                    None => continue,
+                };
+                if outcome_status
+                    == TRANSACTION_OUTCOME_EXCEPTION_WITHOUT_NONCE_BUMPING
+                {
+                    continue;
+                }
+                let mut related = HashSet::new();
+                related.insert(tx.sender);
+                if let Action::Call(to) = tx.action {
+                    related.insert(to);
+                }
+                if let Some(tx_traces) =
+                    traces.as_ref().and_then(|traces| traces.0.get(index))
+                {
+                    for trace in &tx_traces.0 {
+                        match &trace.action {
+                            TraceAction::Call(call)
+                                if !call.value.is_zero() =>
+                            {
+                                related.insert(call.to);
+                            }
+                            TraceAction::InternalTransferAction(transfer) => {
+                                related.insert(transfer.to);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                related.remove(&Address::zero());
+
+                let entry = AddressTransaction {
+                    transaction_hash: tx.hash(),
+                    block_hash: *block_hash,
+                    index: index as u64,
+                    epoch_number,
+                };
+                for address in related {
+                    if self.is_address_transaction_indexed(
+                        &address,
+                        &entry,
+                        &mut updates,
+                    ) {
+                        continue;
+                    }
+                    let range = updates.range(db_manager, &address);
+                    let position = range.end;
+                    range.end += 1;
+                    updates
+                        .batch
+                        .insert_address_transaction(&address, position, &entry);
+                    updates.last_appended.insert(address, entry.clone());
+                    addresses.push(address);
+                }
+            }
+        }
+        updates.batch.insert_address_transaction_epoch(
+            epoch_number,
+            &AddressTransactionEpoch {
+                epoch_hash: *epoch_hash,
+                addresses,
+            },
+        );
+        progress.next_epoch = epoch_number + 1;
+
+        // Always keep the latest epoch so that it can be rolled back.
+        if let Some(count) = self.config.address_tx_index_maintained_epoch_count
+        {
+            let first_kept =
+                progress.next_epoch.saturating_sub(count.max(1) as u64);
+            for epoch in progress.first_epoch..first_kept {
+                self.prune_address_transaction_epoch(epoch, &mut updates);
+            }
+            progress.first_epoch = progress.first_epoch.max(first_kept);
+        }
+
+        let AddressTransactionUpdates {
+            mut batch, ranges, ..
+        } = updates;
+        for (address, range) in &ranges {
+            batch.insert_address_transaction_range(address, range);
+        }
+        batch.insert_address_transaction_index_progress(&progress);
+        db_manager.write_address_transaction_batch(batch);
+    }
+
+    /// Return the total number of the indexed transactions of `address`, and
+    /// at most `limit` of them from the newest one after skipping `skip`
+    /// ones. Return `None` if the index is not enabled.
+    pub fn address_transactions(
+        &self, address: &Address, skip: u64, limit: u64,
+    ) -> Option<(u64, Vec<AddressTransaction>)> {
+        if !self.config.persist_address_tx_index {
+            return None;
+        }
+        let range =
+            match self.db_manager.address_transaction_range_from_db(address) {
+                Some(range) => range,
+                None => return Some((0, Vec::new())),
+            };
+        let total = range.end - range.start;
+        let end = range.end.saturating_sub(skip).max(range.start);
+        let start = end.saturating_sub(limit).max(range.start);
+        let transactions = (start..end)
+            .rev()
+            .filter_map(|position| {
+                self.db_manager
+                    .address_transaction_from_db(address, position)
+            })
+            .collect();
+        Some((total, transactions))
+    }
+
+    /// Whether `entry` is already the last indexed transaction of `address`,
+    /// so that indexing the same transaction again does not duplicate it.
+    fn is_address_transaction_indexed(
+        &self, address: &Address, entry: &AddressTransaction,
+        updates: &mut AddressTransactionUpdates,
+    ) -> bool
+    {
+        let last = match updates.last_appended.get(address) {
+            Some(last) => Some(last.clone()),
+            None => {
+                let range = updates.range(&self.db_manager, address);
+                if range.end > range.start {
+                    let position = range.end - 1;
+                    self.db_manager
+                        .address_transaction_from_db(address, position)
+                } else {
+                    None
+                }
+            }
+        };
+        match last {
+            Some(last) => {
+                last.epoch_number == entry.epoch_number
+                    && last.block_hash == entry.block_hash
+                    && last.index == entry.index
+            }
+            None => false,
+        }
+    }
+
+    fn rollback_address_transaction_epoch(
+        &self, epoch: u64, updates: &mut AddressTransactionUpdates,
+    ) {
+        let indexed =
+            match self.db_manager.address_transaction_epoch_from_db(epoch) {
+                Some(indexed) => indexed,
+                None => return,
+            };
+        for address in indexed.addresses.iter().rev() {
+            let range = updates.range(&self.db_manager, address);
+            if range.end > range.start {
+                range.end -= 1;
+                let position = range.end;
+                updates.batch.remove_address_transaction(address, position);
+            }
+        }
+        updates.batch.remove_address_transaction_epoch(epoch);
+    }
+
+    /// The entries of an address are appended in epoch order, so the entries
+    /// of the oldest epoch are at the start of its range.
+    fn prune_address_transaction_epoch(
+        &self, epoch: u64, updates: &mut AddressTransactionUpdates,
+    ) {
+        let indexed =
+            match self.db_manager.address_transaction_epoch_from_db(epoch) {
+                Some(indexed) => indexed,
+                None => return,
+            };
+        for address in &indexed.addresses {
+            let range = updates.range(&self.db_manager, address);
+            if range.start < range.end {
+                let position = range.start;
+                range.start += 1;
+                updates.batch.remove_address_transaction(address, position);
+            }
+        }
+        updates.batch.remove_address_transaction_epoch(epoch);
+    }
+}
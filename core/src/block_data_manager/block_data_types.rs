@@ -1,6 +1,6 @@
 use crate::trace::trace::BlockExecTraces;
 use cfx_internal_common::{DatabaseDecodable, DatabaseEncodable};
-use cfx_types::{Bloom, H256, U256};
+use cfx_types::{Address, Bloom, H256, U256};
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use primitives::BlockReceipts;
@@ -323,6 +323,60 @@ impl MallocSizeOf for BlamedHeaderVerifiedRoots {
     fn size_of(&self, _ops: &mut MallocSizeOfOps) -> usize { 0 }
 }
 
+/// The header sync progress stored on disk on light nodes: the pivot chain is
+/// verified up to the header at `height`, and the correct roots of the blamed
+/// headers up to it are stored.
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct LightSyncProgress {
+    pub height: u64,
+    pub pivot_hash: H256,
+}
+
+/// A transaction sent or received by an address, stored in the optional
+/// address transaction index.
+#[derive(Clone, Debug, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct AddressTransaction {
+    pub transaction_hash: H256,
+    pub block_hash: H256,
+    /// The position of the transaction in the block.
+    pub index: u64,
+    pub epoch_number: u64,
+}
+
+/// The positions of the indexed transactions of an address. The entries in
+/// `[start, end)` are kept in the database, and the older ones have been
+/// garbage collected.
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct AddressTransactionRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The addresses whose indexed transactions are appended when an epoch is
+/// indexed. An address appears once for each appended entry, so the epoch can
+/// be rolled back by popping these entries.
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct AddressTransactionEpoch {
+    pub epoch_hash: H256,
+    pub addresses: Vec<Address>,
+}
+
+/// The epochs kept in an epoch-based index (the address transaction index or
+/// the log index) are in `[first_epoch, next_epoch)`.
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct EpochIndexProgress {
+    pub first_epoch: u64,
+    pub next_epoch: u64,
+}
+
+/// The logs bloom of a pivot epoch stored in the log index. The bits of
+/// `bloom` are the bits of this epoch in the bloom bits rows of its section.
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct LogIndexEpoch {
+    pub epoch_hash: H256,
+    pub bloom: Bloom,
+}
+
 pub fn db_encode_list<T>(list: &[T]) -> Vec<u8>
 where T: DatabaseEncodable {
     let mut rlp_stream = RlpStream::new();
@@ -349,3 +403,9 @@ impl_db_encoding_as_rlp!(CheckpointHashes);
 impl_db_encoding_as_rlp!(EpochExecutionContext);
 impl_db_encoding_as_rlp!(BlockRewardResult);
 impl_db_encoding_as_rlp!(BlamedHeaderVerifiedRoots);
+impl_db_encoding_as_rlp!(LightSyncProgress);
+impl_db_encoding_as_rlp!(AddressTransaction);
+impl_db_encoding_as_rlp!(AddressTransactionRange);
+impl_db_encoding_as_rlp!(AddressTransactionEpoch);
+impl_db_encoding_as_rlp!(EpochIndexProgress);
+impl_db_encoding_as_rlp!(LogIndexEpoch);
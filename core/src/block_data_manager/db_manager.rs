@@ -1,12 +1,15 @@
 use crate::{
     block_data_manager::{
-        db_decode_list, db_encode_list, BlamedHeaderVerifiedRoots,
-        BlockExecutionResultWithEpoch, BlockRewardResult, BlockTracesWithEpoch,
-        CheckpointHashes, EpochExecutionContext, LocalBlockInfo,
+        db_decode_list, db_encode_list, AddressTransaction,
+        AddressTransactionEpoch, AddressTransactionRange,
+        BlamedHeaderVerifiedRoots, BlockExecutionResultWithEpoch,
+        BlockRewardResult, BlockTracesWithEpoch, CheckpointHashes,
+        EpochExecutionContext, EpochIndexProgress, LightSyncProgress,
+        LocalBlockInfo, LogIndexEpoch,
     },
     db::{
         COL_BLAMED_HEADER_VERIFIED_ROOTS, COL_BLOCKS, COL_BLOCK_TRACES,
-        COL_EPOCH_NUMBER, COL_MISC, COL_TX_INDEX,
+        COL_EPOCH_NUMBER, COL_LOG_INDEX, COL_MISC, COL_TX_INDEX,
     },
     pow::PowComputer,
     verification::VerificationConfig,
@@ -16,13 +19,17 @@ use cfx_internal_common::{
     DatabaseDecodable, DatabaseEncodable, EpochExecutionCommitment,
 };
 use cfx_storage::{
-    storage_db::KeyValueDbTrait, KvdbRocksdb, KvdbSqlite, KvdbSqliteStatements,
+    storage_db::{
+        KeyValueDbAsAnyTrait, KeyValueDbTrait, KeyValueDbTraitSingleWriter,
+        KeyValueDbTraitTransactionalDyn,
+    },
+    KvdbRocksdb, KvdbSqlite, KvdbSqliteStatements,
 };
-use cfx_types::H256;
+use cfx_types::{Address, H256};
 use db::SystemDB;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use primitives::{Block, BlockHeader, SignedTransaction, TransactionIndex};
-use rlp::Rlp;
+use rlp::{DecoderError, Rlp};
 use std::{collections::HashMap, fs, path::Path, sync::Arc};
 
 const LOCAL_BLOCK_INFO_SUFFIX_BYTE: u8 = 1;
@@ -33,8 +40,18 @@ const EPOCH_CONSENSUS_EXECUTION_INFO_SUFFIX_BYTE: u8 = 5;
 const EPOCH_EXECUTED_BLOCK_SET_SUFFIX_BYTE: u8 = 6;
 const EPOCH_SKIPPED_BLOCK_SET_SUFFIX_BYTE: u8 = 7;
 const BLOCK_REWARD_RESULT_SUFFIX_BYTE: u8 = 8;
+const ADDRESS_TRANSACTION_SUFFIX_BYTE: u8 = 9;
+const ADDRESS_TRANSACTION_RANGE_SUFFIX_BYTE: u8 = 10;
+const ADDRESS_TRANSACTION_EPOCH_SUFFIX_BYTE: u8 = 11;
+const LOG_INDEX_EPOCH_SUFFIX_BYTE: u8 = 12;
+const LOG_INDEX_BITS_SUFFIX_BYTE: u8 = 13;
 const BLOCK_TERMINAL_KEY: &[u8] = b"block_terminals";
 const GC_PROGRESS_KEY: &[u8] = b"gc_progress";
+const ADDRESS_TRANSACTION_INDEX_PROGRESS_KEY: &[u8] =
+    b"address_tx_index_progress";
+const LOG_INDEX_PROGRESS_KEY: &[u8] = b"log_index_progress";
+const LOG_INDEX_PRUNED_KEY: &[u8] = b"log_index_pruned";
+const LIGHT_SYNC_PROGRESS_KEY: &[u8] = b"light_sync_progress";
 
 #[derive(Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
 enum DBTable {
@@ -44,6 +61,7 @@ enum DBTable {
     EpochNumbers,
     BlamedHeaderVerifiedRoots,
     BlockTraces,
+    LogIndex,
 }
 
 fn rocks_db_col(table: DBTable) -> u32 {
@@ -54,6 +72,7 @@ fn rocks_db_col(table: DBTable) -> u32 {
         DBTable::EpochNumbers => COL_EPOCH_NUMBER,
         DBTable::BlamedHeaderVerifiedRoots => COL_BLAMED_HEADER_VERIFIED_ROOTS,
         DBTable::BlockTraces => COL_BLOCK_TRACES,
+        DBTable::LogIndex => COL_LOG_INDEX,
     }
 }
 
@@ -65,12 +84,84 @@ fn sqlite_db_table(table: DBTable) -> String {
         DBTable::EpochNumbers => "epoch_numbers",
         DBTable::BlamedHeaderVerifiedRoots => "blamed_header_verified_roots",
         DBTable::BlockTraces => "block_traces",
+        DBTable::LogIndex => "log_index",
     }
     .into()
 }
 
+/// A table that can also be written in a transaction.
+trait TableDb:
+    KeyValueDbTrait<ValueType = Box<[u8]>>
+    + KeyValueDbTraitTransactionalDyn<ValueType = Box<[u8]>>
+{
+}
+
+impl<T> TableDb for T where T: KeyValueDbTrait<ValueType = Box<[u8]>>
+        + KeyValueDbTraitTransactionalDyn<ValueType = Box<[u8]>>
+{
+}
+
+/// The updates of the address transaction index, which are written together
+/// by `DBManager::write_address_transaction_batch`.
+#[derive(Default)]
+pub struct AddressTransactionBatch {
+    /// (key, value) pairs in the transaction table, where `None` values are
+    /// removed.
+    ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl AddressTransactionBatch {
+    pub fn insert_address_transaction(
+        &mut self, address: &Address, position: u64, value: &AddressTransaction,
+    ) {
+        self.ops.push((
+            address_transaction_key(address, position),
+            Some(value.db_encode()),
+        ));
+    }
+
+    pub fn remove_address_transaction(
+        &mut self, address: &Address, position: u64,
+    ) {
+        self.ops
+            .push((address_transaction_key(address, position), None));
+    }
+
+    pub fn insert_address_transaction_range(
+        &mut self, address: &Address, value: &AddressTransactionRange,
+    ) {
+        self.ops.push((
+            address_transaction_range_key(address),
+            Some(value.db_encode()),
+        ));
+    }
+
+    pub fn insert_address_transaction_epoch(
+        &mut self, epoch: u64, value: &AddressTransactionEpoch,
+    ) {
+        self.ops.push((
+            address_transaction_epoch_key(epoch).to_vec(),
+            Some(value.db_encode()),
+        ));
+    }
+
+    pub fn remove_address_transaction_epoch(&mut self, epoch: u64) {
+        self.ops
+            .push((address_transaction_epoch_key(epoch).to_vec(), None));
+    }
+
+    pub fn insert_address_transaction_index_progress(
+        &mut self, progress: &EpochIndexProgress,
+    ) {
+        self.ops.push((
+            ADDRESS_TRANSACTION_INDEX_PROGRESS_KEY.to_vec(),
+            Some(progress.db_encode()),
+        ));
+    }
+}
+
 pub struct DBManager {
-    table_db: HashMap<DBTable, Box<dyn KeyValueDbTrait<ValueType = Box<[u8]>>>>,
+    table_db: HashMap<DBTable, Box<dyn TableDb>>,
     pow: Arc<PowComputer>,
 }
 
@@ -84,14 +175,14 @@ impl DBManager {
             DBTable::EpochNumbers,
             DBTable::BlamedHeaderVerifiedRoots,
             DBTable::BlockTraces,
+            DBTable::LogIndex,
         ] {
             table_db.insert(
                 table,
                 Box::new(KvdbRocksdb {
                     kvdb: db.key_value().clone(),
                     col: rocks_db_col(table),
-                })
-                    as Box<dyn KeyValueDbTrait<ValueType = Box<[u8]>>>,
+                }) as Box<dyn TableDb>,
             );
         }
         Self { table_db, pow }
@@ -111,6 +202,7 @@ impl DBManager {
             DBTable::EpochNumbers,
             DBTable::BlamedHeaderVerifiedRoots,
             DBTable::BlockTraces,
+            DBTable::LogIndex,
         ] {
             let table_str = sqlite_db_table(table);
             let (_, sqlite_db) = KvdbSqlite::open_or_create(
@@ -129,11 +221,7 @@ impl DBManager {
                 false, /* unsafe_mode */
             )
             .expect("Open sqlite failure");
-            table_db.insert(
-                table,
-                Box::new(sqlite_db)
-                    as Box<dyn KeyValueDbTrait<ValueType = Box<[u8]>>>,
-            );
+            table_db.insert(table, Box::new(sqlite_db) as Box<dyn TableDb>);
         }
         Self { table_db, pow }
     }
@@ -468,6 +556,211 @@ impl DBManager {
         self.load_decodable_val(DBTable::Misc, GC_PROGRESS_KEY)
     }
 
+    /// The address transaction index, including its progress, is stored in
+    /// the transaction table. Its keys do not collide with transaction hashes
+    /// because of their lengths. All the updates made when an epoch is
+    /// indexed are written in one transaction.
+    pub fn write_address_transaction_batch(
+        &self, batch: AddressTransactionBatch,
+    ) {
+        let table = self.table_db.get(&DBTable::Transactions).unwrap();
+        let mut transaction = table
+            .start_transaction_dyn(true /* immediate_write */)
+            .expect("db transaction failure");
+        for (key, value) in &batch.ops {
+            match value {
+                Some(value) => transaction.put(key, value),
+                None => transaction.delete(key),
+            }
+            .expect("db insertion failure");
+        }
+        transaction
+            .commit(table.as_any())
+            .expect("db transaction failure");
+    }
+
+    pub fn address_transaction_from_db(
+        &self, address: &Address, position: u64,
+    ) -> Option<AddressTransaction> {
+        self.load_decodable_val(
+            DBTable::Transactions,
+            &address_transaction_key(address, position),
+        )
+    }
+
+    pub fn address_transaction_range_from_db(
+        &self, address: &Address,
+    ) -> Option<AddressTransactionRange> {
+        self.load_decodable_val(
+            DBTable::Transactions,
+            &address_transaction_range_key(address),
+        )
+    }
+
+    pub fn address_transaction_epoch_from_db(
+        &self, epoch: u64,
+    ) -> Option<AddressTransactionEpoch> {
+        self.load_decodable_val(
+            DBTable::Transactions,
+            &address_transaction_epoch_key(epoch),
+        )
+    }
+
+    pub fn address_transaction_index_progress_from_db(
+        &self,
+    ) -> Option<EpochIndexProgress> {
+        self.load_decodable_val(
+            DBTable::Transactions,
+            ADDRESS_TRANSACTION_INDEX_PROGRESS_KEY,
+        )
+    }
+
+    pub fn insert_log_index_epoch_to_db(
+        &self, epoch: u64, value: &LogIndexEpoch,
+    ) {
+        self.insert_encodable_val(
+            DBTable::LogIndex,
+            &log_index_epoch_key(epoch),
+            value,
+        )
+    }
+
+    pub fn log_index_epoch_from_db(&self, epoch: u64) -> Option<LogIndexEpoch> {
+        self.load_decodable_val(DBTable::LogIndex, &log_index_epoch_key(epoch))
+    }
+
+    pub fn remove_log_index_epoch_from_db(&self, epoch: u64) {
+        self.remove_from_db(DBTable::LogIndex, &log_index_epoch_key(epoch))
+    }
+
+    /// The bloom bits row of `bit` in `section` is a bitmap with one bit for
+    /// each epoch in the section.
+    pub fn insert_log_index_bits_to_db(
+        &self, section: u64, bit: u16, bits: Vec<u8>,
+    ) {
+        self.insert_to_db(
+            DBTable::LogIndex,
+            &log_index_bits_key(section, bit),
+            bits,
+        )
+    }
+
+    pub fn log_index_bits_from_db(
+        &self, section: u64, bit: u16,
+    ) -> Option<Vec<u8>> {
+        self.load_from_db(DBTable::LogIndex, &log_index_bits_key(section, bit))
+            .map(Into::into)
+    }
+
+    pub fn remove_log_index_bits_from_db(&self, section: u64, bit: u16) {
+        self.remove_from_db(
+            DBTable::LogIndex,
+            &log_index_bits_key(section, bit),
+        )
+    }
+
+    pub fn insert_log_index_progress_to_db(
+        &self, progress: &EpochIndexProgress,
+    ) {
+        self.insert_encodable_val(
+            DBTable::Misc,
+            LOG_INDEX_PROGRESS_KEY,
+            progress,
+        );
+    }
+
+    pub fn log_index_progress_from_db(&self) -> Option<EpochIndexProgress> {
+        self.load_decodable_val(DBTable::Misc, LOG_INDEX_PROGRESS_KEY)
+    }
+
+    pub fn insert_log_index_pruned_to_db(&self, epoch: u64) {
+        self.insert_encodable_val(DBTable::Misc, LOG_INDEX_PRUNED_KEY, &epoch);
+    }
+
+    pub fn log_index_pruned_from_db(&self) -> Option<u64> {
+        self.load_decodable_val(DBTable::Misc, LOG_INDEX_PRUNED_KEY)
+    }
+
+    pub fn insert_light_sync_progress_to_db(
+        &self, progress: &LightSyncProgress,
+    ) {
+        self.insert_encodable_val(
+            DBTable::Misc,
+            LIGHT_SYNC_PROGRESS_KEY,
+            progress,
+        );
+    }
+
+    pub fn remove_light_sync_progress_from_db(&self) {
+        self.remove_from_db(DBTable::Misc, LIGHT_SYNC_PROGRESS_KEY)
+    }
+
+    /// The functions below read the data that nodes recover from after a
+    /// restart. Unlike the functions above, they return an error instead of
+    /// panicking if the stored value is corrupt.
+    pub fn light_sync_progress_from_db(
+        &self,
+    ) -> Result<Option<LightSyncProgress>, DecoderError> {
+        self.try_load_decodable_val(DBTable::Misc, LIGHT_SYNC_PROGRESS_KEY)
+    }
+
+    pub fn checked_terminals_from_db(
+        &self,
+    ) -> Result<Option<Vec<H256>>, DecoderError> {
+        match self.load_from_db(DBTable::Misc, BLOCK_TERMINAL_KEY) {
+            None => Ok(None),
+            Some(encoded) => db_decode_list(&encoded).map(Some),
+        }
+    }
+
+    pub fn checked_block_header_from_db(
+        &self, hash: &H256,
+    ) -> Result<Option<BlockHeader>, DecoderError> {
+        let mut block_header = match self
+            .try_load_decodable_val::<BlockHeader>(
+                DBTable::Blocks,
+                hash.as_bytes(),
+            )? {
+            Some(block_header) => block_header,
+            None => return Ok(None),
+        };
+        // the hash is computed from the decoded fields
+        if block_header.hash() != *hash {
+            return Err(DecoderError::Custom("block header hash mismatch"));
+        }
+        VerificationConfig::get_or_fill_header_pow_quality(
+            &self.pow,
+            &mut block_header,
+        );
+        Ok(Some(block_header))
+    }
+
+    pub fn checked_local_block_info_from_db(
+        &self, block_hash: &H256,
+    ) -> Result<Option<LocalBlockInfo>, DecoderError> {
+        self.try_load_decodable_val(
+            DBTable::Blocks,
+            &local_block_info_key(block_hash),
+        )
+    }
+
+    pub fn checked_blamed_header_verified_roots_from_db(
+        &self, block_height: u64,
+    ) -> Result<Option<BlamedHeaderVerifiedRoots>, DecoderError> {
+        self.try_load_decodable_val(
+            DBTable::BlamedHeaderVerifiedRoots,
+            &blamed_header_verified_roots_key(block_height),
+        )
+    }
+
+    /// Overwrite the light sync progress and the header of `hash` with values
+    /// that cannot be decoded.
+    #[cfg(test)]
+    pub fn corrupt_light_sync_data(&self, hash: &H256) {
+        self.insert_to_db(DBTable::Misc, LIGHT_SYNC_PROGRESS_KEY, vec![0xff]);
+        self.insert_to_db(DBTable::Blocks, hash.as_bytes(), vec![0xff]);
+    }
+
     /// The functions below are private utils used by the DBManager to access
     /// database
     fn insert_to_db(&self, table: DBTable, db_key: &[u8], value: Vec<u8>) {
@@ -514,6 +807,16 @@ impl DBManager {
         Some(V::db_decode(&encoded).expect("decode succeeds"))
     }
 
+    fn try_load_decodable_val<V>(
+        &self, table: DBTable, db_key: &[u8],
+    ) -> Result<Option<V>, DecoderError>
+    where V: DatabaseDecodable {
+        match self.load_from_db(table, db_key) {
+            None => Ok(None),
+            Some(encoded) => V::db_decode(&encoded).map(Some),
+        }
+    }
+
     fn load_decodable_list<V>(
         &self, table: DBTable, db_key: &[u8],
     ) -> Option<Vec<V>>
@@ -574,6 +877,45 @@ fn epoch_consensus_epoch_execution_commitment_key(hash: &H256) -> Vec<u8> {
     append_suffix(hash, EPOCH_CONSENSUS_EXECUTION_INFO_SUFFIX_BYTE)
 }
 
+fn address_transaction_key(address: &Address, position: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(Address::len_bytes() + 9);
+    key.extend_from_slice(address.as_bytes());
+    let mut position_key = [0; 8];
+    LittleEndian::write_u64(&mut position_key, position);
+    key.extend_from_slice(&position_key);
+    key.push(ADDRESS_TRANSACTION_SUFFIX_BYTE);
+    key
+}
+
+fn address_transaction_range_key(address: &Address) -> Vec<u8> {
+    let mut key = Vec::with_capacity(Address::len_bytes() + 1);
+    key.extend_from_slice(address.as_bytes());
+    key.push(ADDRESS_TRANSACTION_RANGE_SUFFIX_BYTE);
+    key
+}
+
+fn address_transaction_epoch_key(epoch_number: u64) -> [u8; 9] {
+    let mut epoch_key = [0; 9];
+    LittleEndian::write_u64(&mut epoch_key[0..8], epoch_number);
+    epoch_key[8] = ADDRESS_TRANSACTION_EPOCH_SUFFIX_BYTE;
+    epoch_key
+}
+
+fn log_index_epoch_key(epoch_number: u64) -> [u8; 9] {
+    let mut epoch_key = [0; 9];
+    LittleEndian::write_u64(&mut epoch_key[0..8], epoch_number);
+    epoch_key[8] = LOG_INDEX_EPOCH_SUFFIX_BYTE;
+    epoch_key
+}
+
+fn log_index_bits_key(section: u64, bit: u16) -> [u8; 11] {
+    let mut bits_key = [0; 11];
+    LittleEndian::write_u64(&mut bits_key[0..8], section);
+    LittleEndian::write_u16(&mut bits_key[8..10], bit);
+    bits_key[10] = LOG_INDEX_BITS_SUFFIX_BYTE;
+    bits_key
+}
+
 impl MallocSizeOf for DBManager {
     fn size_of(&self, ops: &mut MallocSizeOfOps) -> usize {
         // Here we only handle the case that all columns are stored within the
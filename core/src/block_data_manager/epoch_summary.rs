@@ -0,0 +1,180 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{BlockDataManager, BlockRewardResult};
+use cfx_parameters::staking::DRIPS_PER_STORAGE_COLLATERAL_UNIT;
+use cfx_types::{H256, U256};
+use primitives::receipt::{
+    BlockReceipts, StorageChange,
+    TRANSACTION_OUTCOME_EXCEPTION_WITHOUT_NONCE_BUMPING,
+};
+use std::sync::Arc;
+
+/// The maximum number of epoch summaries cached in memory.
+pub const EPOCH_SUMMARY_CACHE_SIZE: usize = 10_000;
+
+/// The aggregates of the execution results of a pivot epoch.
+#[derive(Clone, Debug)]
+pub struct EpochSummary {
+    pub epoch_number: u64,
+    pub pivot_hash: H256,
+    pub timestamp: u64,
+    pub block_count: u64,
+    /// The number of the executed transactions, including the failed ones
+    /// but not the skipped ones.
+    pub transaction_count: u64,
+    pub gas_used: U256,
+    /// The gas fees charged for the executed transactions.
+    pub total_fee: U256,
+    /// The storage collateral deposited by the transactions, in Drip.
+    pub storage_collateralized: U256,
+    /// The storage collateral refunded by the transactions, in Drip.
+    pub storage_released: U256,
+    /// The sum of the rewards of the blocks. It is `None` if the rewards are
+    /// not computed yet, which happens `REWARD_EPOCH_COUNT` epochs later.
+    pub rewards: Option<BlockRewardResult>,
+}
+
+impl EpochSummary {
+    /// Aggregate the receipts of the blocks in a pivot epoch. The rewards
+    /// are left as `None`.
+    fn new(
+        epoch_number: u64, pivot_hash: H256, timestamp: u64,
+        block_count: usize, epoch_receipts: &[Arc<BlockReceipts>],
+    ) -> Self
+    {
+        let mut summary = EpochSummary {
+            epoch_number,
+            pivot_hash,
+            timestamp,
+            block_count: block_count as u64,
+            transaction_count: 0,
+            gas_used: U256::zero(),
+            total_fee: U256::zero(),
+            storage_collateralized: U256::zero(),
+            storage_released: U256::zero(),
+            rewards: None,
+        };
+
+        let collateral = |changes: &Vec<StorageChange>| {
+            changes.iter().fold(U256::zero(), |sum, change| {
+                sum + U256::from(change.collaterals.as_u64())
+                    * *DRIPS_PER_STORAGE_COLLATERAL_UNIT
+            })
+        };
+        for receipts in epoch_receipts {
+            if let Some(last) = receipts.receipts.last() {
+                summary.gas_used += last.accumulated_gas_used;
+            }
+            for receipt in &receipts.receipts {
+                if receipt.outcome_status
+                    == TRANSACTION_OUTCOME_EXCEPTION_WITHOUT_NONCE_BUMPING
+                {
+                    continue;
+                }
+                summary.transaction_count += 1;
+                summary.total_fee += receipt.gas_fee;
+                summary.storage_collateralized +=
+                    collateral(&receipt.storage_collateralized);
+                summary.storage_released +=
+                    collateral(&receipt.storage_released);
+            }
+        }
+        summary
+    }
+}
+
+impl BlockDataManager {
+    /// Cache the summary of a pivot epoch that has just been executed, if
+    /// `cache_epoch_summary` is enabled. Otherwise, the summaries are computed
+    /// from the stored receipts when they are requested. The rewards are not
+    /// cached because they are computed later.
+    pub fn cache_epoch_summary(
+        &self, epoch_number: u64, epoch_hash: &H256, timestamp: u64,
+        epoch_receipts: &[Arc<BlockReceipts>],
+    )
+    {
+        if !self.config.cache_epoch_summary {
+            return;
+        }
+        let summary = EpochSummary::new(
+            epoch_number,
+            *epoch_hash,
+            timestamp,
+            epoch_receipts.len(),
+            epoch_receipts,
+        );
+        self.epoch_summaries.lock().insert(*epoch_hash, summary);
+    }
+
+    /// Return the summary of a pivot epoch, or `None` if its execution results
+    /// are not available.
+    pub fn epoch_summary(
+        &self, epoch_number: u64, epoch_hash: &H256,
+        epoch_block_hashes: &Vec<H256>,
+    ) -> Option<EpochSummary>
+    {
+        let cached = self.epoch_summaries.lock().get(epoch_hash).cloned();
+        let mut summary = match cached {
+            Some(summary) => summary,
+            None => {
+                let summary = self.compute_epoch_summary(
+                    epoch_number,
+                    epoch_hash,
+                    epoch_block_hashes,
+                )?;
+                if self.config.cache_epoch_summary {
+                    self.epoch_summaries
+                        .lock()
+                        .insert(*epoch_hash, summary.clone());
+                }
+                summary
+            }
+        };
+        summary.rewards = self.epoch_rewards(epoch_block_hashes);
+        Some(summary)
+    }
+
+    fn compute_epoch_summary(
+        &self, epoch_number: u64, epoch_hash: &H256,
+        epoch_block_hashes: &Vec<H256>,
+    ) -> Option<EpochSummary>
+    {
+        let pivot_header = self.block_header_by_hash(epoch_hash)?;
+        let mut epoch_receipts = Vec::with_capacity(epoch_block_hashes.len());
+        // The genesis block is not executed.
+        if epoch_number != 0 {
+            for block_hash in epoch_block_hashes {
+                epoch_receipts.push(
+                    self.block_execution_result_by_hash_with_epoch(
+                        block_hash, epoch_hash,
+                        false, /* update_pivot_assumption */
+                        false, /* update_cache */
+                    )?
+                    .block_receipts,
+                );
+            }
+        }
+        Some(EpochSummary::new(
+            epoch_number,
+            *epoch_hash,
+            pivot_header.timestamp(),
+            epoch_block_hashes.len(),
+            &epoch_receipts,
+        ))
+    }
+
+    fn epoch_rewards(
+        &self, epoch_block_hashes: &Vec<H256>,
+    ) -> Option<BlockRewardResult> {
+        let mut rewards = BlockRewardResult::default();
+        for block_hash in epoch_block_hashes {
+            let reward = self.block_reward_result_by_hash(block_hash)?;
+            rewards.total_reward += reward.total_reward;
+            rewards.base_reward += reward.base_reward;
+            rewards.tx_fee += reward.tx_fee;
+        }
+        Some(rewards)
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Light nodes keep the verified header graph, the correct roots of blamed
+//! headers and their sync progress on disk, so that they can resume from them
+//! after a restart instead of syncing again from the checkpoint.
+//!
+//! The headers are recovered by `SynchronizationGraph::recover_graph_from_db`,
+//! which skips the corrupt ones. The data below is read without panicking on
+//! corrupt values, as it is only a hint: the restored roots are validated
+//! against the recovered witness headers before being used.
+
+use super::{BlamedHeaderVerifiedRoots, BlockDataManager, LightSyncProgress};
+
+impl BlockDataManager {
+    pub fn insert_light_sync_progress(&self, progress: &LightSyncProgress) {
+        self.db_manager.insert_light_sync_progress_to_db(progress);
+    }
+
+    /// Get the sync progress stored before the last restart, or `None` if it
+    /// was made before the current era genesis. Corrupt progress is removed,
+    /// so that the node syncs again from peers.
+    pub fn light_sync_progress(&self) -> Option<LightSyncProgress> {
+        let progress = match self.db_manager.light_sync_progress_from_db() {
+            Ok(progress) => progress?,
+            Err(e) => {
+                warn!("Remove corrupt light sync progress: {:?}", e);
+                self.db_manager.remove_light_sync_progress_from_db();
+                return None;
+            }
+        };
+
+        let genesis_hash = self.get_cur_consensus_era_genesis_hash();
+        let genesis_height = self.block_header_by_hash(&genesis_hash)?.height();
+
+        // the progress was made before the last checkpoint
+        if progress.height < genesis_height {
+            return None;
+        }
+
+        Some(progress)
+    }
+
+    /// Get the roots of the header blamed at `height` stored before the last
+    /// restart, or `None` if they are missing or corrupt.
+    pub fn restored_blamed_roots_by_height(
+        &self, height: u64,
+    ) -> Option<BlamedHeaderVerifiedRoots> {
+        match self
+            .db_manager
+            .checked_blamed_header_verified_roots_from_db(height)
+        {
+            Ok(roots) => roots,
+            Err(e) => {
+                warn!("Ignore corrupt roots at height {}: {:?}", height, e);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! The log index stores the logs bloom of each pivot epoch as bloom bits:
+//! the epochs are grouped into sections, and for each section and each bit of
+//! the bloom, a row keeps one bit for every epoch in the section. To find the
+//! epochs whose blooms may contain a bloom, we only need to AND the rows of the
+//! bits set in that bloom, instead of loading the receipts of every epoch.
+
+use super::{BlockDataManager, EpochIndexProgress, LogIndexEpoch};
+use cfx_types::{Bloom, H256};
+use std::{
+    cmp::{max, min},
+    collections::HashMap,
+};
+
+/// The number of epochs in a section of the log index.
+const LOG_INDEX_SECTION_SIZE: u64 = 4096;
+const LOG_INDEX_ROW_BYTES: usize = (LOG_INDEX_SECTION_SIZE / 8) as usize;
+
+impl BlockDataManager {
+    /// Index the logs bloom of a pivot epoch.
+    ///
+    /// If the epoch has been indexed with another pivot block, its bloom is
+    /// replaced, and the epochs after it are not used until they are indexed
+    /// again with the new pivot chain.
+    pub fn index_epoch_logs(
+        &self, epoch_number: u64, epoch_hash: &H256,
+        epoch_block_hashes: &Vec<H256>,
+    )
+    {
+        if !self.config.persist_log_index {
+            return;
+        }
+        let _lock = self.log_index_lock.lock();
+        let mut progress = self
+            .db_manager
+            .log_index_progress_from_db()
+            .unwrap_or(EpochIndexProgress {
+                first_epoch: epoch_number,
+                next_epoch: epoch_number,
+            });
+        // Restart the index if it cannot be extended with this epoch.
+        if epoch_number < progress.first_epoch
+            || epoch_number > progress.next_epoch
+        {
+            progress.first_epoch = epoch_number;
+            progress.next_epoch = epoch_number;
+        }
+
+        let indexed = self.db_manager.log_index_epoch_from_db(epoch_number);
+        match &indexed {
+            Some(indexed) if indexed.epoch_hash == *epoch_hash => {
+                progress.next_epoch =
+                    max(progress.next_epoch, epoch_number + 1);
+            }
+            _ => {
+                let mut bloom = Bloom::zero();
+                for block_hash in epoch_block_hashes {
+                    match self.block_execution_result_by_hash_with_epoch(
+                        block_hash, epoch_hash,
+                        false, /* update_pivot_assumption */
+                        false, /* update_cache */
+                    ) {
+                        Some(result) => bloom.accrue_bloom(&result.bloom),
+                        None => {
+                            warn!(
+                                "Execution result of block {:?} in epoch {:?} \
+                                 not found, skip indexing its logs",
+                                block_hash, epoch_hash
+                            );
+                            progress.next_epoch = epoch_number;
+                            self.db_manager
+                                .insert_log_index_progress_to_db(&progress);
+                            return;
+                        }
+                    }
+                }
+                let old_bloom =
+                    indexed.as_ref().map_or_else(Bloom::zero, |i| i.bloom);
+                self.update_log_index_bits(epoch_number, &old_bloom, &bloom);
+                self.db_manager.insert_log_index_epoch_to_db(
+                    epoch_number,
+                    &LogIndexEpoch {
+                        epoch_hash: *epoch_hash,
+                        bloom,
+                    },
+                );
+                progress.next_epoch = epoch_number + 1;
+            }
+        }
+        self.db_manager.insert_log_index_progress_to_db(&progress);
+    }
+
+    /// Return `(first, last, matched)`, where `[first, last]` are the epochs
+    /// in `[from_epoch, to_epoch]` covered by the log index, and `matched` are
+    /// the covered epochs, in ascending order, whose blooms contain any of
+    /// `bloom_possibilities`.
+    ///
+    /// Return `None` if the index is not enabled, if it covers no epoch in the
+    /// range, or if it is not built with the pivot chain given by
+    /// `pivot_hash_at`.
+    pub fn log_index_matching_epochs<F>(
+        &self, from_epoch: u64, to_epoch: u64, bloom_possibilities: &[Bloom],
+        pivot_hash_at: F,
+    ) -> Option<(u64, u64, Vec<u64>)>
+    where
+        F: Fn(u64) -> Option<H256>,
+    {
+        if !self.config.persist_log_index {
+            return None;
+        }
+        let progress = self.db_manager.log_index_progress_from_db()?;
+        if progress.next_epoch == 0 {
+            return None;
+        }
+        let first = max(from_epoch, progress.first_epoch);
+        let last = min(to_epoch, progress.next_epoch - 1);
+        if first > last {
+            return None;
+        }
+        // Reorgs re-index epochs in order, so if the last covered epoch is
+        // indexed with the current pivot block, so are the epochs before it.
+        let last_hash =
+            self.db_manager.log_index_epoch_from_db(last)?.epoch_hash;
+        if pivot_hash_at(last) != Some(last_hash) {
+            return None;
+        }
+
+        let possibilities: Vec<Vec<u16>> =
+            bloom_possibilities.iter().map(bloom_bits).collect();
+        let mut matched = Vec::new();
+        for section in
+            first / LOG_INDEX_SECTION_SIZE..=last / LOG_INDEX_SECTION_SIZE
+        {
+            let mut rows = HashMap::new();
+            let mut section_matched = vec![0u8; LOG_INDEX_ROW_BYTES];
+            for bits in &possibilities {
+                let mut candidates = vec![0xffu8; LOG_INDEX_ROW_BYTES];
+                for bit in bits {
+                    let row = rows.entry(*bit).or_insert_with(|| {
+                        self.db_manager.log_index_bits_from_db(section, *bit)
+                    });
+                    match row {
+                        Some(row) => {
+                            for (c, r) in candidates.iter_mut().zip(row.iter())
+                            {
+                                *c &= *r;
+                            }
+                        }
+                        None => {
+                            candidates = vec![0u8; LOG_INDEX_ROW_BYTES];
+                            break;
+                        }
+                    }
+                }
+                for (m, c) in section_matched.iter_mut().zip(candidates.iter())
+                {
+                    *m |= *c;
+                }
+            }
+
+            let section_start = section * LOG_INDEX_SECTION_SIZE;
+            let start = max(first, section_start);
+            let end = min(last, section_start + LOG_INDEX_SECTION_SIZE - 1);
+            for epoch in start..=end {
+                let pos = (epoch - section_start) as usize;
+                if section_matched[pos / 8] & (1 << (pos % 8)) != 0 {
+                    matched.push(epoch);
+                }
+            }
+        }
+
+        // The index may have been updated by a reorg during the query.
+        match self.db_manager.log_index_epoch_from_db(last) {
+            Some(indexed) if indexed.epoch_hash == last_hash => {
+                Some((first, last, matched))
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the epoch before which the log index has been pruned, or
+    /// `None` if the index is not enabled or nothing has been pruned. The log
+    /// filters cannot start before it.
+    pub fn log_index_pruned_epoch(&self) -> Option<u64> {
+        if !self.config.persist_log_index {
+            return None;
+        }
+        self.db_manager.log_index_pruned_from_db()
+    }
+
+    /// Remove an epoch from the log index. It is called when the execution
+    /// results of the epoch are garbage collected, so the epoch will not be
+    /// used in the log filters anymore, and the lower bound of the index is
+    /// advanced past it.
+    pub(super) fn remove_log_index_epoch(&self, epoch_number: u64) {
+        if !self.config.persist_log_index {
+            return;
+        }
+        let _lock = self.log_index_lock.lock();
+        let indexed =
+            match self.db_manager.log_index_epoch_from_db(epoch_number) {
+                Some(indexed) => indexed,
+                None => return,
+            };
+        self.update_log_index_bits(
+            epoch_number,
+            &indexed.bloom,
+            &Bloom::zero(),
+        );
+        self.db_manager.remove_log_index_epoch_from_db(epoch_number);
+
+        if let Some(mut progress) = self.db_manager.log_index_progress_from_db()
+        {
+            if progress.first_epoch <= epoch_number {
+                progress.first_epoch = epoch_number + 1;
+                progress.next_epoch =
+                    max(progress.next_epoch, progress.first_epoch);
+                self.db_manager.insert_log_index_progress_to_db(&progress);
+            }
+        }
+        let pruned = self.db_manager.log_index_pruned_from_db().unwrap_or(0);
+        if pruned <= epoch_number {
+            self.db_manager
+                .insert_log_index_pruned_to_db(epoch_number + 1);
+        }
+    }
+
+    fn update_log_index_bits(
+        &self, epoch_number: u64, old_bloom: &Bloom, new_bloom: &Bloom,
+    ) {
+        let section = epoch_number / LOG_INDEX_SECTION_SIZE;
+        let pos = (epoch_number % LOG_INDEX_SECTION_SIZE) as usize;
+        let new_bits = bloom_bits(new_bloom);
+        for bit in bloom_bits(&(*old_bloom | *new_bloom)) {
+            let is_set = new_bits.binary_search(&bit).is_ok();
+            let mut row = self
+                .db_manager
+                .log_index_bits_from_db(section, bit)
+                .unwrap_or_else(|| vec![0u8; LOG_INDEX_ROW_BYTES]);
+            if is_set {
+                row[pos / 8] |= 1 << (pos % 8);
+            } else {
+                row[pos / 8] &= !(1 << (pos % 8));
+            }
+            if row.iter().all(|b| *b == 0) {
+                self.db_manager.remove_log_index_bits_from_db(section, bit);
+            } else {
+                self.db_manager
+                    .insert_log_index_bits_to_db(section, bit, row);
+            }
+        }
+    }
+}
+
+/// Return the positions of the bits set in `bloom` in ascending order.
+fn bloom_bits(bloom: &Bloom) -> Vec<u16> {
+    let mut bits = Vec::new();
+    for (i, byte) in bloom.as_bytes().iter().enumerate() {
+        for j in 0..8 {
+            if byte & (1 << j) != 0 {
+                bits.push((i * 8 + j) as u16);
+            }
+        }
+    }
+    bits
+}
@@ -27,13 +27,17 @@ use primitives::{
 };
 use rlp::DecoderError;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
 };
 use threadpool::ThreadPool;
+mod address_tx_index;
 pub mod block_data_types;
 pub mod db_gc_manager;
 pub mod db_manager;
+mod epoch_summary;
+mod light_sync;
+mod log_index;
 pub mod tx_data_manager;
 use crate::{
     block_data_manager::{
@@ -46,6 +50,9 @@ use cfx_internal_common::{
     EpochExecutionCommitment, StateAvailabilityBoundary, StateRootWithAuxInfo,
 };
 use db_gc_manager::GCProgress;
+pub use epoch_summary::EpochSummary;
+use epoch_summary::EPOCH_SUMMARY_CACHE_SIZE;
+use lru_time_cache::LruCache;
 use metrics::{register_meter_with_group, Meter, MeterTimer};
 use std::{hash::Hash, path::Path, time::Duration};
 
@@ -139,6 +146,14 @@ pub struct BlockDataManager {
     cache_man: Arc<Mutex<CacheManager<CacheId>>>,
     pub target_difficulty_manager: TargetDifficultyManager,
     gc_progress: Arc<Mutex<GCProgress>>,
+    /// Serializes the updates of the log index from epoch execution and
+    /// database GC.
+    #[ignore_malloc_size_of = "nothing to measure"]
+    log_index_lock: Mutex<()>,
+    /// The summaries of the recently executed pivot epochs, indexed by their
+    /// pivot hashes.
+    #[ignore_malloc_size_of = "Add later"]
+    epoch_summaries: Mutex<LruCache<H256, EpochSummary>>,
 
     /// This maintains the boundary height of available state and commitments
     /// (executed but not deleted or in `ExecutionTaskQueue`).
@@ -227,6 +242,10 @@ impl BlockDataManager {
             gc_progress: Arc::new(Mutex::new(GCProgress::new(
                 previous_db_progress,
             ))),
+            log_index_lock: Default::default(),
+            epoch_summaries: Mutex::new(LruCache::with_capacity(
+                EPOCH_SUMMARY_CACHE_SIZE,
+            )),
         };
 
         data_man.initialize_instance_id();
@@ -591,6 +610,23 @@ impl BlockDataManager {
         )
     }
 
+    /// Like `block_header_by_hash`, except that an error is returned instead
+    /// of panicking if the header is corrupt in db.
+    pub fn checked_block_header_by_hash(
+        &self, hash: &H256,
+    ) -> Result<Option<Arc<BlockHeader>>, DecoderError> {
+        if let Some(header) = self.block_headers.read().get(hash) {
+            return Ok(Some(header.clone()));
+        }
+        let header = match self.db_manager.checked_block_header_from_db(hash)? {
+            Some(header) => Arc::new(header),
+            None => return Ok(None),
+        };
+        self.block_headers.write().insert(*hash, header.clone());
+        self.cache_man.lock().note_used(CacheId::BlockHeader(*hash));
+        Ok(Some(header))
+    }
+
     pub fn insert_block_header(
         &self, hash: H256, header: Arc<BlockHeader>, persistent: bool,
     ) {
@@ -943,6 +979,101 @@ impl BlockDataManager {
         self.db_manager.terminals_from_db()
     }
 
+    /// Traverse backward from the terminals stored in db the headers in the
+    /// future of the current era genesis, and call `f` with each of them.
+    /// Return the hashes of the headers that are missing or corrupt in db,
+    /// which need to be fetched from peers again. It is only used by light
+    /// nodes.
+    pub fn traverse_headers_from_db<F>(
+        &self, genesis_hash: &H256, genesis_seq_num: u64, mut f: F,
+    ) -> HashSet<H256>
+    where F: FnMut(BlockHeader) {
+        let mut missed_hashes = HashSet::new();
+
+        let terminals = match self.db_manager.checked_terminals_from_db() {
+            Ok(Some(terminals)) => terminals,
+            Ok(None) => return missed_hashes,
+            Err(e) => {
+                warn!("Ignore corrupt terminals in db: {:?}", e);
+                return missed_hashes;
+            }
+        };
+        debug!("Get terminals {:?}", terminals);
+
+        // This traversal will visit all the blocks under the future of
+        // current era genesis till the terminals. However, some blocks may
+        // not be graph-ready since they may have references or parents which
+        // are out of the current era.
+        let mut queue = VecDeque::new();
+        let mut visited_blocks: HashSet<H256> = HashSet::new();
+        for terminal in terminals {
+            // header terminals and block terminals may contain the same hash
+            if !visited_blocks.contains(&terminal) {
+                queue.push_back(terminal);
+                visited_blocks.insert(terminal);
+            }
+        }
+
+        while let Some(hash) = queue.pop_front() {
+            if hash == *genesis_hash {
+                // Genesis block is already in consensus graph.
+                continue;
+            }
+
+            // Ignore blocks beyond the future of current genesis era.
+            // If block_local_info is missing, consider it is in current
+            // genesis era.
+            match self.db_manager.checked_local_block_info_from_db(&hash) {
+                Ok(Some(block_local_info)) => {
+                    if block_local_info.get_seq_num() < genesis_seq_num {
+                        debug!(
+                            "Skip block {:?} before checkpoint: seq_num={}",
+                            hash,
+                            block_local_info.get_seq_num()
+                        );
+                        continue;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Ignore corrupt local info of {:?}: {:?}", hash, e);
+                    missed_hashes.insert(hash);
+                    continue;
+                }
+            }
+
+            let block_header = match self.checked_block_header_by_hash(&hash) {
+                Ok(Some(header)) => header,
+                Ok(None) => {
+                    missed_hashes.insert(hash);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Ignore corrupt header {:?}: {:?}", hash, e);
+                    missed_hashes.insert(hash);
+                    continue;
+                }
+            };
+
+            let parent = block_header.parent_hash().clone();
+            let referees = block_header.referee_hashes().clone();
+            f(block_header.as_ref().clone());
+
+            if !visited_blocks.contains(&parent) {
+                queue.push_back(parent);
+                visited_blocks.insert(parent);
+            }
+            for referee in referees {
+                if !visited_blocks.contains(&referee) {
+                    queue.push_back(referee);
+                    visited_blocks.insert(referee);
+                }
+            }
+        }
+
+        missed_hashes
+    }
+
     pub fn insert_executed_epoch_set_hashes_to_db(
         &self, epoch_number: u64, epoch_set: &Vec<H256>,
     ) {
@@ -1179,13 +1310,16 @@ impl BlockDataManager {
                     {
                         TRANSACTION_OUTCOME_SUCCESS
                         | TRANSACTION_OUTCOME_EXCEPTION_WITH_NONCE_BUMPING => {
-                            self.insert_transaction_index(
-                                &tx.hash,
-                                &TransactionIndex {
-                                    block_hash: *block_hash,
-                                    index: tx_idx,
-                                },
-                            )
+                            let tx_index = TransactionIndex {
+                                block_hash: *block_hash,
+                                index: tx_idx,
+                            };
+                            self.insert_transaction_index(&tx.hash, &tx_index);
+                            if let Some(eth_hash) = tx.eth_hash() {
+                                self.insert_transaction_index(
+                                    &eth_hash, &tx_index,
+                                );
+                            }
                         }
                         _ => {}
                     }
@@ -1569,6 +1703,15 @@ impl BlockDataManager {
                 .additional_maintained_execution_result_epoch_count,
             |h| self.remove_block_result(h, true /* remove_db */),
         );
+        // The log index is only used for epochs with execution results.
+        if let Some(defer_epochs) = self
+            .config
+            .additional_maintained_execution_result_epoch_count
+        {
+            if base_epoch > defer_epochs as u64 {
+                self.remove_log_index_epoch(base_epoch - defer_epochs as u64);
+            }
+        }
         self.gc_epoch_with_defer(
             base_epoch,
             self.config.additional_maintained_reward_epoch_count,
@@ -1620,6 +1763,13 @@ pub struct DataManagerConfiguration {
     pub additional_maintained_transaction_index_epoch_count: Option<usize>,
     pub checkpoint_gc_time_in_epoch_count: usize,
     pub strict_tx_index_gc: bool,
+    pub persist_address_tx_index: bool,
+    /// `None` means the address transaction index is never garbage collected.
+    pub address_tx_index_maintained_epoch_count: Option<usize>,
+    pub persist_log_index: bool,
+    /// Whether to cache the summaries of the pivot epochs when they are
+    /// executed, so that `cfx_getEpochSummary` needs not load their receipts.
+    pub cache_epoch_summary: bool,
 }
 
 impl MallocSizeOf for DataManagerConfiguration {
@@ -1643,6 +1793,10 @@ impl DataManagerConfiguration {
             additional_maintained_transaction_index_epoch_count: None,
             checkpoint_gc_time_in_epoch_count: 1,
             strict_tx_index_gc: true,
+            persist_address_tx_index: false,
+            address_tx_index_maintained_epoch_count: None,
+            persist_log_index: false,
+            cache_epoch_summary: false,
         }
     }
 }
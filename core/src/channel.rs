@@ -2,9 +2,10 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::UniqueId;
+use crate::{sync::SyncPhaseProgress, UniqueId};
 use cfx_types::H256;
 use parking_lot::RwLock;
+use primitives::SignedTransaction;
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
 use tokio::{runtime, sync::mpsc, time::timeout};
 
@@ -113,6 +114,11 @@ pub struct Notifications {
     pub new_block_hashes: Arc<Channel<H256>>,
     pub epochs_ordered: Arc<Channel<(u64, Vec<H256>)>>,
     pub blame_verification_results: Arc<Channel<(u64, Option<u64>)>>, /* <height, witness> */
+    /// Transactions newly accepted into the transaction pool, in batches.
+    pub new_pending_transactions: Arc<Channel<Vec<Arc<SignedTransaction>>>>,
+    pub sync_progress: Arc<Channel<SyncPhaseProgress>>,
+    /// Heights of the pivot headers verified by the light sync handler.
+    pub verified_header_heights: Arc<Channel<u64>>,
 }
 
 impl Notifications {
@@ -123,6 +129,13 @@ impl Notifications {
             blame_verification_results: Arc::new(Channel::new(
                 "blame-verification-results",
             )),
+            new_pending_transactions: Arc::new(Channel::new(
+                "new-pending-transactions",
+            )),
+            sync_progress: Arc::new(Channel::new("sync-progress")),
+            verified_header_heights: Arc::new(Channel::new(
+                "verified-header-heights",
+            )),
         })
     }
 }
@@ -9,11 +9,11 @@ use crate::{
             consensus_new_block_handler::ConsensusNewBlockHandler,
             StateBlameInfo,
         },
-        ConsensusGraphInner,
+        ConsensusGraphInner, EpochGasUsage, GasUsageCache,
     },
     executive::{
         revert_reason_decode, ExecutionError, ExecutionOutcome, Executive,
-        TransactOptions,
+        ExecutiveGeneric, TransactOptions,
     },
     machine::Machine,
     rpc_errors::{invalid_params_check, Result as RpcResult},
@@ -22,14 +22,18 @@ use crate::{
         prefetcher::{
             prefetch_accounts, ExecutionStatePrefetcher, PrefetchTaskHandle,
         },
-        State,
+        State, StateDiff, StateGeneric, StateOverride,
     },
-    trace::trace::{ExecTrace, TransactionExecTraces},
+    trace::{
+        trace::{ExecTrace, TransactionExecTraces},
+        Tracer,
+    },
+    transaction_pool::SkippedTransaction,
     verification::{
         compute_receipts_root, VerificationConfig, VerifyTxLocalMode,
         VerifyTxMode,
     },
-    vm::{Env, Error as VmErr},
+    vm::{Env, Error as VmErr, Spec},
     SharedTransactionPool,
 };
 use cfx_internal_common::{
@@ -37,10 +41,10 @@ use cfx_internal_common::{
 };
 use cfx_parameters::consensus::*;
 use cfx_state::{state_trait::*, CleanupMode};
-use cfx_statedb::{Result as DbResult, StateDb};
+use cfx_statedb::{Result as DbResult, StateDb, StateDbGeneric};
 use cfx_storage::{
     defaults::DEFAULT_EXECUTION_PREFETCH_THREADS, StateIndex,
-    StorageManagerTrait,
+    StorageManagerTrait, StorageStateTrait,
 };
 use cfx_types::{
     address_util::AddressUtil, BigEndianHash, H160, H256, KECCAK_EMPTY_BLOOM,
@@ -57,12 +61,12 @@ use primitives::{
         TRANSACTION_OUTCOME_EXCEPTION_WITH_NONCE_BUMPING,
         TRANSACTION_OUTCOME_SUCCESS,
     },
-    Action, Block, BlockHeaderBuilder, EpochId, SignedTransaction,
+    Action, Block, BlockHeader, BlockHeaderBuilder, EpochId, SignedTransaction,
     TransactionIndex, MERKLE_NULL_NODE,
 };
 use rustc_hex::ToHex;
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::From,
     fmt::{Debug, Formatter},
     sync::{
@@ -189,6 +193,7 @@ impl ConsensusExecutor {
         consensus_inner: Arc<RwLock<ConsensusGraphInner>>,
         config: ConsensusExecutionConfiguration,
         verification_config: VerificationConfig, bench_mode: bool,
+        gas_usage_cache: Option<Arc<GasUsageCache>>,
     ) -> Arc<Self>
     {
         let machine = tx_pool.machine();
@@ -198,6 +203,7 @@ impl ConsensusExecutor {
             config,
             verification_config,
             machine,
+            gas_usage_cache,
         ));
         let (sender, receiver) = channel();
 
@@ -619,8 +625,65 @@ impl ConsensusExecutor {
 
     pub fn call_virtual(
         &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
-    ) -> RpcResult<ExecutionOutcome> {
-        self.handler.call_virtual(tx, epoch_id, epoch_size)
+        state_override: Option<&StateOverride>,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        self.handler
+            .call_virtual(tx, epoch_id, epoch_size, state_override)
+    }
+
+    pub fn call_virtual_many(
+        &self, txs: &[SignedTransaction], carry_nonce: &[bool],
+        epoch_id: &H256, epoch_size: usize,
+        state_override: Option<&StateOverride>,
+    ) -> RpcResult<Vec<ExecutionOutcome>>
+    {
+        self.handler.call_virtual_many(
+            txs,
+            carry_nonce,
+            epoch_id,
+            epoch_size,
+            state_override,
+        )
+    }
+
+    pub fn call_virtual_with_state_diff(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+    ) -> RpcResult<(ExecutionOutcome, StateDiff)> {
+        self.handler
+            .call_virtual_with_state_diff(tx, epoch_id, epoch_size)
+    }
+
+    pub fn call_virtual_on_storage<Storage: StorageStateTrait>(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        state_override: Option<&StateOverride>, storage: Storage,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        self.handler.call_virtual_on_storage(
+            tx,
+            epoch_id,
+            epoch_size,
+            state_override,
+            storage,
+        )
+    }
+
+    pub fn replay_transaction<T>(
+        &self, pivot_hash: &H256, epoch_block_hashes: &Vec<H256>,
+        start_block_number: u64, tx_index: &TransactionIndex, tracer: T,
+        collect_state_diff: bool,
+    ) -> RpcResult<(ExecutionOutcome, Option<StateDiff>)>
+    where
+        T: Tracer<Output = ExecTrace>,
+    {
+        self.handler.replay_transaction(
+            pivot_hash,
+            epoch_block_hashes,
+            start_block_number,
+            tx_index,
+            tracer,
+            collect_state_diff,
+        )
     }
 
     pub fn stop(&self) {
@@ -792,6 +855,14 @@ impl ConsensusExecutor {
     }
 }
 
+/// The block environment shared by the transactions of a virtual call.
+struct VirtualCallContext {
+    best_block_header: Arc<BlockHeader>,
+    block_height: u64,
+    start_block_number: u64,
+    spec: Spec,
+}
+
 pub struct ConsensusExecutionHandler {
     tx_pool: SharedTransactionPool,
     data_man: Arc<BlockDataManager>,
@@ -799,6 +870,9 @@ pub struct ConsensusExecutionHandler {
     verification_config: VerificationConfig,
     machine: Arc<Machine>,
     execution_state_prefetcher: Option<Arc<ExecutionStatePrefetcher>>,
+    /// If it is set, the gas usage of the executed pivot epochs is collected
+    /// for the gas price oracle.
+    gas_usage_cache: Option<Arc<GasUsageCache>>,
 }
 
 impl ConsensusExecutionHandler {
@@ -806,6 +880,7 @@ impl ConsensusExecutionHandler {
         tx_pool: SharedTransactionPool, data_man: Arc<BlockDataManager>,
         config: ConsensusExecutionConfiguration,
         verification_config: VerificationConfig, machine: Arc<Machine>,
+        gas_usage_cache: Option<Arc<GasUsageCache>>,
     ) -> Self
     {
         ConsensusExecutionHandler {
@@ -814,6 +889,7 @@ impl ConsensusExecutionHandler {
             config,
             verification_config,
             machine,
+            gas_usage_cache,
             execution_state_prefetcher: if DEFAULT_EXECUTION_PREFETCH_THREADS
                 > 0
             {
@@ -920,6 +996,16 @@ impl ConsensusExecutionHandler {
                 .expect("must exists");
 
             if on_local_pivot {
+                self.data_man.index_address_transactions(
+                    pivot_block_header.height(),
+                    epoch_hash,
+                    epoch_block_hashes,
+                );
+                self.data_man.index_epoch_logs(
+                    pivot_block_header.height(),
+                    epoch_hash,
+                    epoch_block_hashes,
+                );
                 // Unwrap is safe here because it's guaranteed by outer if.
                 let state_root = &self
                     .data_man
@@ -1048,6 +1134,28 @@ impl ConsensusExecutionHandler {
             compute_receipts_root(&epoch_receipts),
             BlockHeaderBuilder::compute_block_logs_bloom_hash(&epoch_receipts),
         );
+        if on_local_pivot {
+            self.data_man.index_address_transactions(
+                pivot_block.block_header.height(),
+                epoch_hash,
+                epoch_block_hashes,
+            );
+            self.data_man.index_epoch_logs(
+                pivot_block.block_header.height(),
+                epoch_hash,
+                epoch_block_hashes,
+            );
+            self.data_man.cache_epoch_summary(
+                pivot_block.block_header.height(),
+                epoch_hash,
+                pivot_block.block_header.timestamp(),
+                &epoch_receipts,
+            );
+            if let Some(cache) = &self.gas_usage_cache {
+                let usage = EpochGasUsage::new(&epoch_blocks, &epoch_receipts);
+                cache.insert(*epoch_hash, Arc::new(usage));
+            }
+        }
         let epoch_execution_commitment = self
             .data_man
             .get_epoch_execution_commitment(&epoch_hash)
@@ -1107,6 +1215,7 @@ impl ConsensusExecutionHandler {
         let pivot_block = epoch_blocks.last().expect("Epoch not empty");
         let mut epoch_receipts = Vec::with_capacity(epoch_blocks.len());
         let mut to_pending = Vec::new();
+        let mut skipped = Vec::new();
         let mut block_number = start_block_number;
         let mut last_block_hash =
             pivot_block.block_header.parent_hash().clone();
@@ -1176,6 +1285,14 @@ impl ConsensusExecutionHandler {
                             transaction,
                             e
                         );
+                        skipped.push((
+                            transaction.hash(),
+                            SkippedTransaction {
+                                block_hash: block.hash(),
+                                epoch_number: env.epoch_height,
+                                reason: (&e).into(),
+                            },
+                        ));
                         gas_fee = U256::zero();
                         if self.config.executive_trace {
                             block_traces.push(Vec::<ExecTrace>::new().into());
@@ -1199,6 +1316,14 @@ impl ConsensusExecutionHandler {
                             );
                             to_pending.push(transaction.clone())
                         }
+                        skipped.push((
+                            transaction.hash(),
+                            SkippedTransaction {
+                                block_hash: block.hash(),
+                                epoch_number: env.epoch_height,
+                                reason: (&e).into(),
+                            },
+                        ));
                         gas_fee = U256::zero();
                         if self.config.executive_trace {
                             block_traces.push(Vec::<ExecTrace>::new().into());
@@ -1282,6 +1407,13 @@ impl ConsensusExecutionHandler {
                     {
                         self.data_man
                             .insert_transaction_index(&hash, &tx_index);
+
+                        // also index Ethereum like transactions by the hash
+                        // used by the `eth` RPCs
+                        if let Some(eth_hash) = transaction.eth_hash() {
+                            self.data_man
+                                .insert_transaction_index(&eth_hash, &tx_index);
+                        }
                     }
                 }
             }
@@ -1313,6 +1445,7 @@ impl ConsensusExecutionHandler {
 
         if on_local_pivot {
             self.tx_pool.recycle_transactions(to_pending);
+            self.tx_pool.set_tx_skipped(skipped);
         }
 
         debug!("Finish processing tx for epoch");
@@ -1674,7 +1807,137 @@ impl ConsensusExecutionHandler {
 
     pub fn call_virtual(
         &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
-    ) -> RpcResult<ExecutionOutcome> {
+        state_override: Option<&StateOverride>,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        let mut outcomes = self.call_virtual_many(
+            std::slice::from_ref(tx),
+            &[], /* carry_nonce */
+            epoch_id,
+            epoch_size,
+            state_override,
+        )?;
+        Ok(outcomes.pop().expect("one outcome for each transaction"))
+    }
+
+    /// Execute `txs` one after another on the same state, so that each
+    /// transaction sees the effects of the previous ones. The state is
+    /// never committed.
+    ///
+    /// If `carry_nonce[i]` is set, the nonce of `txs[i]` is replaced by the
+    /// nonce of its sender left by the previous transactions from the same
+    /// sender, so that consecutive calls do not reuse a nonce. Missing
+    /// entries are treated as unset.
+    pub fn call_virtual_many(
+        &self, txs: &[SignedTransaction], carry_nonce: &[bool],
+        epoch_id: &H256, epoch_size: usize,
+        state_override: Option<&StateOverride>,
+    ) -> RpcResult<Vec<ExecutionOutcome>>
+    {
+        Ok(self
+            .call_virtual_impl(
+                txs,
+                carry_nonce,
+                epoch_id,
+                epoch_size,
+                state_override,
+                false, /* collect_state_diff */
+            )?
+            .into_iter()
+            .map(|(outcome, _)| outcome)
+            .collect())
+    }
+
+    /// Like `call_virtual`, but also returns the changes made by `tx` to the
+    /// state, including the balance given to the sender to pay for the call.
+    pub fn call_virtual_with_state_diff(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+    ) -> RpcResult<(ExecutionOutcome, StateDiff)> {
+        let (outcome, state_diff) = self
+            .call_virtual_impl(
+                std::slice::from_ref(tx),
+                &[], /* carry_nonce */
+                epoch_id,
+                epoch_size,
+                None, /* state_override */
+                true, /* collect_state_diff */
+            )?
+            .pop()
+            .expect("one outcome for each transaction");
+        Ok((outcome, state_diff.expect("state diff is collected")))
+    }
+
+    fn call_virtual_impl(
+        &self, txs: &[SignedTransaction], carry_nonce: &[bool],
+        epoch_id: &H256, epoch_size: usize,
+        state_override: Option<&StateOverride>, collect_state_diff: bool,
+    ) -> RpcResult<Vec<(ExecutionOutcome, Option<StateDiff>)>>
+    {
+        let context = self.virtual_call_context(txs, epoch_id, epoch_size)?;
+
+        // Keep the lock until we get the desired State, otherwise the State may
+        // expire.
+        let state_availability_boundary =
+            self.data_man.state_availability_boundary.read();
+        if !state_availability_boundary
+            .check_availability(context.best_block_header.height(), epoch_id)
+        {
+            bail!("state is not ready");
+        }
+        let state_index = self.data_man.get_state_readonly_index(epoch_id);
+        let state = State::new(StateDb::new(
+            self.data_man
+                .storage_manager
+                .get_state_no_commit(
+                    state_index.unwrap(),
+                    /* try_open = */ true,
+                )?
+                .ok_or("state deleted")?,
+        ))?;
+        drop(state_availability_boundary);
+
+        self.execute_virtual(
+            state,
+            txs,
+            carry_nonce,
+            epoch_id,
+            &context,
+            state_override,
+            collect_state_diff,
+        )
+    }
+
+    /// Execute `tx` like `call_virtual`, but on the state backed by `storage`
+    /// instead of the local state of `epoch_id`. Light nodes use it with the
+    /// state entries retrieved from their peers.
+    pub fn call_virtual_on_storage<Storage: StorageStateTrait>(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        state_override: Option<&StateOverride>, storage: Storage,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        let txs = std::slice::from_ref(tx);
+        let context = self.virtual_call_context(txs, epoch_id, epoch_size)?;
+        let state = StateGeneric::new(StateDbGeneric::new(storage))?;
+        let (outcome, _) = self
+            .execute_virtual(
+                state,
+                txs,
+                &[], /* carry_nonce */
+                epoch_id,
+                &context,
+                state_override,
+                false, /* collect_state_diff */
+            )?
+            .pop()
+            .expect("one outcome for each transaction");
+        Ok(outcome)
+    }
+
+    /// Check `txs` and prepare the block environment to execute them on the
+    /// state of `epoch_id`.
+    fn virtual_call_context(
+        &self, txs: &[SignedTransaction], epoch_id: &H256, epoch_size: usize,
+    ) -> RpcResult<VirtualCallContext> {
         let best_block_header = self.data_man.block_header_by_hash(epoch_id);
         if best_block_header.is_none() {
             bail!("invalid epoch id");
@@ -1688,65 +1951,228 @@ impl ConsensusExecutionHandler {
         let spec = self.machine.spec(start_block_number);
         let transitions = &self.machine.params().transition_heights;
 
-        invalid_params_check(
-            "tx",
-            self.verification_config.verify_transaction_common(
-                tx,
-                tx.chain_id,
-                block_height,
-                transitions,
-                VerifyTxMode::Local(VerifyTxLocalMode::Full, &spec),
-            ),
-        )?;
+        for tx in txs {
+            invalid_params_check(
+                "tx",
+                self.verification_config.verify_transaction_common(
+                    tx,
+                    tx.chain_id,
+                    block_height,
+                    transitions,
+                    VerifyTxMode::Local(VerifyTxLocalMode::Full, &spec),
+                ),
+            )?;
+        }
+
+        Ok(VirtualCallContext {
+            best_block_header,
+            block_height,
+            start_block_number,
+            spec,
+        })
+    }
+
+    fn execute_virtual<Storage: StorageStateTrait>(
+        &self, mut state: StateGeneric<Storage>, txs: &[SignedTransaction],
+        carry_nonce: &[bool], epoch_id: &H256, context: &VirtualCallContext,
+        state_override: Option<&StateOverride>, collect_state_diff: bool,
+    ) -> RpcResult<Vec<(ExecutionOutcome, Option<StateDiff>)>>
+    {
+        trace!("best_block_header: {:?}", context.best_block_header);
+        let spec = &context.spec;
+        let time_stamp = context.best_block_header.timestamp();
+
+        let author = {
+            let mut address = H160::random();
+            address.set_user_account_type_bits();
+            address
+        };
+
+        if let Some(state_override) = state_override {
+            // The state is dropped after the calls, so the overrides are
+            // never committed.
+            state.apply_state_override(state_override, spec)?;
+        }
+
+        let mut outcomes = Vec::with_capacity(txs.len());
+        let mut executed_senders = HashSet::new();
+        for (i, tx) in txs.iter().enumerate() {
+            let carried_tx;
+            let tx = if carry_nonce.get(i).cloned().unwrap_or(false)
+                && executed_senders.contains(&tx.sender())
+            {
+                let mut unsigned = tx.transaction.transaction.unsigned.clone();
+                unsigned.nonce = state.nonce(&tx.sender())?;
+                carried_tx = unsigned.fake_sign(tx.sender());
+                &carried_tx
+            } else {
+                tx
+            };
+            executed_senders.insert(tx.sender());
+            let env = Env {
+                number: context.start_block_number,
+                author,
+                timestamp: time_stamp,
+                difficulty: Default::default(),
+                accumulated_gas_used: U256::zero(),
+                last_hash: epoch_id.clone(),
+                gas_limit: tx.gas.clone(),
+                epoch_height: context.block_height,
+                transaction_epoch_bound: self
+                    .verification_config
+                    .transaction_epoch_bound,
+            };
+            if collect_state_diff {
+                state.diff_checkpoint();
+            }
+            let mut ex = ExecutiveGeneric::new(
+                &mut state,
+                &env,
+                self.machine.as_ref(),
+                spec,
+            );
+            let r = ex.transact_virtual(tx);
+            trace!("Execution result {:?}", r);
+            let outcome = r?;
+            let state_diff = if collect_state_diff {
+                let state_diff = state.diff_since_checkpoint()?;
+                state.discard_diff_checkpoint();
+                Some(state_diff)
+            } else {
+                None
+            };
+            outcomes.push((outcome, state_diff));
+        }
+        Ok(outcomes)
+    }
+
+    /// Re-execute the transaction at `tx_index` on the state it was executed
+    /// on, with `tracer` attached. The blocks and transactions before it in
+    /// the epoch are executed again without tracing, and nothing is
+    /// committed. If `collect_state_diff` is set, the changes made by the
+    /// transaction to the state are returned as well.
+    pub fn replay_transaction<T>(
+        &self, pivot_hash: &H256, epoch_block_hashes: &Vec<H256>,
+        start_block_number: u64, tx_index: &TransactionIndex, tracer: T,
+        collect_state_diff: bool,
+    ) -> RpcResult<(ExecutionOutcome, Option<StateDiff>)>
+    where
+        T: Tracer<Output = ExecTrace>,
+    {
+        let epoch_blocks = self
+            .data_man
+            .blocks_by_hash_list(
+                epoch_block_hashes,
+                false, /* update_cache */
+            )
+            .ok_or("cannot obtain the blocks of the epoch")?;
+        let pivot_block = match self.data_man.block_header_by_hash(pivot_hash) {
+            Some(header) => header,
+            None => bail!("invalid epoch id"),
+        };
+        let parent_hash = pivot_block.parent_hash();
+        let parent_height = match pivot_block.height().checked_sub(1) {
+            Some(height) => height,
+            None => {
+                bail!("cannot replay the transactions of the genesis block")
+            }
+        };
 
         // Keep the lock until we get the desired State, otherwise the State may
         // expire.
         let state_availability_boundary =
             self.data_man.state_availability_boundary.read();
         if !state_availability_boundary
-            .check_availability(best_block_header.height(), epoch_id)
+            .check_availability(parent_height, parent_hash)
         {
-            bail!("state is not ready");
+            bail!("the state of the parent epoch is not available");
         }
-        let state_index = self.data_man.get_state_readonly_index(epoch_id);
-        trace!("best_block_header: {:?}", best_block_header);
-        let time_stamp = best_block_header.timestamp();
+        let parent_state_root = match self
+            .data_man
+            .get_epoch_execution_commitment_with_db(parent_hash)
+        {
+            Some(commitment) => commitment.state_root_with_aux_info,
+            None => bail!("cannot obtain the execution commitment. Database is potentially corrupted!"),
+        };
         let mut state = State::new(StateDb::new(
             self.data_man
                 .storage_manager
-                .get_state_no_commit(
-                    state_index.unwrap(),
-                    /* try_open = */ true,
-                )?
+                .get_state_for_next_epoch(StateIndex::new_for_next_epoch(
+                    parent_hash,
+                    &parent_state_root,
+                    parent_height,
+                    self.data_man.get_snapshot_epoch_count(),
+                ))?
                 .ok_or("state deleted")?,
         ))?;
         drop(state_availability_boundary);
 
-        let author = {
-            let mut address = H160::random();
-            address.set_user_account_type_bits();
-            address
-        };
+        let mut last_block_hash = parent_hash.clone();
+        for (block_number, block) in
+            (start_block_number..).zip(epoch_blocks.iter())
+        {
+            let mut env = Env {
+                number: block_number,
+                author: block.block_header.author().clone(),
+                timestamp: pivot_block.timestamp(),
+                difficulty: block.block_header.difficulty().clone(),
+                accumulated_gas_used: U256::zero(),
+                last_hash: last_block_hash,
+                gas_limit: U256::from(block.block_header.gas_limit()),
+                epoch_height: pivot_block.height(),
+                transaction_epoch_bound: self
+                    .verification_config
+                    .transaction_epoch_bound,
+            };
+            let spec = self.machine.spec(env.number);
+            state.bump_block_number_accumulate_interest();
+            initialize_internal_contract_accounts(
+                &mut state,
+                self.machine.internal_contracts().initialized_at(env.number),
+                spec.contract_start_nonce,
+            );
+            last_block_hash = block.hash();
 
-        let env = Env {
-            number: start_block_number,
-            author,
-            timestamp: time_stamp,
-            difficulty: Default::default(),
-            accumulated_gas_used: U256::zero(),
-            last_hash: epoch_id.clone(),
-            gas_limit: tx.gas.clone(),
-            epoch_height: block_height,
-            transaction_epoch_bound: self
-                .verification_config
-                .transaction_epoch_bound,
-        };
-        let spec = self.machine.spec(env.number);
-        let mut ex =
-            Executive::new(&mut state, &env, self.machine.as_ref(), &spec);
-        let r = ex.transact_virtual(tx);
-        trace!("Execution result {:?}", r);
-        Ok(r?)
+            for (idx, transaction) in block.transactions.iter().enumerate() {
+                if block.hash() == tx_index.block_hash && idx == tx_index.index
+                {
+                    if collect_state_diff {
+                        state.diff_checkpoint();
+                    }
+                    let r = Executive::new(
+                        &mut state,
+                        &env,
+                        self.machine.as_ref(),
+                        &spec,
+                    )
+                    .transact(transaction, TransactOptions::new(tracer));
+                    trace!("Replay result {:?}", r);
+                    let outcome = r?;
+                    let state_diff = if collect_state_diff {
+                        Some(state.diff_since_checkpoint()?)
+                    } else {
+                        None
+                    };
+                    return Ok((outcome, state_diff));
+                }
+                let mut ex = Executive::new(
+                    &mut state,
+                    &env,
+                    self.machine.as_ref(),
+                    &spec,
+                );
+                match ex
+                    .transact(transaction, TransactOptions::with_no_tracing())?
+                {
+                    ExecutionOutcome::ExecutionErrorBumpNonce(_, executed)
+                    | ExecutionOutcome::Finished(executed) => {
+                        env.accumulated_gas_used += executed.gas_used;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        bail!("the transaction is not found in its epoch")
     }
 }
 
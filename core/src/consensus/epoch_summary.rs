@@ -0,0 +1,55 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::ConsensusGraph;
+use crate::block_data_manager::EpochSummary;
+use primitives::EpochNumber;
+
+/// The maximum number of epochs returned by `epoch_summaries`.
+pub const MAX_EPOCH_SUMMARY_COUNT: u64 = 1000;
+
+impl ConsensusGraph {
+    /// Return the summaries of the executed pivot epochs from `from_epoch` to
+    /// `to_epoch`, both included.
+    pub fn epoch_summaries(
+        &self, from_epoch: EpochNumber, to_epoch: EpochNumber,
+    ) -> Result<Vec<EpochSummary>, String> {
+        let from = self.get_height_from_epoch_number(from_epoch)?;
+        let to = self.get_height_from_epoch_number(to_epoch)?;
+        if from > to {
+            return Err(format!(
+                "from epoch {} is larger than to epoch {}",
+                from, to
+            ));
+        }
+        if to - from + 1 > MAX_EPOCH_SUMMARY_COUNT {
+            return Err(format!(
+                "epoch count should be at most {}",
+                MAX_EPOCH_SUMMARY_COUNT
+            ));
+        }
+        if to > self.best_executed_state_epoch_number() {
+            return Err(format!("Epoch {} is not executed yet", to));
+        }
+
+        let mut summaries = Vec::with_capacity((to - from + 1) as usize);
+        for epoch in from..=to {
+            let (pivot_hash, block_hashes) = {
+                let inner = self.inner.read_recursive();
+                (
+                    inner.get_pivot_hash_from_epoch_number(epoch)?,
+                    inner.block_hashes_by_epoch(epoch)?,
+                )
+            };
+            let summary = self
+                .data_man
+                .epoch_summary(epoch, &pivot_hash, &block_hashes)
+                .ok_or_else(|| {
+                    format!("Execution results of epoch {} not found", epoch)
+                })?;
+            summaries.push(summary);
+        }
+        Ok(summaries)
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::ConsensusGraph;
+use cfx_types::{H256, U256};
+use lru_time_cache::LruCache;
+use parking_lot::Mutex;
+use primitives::{
+    receipt::{
+        BlockReceipts, TRANSACTION_OUTCOME_EXCEPTION_WITHOUT_NONCE_BUMPING,
+    },
+    Block, EpochNumber,
+};
+use std::{cmp::max, sync::Arc};
+
+/// The maximum number of epochs returned by `fee_history`.
+pub const MAX_FEE_HISTORY_EPOCH_COUNT: u64 = 1024;
+
+/// If it is set, the gas price is a percentile of the gas prices of the
+/// transactions executed in recent epochs, instead of the sampled price.
+#[derive(Clone, Debug)]
+pub struct GasPriceOracleConfig {
+    /// The percentile of the gas prices, from 0 to 100. It is raised towards
+    /// 100 as the transaction pool backlog grows.
+    pub percentile: u8,
+    /// The number of recent executed epochs to collect gas prices from.
+    pub epoch_window: u64,
+    /// The minimum gas price returned.
+    pub min_price: U256,
+}
+
+/// The gas usage and the gas price percentiles of a range of epochs.
+#[derive(Debug, PartialEq)]
+pub struct FeeHistory {
+    pub oldest_epoch: u64,
+    /// The ratio of the gas used to the gas limit of the blocks in each
+    /// epoch.
+    pub gas_used_ratio: Vec<f64>,
+    /// The requested percentiles of the gas prices of the transactions in
+    /// each epoch, weighted by their gas used.
+    pub reward: Vec<Vec<U256>>,
+}
+
+/// The gas usage of the blocks in an epoch.
+pub struct EpochGasUsage {
+    gas_used: U256,
+    gas_limit: U256,
+    /// The gas prices and the gas used of the executed transactions, sorted
+    /// by gas price.
+    transactions: Vec<(U256, U256)>,
+}
+
+impl EpochGasUsage {
+    /// Collect the gas usage of an executed epoch from its blocks and their
+    /// receipts.
+    pub fn new(blocks: &[Arc<Block>], receipts: &[Arc<BlockReceipts>]) -> Self {
+        let mut usage = EpochGasUsage {
+            gas_used: U256::zero(),
+            gas_limit: U256::zero(),
+            transactions: Vec::new(),
+        };
+        for block in blocks {
+            usage.gas_limit += *block.block_header.gas_limit();
+        }
+        for (block, receipts) in blocks.iter().zip(receipts) {
+            let mut accumulated_gas_used = U256::zero();
+            for (tx, receipt) in
+                block.transactions.iter().zip(receipts.receipts.iter())
+            {
+                let gas_used =
+                    receipt.accumulated_gas_used - accumulated_gas_used;
+                accumulated_gas_used = receipt.accumulated_gas_used;
+                if receipt.outcome_status
+                    == TRANSACTION_OUTCOME_EXCEPTION_WITHOUT_NONCE_BUMPING
+                {
+                    continue;
+                }
+                usage.transactions.push((*tx.gas_price(), gas_used));
+            }
+            usage.gas_used += accumulated_gas_used;
+        }
+        usage.transactions.sort();
+        usage
+    }
+}
+
+/// The gas usage of recently executed pivot epochs, by pivot hash. It is
+/// filled in when the epochs are executed, so that the oracle does not load
+/// their receipts again.
+pub struct GasUsageCache {
+    epochs: Mutex<LruCache<H256, Arc<EpochGasUsage>>>,
+}
+
+impl GasUsageCache {
+    pub fn new(capacity: usize) -> Self {
+        GasUsageCache {
+            epochs: Mutex::new(LruCache::with_capacity(capacity)),
+        }
+    }
+
+    pub fn insert(&self, pivot_hash: H256, usage: Arc<EpochGasUsage>) {
+        self.epochs.lock().insert(pivot_hash, usage);
+    }
+
+    fn get(&self, pivot_hash: &H256) -> Option<Arc<EpochGasUsage>> {
+        self.epochs.lock().get(pivot_hash).cloned()
+    }
+}
+
+impl ConsensusGraph {
+    /// Return the gas used ratio and the gas price percentiles of
+    /// `epoch_count` epochs up to `newest_epoch`. `reward_percentiles` should
+    /// be in ascending order, between 0 and 100.
+    pub fn fee_history(
+        &self, newest_epoch: EpochNumber, epoch_count: u64,
+        reward_percentiles: &[f64],
+    ) -> Result<FeeHistory, String>
+    {
+        if epoch_count == 0 || epoch_count > MAX_FEE_HISTORY_EPOCH_COUNT {
+            return Err(format!(
+                "epoch count should be between 1 and {}",
+                MAX_FEE_HISTORY_EPOCH_COUNT
+            ));
+        }
+        let mut last_percentile = 0.0;
+        for percentile in reward_percentiles {
+            if !(*percentile >= last_percentile && *percentile <= 100.0) {
+                return Err(
+                    "reward percentiles should be in ascending order, \
+                            between 0 and 100"
+                        .into(),
+                );
+            }
+            last_percentile = *percentile;
+        }
+
+        let newest = self.get_height_from_epoch_number(newest_epoch)?;
+        if newest > self.best_executed_state_epoch_number() {
+            return Err(format!("Epoch {} is not executed yet", newest));
+        }
+        let oldest = (newest + 1).saturating_sub(epoch_count);
+        let mut history = FeeHistory {
+            oldest_epoch: oldest,
+            gas_used_ratio: Vec::new(),
+            reward: Vec::new(),
+        };
+        for epoch in oldest..=newest {
+            let usage = self.epoch_gas_usage(epoch)?;
+            history
+                .gas_used_ratio
+                .push(ratio(&usage.gas_used, &usage.gas_limit));
+            history.reward.push(
+                reward_percentiles
+                    .iter()
+                    .map(|p| weighted_percentile(&usage.transactions, *p))
+                    .collect(),
+            );
+        }
+        Ok(history)
+    }
+
+    /// Return the gas price given by the oracle. The epochs whose execution
+    /// results are not available are skipped.
+    pub(super) fn gas_price_from_oracle(
+        &self, config: &GasPriceOracleConfig, cache: &GasUsageCache,
+    ) -> U256 {
+        let newest = self.best_executed_state_epoch_number();
+        let oldest = (newest + 1).saturating_sub(max(config.epoch_window, 1));
+        let mut transactions = Vec::new();
+        for epoch in oldest..=newest {
+            let pivot_hash = match self
+                .inner
+                .read_recursive()
+                .get_pivot_hash_from_epoch_number(epoch)
+            {
+                Ok(pivot_hash) => pivot_hash,
+                Err(_) => continue,
+            };
+            // the epochs executed before the node started are not cached
+            let usage = match cache.get(&pivot_hash) {
+                Some(usage) => usage,
+                None => match self.epoch_gas_usage(epoch) {
+                    Ok(usage) => {
+                        let usage = Arc::new(usage);
+                        cache.insert(pivot_hash, usage.clone());
+                        usage
+                    }
+                    Err(_) => continue,
+                },
+            };
+            transactions.extend_from_slice(&usage.transactions);
+        }
+        let percentile = config.percentile as f64;
+        let percentile = if transactions.is_empty() {
+            // Nothing is executed in the window, so the price is given by the
+            // ready transactions in the pool alone.
+            let (ready_txs, _) = self.txpool.content(None);
+            transactions = ready_txs
+                .iter()
+                .map(|tx| (*tx.gas_price(), *tx.gas()))
+                .collect();
+            percentile
+        } else {
+            // If the transaction pool has more transactions to pack than
+            // those executed in the window, the highest price is used.
+            let (_, _, _, unpacked) = self.txpool.stats();
+            let backlog_ratio =
+                (unpacked as f64 / transactions.len() as f64).min(1.0);
+            percentile + (100.0 - percentile) * backlog_ratio
+        };
+        transactions.sort();
+        max(
+            weighted_percentile(&transactions, percentile),
+            config.min_price,
+        )
+    }
+
+    fn epoch_gas_usage(&self, epoch: u64) -> Result<EpochGasUsage, String> {
+        let block_hashes =
+            self.inner.read_recursive().block_hashes_by_epoch(epoch)?;
+        let pivot_hash = *block_hashes.last().expect("Epoch set not empty");
+        let mut blocks = Vec::new();
+        let mut receipts = Vec::new();
+        for block_hash in &block_hashes {
+            let block = self
+                .data_man
+                .block_by_hash(block_hash, false /* update_cache */)
+                .ok_or_else(|| format!("Block {:?} not found", block_hash))?;
+            blocks.push(block);
+            // The genesis block is not executed.
+            if epoch == 0 {
+                continue;
+            }
+            let block_receipts = self
+                .data_man
+                .block_execution_result_by_hash_with_epoch(
+                    block_hash,
+                    &pivot_hash,
+                    false, /* update_pivot_assumption */
+                    false, /* update_cache */
+                )
+                .ok_or_else(|| {
+                    format!(
+                        "Execution results of block {:?} not found",
+                        block_hash
+                    )
+                })?
+                .block_receipts;
+            receipts.push(block_receipts);
+        }
+        Ok(EpochGasUsage::new(&blocks, &receipts))
+    }
+}
+
+/// Return `numerator / denominator` as a float, or 0 if `denominator` is 0.
+fn ratio(numerator: &U256, denominator: &U256) -> f64 {
+    const PRECISION: u64 = 1_000_000;
+    if denominator.is_zero() {
+        return 0.0;
+    }
+    let scaled = numerator.saturating_mul(U256::from(PRECISION)) / denominator;
+    if scaled > U256::from(u64::MAX) {
+        u64::MAX as f64 / PRECISION as f64
+    } else {
+        scaled.as_u64() as f64 / PRECISION as f64
+    }
+}
+
+/// Return the gas price at `percentile` of `transactions`, weighted by their
+/// gas used. `transactions` should be sorted by gas price.
+fn weighted_percentile(transactions: &[(U256, U256)], percentile: f64) -> U256 {
+    let total_gas_used = transactions
+        .iter()
+        .fold(U256::zero(), |sum, (_, gas_used)| sum + *gas_used);
+    // `percentile` is converted to basis points to compute with `U256`.
+    let threshold = total_gas_used * U256::from((percentile * 100.0) as u64)
+        / U256::from(10000);
+    let mut accumulated_gas_used = U256::zero();
+    for (gas_price, gas_used) in transactions {
+        accumulated_gas_used += *gas_used;
+        if accumulated_gas_used >= threshold {
+            return *gas_price;
+        }
+    }
+    transactions
+        .last()
+        .map_or_else(U256::zero, |(gas_price, _)| *gas_price)
+}
@@ -6,11 +6,18 @@ mod anticone_cache;
 pub mod consensus_inner;
 pub mod consensus_trait;
 pub mod debug_recompute;
+mod epoch_summary;
+mod gas_price_oracle;
 mod pastset_cache;
 
 pub use crate::consensus::{
     consensus_inner::{ConsensusGraphInner, ConsensusInnerConfig},
     consensus_trait::{ConsensusGraphTrait, SharedConsensusGraph},
+    epoch_summary::MAX_EPOCH_SUMMARY_COUNT,
+    gas_price_oracle::{
+        EpochGasUsage, FeeHistory, GasPriceOracleConfig, GasUsageCache,
+        MAX_FEE_HISTORY_EPOCH_COUNT,
+    },
 };
 
 use super::consensus::consensus_inner::{
@@ -28,11 +35,12 @@ use crate::{
     executive::ExecutionOutcome,
     pow::{PowComputer, ProofOfWorkConfig},
     rpc_errors::{invalid_params_check, Result as RpcResult},
-    state::State,
+    state::{State, StateDiff, StateOverride},
     statistics::SharedStatistics,
     trace::{
-        trace::{ActionType, BlockExecTraces, LocalizedTrace},
+        trace::{ActionType, BlockExecTraces, ExecTrace, LocalizedTrace},
         trace_filter::TraceFilter,
+        Tracer,
     },
     transaction_pool::SharedTransactionPool,
     verification::VerificationConfig,
@@ -50,7 +58,7 @@ use cfx_parameters::{
 };
 use cfx_state::state_trait::StateOpsTrait;
 use cfx_statedb::StateDb;
-use cfx_storage::state_manager::StateManagerTrait;
+use cfx_storage::{state_manager::StateManagerTrait, StorageStateTrait};
 use cfx_types::{Bloom, H160, H256, U256};
 use either::Either;
 use itertools::Itertools;
@@ -62,7 +70,7 @@ use metrics::{
 use parking_lot::{Mutex, RwLock};
 use primitives::{
     epoch::BlockHashOrEpochNumber,
-    filter::{FilterError, LogFilter},
+    filter::{FilterCursor, FilterError, LogFilter},
     log_entry::LocalizedLogEntry,
     receipt::Receipt,
     EpochId, EpochNumber, SignedTransaction, TransactionIndex,
@@ -122,6 +130,8 @@ pub struct ConsensusConfig {
     /// consensus from making progress under high RPC load.
     pub get_logs_epoch_batch_size: usize,
     pub get_logs_filter_max_epoch_range: Option<u64>,
+    /// If it is set, `gas_price` is given by the gas price oracle.
+    pub gas_price_oracle: Option<GasPriceOracleConfig>,
 
     /// TODO: These parameters are only utilized in catch-up now.
     /// TODO: They should be used in data garbage collection, too.
@@ -205,6 +215,9 @@ pub struct ConsensusGraph {
     /// This is always `None` for archive nodes.
     pub synced_epoch_id: Mutex<Option<EpochId>>,
     pub config: ConsensusConfig,
+
+    /// The gas usage of recent epochs, if the gas price oracle is enabled.
+    gas_usage_cache: Option<Arc<GasUsageCache>>,
 }
 
 impl MallocSizeOf for ConsensusGraph {
@@ -240,6 +253,11 @@ impl ConsensusGraph {
                 era_genesis_block_hash,
                 era_stable_block_hash,
             )));
+        let gas_usage_cache = conf.gas_price_oracle.as_ref().map(|oracle| {
+            // keep some more epochs for pivot chain reorgs
+            let capacity = 2 * max(oracle.epoch_window, 1) as usize;
+            Arc::new(GasUsageCache::new(capacity))
+        });
         let executor = ConsensusExecutor::start(
             txpool.clone(),
             data_man.clone(),
@@ -247,6 +265,7 @@ impl ConsensusGraph {
             execution_conf,
             verification_config,
             conf.bench_mode,
+            gas_usage_cache.clone(),
         );
         let confirmation_meter = ConfirmationMeter::new();
 
@@ -270,6 +289,7 @@ impl ConsensusGraph {
             ready_for_mining: AtomicBool::new(false),
             synced_epoch_id: Default::default(),
             config: conf,
+            gas_usage_cache,
         };
         graph.update_best_info(false /* ready_for_mining */);
         graph
@@ -384,6 +404,11 @@ impl ConsensusGraph {
     /// Get the average gas price of the last GAS_PRICE_TRANSACTION_SAMPLE_SIZE
     /// blocks
     pub fn gas_price(&self) -> Option<U256> {
+        if let (Some(oracle_config), Some(cache)) =
+            (&self.config.gas_price_oracle, &self.gas_usage_cache)
+        {
+            return Some(self.gas_price_from_oracle(oracle_config, cache));
+        }
         let inner = self.inner.read();
         let mut last_epoch_number = inner.best_epoch_number();
         let mut number_of_blocks_to_sample = GAS_PRICE_BLOCK_SAMPLE_SIZE;
@@ -780,11 +805,14 @@ impl ConsensusGraph {
         Ok(epoch_batch_logs.into_iter().flatten().collect())
     }
 
+    /// Return the epochs in the range of `filter` that may contain matching
+    /// logs, in reverse order. The epochs covered by the log index are only
+    /// returned if their blooms may match `bloom_possibilities`.
     pub fn get_log_filter_epoch_range(
-        &self, filter: &LogFilter,
-    ) -> Result<impl Iterator<Item = u64>, FilterError> {
+        &self, filter: &LogFilter, bloom_possibilities: &Vec<Bloom>,
+    ) -> Result<impl DoubleEndedIterator<Item = u64>, FilterError> {
         // lock so that we have a consistent view
-        let _inner = self.inner.read_recursive();
+        let inner = self.inner.read_recursive();
 
         let from_epoch =
             self.get_height_from_epoch_number(filter.from_epoch.clone())?;
@@ -805,8 +833,18 @@ impl ConsensusGraph {
             });
         }
 
+        if let Some(pruned) = self.data_man.log_index_pruned_epoch() {
+            if from_epoch < pruned {
+                return Err(FilterError::EpochAlreadyPruned {
+                    epoch: from_epoch,
+                    min: pruned,
+                });
+            }
+        }
+
+        // The limit applies to the whole requested range, including the
+        // epochs skipped with the log index.
         if let Some(max_gap) = self.config.get_logs_filter_max_epoch_range {
-            // The range includes both ends.
             if to_epoch - from_epoch + 1 > max_gap {
                 return Err(FilterError::EpochNumberGapTooLarge {
                     from_epoch,
@@ -816,7 +854,22 @@ impl ConsensusGraph {
             }
         }
 
-        return Ok((from_epoch..=to_epoch).rev());
+        let epochs = match self.data_man.log_index_matching_epochs(
+            from_epoch,
+            to_epoch,
+            bloom_possibilities,
+            |epoch| inner.get_pivot_hash_from_epoch_number(epoch).ok(),
+        ) {
+            Some((first, last, matched)) => Either::Right(
+                (last + 1..=to_epoch)
+                    .rev()
+                    .chain(matched.into_iter().rev())
+                    .chain((from_epoch..first).rev()),
+            ),
+            None => Either::Left((from_epoch..=to_epoch).rev()),
+        };
+
+        return Ok(epochs);
     }
 
     pub fn get_trace_filter_epoch_range(
@@ -861,7 +914,7 @@ impl ConsensusGraph {
 
         let mut logs = self
             // iterate over epochs in reverse order
-            .get_log_filter_epoch_range(&filter)?
+            .get_log_filter_epoch_range(&filter, &bloom_possibilities)?
             // we process epochs in each batch in parallel
             // but batches are processed one-by-one
             .chunks(self.config.get_logs_epoch_batch_size)
@@ -1027,9 +1080,262 @@ impl ConsensusGraph {
             .collect())
     }
 
+    /// Return at most `page_size` logs matching `filter` in execution order,
+    /// starting from `cursor` if it is given, and the cursor of the next page
+    /// if there are more logs. The offset and limit of `filter` are ignored.
+    pub fn logs_page(
+        &self, mut filter: LogFilter, cursor: Option<FilterCursor>,
+        page_size: usize,
+    ) -> Result<(Vec<LocalizedLogEntry>, Option<FilterCursor>), FilterError>
+    {
+        if filter.block_hashes.is_some() {
+            bail!(FilterError::Custom(
+                "Pagination is not supported for filters with block hashes"
+                    .into()
+            ));
+        }
+        let bloom_possibilities = filter.bloom_possibilities();
+        if let Some(cursor) = &cursor {
+            filter.from_epoch =
+                self.get_page_from_epoch(filter.from_epoch.clone(), cursor)?;
+        }
+        let epochs = self
+            .get_log_filter_epoch_range(&filter, &bloom_possibilities)?
+            .rev();
+
+        self.filter_epochs_page(epochs, cursor, page_size, |epoch, _| {
+            let block_hashes =
+                self.inner.read_recursive().block_hashes_by_epoch(epoch)?;
+            let pivot_hash = *block_hashes.last().expect("Epoch set not empty");
+            let mut logs = Vec::new();
+            for (block_index, block_hash) in
+                block_hashes.into_iter().enumerate()
+            {
+                // `filter_block` returns the logs in reverse order.
+                let mut block_logs: Vec<_> = self
+                    .filter_block(
+                        &filter,
+                        &bloom_possibilities,
+                        epoch,
+                        pivot_hash,
+                        block_hash,
+                    )?
+                    .collect();
+                block_logs.reverse();
+                logs.extend(
+                    block_logs
+                        .into_iter()
+                        .map(|log| (block_index, log.log_index, log)),
+                );
+            }
+            Ok(logs)
+        })
+    }
+
+    /// Return at most `page_size` traces matching `filter` in execution
+    /// order, starting from `cursor` if it is given, and the cursor of the
+    /// next page if there are more traces. `filter.after` and `filter.count`
+    /// are ignored.
+    pub fn filter_traces_page(
+        &self, mut filter: TraceFilter, cursor: Option<FilterCursor>,
+        page_size: usize,
+    ) -> Result<(Vec<LocalizedTrace>, Option<FilterCursor>), FilterError>
+    {
+        if filter.block_hashes.is_some() {
+            bail!(FilterError::Custom(
+                "Pagination is not supported for filters with block hashes"
+                    .into()
+            ));
+        }
+        if let Some(cursor) = &cursor {
+            filter.from_epoch =
+                self.get_page_from_epoch(filter.from_epoch.clone(), cursor)?;
+        }
+        let epochs = self.get_trace_filter_epoch_range(&filter)?;
+
+        self.filter_epochs_page(epochs, cursor, page_size, |epoch, pivot| {
+            let mut traces = Vec::new();
+            for (block_index, (pivot_hash, block_hash, block_trace)) in self
+                .filter_traces_single_epoch(epoch, pivot)?
+                .into_iter()
+                .enumerate()
+            {
+                traces.extend(
+                    self.localize_block_traces(
+                        &filter,
+                        pivot_hash,
+                        block_hash,
+                        block_trace,
+                    )?
+                    .into_iter()
+                    .map(|(index, trace)| (block_index, index, trace)),
+                );
+            }
+            Ok(traces)
+        })
+    }
+
+    /// Return the epoch to start a page from. The pivot block of the epoch in
+    /// `cursor` must not have changed since the cursor was returned.
+    fn get_page_from_epoch(
+        &self, from_epoch: EpochNumber, cursor: &FilterCursor,
+    ) -> Result<EpochNumber, FilterError> {
+        let pivot_hash = self
+            .inner
+            .read_recursive()
+            .get_pivot_hash_from_epoch_number(cursor.epoch_number)?;
+        if pivot_hash != cursor.epoch_hash {
+            bail!(FilterError::PivotChainReorg {
+                epoch: cursor.epoch_number,
+                from: cursor.epoch_hash,
+                to: pivot_hash,
+            });
+        }
+        let from_epoch = self.get_height_from_epoch_number(from_epoch)?;
+        Ok(EpochNumber::Number(max(from_epoch, cursor.epoch_number)))
+    }
+
+    /// Collect the entries of `epochs` in order until `page_size` entries are
+    /// collected. `filter_epoch` returns the entries of an epoch with their
+    /// block indices and entry indices in execution order, given the epoch
+    /// number and its pivot hash. Epochs are filtered in batches in parallel,
+    /// so at most one batch is filtered after the page is full.
+    fn filter_epochs_page<T, F>(
+        &self, epochs: impl Iterator<Item = u64>, cursor: Option<FilterCursor>,
+        page_size: usize, filter_epoch: F,
+    ) -> Result<(Vec<T>, Option<FilterCursor>), FilterError>
+    where
+        T: Send,
+        F: Fn(u64, H256) -> Result<Vec<(usize, usize, T)>, FilterError> + Sync,
+    {
+        let skip_before = cursor.map(|cursor| {
+            (
+                cursor.epoch_number,
+                cursor.block_index as usize,
+                cursor.entry_index as usize,
+            )
+        });
+        let mut entries = Vec::new();
+        let mut consistency_check_data: Option<(u64, H256)> = None;
+
+        for epochs in &epochs.chunks(self.config.get_logs_epoch_batch_size) {
+            // lock so that we have a consistent view during this batch
+            let inner = self.inner.read();
+
+            if let Some((epoch, pivot)) = consistency_check_data {
+                let new_pivot =
+                    inner.get_pivot_hash_from_epoch_number(epoch)?;
+                if pivot != new_pivot {
+                    bail!(FilterError::PivotChainReorg {
+                        epoch,
+                        from: pivot,
+                        to: new_pivot,
+                    });
+                }
+            }
+
+            let epochs_and_pivot_hash = epochs
+                .map(|epoch| {
+                    Ok((epoch, inner.get_pivot_hash_from_epoch_number(epoch)?))
+                })
+                .collect::<Result<Vec<_>, FilterError>>()?;
+            consistency_check_data = epochs_and_pivot_hash.last().cloned();
+
+            let batch = epochs_and_pivot_hash
+                .into_par_iter()
+                .map(|(epoch, pivot_hash)| {
+                    Ok((epoch, pivot_hash, filter_epoch(epoch, pivot_hash)?))
+                })
+                .collect::<Result<Vec<_>, FilterError>>()?;
+
+            for (epoch, pivot_hash, epoch_entries) in batch {
+                for (block_index, entry_index, entry) in epoch_entries {
+                    if let Some(start) = skip_before {
+                        if (epoch, block_index, entry_index) < start {
+                            continue;
+                        }
+                    }
+                    if entries.len() >= page_size {
+                        let next = FilterCursor {
+                            epoch_number: epoch,
+                            epoch_hash: pivot_hash,
+                            block_index: block_index as u64,
+                            entry_index: entry_index as u64,
+                        };
+                        return Ok((entries, Some(next)));
+                    }
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok((entries, None))
+    }
+
+    /// Return the traces of epoch `epoch_number` executed with pivot block
+    /// `assumed_pivot` that match `filter`. The epoch range and block hashes
+    /// of `filter` are ignored.
+    pub fn filter_epoch_traces(
+        &self, filter: &TraceFilter, epoch_number: u64, assumed_pivot: H256,
+    ) -> Result<Vec<LocalizedTrace>, FilterError> {
+        let block_traces =
+            self.filter_traces_single_epoch(epoch_number, assumed_pivot)?;
+        let traces = self.filter_block_traces(filter, block_traces)?;
+        Ok(traces
+            .into_iter()
+            .skip(filter.after.unwrap_or(0))
+            .take(filter.count.unwrap_or(usize::max_value()))
+            .collect())
+    }
+
     pub fn call_virtual(
         &self, tx: &SignedTransaction, epoch: EpochNumber,
-    ) -> RpcResult<ExecutionOutcome> {
+        state_override: Option<&StateOverride>,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        // only allow to call against stated epoch
+        self.validate_stated_epoch(&epoch)?;
+        let (epoch_id, epoch_size) = if let Ok(v) =
+            self.get_block_hashes_by_epoch(epoch)
+        {
+            (v.last().expect("pivot block always exist").clone(), v.len())
+        } else {
+            bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
+        };
+        self.executor
+            .call_virtual(tx, &epoch_id, epoch_size, state_override)
+    }
+
+    /// Execute `txs` in order on the state of `epoch`, without committing the
+    /// changes. Each transaction sees the effects of the previous ones. See
+    /// `ConsensusExecutionHandler::call_virtual_many` for `carry_nonce`.
+    pub fn call_virtual_many(
+        &self, txs: &[SignedTransaction], carry_nonce: &[bool],
+        epoch: EpochNumber, state_override: Option<&StateOverride>,
+    ) -> RpcResult<Vec<ExecutionOutcome>>
+    {
+        // only allow to call against stated epoch
+        self.validate_stated_epoch(&epoch)?;
+        let (epoch_id, epoch_size) = if let Ok(v) =
+            self.get_block_hashes_by_epoch(epoch)
+        {
+            (v.last().expect("pivot block always exist").clone(), v.len())
+        } else {
+            bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
+        };
+        self.executor.call_virtual_many(
+            txs,
+            carry_nonce,
+            &epoch_id,
+            epoch_size,
+            state_override,
+        )
+    }
+
+    /// Execute `tx` on the state of `epoch` like `call_virtual`, and return
+    /// the changes it makes to the state.
+    pub fn call_virtual_with_state_diff(
+        &self, tx: &SignedTransaction, epoch: EpochNumber,
+    ) -> RpcResult<(ExecutionOutcome, StateDiff)> {
         // only allow to call against stated epoch
         self.validate_stated_epoch(&epoch)?;
         let (epoch_id, epoch_size) = if let Ok(v) =
@@ -1039,7 +1345,66 @@ impl ConsensusGraph {
         } else {
             bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
         };
-        self.executor.call_virtual(tx, &epoch_id, epoch_size)
+        self.executor
+            .call_virtual_with_state_diff(tx, &epoch_id, epoch_size)
+    }
+
+    /// Execute `tx` on `storage`, which provides the state of `epoch`. Unlike
+    /// `call_virtual`, the state is not required to be available locally, so
+    /// that light nodes can execute on the state entries retrieved from their
+    /// peers.
+    pub fn call_virtual_on_storage<Storage: StorageStateTrait>(
+        &self, tx: &SignedTransaction, epoch: u64,
+        state_override: Option<&StateOverride>, storage: Storage,
+    ) -> RpcResult<ExecutionOutcome>
+    {
+        let hashes = self.get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+        let epoch_id = hashes.last().expect("pivot block always exist");
+        self.executor.call_virtual_on_storage(
+            tx,
+            epoch_id,
+            hashes.len(),
+            state_override,
+            storage,
+        )
+    }
+
+    /// Re-execute an executed transaction on the state of its epoch with
+    /// `tracer` attached. The changes made by the transaction to the state
+    /// are returned if `collect_state_diff` is set.
+    pub fn replay_transaction<T>(
+        &self, tx_hash: &H256, tracer: T, collect_state_diff: bool,
+    ) -> RpcResult<(ExecutionOutcome, Option<StateDiff>)>
+    where T: Tracer<Output = ExecTrace> {
+        let tx_index = match self
+            .data_man
+            .transaction_index_by_hash(tx_hash, false /* update_cache */)
+        {
+            Some(tx_index) => tx_index,
+            None => bail!("transaction not found or not executed yet"),
+        };
+        let epoch_number =
+            match self.get_block_epoch_number(&tx_index.block_hash) {
+                Some(epoch_number) => epoch_number,
+                None => bail!("the block of the transaction is not executed"),
+            };
+        let epoch_block_hashes =
+            self.get_block_hashes_by_epoch(EpochNumber::Number(epoch_number))?;
+        let pivot_hash =
+            epoch_block_hashes.last().expect("pivot block always exist");
+        let start_block_number =
+            match self.data_man.get_epoch_execution_context(pivot_hash) {
+                Some(ctx) => ctx.start_block_number,
+                None => bail!("cannot obtain the execution context. Database is potentially corrupted!"),
+            };
+        self.executor.replay_transaction(
+            pivot_hash,
+            &epoch_block_hashes,
+            start_block_number,
+            &tx_index,
+            tracer,
+            collect_state_diff,
+        )
     }
 
     /// Get the number of processed blocks (i.e., the number of calls to
@@ -1216,52 +1581,74 @@ impl ConsensusGraph {
     {
         let mut traces = Vec::new();
         for (pivot_hash, block_hash, block_trace) in block_traces {
-            let tx_hashes: Vec<H256> = self
-                .data_man
-                .block_by_hash(&block_hash, true /* update_cache */)
-                .ok_or(FilterError::BlockAlreadyPruned { block_hash })?
-                .transactions
-                .iter()
-                .map(|tx| tx.hash())
-                .collect();
-            if tx_hashes.len() != block_trace.0.len() {
-                bail!(format!(
-                    "tx list and trace length unmatch: block_hash={:?}",
-                    block_hash
-                ));
-            }
-            let epoch_number = self
-                .data_man
-                .block_height_by_hash(&pivot_hash)
-                .ok_or_else(|| {
-                    FilterError::Custom(
-                        format!(
-                            "pivot block header missing, hash={:?}",
-                            pivot_hash
-                        )
-                        .into(),
+            traces.extend(
+                self.localize_block_traces(
+                    filter,
+                    pivot_hash,
+                    block_hash,
+                    block_trace,
+                )?
+                .into_iter()
+                .map(|(_, trace)| trace),
+            );
+        }
+        Ok(traces)
+    }
+
+    /// Return the traces of a block that match `filter`, with their indices
+    /// in all the traces of the block.
+    fn localize_block_traces(
+        &self, filter: &TraceFilter, pivot_hash: H256, block_hash: H256,
+        block_trace: BlockExecTraces,
+    ) -> Result<Vec<(usize, LocalizedTrace)>, FilterError>
+    {
+        let tx_hashes: Vec<H256> = self
+            .data_man
+            .block_by_hash(&block_hash, true /* update_cache */)
+            .ok_or(FilterError::BlockAlreadyPruned { block_hash })?
+            .transactions
+            .iter()
+            .map(|tx| tx.hash())
+            .collect();
+        if tx_hashes.len() != block_trace.0.len() {
+            bail!(format!(
+                "tx list and trace length unmatch: block_hash={:?}",
+                block_hash
+            ));
+        }
+        let epoch_number = self
+            .data_man
+            .block_height_by_hash(&pivot_hash)
+            .ok_or_else(|| {
+                FilterError::Custom(
+                    format!(
+                        "pivot block header missing, hash={:?}",
+                        pivot_hash
                     )
-                })?;
-            for (tx_position, tx_trace) in block_trace.0.into_iter().enumerate()
-            {
-                for trace in tx_trace.0 {
-                    if let Some(action_types) = &filter.action_types {
-                        if !action_types
-                            .contains(&ActionType::from(&trace.action))
-                        {
-                            continue;
-                        }
+                    .into(),
+                )
+            })?;
+        let mut traces = Vec::new();
+        let mut next_index = 0;
+        for (tx_position, tx_trace) in block_trace.0.into_iter().enumerate() {
+            for trace in tx_trace.0 {
+                let index = next_index;
+                next_index += 1;
+                if let Some(action_types) = &filter.action_types {
+                    if !action_types.contains(&ActionType::from(&trace.action))
+                    {
+                        continue;
                     }
-                    let trace = LocalizedTrace {
-                        action: trace.action,
-                        epoch_hash: pivot_hash,
-                        epoch_number: epoch_number.into(),
-                        block_hash,
-                        transaction_position: tx_position.into(),
-                        transaction_hash: tx_hashes[tx_position],
-                    };
-                    traces.push(trace);
                 }
+                let trace = LocalizedTrace {
+                    action: trace.action,
+                    epoch_hash: pivot_hash,
+                    epoch_number: epoch_number.into(),
+                    block_hash,
+                    transaction_position: tx_position.into(),
+                    transaction_hash: tx_hashes[tx_position],
+                };
+                traces.push((index, trace));
             }
         }
         Ok(traces)
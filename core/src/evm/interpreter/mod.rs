@@ -44,7 +44,7 @@ use super::{
 use crate::{
     bytes::Bytes,
     hash::keccak,
-    trace::{trace::ExecTrace, Tracer},
+    trace::{trace::ExecTrace, OpcodeStep, Tracer},
     vm::{
         self, ActionParams, ActionValue, CallType, ContractCreateResult,
         CreateContractAddress, GasLeft, MessageCallResult, ParamsType,
@@ -379,6 +379,12 @@ impl<Cost: CostType> Interpreter<Cost> {
             self.step_inner(context, tracer)
         };
 
+        if let InterpreterResult::Done(Err(ref e)) = result {
+            if tracer.trace_opcodes() {
+                tracer.trace_opcode_failed(context.depth() + 1, e);
+            }
+        }
+
         if let &InterpreterResult::Done(_) = &result {
             self.done = true;
             self.informant.done();
@@ -456,6 +462,26 @@ impl<Cost: CostType> Interpreter<Cost> {
                         Self::store_written(instruction, &self.stack),
                     );
                 }
+                if tracer.trace_opcodes() {
+                    tracer.trace_opcode_step(OpcodeStep {
+                        pc: self.reader.position - 1,
+                        op: info.name,
+                        gas: self
+                            .gasometer
+                            .as_mut()
+                            .expect(GASOMETER_PROOF)
+                            .current_gas
+                            .as_u256(),
+                        gas_cost: requirements.gas_cost.as_u256(),
+                        depth: context.depth() + 1,
+                        stack: self.stack.peek_top(self.stack.size()),
+                        memory: &self.mem,
+                        store_written: Self::store_written(
+                            instruction,
+                            &self.stack,
+                        ),
+                    });
+                }
 
                 if let Err(e) = self
                     .gasometer
@@ -531,6 +557,16 @@ impl<Cost: CostType> Interpreter<Cost> {
                 &self.mem,
             );
         }
+        if tracer.trace_opcodes() {
+            tracer.trace_opcode_executed(
+                self.gasometer
+                    .as_mut()
+                    .expect(GASOMETER_PROOF)
+                    .current_gas
+                    .as_u256(),
+                self.stack.peek_top(self.last_stack_ret_len),
+            );
+        }
 
         // Advance
         match result {
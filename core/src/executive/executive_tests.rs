@@ -7,7 +7,7 @@ use crate::{
     evm::FinalizationResult,
     executive::{CollateralCheckResultToVmResult, ExecutionOutcome},
     machine::Machine,
-    state::{State, Substate},
+    state::{Diff, State, Substate},
     test_helpers::get_state_for_genesis_write,
     trace,
     vm::{
@@ -584,6 +584,61 @@ fn test_not_enough_cash() {
     }
 }
 
+#[test]
+fn test_suicide_with_state_diff() {
+    let keypair = Random.generate().unwrap();
+    // The contract destroys itself during creation: CALLER SELFDESTRUCT.
+    let t = Transaction {
+        action: Action::Create,
+        value: U256::zero(),
+        data: "33ff".from_hex().unwrap(),
+        gas: U256::from(100_000),
+        gas_price: U256::one(),
+        storage_limit: 0,
+        epoch_height: 0,
+        chain_id: 0,
+        nonce: U256::zero(),
+    }
+    .sign(keypair.secret());
+    let sender = t.sender();
+
+    let mut env = Env::default();
+    env.gas_limit = U256::from(100_000);
+    let machine = make_byzantium_machine(0);
+    let spec = machine.spec(env.number);
+
+    let storage_manager = new_state_manager_for_unit_test();
+    let mut state = get_state_for_genesis_write(&storage_manager);
+    state
+        .add_balance(
+            &sender,
+            &U256::from(1_000_000),
+            CleanupMode::NoEmpty,
+            spec.account_start_nonce,
+        )
+        .unwrap();
+
+    // Killing the contract updates the total issued tokens, which must not
+    // conflict with the checkpoint taken to collect the state diff.
+    state.diff_checkpoint();
+    let res = {
+        let mut ex = Executive::new(&mut state, &env, &machine, &spec);
+        let options = TransactOptions::with_no_tracing();
+        ex.transact(&t, options).unwrap()
+    };
+    match res {
+        ExecutionOutcome::Finished(_) => {}
+        _ => panic!("Expected the transaction to be executed. {:?}", res),
+    }
+
+    let state_diff = state.diff_since_checkpoint().unwrap();
+    state.discard_diff_checkpoint();
+    assert_eq!(
+        state_diff[&sender].nonce,
+        Diff::Changed(U256::zero(), U256::one())
+    );
+}
+
 #[test]
 fn test_deposit_withdraw_lock() {
     let mut sender = Address::zero();
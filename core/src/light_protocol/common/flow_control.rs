@@ -0,0 +1,267 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use crate::{light_protocol::message::msgid, message::MsgId};
+use cfx_parameters::light::{
+    FLOW_CONTROL_BUFFER_LIMIT, FLOW_CONTROL_RECHARGE_RATE,
+};
+use rlp_derive::{RlpDecodable, RlpEncodable};
+use std::{
+    cmp::{max, min},
+    time::{Duration, Instant},
+};
+
+// (request, base cost, cost per requested item)
+const DEFAULT_REQUEST_COSTS: [(MsgId, u64, u64); 14] = [
+    (msgid::GET_ACCOUNT_PENDING_TXS, 1_000, 5_000),
+    (msgid::GET_BLOCK_HASHES_BY_EPOCH, 1_000, 100),
+    (msgid::GET_BLOCK_HEADERS, 1_000, 100),
+    (msgid::GET_BLOCK_REWARDS, 1_000, 200),
+    (msgid::GET_BLOCK_TXS, 1_000, 1_000),
+    (msgid::GET_BLOOMS, 1_000, 100),
+    (msgid::GET_RECEIPTS, 1_000, 1_000),
+    (msgid::GET_STATE_ENTRIES, 1_000, 2_000),
+    (msgid::GET_STATE_ROOTS, 1_000, 500),
+    (msgid::GET_STORAGE_ROOTS, 1_000, 2_000),
+    (msgid::GET_TX_INFOS, 1_000, 2_000),
+    (msgid::GET_TXS, 1_000, 100),
+    (msgid::GET_WITNESS_INFO, 1_000, 200),
+    (msgid::SEND_RAW_TX, 1_000, 0),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct RequestCost {
+    pub msg_id: MsgId,
+    pub base_cost: u64,
+    pub per_item_cost: u64,
+}
+
+/// Flow control parameters advertised by full nodes to their light peers.
+/// Requests not listed in `costs` are free.
+#[derive(Clone, Debug, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct FlowControlParams {
+    pub buffer_limit: u64,
+    // credits recharged per second
+    pub recharge_rate: u64,
+    pub costs: Vec<RequestCost>,
+}
+
+impl Default for FlowControlParams {
+    fn default() -> Self {
+        FlowControlParams::new(
+            FLOW_CONTROL_BUFFER_LIMIT,
+            FLOW_CONTROL_RECHARGE_RATE,
+        )
+    }
+}
+
+impl FlowControlParams {
+    /// Flow control parameters with the default request costs.
+    pub fn new(buffer_limit: u64, recharge_rate: u64) -> Self {
+        let costs = DEFAULT_REQUEST_COSTS
+            .iter()
+            .map(|&(msg_id, base_cost, per_item_cost)| RequestCost {
+                msg_id,
+                base_cost,
+                per_item_cost,
+            })
+            .collect();
+
+        FlowControlParams {
+            buffer_limit,
+            recharge_rate,
+            costs,
+        }
+    }
+
+    #[inline]
+    fn cost_entry(&self, msg_id: MsgId) -> Option<&RequestCost> {
+        self.costs.iter().find(|c| c.msg_id == msg_id)
+    }
+
+    pub fn cost_of(&self, msg_id: MsgId, num_items: usize) -> u64 {
+        match self.cost_entry(msg_id) {
+            None => 0,
+            Some(c) => c
+                .per_item_cost
+                .saturating_mul(num_items as u64)
+                .saturating_add(c.base_cost),
+        }
+    }
+}
+
+/// The credits of a light peer, as tracked by the full node serving it.
+/// Light nodes keep a local copy for each of their full peers, so that they
+/// only send requests that the peer will serve.
+#[derive(Debug)]
+pub struct CreditBuffer {
+    params: FlowControlParams,
+    credits: u64,
+    updated_at: Instant,
+}
+
+impl CreditBuffer {
+    pub fn new(params: FlowControlParams) -> Self {
+        CreditBuffer {
+            credits: params.buffer_limit,
+            params,
+            updated_at: Instant::now(),
+        }
+    }
+
+    pub fn set_params(&mut self, params: FlowControlParams) {
+        self.recharge();
+        self.credits = min(self.credits, params.buffer_limit);
+        self.params = params;
+    }
+
+    // credits after recharging them up to `now`, without updating the buffer
+    fn credits_at(&self, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+
+        let recharged = elapsed.as_nanos() * self.params.recharge_rate as u128
+            / 1_000_000_000;
+
+        min(
+            self.credits as u128 + recharged,
+            self.params.buffer_limit as u128,
+        ) as u64
+    }
+
+    fn recharge(&mut self) {
+        let now = Instant::now();
+        let credits = self.credits_at(now);
+
+        // keep the timestamp so that short intervals add up
+        if credits == self.credits && self.credits < self.params.buffer_limit {
+            return;
+        }
+
+        self.credits = credits;
+        self.updated_at = now;
+    }
+
+    /// Charge the cost of a request. If there are not enough credits, return
+    /// how long the peer needs to wait before sending the request again.
+    pub fn try_charge(
+        &mut self, msg_id: MsgId, num_items: usize,
+    ) -> Result<(), Duration> {
+        self.recharge();
+        let cost = self.params.cost_of(msg_id, num_items);
+
+        if cost <= self.credits {
+            self.credits -= cost;
+            return Ok(());
+        }
+
+        let missing = (cost - self.credits) as u128;
+        let rate = max(self.params.recharge_rate, 1) as u128;
+        let wait_nanos = missing * 1_000_000_000 / rate;
+        Err(Duration::from_nanos(
+            min(wait_nanos, u64::MAX as u128) as u64
+        ))
+    }
+
+    /// Charge the cost of a request that has already been sent.
+    pub fn charge(&mut self, msg_id: MsgId, num_items: usize) {
+        self.recharge();
+        let cost = self.params.cost_of(msg_id, num_items);
+        self.credits = self.credits.saturating_sub(cost);
+    }
+
+    /// Maximum number of items that can be requested in one request now.
+    pub fn max_items(&self, msg_id: MsgId) -> usize {
+        let cost = match self.params.cost_entry(msg_id) {
+            None => return usize::MAX,
+            Some(cost) => cost,
+        };
+
+        let credits = self.credits_at(Instant::now());
+
+        if credits < cost.base_cost {
+            return 0;
+        }
+
+        match cost.per_item_cost {
+            0 => usize::MAX,
+            c => {
+                min((credits - cost.base_cost) / c, usize::MAX as u64) as usize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CreditBuffer, FlowControlParams, RequestCost};
+    use crate::light_protocol::message::msgid;
+    use std::time::Duration;
+
+    fn params(buffer_limit: u64, recharge_rate: u64) -> FlowControlParams {
+        FlowControlParams {
+            buffer_limit,
+            recharge_rate,
+            costs: vec![RequestCost {
+                msg_id: msgid::GET_BLOCK_HEADERS,
+                base_cost: 100,
+                per_item_cost: 10,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let params = params(1_000, 0);
+        assert_eq!(params.cost_of(msgid::GET_BLOCK_HEADERS, 0), 100);
+        assert_eq!(params.cost_of(msgid::GET_BLOCK_HEADERS, 5), 150);
+        assert_eq!(params.cost_of(msgid::GET_TXS, 5), 0);
+    }
+
+    #[test]
+    fn test_default_costs_fit_in_buffer() {
+        let params = FlowControlParams::default();
+
+        for cost in &params.costs {
+            assert!(params.cost_of(cost.msg_id, 1) <= params.buffer_limit);
+        }
+    }
+
+    #[test]
+    fn test_credit_buffer() {
+        let mut buffer = CreditBuffer::new(params(1_000, 0));
+        assert_eq!(buffer.credits, 1_000);
+        assert_eq!(buffer.max_items(msgid::GET_BLOCK_HEADERS), 90);
+        assert_eq!(buffer.max_items(msgid::GET_TXS), usize::MAX);
+
+        assert_eq!(buffer.try_charge(msgid::GET_BLOCK_HEADERS, 50), Ok(()));
+        assert_eq!(buffer.credits, 400);
+        assert_eq!(buffer.max_items(msgid::GET_BLOCK_HEADERS), 30);
+
+        // cannot afford the request and the buffer does not recharge
+        assert!(buffer.try_charge(msgid::GET_BLOCK_HEADERS, 31).is_err());
+        assert_eq!(buffer.credits, 400);
+
+        // requests already sent are charged regardless
+        buffer.charge(msgid::GET_BLOCK_HEADERS, 50);
+        assert_eq!(buffer.credits, 0);
+        assert_eq!(buffer.max_items(msgid::GET_BLOCK_HEADERS), 0);
+
+        // lowering the limit caps the credits
+        let mut buffer = CreditBuffer::new(params(1_000, 0));
+        buffer.set_params(params(500, 0));
+        assert_eq!(buffer.credits, 500);
+    }
+
+    #[test]
+    fn test_wait_time() {
+        let mut buffer = CreditBuffer::new(params(1_000, 1_000));
+        assert_eq!(buffer.try_charge(msgid::GET_BLOCK_HEADERS, 90), Ok(()));
+
+        // at most 1s is needed to recharge 1000 credits
+        match buffer.try_charge(msgid::GET_BLOCK_HEADERS, 90) {
+            Ok(()) => panic!("request should not be affordable"),
+            Err(wait) => assert!(wait <= Duration::from_secs(1)),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use cfx_internal_common::StateRootWithAuxInfo;
+use cfx_storage::{
+    state::StateTrait, utils::access_mode, MptKeyValue, Result as StorageResult,
+};
+use parking_lot::{Mutex, RwLock};
+use primitives::{EpochId, StorageKey};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// The state entries of an epoch retrieved so far, and the keys accessed
+/// during execution that are not retrieved yet.
+#[derive(Default)]
+pub struct LazyStateEntries {
+    // `None` means the entry is verified to not exist.
+    entries: RwLock<HashMap<Vec<u8>, Option<Box<[u8]>>>>,
+    missing: Mutex<BTreeSet<Vec<u8>>>,
+}
+
+impl LazyStateEntries {
+    pub fn insert(&self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        self.entries
+            .write()
+            .insert(key, value.map(Vec::into_boxed_slice));
+    }
+
+    pub fn len(&self) -> usize { self.entries.read().len() }
+
+    /// Return the keys accessed since the last call that are not retrieved.
+    pub fn take_missing(&self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut *self.missing.lock())
+            .into_iter()
+            .collect()
+    }
+}
+
+// `LazyState` is a read-only storage backed by `LazyStateEntries`. Reading an
+// entry that is not retrieved records its key and returns `None`, so that the
+// execution goes on and records the other missing entries it accesses. The
+// caller discards the outcome, retrieves the missing entries from peers in one
+// batch, and executes again.
+pub struct LazyState {
+    entries: Arc<LazyStateEntries>,
+}
+
+impl LazyState {
+    pub fn new(entries: Arc<LazyStateEntries>) -> Self { LazyState { entries } }
+}
+
+impl StateTrait for LazyState {
+    fn get(&self, access_key: StorageKey) -> StorageResult<Option<Box<[u8]>>> {
+        let key = access_key.to_key_bytes();
+        if let Some(value) = self.entries.entries.read().get(&key) {
+            return Ok(value.clone());
+        }
+        self.entries.missing.lock().insert(key);
+        Ok(None)
+    }
+
+    fn set(
+        &mut self, _access_key: StorageKey, _value: Box<[u8]>,
+    ) -> StorageResult<()> {
+        Err("Light node state is read-only".into())
+    }
+
+    fn delete(&mut self, _access_key: StorageKey) -> StorageResult<()> {
+        Err("Light node state is read-only".into())
+    }
+
+    fn delete_test_only(
+        &mut self, _access_key: StorageKey,
+    ) -> StorageResult<Option<Box<[u8]>>> {
+        Err("Light node state is read-only".into())
+    }
+
+    // Peers cannot prove that a prefix has no other entries, so the entries
+    // under a prefix cannot be listed. This happens when a contract is killed,
+    // since the storage collateral of all its entries is refunded, so virtual
+    // calls that kill contracts are not supported on light nodes.
+    fn delete_all<AM: access_mode::AccessMode>(
+        &mut self, _access_key_prefix: StorageKey,
+    ) -> StorageResult<Option<Vec<MptKeyValue>>> {
+        Err(
+            "Killing contracts is not supported in virtual calls on light \
+             nodes, as the storage entries of contracts cannot be listed"
+                .into(),
+        )
+    }
+
+    fn compute_state_root(&mut self) -> StorageResult<StateRootWithAuxInfo> {
+        Err("Light node state has no state root".into())
+    }
+
+    fn get_state_root(&self) -> StorageResult<StateRootWithAuxInfo> {
+        Err("Light node state has no state root".into())
+    }
+
+    fn commit(
+        &mut self, _epoch: EpochId,
+    ) -> StorageResult<StateRootWithAuxInfo> {
+        Err("Light node state is read-only".into())
+    }
+}
@@ -2,9 +2,13 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+mod flow_control;
+mod lazy_state;
 mod ledger_info;
 mod peers;
 
+pub use flow_control::{CreditBuffer, FlowControlParams};
+pub use lazy_state::{LazyState, LazyStateEntries};
 pub use ledger_info::LedgerInfo;
 pub use peers::{FullPeerFilter, FullPeerState, LightPeerState, Peers};
 
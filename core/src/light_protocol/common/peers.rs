@@ -10,6 +10,7 @@ use std::{
     sync::Arc,
 };
 
+use super::CreditBuffer;
 use crate::message::MsgId;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
@@ -20,6 +21,7 @@ use throttling::token_bucket::{ThrottledManager, TokenBucketManager};
 #[derive(Default)]
 pub struct FullPeerState {
     pub best_epoch: u64,
+    pub credits: Option<CreditBuffer>,
     pub handshake_completed: bool,
     pub protocol_version: ProtocolVersion,
     pub terminals: HashSet<H256>,
@@ -27,8 +29,28 @@ pub struct FullPeerState {
     pub unexpected_msgs: TokenBucketManager,
 }
 
+impl FullPeerState {
+    /// Maximum number of items that can be requested from this peer in a
+    /// single request without exceeding its flow control credits.
+    pub fn max_items(&self, msg_id: MsgId) -> usize {
+        match self.credits {
+            Some(ref credits) => credits.max_items(msg_id),
+            None => usize::MAX,
+        }
+    }
+
+    /// Deduct the cost of a request sent to this peer from its credits.
+    pub fn charge(&mut self, msg_id: MsgId, num_items: usize) {
+        if let Some(ref mut credits) = self.credits {
+            credits.charge(msg_id, num_items);
+        }
+    }
+}
+
 #[derive(Default, DeriveMallocSizeOf)]
 pub struct LightPeerState {
+    #[ignore_malloc_size_of = "insignificant"]
+    pub credits: Option<CreditBuffer>,
     pub handshake_completed: bool,
     pub protocol_version: ProtocolVersion,
     pub throttling: TokenBucketManager,
@@ -91,6 +113,7 @@ where T: Default
 pub struct FullPeerFilter {
     msg_id: MsgId,
     min_best_epoch: Option<u64>,
+    min_protocol_version: Option<ProtocolVersion>,
 }
 
 impl FullPeerFilter {
@@ -98,6 +121,7 @@ impl FullPeerFilter {
         FullPeerFilter {
             msg_id,
             min_best_epoch: None,
+            min_protocol_version: None,
         }
     }
 
@@ -106,6 +130,13 @@ impl FullPeerFilter {
         self
     }
 
+    pub fn with_min_protocol_version(
+        mut self, min_protocol_version: Option<ProtocolVersion>,
+    ) -> Self {
+        self.min_protocol_version = min_protocol_version;
+        self
+    }
+
     pub fn select(self, peers: Arc<Peers<FullPeerState>>) -> Option<NodeId> {
         self.select_all(peers)
             .choose(&mut rand::thread_rng())
@@ -118,6 +149,17 @@ impl FullPeerFilter {
                 return false;
             }
 
+            // skip peers that would reject the request for lack of credits
+            if peer.max_items(self.msg_id) == 0 {
+                return false;
+            }
+
+            let min_protocol_version =
+                self.min_protocol_version.unwrap_or_default();
+            if peer.protocol_version < min_protocol_version {
+                return false;
+            }
+
             let min_best_epoch = self.min_best_epoch.unwrap_or_default();
             peer.best_epoch >= min_best_epoch
         })
@@ -54,6 +54,16 @@ error_chain! {
             display("Internal error: {:?}", details),
         }
 
+        InvalidAccountPendingTxs{ address: H160, reason: String } {
+            description("Invalid account pending txs"),
+            display("Invalid pending txs for account {:?}: {}", address, reason),
+        }
+
+        InvalidBlockReward{ hash: H256, reason: &'static str } {
+            description("Invalid block reward"),
+            display("Invalid reward for block {:?}: {}", hash, reason),
+        }
+
         InvalidBloom{ epoch: u64, expected: H256, received: H256 } {
             description("Logs bloom hash validation failed"),
             display("Logs bloom hash validation for epoch {} failed, expected={:?}, received={:?}", epoch, expected, received),
@@ -230,7 +240,9 @@ pub fn handle(
             op = Some(UpdateNodeOperation::Demotion)
         }
 
-        ErrorKind::InvalidBloom{..}
+        ErrorKind::InvalidAccountPendingTxs{..}
+        | ErrorKind::InvalidBlockReward{..}
+        | ErrorKind::InvalidBloom{..}
         | ErrorKind::InvalidLedgerProofSize{..}
         | ErrorKind::InvalidMessageFormat
         | ErrorKind::InvalidPreviousStateRoot{..}
@@ -8,17 +8,19 @@ use crate::{
     block_data_manager::BlockDataManager,
     consensus::SharedConsensusGraph,
     light_protocol::{
-        common::{validate_chain_id, FullPeerState, Peers},
+        common::{validate_chain_id, CreditBuffer, FullPeerState, Peers},
         error::*,
         handle_error,
         message::{
-            msgid, BlockHashes as GetBlockHashesResponse,
+            msgid, AccountPendingTxs as GetAccountPendingTxsResponse,
+            BlockHashes as GetBlockHashesResponse,
             BlockHeaders as GetBlockHeadersResponse,
+            BlockRewards as GetBlockRewardsResponse,
             BlockTxs as GetBlockTxsResponse, Blooms as GetBloomsResponse,
             NewBlockHashes, NodeType, Receipts as GetReceiptsResponse,
             SendRawTx, StateEntries as GetStateEntriesResponse,
             StateRoots as GetStateRootsResponse, StatusPingDeprecatedV1,
-            StatusPingV2, StatusPongDeprecatedV1, StatusPongV2,
+            StatusPingV2, StatusPongDeprecatedV1, StatusPongV2, StatusPongV3,
             StorageRoots as GetStorageRootsResponse,
             TxInfos as GetTxInfosResponse, Txs as GetTxsResponse,
             WitnessInfo as GetWitnessInfoResponse,
@@ -51,8 +53,9 @@ use std::{
     time::{Duration, Instant},
 };
 use sync::{
-    BlockTxs, Blooms, Epochs, HashSource, Headers, Receipts, StateEntries,
-    StateRoots, StorageRoots, TxInfos, Txs, Witnesses,
+    AccountPendingTxs, BlockRewards, BlockTxs, Blooms, Epochs, HashSource,
+    Headers, Receipts, StateEntries, StateRoots, StorageRoots, TxInfos, Txs,
+    Witnesses,
 };
 use throttling::token_bucket::TokenBucketManager;
 
@@ -67,6 +70,12 @@ const TOTAL_WEIGHT_IN_PAST_TIMER: TimerToken = 4;
 pub struct Handler {
     pub protocol_version: ProtocolVersion,
 
+    // account pending tx sync manager
+    pub account_pending_txs: AccountPendingTxs,
+
+    // block reward sync manager
+    pub block_rewards: BlockRewards,
+
     // block tx sync manager
     pub block_txs: Arc<BlockTxs>,
 
@@ -126,6 +135,18 @@ impl Handler {
         let peers = Arc::new(Peers::new());
         let request_id_allocator = Arc::new(UniqueId::new());
 
+        let data_man = consensus.get_data_manager().clone();
+
+        // resume from the witnesses stored before the last restart; the
+        // headers are recovered by `recover_graph_from_db` below
+        let restored_height = match data_man.light_sync_progress() {
+            Some(progress) => {
+                info!("Resuming light sync from height {}", progress.height);
+                progress.height
+            }
+            None => 0,
+        };
+
         let headers = Arc::new(Headers::new(
             graph.clone(),
             peers.clone(),
@@ -145,6 +166,7 @@ impl Handler {
             consensus.clone(),
             peers.clone(),
             request_id_allocator.clone(),
+            restored_height,
         ));
 
         let blooms = Blooms::new(
@@ -198,18 +220,26 @@ impl Handler {
             witnesses.clone(),
         );
 
+        let block_rewards =
+            BlockRewards::new(peers.clone(), request_id_allocator.clone());
+
+        let account_pending_txs =
+            AccountPendingTxs::new(peers.clone(), request_id_allocator.clone());
+
         let stopped = Arc::new(AtomicBool::new(false));
 
         let join_handle = Some(Self::start_witness_worker(
             notifications,
             witnesses.clone(),
             stopped.clone(),
-            consensus.get_data_manager().clone(),
+            data_man,
         ));
 
-        graph.recover_graph_from_db();
+        graph.recover_graph_from_db(true /* light_node */);
 
         Handler {
+            account_pending_txs,
+            block_rewards,
             block_txs,
             blooms,
             consensus,
@@ -284,26 +314,40 @@ impl Handler {
                     // after chain reorg, height X is not blamed anymore
                     // --> need to make sure to serve correct roots directly from
                     //     header D instead of the stale roots retrieved for B
-                    data_man.remove_blamed_header_verified_roots(height);
+                    //
+                    // roots stored before a restart are only reused if they
+                    // match the current witness header
 
                     // handle witness
                     match maybe_witness {
                         // request witness for blamed headers
                         Some(w) => {
-                            // this request covers all blamed headers:
-                            // [w - w.blame, w - w.blame + 1, ..., w]
-                            debug!("Requesting witness at height {}", w);
-                            witnesses.request(w);
+                            if witnesses.restore(w) {
+                                debug!("Restored witness at height {}", w);
+                            } else {
+                                data_man.remove_blamed_header_verified_roots(height);
+
+                                // this request covers all blamed headers:
+                                // [w - w.blame, w - w.blame + 1, ..., w]
+                                debug!("Requesting witness at height {}", w);
+                                witnesses.request(w);
+                            }
                         }
 
                         // for non-blamed headers, we will serve roots from disk
                         None => {
+                            data_man.remove_blamed_header_verified_roots(height);
+
                             // `height` might have been blamed before a chain reorg
                             witnesses.in_flight.write().remove(&height);
                         }
                     }
 
                     *witnesses.height_of_latest_verified_header.write() = height;
+                    witnesses.persist_progress(height);
+
+                    // send verified height to pub-sub layer
+                    notifications.verified_header_heights.send(height);
                 }
             })
             .expect("Starting the Witness Worker should succeed")
@@ -338,13 +382,15 @@ impl Handler {
 
         if msg_id != msgid::STATUS_PONG_DEPRECATED
             && msg_id != msgid::STATUS_PONG_V2
+            && msg_id != msgid::STATUS_PONG_V3
             && !state.read().handshake_completed
         {
             warn!("Received msg={:?} from handshaking peer={:?}", msg_id, peer);
             bail!(ErrorKind::UnexpectedMessage {
                 expected: vec![
                     msgid::STATUS_PONG_DEPRECATED,
-                    msgid::STATUS_PONG_V2
+                    msgid::STATUS_PONG_V2,
+                    msgid::STATUS_PONG_V3,
                 ],
                 received: msg_id,
             });
@@ -387,10 +433,13 @@ impl Handler {
             // general messages
             msgid::STATUS_PONG_DEPRECATED => self.on_status_deprecated(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::STATUS_PONG_V2 => self.on_status_v2(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::STATUS_PONG_V3 => self.on_status_v3(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
 
             // sync messages
+            msgid::ACCOUNT_PENDING_TXS => self.on_account_pending_txs(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::BLOCK_HASHES => self.on_block_hashes(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::BLOCK_HEADERS => self.on_block_headers(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::BLOCK_REWARDS => self.on_block_rewards(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::BLOCK_TXS => self.on_block_txs(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::BLOOMS => self.on_blooms(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::NEW_BLOCK_HASHES => self.on_new_block_hashes(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
@@ -544,9 +593,51 @@ impl Handler {
     ) -> Result<()> {
         let msg: Box<dyn Message> = Box::new(SendRawTx { raw });
         msg.send(io, peer)?;
+
+        // the message is already sent, so do not fail if the peer has
+        // disconnected in the meantime
+        if let Some(state) = self.peers.get(peer) {
+            state.write().charge(msgid::SEND_RAW_TX, 1);
+        }
+
         Ok(())
     }
 
+    fn on_status_v3(
+        &self, io: &dyn NetworkContext, peer: &NodeId, status: StatusPongV3,
+    ) -> Result<()> {
+        debug!("on_status (v3) peer={:?} status={:?}", peer, status);
+
+        // keep track of the credits the peer allows us, so that we only
+        // send requests that it will serve; heartbeats update the params
+        {
+            let state = self.get_existing_peer_state(peer)?;
+            let mut state = state.write();
+
+            let credits = match state.credits.take() {
+                Some(mut credits) => {
+                    credits.set_params(status.flow_control);
+                    credits
+                }
+                None => CreditBuffer::new(status.flow_control),
+            };
+
+            state.credits = Some(credits);
+        }
+
+        self.on_status_v2(
+            io,
+            peer,
+            StatusPongV2 {
+                chain_id: status.chain_id,
+                node_type: status.node_type,
+                genesis_hash: status.genesis_hash,
+                best_epoch: status.best_epoch,
+                terminals: status.terminals,
+            },
+        )
+    }
+
     fn on_status_v2(
         &self, io: &dyn NetworkContext, peer: &NodeId, status: StatusPongV2,
     ) -> Result<()> {
@@ -596,6 +687,28 @@ impl Handler {
         )
     }
 
+    fn on_account_pending_txs(
+        &self, io: &dyn NetworkContext, peer: &NodeId,
+        resp: GetAccountPendingTxsResponse,
+    ) -> Result<()>
+    {
+        debug!(
+            "received {} account pending txs (request id = {})",
+            resp.txs.len(),
+            resp.request_id
+        );
+        trace!("on_account_pending_txs resp={:?}", resp);
+
+        self.account_pending_txs.receive(
+            peer,
+            resp.request_id,
+            resp.txs.into_iter(),
+        )?;
+
+        self.account_pending_txs.sync(io);
+        Ok(())
+    }
+
     fn on_block_hashes(
         &self, io: &dyn NetworkContext, _peer: &NodeId,
         resp: GetBlockHashesResponse,
@@ -640,6 +753,28 @@ impl Handler {
         Ok(())
     }
 
+    fn on_block_rewards(
+        &self, io: &dyn NetworkContext, peer: &NodeId,
+        resp: GetBlockRewardsResponse,
+    ) -> Result<()>
+    {
+        debug!(
+            "received {} block rewards (request id = {})",
+            resp.rewards.len(),
+            resp.request_id
+        );
+        trace!("on_block_rewards resp={:?}", resp);
+
+        self.block_rewards.receive(
+            peer,
+            resp.request_id,
+            resp.rewards.into_iter(),
+        )?;
+
+        self.block_rewards.sync(io);
+        Ok(())
+    }
+
     fn on_block_txs(
         &self, io: &dyn NetworkContext, peer: &NodeId,
         resp: GetBlockTxsResponse,
@@ -864,6 +999,8 @@ impl Handler {
         self.blooms.sync(io);
         self.receipts.sync(io);
         self.block_txs.sync(io);
+        self.block_rewards.sync(io);
+        self.account_pending_txs.sync(io);
         self.state_entries.sync(io);
         self.state_roots.sync(io);
         self.storage_roots.sync(io);
@@ -872,6 +1009,8 @@ impl Handler {
     }
 
     fn clean_up_requests(&self) {
+        self.account_pending_txs.clean_up();
+        self.block_rewards.clean_up();
         self.block_txs.clean_up();
         self.blooms.clean_up();
         self.epochs.clean_up();
@@ -1032,6 +1171,8 @@ impl NetworkProtocolHandler for Handler {
             REQUEST_CLEANUP_TIMER => self.clean_up_requests(),
             LOG_STATISTICS_TIMER => {
                 self.print_stats();
+                self.account_pending_txs.print_stats();
+                self.block_rewards.print_stats();
                 self.block_txs.print_stats();
                 self.blooms.print_stats();
                 self.epochs.print_stats();
@@ -0,0 +1,323 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+extern crate lru_time_cache;
+
+use super::common::{FutureItem, PendingItem, SyncManager, TimeOrdered};
+use crate::{
+    light_protocol::{
+        common::{FullPeerState, Peers},
+        error::*,
+        message::{
+            msgid, AccountPendingTxsKey, AccountPendingTxsWithKey,
+            GetAccountPendingTxs,
+        },
+        LIGHT_PROTO_V3,
+    },
+    message::{Message, RequestId},
+    transaction_pool::TransactionStatus,
+    UniqueId,
+};
+use cfx_parameters::light::{
+    ACCOUNT_PENDING_TXS_REQUEST_BATCH_SIZE,
+    ACCOUNT_PENDING_TXS_REQUEST_TIMEOUT, CACHE_TIMEOUT,
+    MAX_ACCOUNT_PENDING_TXS_IN_FLIGHT,
+};
+use cfxkey::public_to_address;
+use futures::future::FutureExt;
+use lru_time_cache::LruCache;
+use network::{node_table::NodeId, NetworkContext};
+use parking_lot::RwLock;
+use primitives::SignedTransaction;
+use std::{future::Future, sync::Arc};
+
+#[derive(Clone, Debug)]
+pub struct AccountPendingTxsValidated {
+    pub pending_txs: Vec<SignedTransaction>,
+    pub first_tx_status: Option<TransactionStatus>,
+    pub pending_count: u64,
+}
+
+#[derive(Debug)]
+struct Statistics {
+    cached: usize,
+    in_flight: usize,
+    waiting: usize,
+}
+
+// prioritize earlier requests
+type MissingAccountPendingTxs = TimeOrdered<AccountPendingTxsKey>;
+
+type PendingAccountPendingTxs =
+    PendingItem<AccountPendingTxsValidated, ClonableError>;
+
+pub struct AccountPendingTxs {
+    // series of unique request ids
+    request_id_allocator: Arc<UniqueId>,
+
+    // sync and request manager
+    sync_manager: SyncManager<AccountPendingTxsKey, MissingAccountPendingTxs>,
+
+    // pending txs received from full node
+    verified:
+        Arc<RwLock<LruCache<AccountPendingTxsKey, PendingAccountPendingTxs>>>,
+}
+
+impl AccountPendingTxs {
+    pub fn new(
+        peers: Arc<Peers<FullPeerState>>, request_id_allocator: Arc<UniqueId>,
+    ) -> Self {
+        let sync_manager =
+            SyncManager::new(peers.clone(), msgid::GET_ACCOUNT_PENDING_TXS)
+                .with_min_protocol_version(LIGHT_PROTO_V3);
+
+        let cache = LruCache::with_expiry_duration(*CACHE_TIMEOUT);
+        let verified = Arc::new(RwLock::new(cache));
+
+        AccountPendingTxs {
+            request_id_allocator,
+            sync_manager,
+            verified,
+        }
+    }
+
+    #[inline]
+    pub fn print_stats(&self) {
+        debug!(
+            "account pending tx sync statistics: {:?}",
+            Statistics {
+                cached: self.verified.read().len(),
+                in_flight: self.sync_manager.num_in_flight(),
+                waiting: self.sync_manager.num_waiting(),
+            }
+        );
+    }
+
+    #[inline]
+    pub fn request_now(
+        &self, io: &dyn NetworkContext, key: AccountPendingTxsKey,
+    ) -> impl Future<Output = Result<AccountPendingTxsValidated>> {
+        let mut verified = self.verified.write();
+
+        // the pending txs of an account change frequently, so we do not serve
+        // them from the cache; we only join requests that are still pending
+        let pending = match verified.get(&key) {
+            Some(PendingItem::Pending(_)) => true,
+            _ => false,
+        };
+
+        if !pending {
+            verified.insert(key.clone(), PendingItem::pending());
+
+            let missing =
+                std::iter::once(MissingAccountPendingTxs::new(key.clone()));
+
+            self.sync_manager.request_now(missing, |peer, keys| {
+                self.send_request(io, peer, keys)
+            });
+        }
+
+        FutureItem::new(key, self.verified.clone())
+            .map(|res| res.map_err(|e| e.into()))
+    }
+
+    #[inline]
+    pub fn receive(
+        &self, peer: &NodeId, id: RequestId,
+        txs: impl Iterator<Item = AccountPendingTxsWithKey>,
+    ) -> Result<()>
+    {
+        for AccountPendingTxsWithKey {
+            key,
+            pending_txs,
+            first_tx_status,
+            pending_count,
+        } in txs
+        {
+            trace!(
+                "Validating {} pending txs with key {:?}",
+                pending_txs.len(),
+                key
+            );
+
+            let validated = AccountPendingTxsValidated {
+                pending_txs,
+                first_tx_status,
+                pending_count,
+            };
+
+            match self.sync_manager.check_if_requested(peer, id, &key)? {
+                None => continue,
+                Some(_) => self.validate_and_store(key, validated)?,
+            };
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn validate_and_store(
+        &self, key: AccountPendingTxsKey, mut txs: AccountPendingTxsValidated,
+    ) -> Result<()> {
+        // validate pending txs
+        if let Err(e) = self.validate_pending_txs(&key, &txs) {
+            // forward error to both rpc caller(s) and sync handler
+            // so we need to make it clonable
+            let e = ClonableError::from(e);
+
+            self.verified
+                .write()
+                .entry(key.clone())
+                .or_insert(PendingItem::pending())
+                .set_error(e.clone());
+
+            bail!(e);
+        }
+
+        // the senders are verified to be the requested address
+        for tx in &mut txs.pending_txs {
+            tx.sender = key.address;
+        }
+
+        self.verified
+            .write()
+            .entry(key.clone())
+            .or_insert(PendingItem::pending())
+            .set(txs);
+
+        self.sync_manager.remove_in_flight(&key);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn clean_up(&self) {
+        // remove timeout in-flight requests
+        let timeout = *ACCOUNT_PENDING_TXS_REQUEST_TIMEOUT;
+        let keys = self.sync_manager.remove_timeout_requests(timeout);
+        trace!("Timeout account pending txs ({}): {:?}", keys.len(), keys);
+        self.sync_manager.insert_waiting(keys.into_iter());
+
+        // trigger cache cleanup
+        self.verified.write().get(&Default::default());
+    }
+
+    #[inline]
+    fn send_request(
+        &self, io: &dyn NetworkContext, peer: &NodeId,
+        keys: Vec<AccountPendingTxsKey>,
+    ) -> Result<Option<RequestId>>
+    {
+        if keys.is_empty() {
+            return Ok(None);
+        }
+
+        let request_id = self.request_id_allocator.next();
+
+        trace!(
+            "send_request GetAccountPendingTxs peer={:?} id={:?} keys={:?}",
+            peer,
+            request_id,
+            keys
+        );
+
+        let msg: Box<dyn Message> =
+            Box::new(GetAccountPendingTxs { request_id, keys });
+
+        msg.send(io, peer)?;
+        Ok(Some(request_id))
+    }
+
+    #[inline]
+    pub fn sync(&self, io: &dyn NetworkContext) {
+        self.sync_manager.sync(
+            MAX_ACCOUNT_PENDING_TXS_IN_FLIGHT,
+            ACCOUNT_PENDING_TXS_REQUEST_BATCH_SIZE,
+            |peer, keys| self.send_request(io, peer, keys),
+        );
+    }
+
+    // NOTE: the content of the tx pool of a full node cannot be proved, so we
+    // can only check that the txs are correctly signed by the account and
+    // that they are consistent with the request.
+    #[inline]
+    fn validate_pending_txs(
+        &self, key: &AccountPendingTxsKey, txs: &AccountPendingTxsValidated,
+    ) -> Result<()> {
+        let address = key.address;
+        let num_txs = txs.pending_txs.len() as u64;
+
+        if num_txs > txs.pending_count {
+            bail!(ErrorKind::InvalidAccountPendingTxs {
+                address,
+                reason: format!(
+                    "Received {} txs but the pending count is {}",
+                    num_txs, txs.pending_count
+                ),
+            });
+        }
+
+        if let Some(limit) = key.limit {
+            if num_txs > limit {
+                bail!(ErrorKind::InvalidAccountPendingTxs {
+                    address,
+                    reason: format!(
+                        "Received {} txs but the limit is {}",
+                        num_txs, limit
+                    ),
+                });
+            }
+        }
+
+        if (txs.pending_count == 0) != txs.first_tx_status.is_none() {
+            bail!(ErrorKind::InvalidAccountPendingTxs {
+                address,
+                reason: format!(
+                    "Unexpected status {:?} with pending count {}",
+                    txs.first_tx_status, txs.pending_count
+                ),
+            });
+        }
+
+        for tx in &txs.pending_txs {
+            match tx.verify_public(false /* skip */) {
+                Ok(true) => {}
+                _ => {
+                    warn!("Tx signature verification failed for {:?}", tx);
+                    bail!(ErrorKind::InvalidTxSignature { hash: tx.hash() });
+                }
+            }
+
+            // `tx.sender()` is decoded as is, so the sender is computed from
+            // the public key verified above
+            let sender =
+                public_to_address(&tx.public().expect("public key verified"));
+            if sender != address {
+                bail!(ErrorKind::InvalidAccountPendingTxs {
+                    address,
+                    reason: format!(
+                        "Tx {:?} is sent by {:?}",
+                        tx.hash(),
+                        sender
+                    ),
+                });
+            }
+
+            if let Some(start_nonce) = key.start_nonce {
+                if tx.nonce() < start_nonce {
+                    bail!(ErrorKind::InvalidAccountPendingTxs {
+                        address,
+                        reason: format!(
+                            "Tx {:?} has nonce {} below the start nonce {}",
+                            tx.hash(),
+                            tx.nonce(),
+                            start_nonce
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+extern crate lru_time_cache;
+
+use super::common::{FutureItem, PendingItem, SyncManager, TimeOrdered};
+use crate::{
+    block_data_manager::BlockRewardResult,
+    light_protocol::{
+        common::{FullPeerState, Peers},
+        error::*,
+        message::{msgid, BlockRewardWithHash, GetBlockRewards},
+        LIGHT_PROTO_V3,
+    },
+    message::{Message, RequestId},
+    UniqueId,
+};
+use cfx_parameters::light::{
+    BLOCK_REWARD_REQUEST_BATCH_SIZE, BLOCK_REWARD_REQUEST_TIMEOUT,
+    CACHE_TIMEOUT, MAX_BLOCK_REWARDS_IN_FLIGHT,
+};
+use cfx_types::H256;
+use futures::future::FutureExt;
+use lru_time_cache::LruCache;
+use network::{node_table::NodeId, NetworkContext};
+use parking_lot::RwLock;
+use std::{future::Future, sync::Arc};
+
+#[derive(Debug)]
+struct Statistics {
+    cached: usize,
+    in_flight: usize,
+    waiting: usize,
+}
+
+// prioritize earlier requests
+type MissingBlockReward = TimeOrdered<H256>;
+
+type PendingBlockReward = PendingItem<BlockRewardResult, ClonableError>;
+
+pub struct BlockRewards {
+    // series of unique request ids
+    request_id_allocator: Arc<UniqueId>,
+
+    // sync and request manager
+    sync_manager: SyncManager<H256, MissingBlockReward>,
+
+    // block rewards received from full node
+    verified: Arc<RwLock<LruCache<H256, PendingBlockReward>>>,
+}
+
+impl BlockRewards {
+    pub fn new(
+        peers: Arc<Peers<FullPeerState>>, request_id_allocator: Arc<UniqueId>,
+    ) -> Self {
+        let sync_manager =
+            SyncManager::new(peers.clone(), msgid::GET_BLOCK_REWARDS)
+                .with_min_protocol_version(LIGHT_PROTO_V3);
+
+        let cache = LruCache::with_expiry_duration(*CACHE_TIMEOUT);
+        let verified = Arc::new(RwLock::new(cache));
+
+        BlockRewards {
+            request_id_allocator,
+            sync_manager,
+            verified,
+        }
+    }
+
+    #[inline]
+    pub fn print_stats(&self) {
+        debug!(
+            "block reward sync statistics: {:?}",
+            Statistics {
+                cached: self.verified.read().len(),
+                in_flight: self.sync_manager.num_in_flight(),
+                waiting: self.sync_manager.num_waiting(),
+            }
+        );
+    }
+
+    #[inline]
+    pub fn request_now(
+        &self, io: &dyn NetworkContext, hash: H256,
+    ) -> impl Future<Output = Result<BlockRewardResult>> {
+        let mut verified = self.verified.write();
+
+        if !verified.contains_key(&hash) {
+            let missing = std::iter::once(MissingBlockReward::new(hash));
+
+            self.sync_manager.request_now(missing, |peer, hashes| {
+                self.send_request(io, peer, hashes)
+            });
+        }
+
+        verified
+            .entry(hash)
+            .or_insert(PendingItem::pending())
+            .clear_error();
+
+        FutureItem::new(hash, self.verified.clone())
+            .map(|res| res.map_err(|e| e.into()))
+    }
+
+    #[inline]
+    pub fn receive(
+        &self, peer: &NodeId, id: RequestId,
+        rewards: impl Iterator<Item = BlockRewardWithHash>,
+    ) -> Result<()>
+    {
+        for BlockRewardWithHash { hash, reward } in rewards {
+            trace!("Validating block reward {:?} for block {:?}", reward, hash);
+
+            match self.sync_manager.check_if_requested(peer, id, &hash)? {
+                None => continue,
+                Some(_) => self.validate_and_store(hash, reward)?,
+            };
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn validate_and_store(
+        &self, hash: H256, reward: BlockRewardResult,
+    ) -> Result<()> {
+        // validate block reward
+        if let Err(e) = self.validate_block_reward(hash, &reward) {
+            // forward error to both rpc caller(s) and sync handler
+            // so we need to make it clonable
+            let e = ClonableError::from(e);
+
+            self.verified
+                .write()
+                .entry(hash)
+                .or_insert(PendingItem::pending())
+                .set_error(e.clone());
+
+            bail!(e);
+        }
+
+        self.verified
+            .write()
+            .entry(hash)
+            .or_insert(PendingItem::pending())
+            .set(reward);
+
+        self.sync_manager.remove_in_flight(&hash);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn clean_up(&self) {
+        // remove timeout in-flight requests
+        let timeout = *BLOCK_REWARD_REQUEST_TIMEOUT;
+        let rewards = self.sync_manager.remove_timeout_requests(timeout);
+        trace!("Timeout block rewards ({}): {:?}", rewards.len(), rewards);
+        self.sync_manager.insert_waiting(rewards.into_iter());
+
+        // trigger cache cleanup
+        self.verified.write().get(&Default::default());
+    }
+
+    #[inline]
+    fn send_request(
+        &self, io: &dyn NetworkContext, peer: &NodeId, hashes: Vec<H256>,
+    ) -> Result<Option<RequestId>> {
+        if hashes.is_empty() {
+            return Ok(None);
+        }
+
+        let request_id = self.request_id_allocator.next();
+
+        trace!(
+            "send_request GetBlockRewards peer={:?} id={:?} hashes={:?}",
+            peer,
+            request_id,
+            hashes
+        );
+
+        let msg: Box<dyn Message> =
+            Box::new(GetBlockRewards { request_id, hashes });
+
+        msg.send(io, peer)?;
+        Ok(Some(request_id))
+    }
+
+    #[inline]
+    pub fn sync(&self, io: &dyn NetworkContext) {
+        self.sync_manager.sync(
+            MAX_BLOCK_REWARDS_IN_FLIGHT,
+            BLOCK_REWARD_REQUEST_BATCH_SIZE,
+            |peer, hashes| self.send_request(io, peer, hashes),
+        );
+    }
+
+    // NOTE: block rewards are not committed to in block headers, so we can
+    // only check that the reward is consistent with its own components.
+    #[inline]
+    fn validate_block_reward(
+        &self, hash: H256, reward: &BlockRewardResult,
+    ) -> Result<()> {
+        // the total reward also includes the secondary reward
+        match reward.base_reward.checked_add(reward.tx_fee) {
+            Some(sum) if sum <= reward.total_reward => Ok(()),
+            _ => bail!(ErrorKind::InvalidBlockReward {
+                hash,
+                reason: "Total reward is less than base reward plus tx fee",
+            }),
+        }
+    }
+}
@@ -10,7 +10,7 @@ use crate::{
     },
     message::{MsgId, RequestId},
 };
-use network::node_table::NodeId;
+use network::{node_table::NodeId, service::ProtocolVersion};
 use parking_lot::{Mutex, RwLock};
 use std::{
     cmp::Ord,
@@ -54,6 +54,9 @@ pub struct SyncManager<Key, Item> {
 
     // used to filter peer to send request
     request_msg_id: MsgId,
+
+    // peers with older protocol versions do not support the request
+    min_protocol_version: Option<ProtocolVersion>,
 }
 
 impl<Key, Item> SyncManager<Key, Item>
@@ -74,9 +77,17 @@ where
             sync_lock,
             waiting,
             request_msg_id,
+            min_protocol_version: None,
         }
     }
 
+    pub fn with_min_protocol_version(
+        mut self, min_protocol_version: ProtocolVersion,
+    ) -> Self {
+        self.min_protocol_version.replace(min_protocol_version);
+        self
+    }
+
     #[inline]
     pub fn num_waiting(&self) -> usize { self.waiting.read().len() }
 
@@ -105,6 +116,21 @@ where
         }
     }
 
+    #[inline]
+    fn max_items(&self, peer: &NodeId) -> usize {
+        match self.peers.get(peer) {
+            Some(state) => state.read().max_items(self.request_msg_id),
+            None => 0,
+        }
+    }
+
+    #[inline]
+    fn charge(&self, peer: &NodeId, num_items: usize) {
+        if let Some(state) = self.peers.get(peer) {
+            state.write().charge(self.request_msg_id, num_items);
+        }
+    }
+
     #[inline]
     pub fn check_if_requested(
         &self, peer: &NodeId, request_id: RequestId, key: &Key,
@@ -205,6 +231,7 @@ where
 
             // select peer for batch
             let peer = match FullPeerFilter::new(self.request_msg_id)
+                .with_min_protocol_version(self.min_protocol_version)
                 .select(self.peers.clone())
             {
                 Some(peer) => peer,
@@ -215,11 +242,24 @@ where
                 }
             };
 
+            // do not request more than the peer's credits allow
+            let max_items = self.max_items(&peer);
+
+            if batch.len() > max_items {
+                waiting.extend(batch.split_off(max_items).into_iter());
+            }
+
+            if batch.is_empty() {
+                return;
+            }
+
             let keys = batch.iter().map(|h| h.key()).collect();
 
             match request(&peer, keys) {
                 Ok(None) => {}
                 Ok(Some(request_id)) => {
+                    self.charge(&peer, batch.len());
+
                     let new_in_flight =
                         batch.to_owned().into_iter().map(|item| {
                             (item.key(), InFlightRequest::new(item, request_id))
@@ -268,6 +308,7 @@ where
         I: Iterator<Item = Item>,
     {
         let peer = match FullPeerFilter::new(self.request_msg_id)
+            .with_min_protocol_version(self.min_protocol_version)
             .select(self.peers.clone())
         {
             Some(peer) => peer,
@@ -290,15 +331,30 @@ where
     {
         let mut in_flight = self.in_flight.write();
 
-        let missing = items
+        let mut missing = items
             .filter(|item| !in_flight.contains_key(&item.key()))
             .collect::<Vec<_>>();
 
+        // do not request more than the peer's credits allow; the rest are
+        // requested later by `sync`
+        let max_items = self.max_items(peer);
+
+        if missing.len() > max_items {
+            // NOTE: these items are not in flight, and `in_flight` is locked
+            self.waiting.write().extend(missing.split_off(max_items));
+        }
+
+        if missing.is_empty() {
+            return;
+        }
+
         let keys = missing.iter().map(|h| h.key()).collect();
 
         match request(peer, keys) {
             Ok(None) => {}
             Ok(Some(request_id)) => {
+                self.charge(peer, missing.len());
+
                 let new_in_flight = missing.into_iter().map(|item| {
                     (item.key(), InFlightRequest::new(item, request_id))
                 });
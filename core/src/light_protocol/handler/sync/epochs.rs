@@ -301,11 +301,34 @@ impl Epochs {
                 }
             };
 
+            // do not request more than the peer's credits allow; as later
+            // batches would skip the rest, they are requested in the next round
+            let max_items = match self.peers.get(&peer) {
+                Some(state) => {
+                    state.read().max_items(msgid::GET_BLOCK_HASHES_BY_EPOCH)
+                }
+                None => 0,
+            };
+
+            let capped = batch.len() > max_items;
+            let batch = &batch[..cmp::min(batch.len(), max_items)];
+
+            if batch.is_empty() {
+                break;
+            }
+
             // request epoch batch
             match self.request_epochs(io, &peer, batch.to_vec()) {
                 Ok(None) => {}
                 Ok(Some(id)) => {
                     self.insert_in_flight(id, batch.to_vec());
+
+                    if let Some(state) = self.peers.get(&peer) {
+                        state.write().charge(
+                            msgid::GET_BLOCK_HASHES_BY_EPOCH,
+                            batch.len(),
+                        );
+                    }
                 }
                 Err(e) => {
                     warn!(
@@ -314,6 +337,10 @@ impl Epochs {
                     );
                 }
             }
+
+            if capped {
+                break;
+            }
         }
     }
 }
@@ -2,6 +2,8 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+mod account_pending_txs;
+mod block_rewards;
 mod block_txs;
 mod blooms;
 mod common;
@@ -15,6 +17,8 @@ mod tx_infos;
 mod txs;
 mod witnesses;
 
+pub use account_pending_txs::{AccountPendingTxs, AccountPendingTxsValidated};
+pub use block_rewards::BlockRewards;
 pub use block_txs::BlockTxs;
 pub use blooms::Blooms;
 pub use epochs::Epochs;
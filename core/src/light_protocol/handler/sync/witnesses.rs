@@ -6,6 +6,7 @@ use super::common::{KeyReverseOrdered, LedgerProof, SyncManager};
 use crate::{
     block_data_manager::{
         block_data_types::BlamedHeaderVerifiedRoots, BlockDataManager,
+        LightSyncProgress,
     },
     consensus::SharedConsensusGraph,
     light_protocol::{
@@ -19,8 +20,8 @@ use crate::{
 use cfx_parameters::{
     consensus::DEFERRED_STATE_EPOCH_COUNT,
     light::{
-        MAX_WITNESSES_IN_FLIGHT, WITNESS_REQUEST_BATCH_SIZE,
-        WITNESS_REQUEST_TIMEOUT,
+        MAX_WITNESSES_IN_FLIGHT, SYNC_PROGRESS_PERSIST_INTERVAL,
+        WITNESS_REQUEST_BATCH_SIZE, WITNESS_REQUEST_TIMEOUT,
     },
 };
 use cfx_types::H256;
@@ -71,6 +72,13 @@ pub struct Witnesses {
     // helper API for retrieving ledger information
     ledger: LedgerInfo,
 
+    // height of the latest header verified before the last restart; the
+    // roots of blamed headers up to this height are stored on disk
+    restored_height: u64,
+
+    // height of the sync progress stored on disk most recently
+    persisted_height: RwLock<u64>,
+
     // series of unique request ids
     request_id_allocator: Arc<UniqueId>,
 
@@ -81,12 +89,13 @@ pub struct Witnesses {
 impl Witnesses {
     pub fn new(
         consensus: SharedConsensusGraph, peers: Arc<Peers<FullPeerState>>,
-        request_id_allocator: Arc<UniqueId>,
+        request_id_allocator: Arc<UniqueId>, restored_height: u64,
     ) -> Self
     {
         let data_man = consensus.get_data_manager().clone();
         let height_of_latest_verified_header = RwLock::new(0);
         let in_flight = RwLock::new(HashSet::new());
+        let persisted_height = RwLock::new(restored_height);
         let ledger = LedgerInfo::new(consensus.clone());
         let sync_manager =
             SyncManager::new(peers.clone(), msgid::GET_WITNESS_INFO);
@@ -96,7 +105,9 @@ impl Witnesses {
             height_of_latest_verified_header,
             in_flight,
             ledger,
+            persisted_height,
             request_id_allocator,
+            restored_height,
             sync_manager,
         }
     }
@@ -168,6 +179,79 @@ impl Witnesses {
         self.sync_manager.insert_waiting(std::iter::once(missing));
     }
 
+    /// Try to use the roots stored before the last restart for the headers
+    /// blamed by `witness`, instead of requesting them again. Return `false`
+    /// if they are not available or they do not match the witness header.
+    pub fn restore(&self, witness: u64) -> bool {
+        if witness > self.restored_height {
+            return false;
+        }
+
+        let header = match self.ledger.pivot_header_of(witness) {
+            Ok(header) => header,
+            Err(_) => return false,
+        };
+
+        let blame = header.blame() as u64;
+        let mut state_roots = vec![];
+        let mut receipts = vec![];
+        let mut blooms = vec![];
+
+        for height in ((witness - blame)..=witness).rev() {
+            match self.data_man.restored_blamed_roots_by_height(height) {
+                None => return false,
+                Some(roots) => {
+                    state_roots.push(roots.deferred_state_root);
+                    receipts.push(roots.deferred_receipts_root);
+                    blooms.push(roots.deferred_logs_bloom_hash);
+                }
+            }
+        }
+
+        let proofs = vec![
+            LedgerProof::StateRoot(state_roots),
+            LedgerProof::ReceiptsRoot(receipts),
+            LedgerProof::LogsBloomHash(blooms),
+        ];
+
+        // the roots might be stale after a chain reorg
+        if proofs.iter().any(|p| p.validate(&header).is_err()) {
+            return false;
+        }
+
+        let mut in_flight = self.in_flight.write();
+
+        for height in (witness - blame)..=witness {
+            in_flight.remove(&height);
+        }
+
+        true
+    }
+
+    /// Store the sync progress on disk if the roots of all blamed headers up
+    /// to `height` have been received. To limit the writes, the progress is
+    /// only stored once every `SYNC_PROGRESS_PERSIST_INTERVAL` heights.
+    pub fn persist_progress(&self, height: u64) {
+        let mut persisted_height = self.persisted_height.write();
+
+        if height < *persisted_height + SYNC_PROGRESS_PERSIST_INTERVAL {
+            return;
+        }
+
+        if self.in_flight.read().iter().any(|h| *h <= height) {
+            return;
+        }
+
+        let pivot_hash = match self.ledger.pivot_header_of(height) {
+            Ok(header) => header.hash(),
+            Err(_) => return,
+        };
+
+        let progress = LightSyncProgress { height, pivot_hash };
+        self.data_man.insert_light_sync_progress(&progress);
+        *persisted_height = height;
+    }
+
     fn handle_witness_info(&self, item: WitnessInfoWithHeight) -> Result<()> {
         let witness = item.height;
         let state_roots = item.state_root_hashes;
@@ -2,11 +2,15 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use cfx_types::{Bloom, H160, H256};
+use cfx_types::{Bloom, H160, H256, U256};
 use rlp_derive::{RlpDecodable, RlpEncodable};
 
 use super::NodeType;
-use crate::message::RequestId;
+use crate::{
+    block_data_manager::BlockRewardResult,
+    light_protocol::common::FlowControlParams, message::RequestId,
+    transaction_pool::TransactionStatus,
+};
 use cfx_internal_common::ChainIdParamsDeprecated;
 use cfx_storage::{NodeMerkleProof, StateProof, TrieProof};
 use primitives::{
@@ -46,6 +50,16 @@ pub struct StatusPongV2 {
     pub terminals: Vec<H256>,
 }
 
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct StatusPongV3 {
+    pub best_epoch: u64,
+    pub chain_id: ChainIdParamsDeprecated,
+    pub flow_control: FlowControlParams,
+    pub genesis_hash: H256,
+    pub node_type: NodeType,
+    pub terminals: Vec<H256>,
+}
+
 #[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
 pub struct GetBlockHashesByEpoch {
     pub request_id: RequestId,
@@ -314,3 +328,65 @@ pub struct StorageRoots {
     pub request_id: RequestId,
     pub roots: Vec<StorageRootWithKey>,
 }
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct GetBlockRewards {
+    pub request_id: RequestId,
+    pub hashes: Vec<H256>,
+}
+
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct BlockRewardWithHash {
+    pub hash: H256,
+
+    // block rewards are computed locally by full nodes and are not committed
+    // to in block headers; no proof available
+    pub reward: BlockRewardResult,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct BlockRewards {
+    pub request_id: RequestId,
+    pub rewards: Vec<BlockRewardWithHash>,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    RlpEncodable,
+    RlpDecodable,
+)]
+pub struct AccountPendingTxsKey {
+    pub address: H160,
+    pub start_nonce: Option<U256>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct GetAccountPendingTxs {
+    pub request_id: RequestId,
+    pub keys: Vec<AccountPendingTxsKey>,
+}
+
+#[derive(Clone, Debug, RlpEncodable, RlpDecodable)]
+pub struct AccountPendingTxsWithKey {
+    pub key: AccountPendingTxsKey,
+
+    // the content of the transaction pool of a full node cannot be proved;
+    // light nodes can only check the signatures and senders of the txs
+    pub pending_txs: Vec<SignedTransaction>,
+    pub first_tx_status: Option<TransactionStatus>,
+    pub pending_count: u64,
+}
+
+#[derive(Clone, Debug, Default, RlpEncodable, RlpDecodable)]
+pub struct AccountPendingTxs {
+    pub request_id: RequestId,
+    pub txs: Vec<AccountPendingTxsWithKey>,
+}
@@ -8,31 +8,34 @@ use crate::{
     },
     light_protocol::{
         common::{
-            partition_results, validate_chain_id, LedgerInfo, LightPeerState,
-            Peers,
+            partition_results, validate_chain_id, CreditBuffer,
+            FlowControlParams, LedgerInfo, LightPeerState, Peers,
         },
         error::*,
         handle_error,
         message::{
-            msgid, BlockHashes as GetBlockHashesResponse,
-            BlockHeaders as GetBlockHeadersResponse,
+            msgid, AccountPendingTxs as GetAccountPendingTxsResponse,
+            AccountPendingTxsWithKey, BlockHashes as GetBlockHashesResponse,
+            BlockHeaders as GetBlockHeadersResponse, BlockRewardWithHash,
+            BlockRewards as GetBlockRewardsResponse,
             BlockTxs as GetBlockTxsResponse, BlockTxsWithHash, BloomWithEpoch,
-            Blooms as GetBloomsResponse, GetBlockHashesByEpoch,
-            GetBlockHeaders, GetBlockTxs, GetBlooms, GetReceipts,
-            GetStateEntries, GetStateRoots, GetStorageRoots, GetTxInfos,
-            GetTxs, GetWitnessInfo, NewBlockHashes, NodeType,
-            Receipts as GetReceiptsResponse, ReceiptsWithEpoch, SendRawTx,
+            Blooms as GetBloomsResponse, GetAccountPendingTxs,
+            GetBlockHashesByEpoch, GetBlockHeaders, GetBlockRewards,
+            GetBlockTxs, GetBlooms, GetReceipts, GetStateEntries,
+            GetStateRoots, GetStorageRoots, GetTxInfos, GetTxs, GetWitnessInfo,
+            NewBlockHashes, NodeType, Receipts as GetReceiptsResponse,
+            ReceiptsWithEpoch, SendRawTx,
             StateEntries as GetStateEntriesResponse, StateEntryProof,
             StateEntryWithKey, StateKey, StateRootWithEpoch,
             StateRoots as GetStateRootsResponse, StatusPingDeprecatedV1,
-            StatusPingV2, StatusPongDeprecatedV1, StatusPongV2, StorageRootKey,
-            StorageRootProof, StorageRootWithKey,
+            StatusPingV2, StatusPongDeprecatedV1, StatusPongV2, StatusPongV3,
+            StorageRootKey, StorageRootProof, StorageRootWithKey,
             StorageRoots as GetStorageRootsResponse, TxInfo,
             TxInfos as GetTxInfosResponse, Txs as GetTxsResponse,
             WitnessInfo as GetWitnessInfoResponse,
         },
         LIGHT_PROTOCOL_ID, LIGHT_PROTOCOL_OLD_VERSIONS_TO_SUPPORT,
-        LIGHT_PROTOCOL_VERSION, LIGHT_PROTO_V1,
+        LIGHT_PROTOCOL_VERSION, LIGHT_PROTO_V1, LIGHT_PROTO_V2, LIGHT_PROTO_V3,
     },
     message::{decode_msg, decode_rlp_and_check_deprecation, Message, MsgId},
     sync::{message::Throttled, SynchronizationGraph},
@@ -56,7 +59,10 @@ use parking_lot::RwLock;
 use primitives::{SignedTransaction, TransactionWithSignature};
 use rand::prelude::SliceRandom;
 use rlp::Rlp;
-use std::sync::{Arc, Weak};
+use std::{
+    cmp::min,
+    sync::{Arc, Weak},
+};
 use throttling::token_bucket::{ThrottleResult, TokenBucketManager};
 
 #[derive(DeriveMallocSizeOf)]
@@ -87,6 +93,9 @@ pub struct Provider {
     tx_pool: Arc<TransactionPool>,
 
     throttling_config_file: Option<String>,
+
+    // request costs and credit parameters advertised to light peers
+    flow_control: FlowControlParams,
 }
 
 impl Provider {
@@ -94,6 +103,7 @@ impl Provider {
         consensus: SharedConsensusGraph, graph: Arc<SynchronizationGraph>,
         network: Weak<NetworkService>, tx_pool: Arc<TransactionPool>,
         throttling_config_file: Option<String>, node_type: NodeType,
+        flow_control_buffer_limit: u64, flow_control_recharge_rate: u64,
     ) -> Self
     {
         let ledger = LedgerInfo::new(consensus.clone());
@@ -109,6 +119,10 @@ impl Provider {
             peers,
             tx_pool,
             throttling_config_file,
+            flow_control: FlowControlParams::new(
+                flow_control_buffer_limit,
+                flow_control_recharge_rate,
+            ),
         }
     }
 
@@ -193,6 +207,8 @@ impl Provider {
             msgid::GET_BLOCK_TXS => self.on_get_block_txs(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::GET_TX_INFOS => self.on_get_tx_infos(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             msgid::GET_STORAGE_ROOTS => self.on_get_storage_roots(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::GET_BLOCK_REWARDS => self.on_get_block_rewards(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
+            msgid::GET_ACCOUNT_PENDING_TXS => self.on_get_account_pending_txs(io, peer, decode_rlp_and_check_deprecation(&rlp, min_supported_ver, protocol)?),
             _ => bail!(ErrorKind::UnknownMessage{id: msg_id}),
         }
     }
@@ -359,7 +375,9 @@ impl Provider {
         let terminals = best_info.bounded_terminal_block_hashes.clone();
 
         let msg: Box<dyn Message>;
-        if self.peer_version(peer)? == LIGHT_PROTO_V1 {
+        let peer_version = self.peer_version(peer)?;
+
+        if peer_version == LIGHT_PROTO_V1 {
             msg = Box::new(StatusPongDeprecatedV1 {
                 protocol_version: self.protocol_version.0,
                 best_epoch: best_info.best_epoch_number,
@@ -367,7 +385,7 @@ impl Provider {
                 node_type: self.node_type,
                 terminals,
             });
-        } else {
+        } else if peer_version == LIGHT_PROTO_V2 {
             msg = Box::new(StatusPongV2 {
                 chain_id: ChainIdParamsDeprecated {
                     chain_id: self.consensus.best_chain_id(),
@@ -377,6 +395,17 @@ impl Provider {
                 node_type: self.node_type,
                 terminals,
             });
+        } else {
+            msg = Box::new(StatusPongV3 {
+                chain_id: ChainIdParamsDeprecated {
+                    chain_id: self.consensus.best_chain_id(),
+                },
+                best_epoch: best_info.best_epoch_number,
+                flow_control: self.flow_control.clone(),
+                genesis_hash,
+                node_type: self.node_type,
+                terminals,
+            });
         }
 
         msg.send(io, peer)?;
@@ -451,6 +480,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_state_roots req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.epochs.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -511,6 +541,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_state_entries req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.keys.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -544,6 +575,7 @@ impl Provider {
     {
         debug!("on_get_block_hashes_by_epoch req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.epochs.len(), MAX_EPOCHS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -575,6 +607,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_block_headers req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.hashes.len(), MAX_HEADERS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -615,6 +648,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_send_raw_tx req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, 1)?;
         let tx: TransactionWithSignature = rlp::decode(&req.raw)?;
 
         let (passed, failed) = self.tx_pool.insert_new_transactions(vec![tx]);
@@ -649,6 +683,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_receipts req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.epochs.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req.epochs.into_iter().take(MAX_ITEMS_TO_SEND).map(|epoch| {
@@ -680,6 +715,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_txs req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.hashes.len(), MAX_TXS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -710,6 +746,11 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_witness_info req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(
+            peer,
+            &req,
+            min(req.witnesses.len(), MAX_WITNESSES_TO_SEND),
+        )?;
         let request_id = req.request_id;
 
         let it = req
@@ -736,6 +777,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_blooms req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.epochs.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req.epochs.into_iter().take(MAX_ITEMS_TO_SEND).map(|epoch| {
@@ -762,6 +804,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_block_txs req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.hashes.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -804,6 +847,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_tx_infos req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.hashes.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -859,6 +903,7 @@ impl Provider {
     ) -> Result<()> {
         debug!("on_get_storage_roots req={:?}", req);
         self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.keys.len(), MAX_ITEMS_TO_SEND))?;
         let request_id = req.request_id;
 
         let it = req
@@ -883,6 +928,97 @@ impl Provider {
         Ok(())
     }
 
+    fn on_get_block_rewards(
+        &self, io: &dyn NetworkContext, peer: &NodeId, req: GetBlockRewards,
+    ) -> Result<()> {
+        debug!("on_get_block_rewards req={:?}", req);
+        self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.hashes.len(), MAX_ITEMS_TO_SEND))?;
+        let request_id = req.request_id;
+
+        let it = req
+            .hashes
+            .into_iter()
+            .take(MAX_ITEMS_TO_SEND)
+            .map::<Result<_>, _>(|hash| {
+                // rewards are computed `REWARD_EPOCH_COUNT` epochs later
+                self.graph
+                    .data_man
+                    .block_reward_result_by_hash(&hash)
+                    .map(|reward| BlockRewardWithHash { hash, reward })
+                    .ok_or_else(|| {
+                        ErrorKind::Msg(format!(
+                            "Reward of block {:?} not found",
+                            hash
+                        ))
+                        .into()
+                    })
+            });
+
+        let (rewards, errors) = partition_results(it);
+
+        if !errors.is_empty() {
+            debug!(
+                "Errors while serving GetBlockRewards request: {:?}",
+                errors
+            );
+        }
+
+        let msg: Box<dyn Message> = Box::new(GetBlockRewardsResponse {
+            request_id,
+            rewards,
+        });
+
+        msg.send(io, peer)?;
+        Ok(())
+    }
+
+    fn on_get_account_pending_txs(
+        &self, io: &dyn NetworkContext, peer: &NodeId,
+        req: GetAccountPendingTxs,
+    ) -> Result<()>
+    {
+        debug!("on_get_account_pending_txs req={:?}", req);
+        self.throttle(peer, &req)?;
+        self.charge(peer, &req, min(req.keys.len(), MAX_ITEMS_TO_SEND))?;
+        let request_id = req.request_id;
+
+        let txs = req
+            .keys
+            .into_iter()
+            .take(MAX_ITEMS_TO_SEND)
+            .map(|key| {
+                let limit = match key.limit {
+                    Some(limit) => min(limit as usize, MAX_TXS_TO_SEND),
+                    None => MAX_TXS_TO_SEND,
+                };
+
+                let (pending_txs, first_tx_status, pending_count) =
+                    self.tx_pool.get_account_pending_transactions(
+                        &key.address,
+                        key.start_nonce,
+                        Some(limit),
+                    );
+
+                AccountPendingTxsWithKey {
+                    key,
+                    pending_txs: pending_txs
+                        .into_iter()
+                        .map(|tx| (*tx).clone())
+                        .collect(),
+                    first_tx_status,
+                    pending_count: pending_count as u64,
+                }
+            })
+            .collect();
+
+        let msg: Box<dyn Message> =
+            Box::new(GetAccountPendingTxsResponse { request_id, txs });
+
+        msg.send(io, peer)?;
+        Ok(())
+    }
+
     fn broadcast(
         &self, io: &dyn NetworkContext, mut peers: Vec<NodeId>,
         msg: &dyn Message,
@@ -967,6 +1103,34 @@ impl Provider {
             }
         }
     }
+
+    /// Charge the cost of a request to the credits of the peer. Requests
+    /// that the peer cannot afford are rejected the same way as throttled
+    /// ones, telling the peer how long to wait before retrying.
+    fn charge<T: Message>(
+        &self, peer: &NodeId, msg: &T, num_items: usize,
+    ) -> Result<()> {
+        let peer = self.get_existing_peer_state(peer)?;
+        let mut peer = peer.write();
+
+        let credits = match peer.credits {
+            Some(ref mut credits) => credits,
+            None => return Ok(()),
+        };
+
+        match credits.try_charge(msg.msg_id(), num_items) {
+            Ok(()) => Ok(()),
+            Err(wait_time) => {
+                let throttled = Throttled {
+                    msg_id: msg.msg_id(),
+                    wait_time_nanos: wait_time.as_nanos() as u64,
+                    request_id: msg.get_request_id(),
+                };
+
+                bail!(ErrorKind::Throttled(msg.msg_name(), throttled))
+            }
+        }
+    }
 }
 
 impl NetworkProtocolHandler for Provider {
@@ -1018,6 +1182,13 @@ impl NetworkProtocolHandler for Provider {
         self.peers.get(peer).unwrap().write().protocol_version =
             peer_protocol_version;
 
+        // light peers start with a full buffer of credits; older peers are
+        // not told about the flow control params, so they are not charged
+        if peer_protocol_version >= LIGHT_PROTO_V3 {
+            self.peers.get(peer).unwrap().write().credits =
+                Some(CreditBuffer::new(self.flow_control.clone()));
+        }
+
         if let Some(ref file) = self.throttling_config_file {
             let peer = self.peers.get(peer).expect("peer not found");
             peer.write().throttling =
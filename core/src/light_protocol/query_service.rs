@@ -3,22 +3,28 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
+    block_data_manager::BlockRewardResult,
     consensus::SharedConsensusGraph,
+    executive::ExecutionOutcome,
     light_protocol::{
-        common::{FullPeerFilter, LedgerInfo},
-        handler::sync::TxInfoValidated,
-        message::msgid,
+        common::{FullPeerFilter, LazyState, LazyStateEntries, LedgerInfo},
+        handler::sync::{AccountPendingTxsValidated, TxInfoValidated},
+        message::{msgid, AccountPendingTxsKey},
         Error, ErrorKind, Handler as LightHandler, LightNodeConfiguration,
         LIGHT_PROTOCOL_ID, LIGHT_PROTOCOL_VERSION,
     },
     rpc_errors::{account_result_to_rpc_result, Error as RpcError},
-    state::COMMISSION_PRIVILEGE_SPECIAL_KEY,
+    spec::genesis::{
+        genesis_contract_address_four_year, genesis_contract_address_two_year,
+    },
+    state::{StateOverride, COMMISSION_PRIVILEGE_SPECIAL_KEY},
     sync::SynchronizationGraph,
     ConsensusGraph, Notifications,
 };
 use cfx_addr::Network;
 use cfx_parameters::{
     consensus::DEFERRED_STATE_EPOCH_COUNT,
+    consensus_internal::REWARD_EPOCH_COUNT,
     internal_contract_addresses::{
         SPONSOR_WHITELIST_CONTROL_CONTRACT_ADDRESS,
         STORAGE_INTEREST_STAKING_CONTRACT_ADDRESS,
@@ -26,29 +32,45 @@ use cfx_parameters::{
     light::{
         GAS_PRICE_BATCH_SIZE, GAS_PRICE_BLOCK_SAMPLE_SIZE,
         GAS_PRICE_TRANSACTION_SAMPLE_SIZE, LOG_FILTERING_LOOKAHEAD,
-        MAX_POLL_TIME, TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_LOW,
-        TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_MEDIUM,
+        MAX_POLL_TIME, MAX_QUEUED_VIRTUAL_CALLS, MAX_TX_SENDERS,
+        MAX_VIRTUAL_CALL_STATE_ENTRIES,
+        TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_LOW,
+        TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_MEDIUM, VIRTUAL_CALL_THREADS,
     },
 };
-use cfx_statedb::{ACCUMULATE_INTEREST_RATE_KEY, INTEREST_RATE_KEY};
+use cfx_statedb::{
+    ACCUMULATE_INTEREST_RATE_KEY, INTEREST_RATE_KEY, TOTAL_BANK_TOKENS_KEY,
+    TOTAL_STORAGE_TOKENS_KEY, TOTAL_TOKENS_KEY,
+};
 use cfx_types::{
     address_util::AddressUtil, BigEndianHash, Bloom, H160, H256,
     KECCAK_EMPTY_BLOOM, U256,
 };
 use futures::{
+    channel::oneshot,
     future::{self, Either},
     stream, FutureExt, StreamExt, TryFutureExt, TryStreamExt,
 };
+use lru_time_cache::LruCache;
 use network::{service::ProtocolVersion, NetworkContext, NetworkService};
+use parking_lot::Mutex;
 use primitives::{
     filter::{FilterError, LogFilter},
     log_entry::{LocalizedLogEntry, LogEntry},
     Account, Block, BlockReceipts, CodeInfo, DepositList, EpochNumber, Receipt,
     SignedTransaction, StorageKey, StorageRoot, StorageValue, TransactionIndex,
-    VoteStakeList,
+    TransactionWithSignature, VoteStakeList,
 };
 use rlp::Rlp;
 use std::{collections::BTreeSet, future::Future, sync::Arc, time::Duration};
+use threadpool::ThreadPool;
+
+pub struct SupplyInfo {
+    pub total_circulating: U256,
+    pub total_issued: U256,
+    pub total_staking: U256,
+    pub total_collateral: U256,
+}
 
 pub struct TxInfo {
     pub tx: SignedTransaction,
@@ -98,6 +120,12 @@ pub struct QueryService {
 
     // shared network service
     network: Arc<NetworkService>,
+
+    // latest senders of the txs relayed through this node
+    tx_senders: Mutex<LruCache<H160, ()>>,
+
+    // threads executing virtual calls
+    virtual_call_pool: Mutex<ThreadPool>,
 }
 
 impl QueryService {
@@ -122,6 +150,11 @@ impl QueryService {
             handler,
             ledger,
             network,
+            tx_senders: Mutex::new(LruCache::with_capacity(MAX_TX_SENDERS)),
+            virtual_call_pool: Mutex::new(ThreadPool::with_name(
+                "Light Virtual Call".into(),
+                VIRTUAL_CALL_THREADS,
+            )),
         }
     }
 
@@ -137,6 +170,33 @@ impl QueryService {
             })
     }
 
+    /// Run `f` on the virtual call threads, and wait for its result without
+    /// blocking the executor. `f` is rejected if too many calls are waiting
+    /// for the threads.
+    async fn run_virtual_call<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        {
+            let pool = self.virtual_call_pool.lock();
+            if pool.queued_count() >= MAX_QUEUED_VIRTUAL_CALLS {
+                bail!(format!(
+                    "Unable to execute: too many calls in progress (max = {})",
+                    VIRTUAL_CALL_THREADS + MAX_QUEUED_VIRTUAL_CALLS
+                ));
+            }
+            pool.execute(move || {
+                // the receiver is gone if the request is cancelled
+                let _ = sender.send(f());
+            });
+        }
+        receiver.await.map_err(|_| {
+            ErrorKind::InternalError("virtual call is cancelled".into()).into()
+        })
+    }
+
     fn with_io<T>(&self, f: impl FnOnce(&dyn NetworkContext) -> T) -> T {
         self.network
             .with_context(self.handler.clone(), LIGHT_PROTOCOL_ID, |io| f(io))
@@ -281,6 +341,19 @@ impl QueryService {
         .await
     }
 
+    async fn retrieve_block_reward(
+        &self, hash: H256,
+    ) -> Result<BlockRewardResult, Error> {
+        trace!("retrieve_block_reward hash = {:?}", hash);
+
+        with_timeout(
+            *MAX_POLL_TIME,
+            format!("Timeout while retrieving reward for block {:?}", hash),
+            self.with_io(|io| self.handler.block_rewards.request_now(io, hash)),
+        )
+        .await
+    }
+
     pub async fn gas_price(&self) -> Result<Option<U256>, Error> {
         // collect block hashes for gas price sample
         let mut epoch = self.consensus.best_epoch_number();
@@ -312,8 +385,8 @@ impl QueryService {
 
         // retrieve blocks in batches
         let mut stream = stream::iter(hashes)
-            .map(|h| {
-                async move { self.retrieve_block(h).await.map(move |b| (h, b)) }
+            .map(|h| async move {
+                self.retrieve_block(h).await.map(move |b| (h, b))
             })
             .buffered(GAS_PRICE_BATCH_SIZE);
 
@@ -582,6 +655,193 @@ impl QueryService {
             .map(|opt| opt.unwrap_or_default())
     }
 
+    /// Retrieve the token supply of `epoch`. All values are verified against
+    /// the state root of `epoch`.
+    pub async fn get_supply_info(
+        &self, epoch: EpochNumber,
+    ) -> Result<SupplyInfo, Error> {
+        debug!("get_supply_info epoch={:?}", epoch);
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+
+        let total_tokens = |key: &'static [u8]| async move {
+            let key = StorageKey::new_storage_key(
+                &STORAGE_INTEREST_STAKING_CONTRACT_ADDRESS,
+                key,
+            )
+            .to_key_bytes();
+
+            self.retrieve_state_entry::<U256>(epoch, key)
+                .await
+                .map(|opt| opt.unwrap_or_default())
+        };
+
+        let locked_balance = |address: H160| async move {
+            let key = Self::account_key(&address);
+
+            match self.retrieve_state_entry_raw(epoch, key).await? {
+                None => Ok(U256::zero()),
+                Some(rlp) => {
+                    Ok(Account::new_from_rlp(address, &Rlp::new(&rlp))?.balance)
+                }
+            }
+        };
+
+        let (
+            total_issued,
+            total_staking,
+            total_collateral,
+            two_year_locked,
+            four_year_locked,
+        ) = future::try_join5(
+            total_tokens(TOTAL_TOKENS_KEY),
+            total_tokens(TOTAL_BANK_TOKENS_KEY),
+            total_tokens(TOTAL_STORAGE_TOKENS_KEY),
+            locked_balance(genesis_contract_address_two_year()),
+            locked_balance(genesis_contract_address_four_year()),
+        )
+        .await?;
+
+        Ok(SupplyInfo {
+            total_circulating: total_issued
+                - two_year_locked
+                - four_year_locked,
+            total_issued,
+            total_staking,
+            total_collateral,
+        })
+    }
+
+    /// Retrieve the rewards of the blocks in `epoch`. Block rewards are not
+    /// committed to in block headers, so they cannot be fully verified.
+    pub async fn get_block_rewards(
+        &self, epoch: EpochNumber,
+    ) -> Result<Vec<(H256, BlockRewardResult)>, Error> {
+        debug!("get_block_rewards epoch={:?}", epoch);
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+
+        // rewards are computed `REWARD_EPOCH_COUNT` epochs later; full nodes
+        // return no rewards for epochs that are not rewarded yet
+        if epoch + REWARD_EPOCH_COUNT
+            > self.get_latest_verifiable_epoch_number()?
+        {
+            return Ok(vec![]);
+        }
+
+        let hashes = self.ledger.block_hashes_in(epoch)?;
+
+        future::try_join_all(hashes.into_iter().map(|hash| {
+            self.retrieve_block_reward(hash)
+                .map_ok(move |reward| (hash, reward))
+        }))
+        .await
+    }
+
+    /// Retrieve the pending txs of `address` from the tx pool of a peer. The
+    /// result cannot be verified beyond the signatures of the txs.
+    pub async fn get_account_pending_txs(
+        &self, address: H160, start_nonce: Option<U256>, limit: Option<u64>,
+    ) -> Result<AccountPendingTxsValidated, Error>
+    {
+        debug!(
+            "get_account_pending_txs address={:?} start_nonce={:?} limit={:?}",
+            address, start_nonce, limit
+        );
+
+        let key = AccountPendingTxsKey {
+            address,
+            start_nonce,
+            limit,
+        };
+
+        with_timeout(
+            *MAX_POLL_TIME,
+            format!("Timeout while retrieving pending txs for {:?}", address),
+            self.with_io(|io| {
+                self.handler.account_pending_txs.request_now(io, key)
+            }),
+        )
+        .await
+    }
+
+    /// Whether txs of `address` were relayed through this node.
+    pub fn is_tx_sender(&self, address: &H160) -> bool {
+        self.tx_senders.lock().contains_key(address)
+    }
+
+    /// Execute `tx` on the state of `epoch` without committing it, like
+    /// `cfx_call` on full nodes. The state entries accessed by the execution
+    /// are retrieved from peers on demand and verified against the state root
+    /// of `epoch`, and the execution is repeated until all of them are
+    /// available. Calls that kill contracts are not supported, see
+    /// `LazyState::delete_all`.
+    pub async fn call_virtual(
+        &self, tx: SignedTransaction, epoch: EpochNumber,
+        state_override: Option<StateOverride>,
+    ) -> Result<ExecutionOutcome, RpcError>
+    {
+        debug!("call_virtual epoch={:?} tx={:?}", epoch, tx.hash());
+
+        let epoch = self.get_height_from_epoch_number(epoch)?;
+
+        let tx = Arc::new(tx);
+        let state_override = Arc::new(state_override);
+        let entries = Arc::new(LazyStateEntries::default());
+
+        loop {
+            // the execution is CPU-bound, so it is run on the virtual call
+            // threads instead of the executor of the RPC futures
+            let (consensus, tx, state_override, state) = (
+                self.consensus.clone(),
+                tx.clone(),
+                state_override.clone(),
+                LazyState::new(entries.clone()),
+            );
+            let outcome = self
+                .run_virtual_call(move || {
+                    consensus
+                        .as_any()
+                        .downcast_ref::<ConsensusGraph>()
+                        .expect("downcast should succeed")
+                        .call_virtual_on_storage(
+                            &tx,
+                            epoch,
+                            (*state_override).as_ref(),
+                            state,
+                        )
+                })
+                .await?;
+
+            // the execution did not access any missing entries
+            let missing = entries.take_missing();
+            if missing.is_empty() {
+                return outcome;
+            }
+
+            if entries.len() + missing.len() > MAX_VIRTUAL_CALL_STATE_ENTRIES {
+                bail!(format!(
+                    "Unable to execute: too many state entries accessed (max = {})",
+                    MAX_VIRTUAL_CALL_STATE_ENTRIES
+                ));
+            }
+
+            trace!("call_virtual retrieving {} state entries", missing.len());
+
+            // retrieve in parallel
+            let retrieved =
+                future::try_join_all(missing.into_iter().map(|key| {
+                    self.retrieve_state_entry_raw(epoch, key.clone())
+                        .map_ok(move |value| (key, value))
+                }))
+                .await?;
+
+            for (key, value) in retrieved {
+                entries.insert(key, value);
+            }
+        }
+    }
+
     pub async fn get_tx_info(&self, hash: H256) -> Result<TxInfo, Error> {
         debug!("get_tx_info hash={:?}", hash);
 
@@ -620,6 +880,14 @@ impl QueryService {
     pub fn send_raw_tx(&self, raw: Vec<u8>) -> bool {
         debug!("send_raw_tx raw={:?}", raw);
 
+        // remember the sender so that we can serve its pending txs later
+        let maybe_sender = rlp::decode::<TransactionWithSignature>(&raw)
+            .ok()
+            .and_then(|tx| {
+                let public = tx.recover_public().ok()?;
+                Some(SignedTransaction::new(public, tx).sender())
+            });
+
         let peers = FullPeerFilter::new(msgid::SEND_RAW_TX)
             .select_all(self.handler.peers.clone());
 
@@ -649,7 +917,13 @@ impl QueryService {
             },
         ) {
             Err(e) => unreachable!(e),
-            Ok(success) => success,
+            Ok(false) => false,
+            Ok(true) => {
+                if let Some(sender) = maybe_sender {
+                    self.tx_senders.lock().insert(sender, ());
+                }
+                true
+            }
         }
     }
 
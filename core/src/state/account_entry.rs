@@ -104,6 +104,12 @@ pub struct OverlayAccount {
     // also be caused by a simple payment transaction, which result into a new
     // basic account at the same address.
     is_newly_created_contract: bool,
+
+    // This flag indicates that the storage of the account is overridden to be
+    // empty, so that storage values are not looked up from the disk. Unlike
+    // `is_newly_created_contract`, it does not affect the staking info or the
+    // storage ownership of the account. It is only set for virtual calls.
+    storage_reset: bool,
 }
 
 impl OverlayAccount {
@@ -128,6 +134,7 @@ impl OverlayAccount {
             code_hash: account.code_hash,
             code: None,
             is_newly_created_contract: false,
+            storage_reset: false,
         };
 
         overlay_account
@@ -163,6 +170,7 @@ impl OverlayAccount {
             code_hash: KECCAK_EMPTY,
             code: None,
             is_newly_created_contract: address.is_contract_address(),
+            storage_reset: false,
         }
     }
 
@@ -208,6 +216,7 @@ impl OverlayAccount {
             code_hash: KECCAK_EMPTY,
             code: None,
             is_newly_created_contract: true,
+            storage_reset: false,
         }
     }
 
@@ -365,6 +374,8 @@ impl OverlayAccount {
         self.is_newly_created_contract
     }
 
+    pub fn is_storage_reset(&self) -> bool { self.storage_reset }
+
     pub fn nonce(&self) -> &U256 { &self.nonce }
 
     pub fn code_hash(&self) -> H256 { self.code_hash.clone() }
@@ -535,6 +546,7 @@ impl OverlayAccount {
             code_hash: self.code_hash,
             code: self.code.clone(),
             is_newly_created_contract: self.is_newly_created_contract,
+            storage_reset: self.storage_reset,
         }
     }
 
@@ -586,7 +598,7 @@ impl OverlayAccount {
         if let Some(value) = self.cached_storage_at(key) {
             return Ok(value);
         }
-        if self.is_newly_created_contract {
+        if self.is_newly_created_contract || self.storage_reset {
             Ok(U256::zero())
         } else {
             Self::get_and_cache_storage(
@@ -631,6 +643,17 @@ impl OverlayAccount {
         }
     }
 
+    /// Drop all cached storage changes and treat the storage values of the
+    /// account as empty. It is only used to override the storage for virtual
+    /// calls, whose state is never committed.
+    pub fn reset_storage(&mut self) {
+        self.storage_value_read_cache = Default::default();
+        self.storage_value_write_cache.clear();
+        self.storage_owner_lv2_write_cache = Default::default();
+        self.storage_owner_lv1_write_cache.clear();
+        self.storage_reset = true;
+    }
+
     pub fn init_code(&mut self, code: Bytes, owner: Address) {
         self.code_hash = keccak(&code);
         self.code = Some(CodeInfo {
@@ -659,6 +682,7 @@ impl OverlayAccount {
         self.deposit_list = other.deposit_list;
         self.vote_stake_list = other.vote_stake_list;
         self.is_newly_created_contract = other.is_newly_created_contract;
+        self.storage_reset = other.storage_reset;
     }
 
     /// Return the owner of `key` before this execution. If it is `None`, it
@@ -676,8 +700,17 @@ impl OverlayAccount {
         }
         let storage_owner_lv2_write_cache =
             &mut *self.storage_owner_lv2_write_cache.write();
+        // the ownership is kept when the storage is reset, but the value
+        // loaded along with it must not be cached
+        let mut discarded_values = HashMap::new();
+        let mut storage_value_read_cache =
+            self.storage_value_read_cache.write();
         Self::get_and_cache_storage(
-            &mut self.storage_value_read_cache.write(),
+            if self.storage_reset {
+                &mut discarded_values
+            } else {
+                &mut *storage_value_read_cache
+            },
             storage_owner_lv2_write_cache,
             db,
             &self.address,
@@ -720,3 +720,24 @@ fn test_clone_overwrite() {
     assert_eq!(overlay_account1.storage_value_write_cache().len(), 2);
     assert_eq!(overlay_account1.storage_owner_lv1_write_cache().len(), 2);
 }
+
+#[test]
+fn test_reset_storage() {
+    let storage_manager = new_state_manager_for_unit_test();
+    let db = StateDb::new(storage_manager.get_state_for_genesis_write());
+    let mut overlay_account = init_test_account();
+    let address = *overlay_account.address();
+
+    overlay_account.set_storage(vec![1], 1.into(), address);
+    overlay_account.reset_storage();
+    assert!(overlay_account.is_storage_reset());
+    assert_eq!(overlay_account.storage_at(&db, &[1]).unwrap(), 0.into());
+
+    // the staking info is kept
+    assert_eq!(overlay_account.is_newly_created_contract(), false);
+    assert_eq!(overlay_account.deposit_list().unwrap().len(), 1);
+    assert_eq!(overlay_account.vote_stake_list().unwrap().len(), 4);
+
+    let cloned = overlay_account.clone_basic();
+    assert!(cloned.is_storage_reset());
+}
@@ -4,6 +4,8 @@
 
 pub use self::{
     account_entry::{OverlayAccount, COMMISSION_PRIVILEGE_SPECIAL_KEY},
+    state_diff::{AccountDiff, Diff, StateDiff},
+    state_override::{AccountOverride, StateOverride},
     substate::{cleanup_mode, CallStackInfo, Substate},
 };
 
@@ -46,6 +48,8 @@ mod account_entry;
 #[cfg(test)]
 mod account_entry_tests;
 pub mod prefetcher;
+mod state_diff;
+mod state_override;
 #[cfg(test)]
 mod state_tests;
 mod substate;
@@ -877,19 +881,8 @@ impl<StateDbStorage: StorageStateTrait> CheckpointTrait
     /// `collect_ownership_changed()` was called before calling
     /// this function.
     fn discard_checkpoint(&mut self) {
-        // merge with previous checkpoint
-        let last = self.checkpoints.get_mut().pop();
-        if let Some(mut checkpoint) = last {
+        if self.merge_last_checkpoint() {
             self.staking_state_checkpoints.get_mut().pop();
-            if let Some(ref mut prev) = self.checkpoints.get_mut().last_mut() {
-                if prev.is_empty() {
-                    **prev = checkpoint;
-                } else {
-                    for (k, v) in checkpoint.drain() {
-                        prev.entry(k).or_insert(v);
-                    }
-                }
-            }
         }
     }
 
@@ -1344,6 +1337,26 @@ impl<StateDbStorage: StorageStateTrait> StateGeneric<StateDbStorage> {
         Ok(())
     }
 
+    /// Merge the last account checkpoint with the previous one. Returns
+    /// `false` if there is no checkpoint.
+    fn merge_last_checkpoint(&mut self) -> bool {
+        let last = self.checkpoints.get_mut().pop();
+        if let Some(mut checkpoint) = last {
+            if let Some(ref mut prev) = self.checkpoints.get_mut().last_mut() {
+                if prev.is_empty() {
+                    **prev = checkpoint;
+                } else {
+                    for (k, v) in checkpoint.drain() {
+                        prev.entry(k).or_insert(v);
+                    }
+                }
+            }
+            true
+        } else {
+            false
+        }
+    }
+
     /// Get the value of storage at a specific checkpoint.
     #[cfg(test)]
     pub fn checkpoint_storage_at(
@@ -1372,7 +1385,9 @@ impl<StateDbStorage: StorageStateTrait> StateGeneric<StateDbStorage> {
                     })) => {
                         if let Some(value) = account.cached_storage_at(key) {
                             return Ok(Some(value));
-                        } else if account.is_newly_created_contract() {
+                        } else if account.is_newly_created_contract()
+                            || account.is_storage_reset()
+                        {
                             return Ok(Some(U256::zero()));
                         } else {
                             kind = Some(ReturnKind::OriginalAt);
@@ -0,0 +1,195 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{AccountEntryProtectedMethods, OverlayAccount, StateGeneric};
+use crate::hash::KECCAK_EMPTY;
+use cfx_bytes::Bytes;
+use cfx_statedb::{Result as DbResult, StateDbExt};
+use cfx_storage::StorageStateTrait;
+use cfx_types::{Address, U256};
+use primitives::{StorageKey, StorageValue};
+use std::collections::{BTreeMap, HashMap};
+
+/// The change of a value between two states.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diff<T> {
+    /// The value is not changed.
+    Same,
+    /// The value is created with the given value.
+    Born(T),
+    /// The value is removed. The removed value is given.
+    Died(T),
+    /// The value is changed from the first value to the second one.
+    Changed(T, T),
+}
+
+impl<T: PartialEq> Diff<T> {
+    fn new(from: T, to: T) -> Self {
+        if from == to {
+            Diff::Same
+        } else {
+            Diff::Changed(from, to)
+        }
+    }
+
+    pub fn is_same(&self) -> bool {
+        match self {
+            Diff::Same => true,
+            _ => false,
+        }
+    }
+}
+
+/// The changes of a single account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountDiff {
+    pub balance: Diff<U256>,
+    pub nonce: Diff<U256>,
+    pub code: Diff<Bytes>,
+    /// The changed storage entries. The keys are raw storage keys, which
+    /// are not always 32 bytes long for internal contracts.
+    pub storage: BTreeMap<Vec<u8>, Diff<U256>>,
+}
+
+impl AccountDiff {
+    pub fn is_same(&self) -> bool {
+        self.balance.is_same()
+            && self.nonce.is_same()
+            && self.code.is_same()
+            && self.storage.is_empty()
+    }
+}
+
+pub type StateDiff = BTreeMap<Address, AccountDiff>;
+
+impl<StateDbStorage: StorageStateTrait> StateGeneric<StateDbStorage> {
+    /// Create a checkpoint of the accounts only, to collect the changes made
+    /// after it with `diff_since_checkpoint`. Unlike `checkpoint`, the staking
+    /// state is not recorded, so the transactions executed after it can still
+    /// update the total issued tokens, e.g. when a contract is destroyed. It
+    /// must be released by `discard_diff_checkpoint` and cannot be reverted.
+    pub fn diff_checkpoint(&mut self) {
+        self.checkpoints.get_mut().push(HashMap::new());
+    }
+
+    /// Discard the checkpoint created by `diff_checkpoint`, keeping the
+    /// changes made after it.
+    pub fn discard_diff_checkpoint(&mut self) { self.merge_last_checkpoint(); }
+
+    /// Returns the changes of the accounts modified since the last
+    /// checkpoint. The checkpoint is kept, and the caller should discard or
+    /// revert it afterwards.
+    pub fn diff_since_checkpoint(&self) -> DbResult<StateDiff> {
+        let checkpoints = self.checkpoints.read();
+        let checkpoint = match checkpoints.last() {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(Default::default()),
+        };
+        let cache = self.cache.read();
+
+        let mut state_diff = StateDiff::new();
+        for (address, old_entry) in checkpoint {
+            // The account is not in the cache at the checkpoint, so its value
+            // is the value in the db.
+            let loaded;
+            let old_account = match old_entry {
+                Some(entry) => entry.account.as_ref(),
+                None => {
+                    loaded = self
+                        .db
+                        .get_account(address)?
+                        .map(|acc| OverlayAccount::from_loaded(address, acc));
+                    loaded.as_ref()
+                }
+            };
+            let new_account =
+                cache.get(address).and_then(|entry| entry.account.as_ref());
+
+            let account_diff = match (old_account, new_account) {
+                (None, None) => continue,
+                (None, Some(new)) => AccountDiff {
+                    balance: Diff::Born(*new.balance()),
+                    nonce: Diff::Born(*new.nonce()),
+                    code: Diff::Born(self.account_code(new)?),
+                    storage: new
+                        .storage_value_write_cache()
+                        .iter()
+                        .filter(|(_, value)| !value.is_zero())
+                        .map(|(key, value)| (key.clone(), Diff::Born(*value)))
+                        .collect(),
+                },
+                (Some(old), None) => AccountDiff {
+                    balance: Diff::Died(*old.balance()),
+                    nonce: Diff::Died(*old.nonce()),
+                    code: Diff::Died(self.account_code(old)?),
+                    storage: Default::default(),
+                },
+                (Some(old), Some(new)) => AccountDiff {
+                    balance: Diff::new(*old.balance(), *new.balance()),
+                    nonce: Diff::new(*old.nonce(), *new.nonce()),
+                    code: if old.code_hash() == new.code_hash() {
+                        Diff::Same
+                    } else {
+                        Diff::new(
+                            self.account_code(old)?,
+                            self.account_code(new)?,
+                        )
+                    },
+                    storage: self
+                        .storage_diff(old, new.storage_value_write_cache())?,
+                },
+            };
+            if !account_diff.is_same() {
+                state_diff.insert(*address, account_diff);
+            }
+        }
+        Ok(state_diff)
+    }
+
+    fn account_code(&self, account: &OverlayAccount) -> DbResult<Bytes> {
+        if let Some(code) = account.code() {
+            return Ok(code.as_ref().clone());
+        }
+        if account.code_hash() == KECCAK_EMPTY {
+            return Ok(Bytes::new());
+        }
+        Ok(self
+            .db
+            .get_code(account.address(), &account.code_hash())?
+            .map_or_else(Bytes::new, |info| info.code.as_ref().clone()))
+    }
+
+    fn storage_diff(
+        &self, old: &OverlayAccount, written: &HashMap<Vec<u8>, U256>,
+    ) -> DbResult<BTreeMap<Vec<u8>, Diff<U256>>> {
+        let mut storage = BTreeMap::new();
+        for (key, value) in written {
+            let old_value = match old.storage_value_write_cache().get(key) {
+                Some(old_value) => *old_value,
+                None if old.is_newly_created_contract()
+                    || old.is_storage_reset() =>
+                {
+                    U256::zero()
+                }
+                None => self
+                    .db
+                    .get::<StorageValue>(StorageKey::new_storage_key(
+                        old.address(),
+                        key,
+                    ))?
+                    .map_or_else(U256::zero, |v| v.value),
+            };
+            let diff = match (old_value.is_zero(), value.is_zero()) {
+                (true, true) => Diff::Same,
+                (true, false) => Diff::Born(*value),
+                (false, true) => Diff::Died(old_value),
+                (false, false) => Diff::new(old_value, *value),
+            };
+            if !diff.is_same() {
+                storage.insert(key.clone(), diff);
+            }
+        }
+        Ok(storage)
+    }
+}
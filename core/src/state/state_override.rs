@@ -0,0 +1,91 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::StateGeneric;
+use crate::vm::Spec;
+use cfx_bytes::Bytes;
+use cfx_state::{state_trait::StateOpsTrait, CleanupMode};
+use cfx_statedb::Result as DbResult;
+use cfx_storage::StorageStateTrait;
+use cfx_types::{Address, BigEndianHash, H256, U256};
+use primitives::storage::STORAGE_LAYOUT_REGULAR_V0;
+use std::collections::HashMap;
+
+/// Overrides of a single account, applied before a virtual call.
+#[derive(Debug, Clone, Default)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<U256>,
+    pub code: Option<Bytes>,
+    /// Replaces the whole storage of the account.
+    pub state: Option<HashMap<H256, H256>>,
+    /// Replaces the given storage slots and keeps the others.
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+pub type StateOverride = HashMap<Address, AccountOverride>;
+
+impl<StateDbStorage: StorageStateTrait> StateGeneric<StateDbStorage> {
+    /// Apply `state_override` on top of the state. A state with overrides
+    /// must never be committed.
+    pub fn apply_state_override(
+        &mut self, state_override: &StateOverride, spec: &Spec,
+    ) -> DbResult<()> {
+        for (address, account) in state_override {
+            if let Some(code) = &account.code {
+                if !self.exists(address)? {
+                    self.new_contract_with_admin(
+                        address,
+                        /* No admin; admin = */ &Address::zero(),
+                        /* balance = */ U256::zero(),
+                        spec.contract_start_nonce,
+                        Some(STORAGE_LAYOUT_REGULAR_V0),
+                    )?;
+                }
+                self.init_code(address, code.clone(), *address)?;
+            }
+            if let Some(balance) = account.balance {
+                let current = self.balance(address)?;
+                if balance > current {
+                    self.add_balance(
+                        address,
+                        &(balance - current),
+                        CleanupMode::NoEmpty,
+                        spec.account_start_nonce,
+                    )?;
+                } else if balance < current {
+                    self.sub_balance(
+                        address,
+                        &(current - balance),
+                        &mut CleanupMode::NoEmpty,
+                    )?;
+                }
+            }
+            if let Some(nonce) = account.nonce {
+                self.set_nonce(address, &nonce)?;
+            }
+            if account.state.is_some() || account.state_diff.is_some() {
+                let mut overlay_account = self.require_or_new_basic_account(
+                    address,
+                    &spec.account_start_nonce,
+                )?;
+                if account.state.is_some() {
+                    overlay_account.reset_storage();
+                }
+            }
+            for storage in account.state.iter().chain(account.state_diff.iter())
+            {
+                for (key, value) in storage {
+                    self.set_storage(
+                        address,
+                        key.as_bytes().to_vec(),
+                        value.into_uint(),
+                        *address,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
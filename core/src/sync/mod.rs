@@ -27,8 +27,8 @@ pub use self::{
     synchronization_phases::{
         CatchUpCheckpointPhase, CatchUpFillBlockBodyPhase,
         CatchUpRecoverBlockHeaderFromDbPhase, CatchUpSyncBlockHeaderPhase,
-        CatchUpSyncBlockPhase, NormalSyncPhase, SyncPhaseType,
-        SynchronizationPhaseManager, SynchronizationPhaseTrait,
+        CatchUpSyncBlockPhase, NormalSyncPhase, SyncPhaseProgress,
+        SyncPhaseType, SynchronizationPhaseManager, SynchronizationPhaseTrait,
     },
     synchronization_protocol_handler::{
         LocalMessageTask, ProtocolConfiguration, SyncHandlerWorkType,
@@ -206,6 +206,16 @@ impl SnapshotChunkSync {
 
     pub fn status(&self) -> Status { self.inner.read().status }
 
+    /// Return the number of downloaded snapshot chunks and the total number
+    /// of chunks, or `None` if the chunks are not being downloaded.
+    pub fn chunk_progress(&self) -> Option<(usize, usize)> {
+        self.inner
+            .read()
+            .chunk_manager
+            .as_ref()
+            .map(|chunk_manager| chunk_manager.num_chunks())
+    }
+
     pub fn handle_snapshot_manifest_response(
         &self, ctx: &Context, response: SnapshotManifestResponse,
         request: &SnapshotManifestRequest,
@@ -182,6 +182,14 @@ impl SnapshotChunkManager {
 
     pub fn is_inactive(&self) -> bool { self.active_peers.is_empty() }
 
+    /// Return the number of downloaded chunks and the total number of chunks.
+    pub fn num_chunks(&self) -> (usize, usize) {
+        let total = self.pending_chunks.len()
+            + self.downloading_chunks.len()
+            + self.num_downloaded;
+        (self.num_downloaded, total)
+    }
+
     pub fn set_active_peers(&mut self, new_active_peers: HashSet<NodeId>) {
         self.active_peers = new_active_peers;
     }
@@ -1056,7 +1056,7 @@ impl SynchronizationGraph {
     /// CatchUpRecoverBlockHeaderFromDbPhase phase.
     /// It tries to construct the consensus graph based on header
     /// information stored in db.
-    pub fn recover_graph_from_db(&self) {
+    pub fn recover_graph_from_db(&self, light_node: bool) {
         info!("Start fast recovery of the block DAG from database");
 
         // Recover the initial sequence number in consensus graph
@@ -1082,6 +1082,25 @@ impl SynchronizationGraph {
             genesis_seq_num
         );
 
+        if light_node {
+            self.recover_light_headers_from_db(genesis_hash, genesis_seq_num);
+        } else {
+            self.recover_headers_from_db(genesis_hash, genesis_seq_num);
+        }
+
+        debug!(
+            "Current frontier after recover from db: {:?}",
+            self.inner.read().not_ready_blocks_frontier.get_frontier()
+        );
+
+        info!("Finish reconstructing the pivot chain of length {}, start to sync from peers", self.consensus.best_epoch_number());
+    }
+
+    /// Insert the headers in the future of the current era genesis stored in
+    /// db into the graph.
+    fn recover_headers_from_db(
+        &self, genesis_hash: H256, genesis_seq_num: u64,
+    ) {
         // Get terminals stored in db.
         let terminals_opt = self.data_man.terminals_from_db();
         if terminals_opt.is_none() {
@@ -1159,13 +1178,28 @@ impl SynchronizationGraph {
                 missed_hashes.insert(hash);
             }
         }
+    }
 
-        debug!(
-            "Current frontier after recover from db: {:?}",
-            self.inner.read().not_ready_blocks_frontier.get_frontier()
+    /// Like `recover_headers_from_db`, except that the headers that are
+    /// corrupt in db are skipped and fetched from peers again, like the missed
+    /// ones. It is used by light nodes, which only have headers.
+    fn recover_light_headers_from_db(
+        &self, genesis_hash: H256, genesis_seq_num: u64,
+    ) {
+        let missed_hashes = self.data_man.traverse_headers_from_db(
+            &genesis_hash,
+            genesis_seq_num,
+            |mut block_header| {
+                self.insert_block_header(
+                    &mut block_header,
+                    true,  /* need_to_verify */
+                    false, /* bench_mode */
+                    true,  /* insert_to_consensus */
+                    false, /* persistent */
+                );
+            },
         );
-
-        info!("Finish reconstructing the pivot chain of length {}, start to sync from peers", self.consensus.best_epoch_number());
+        debug!("Missed blocks after recover from db: {:?}", missed_hashes);
     }
 
     /// Return None if `hash` is not in sync graph
@@ -2,17 +2,20 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::sync::{
-    message::DynamicCapability,
-    state::{SnapshotChunkSync, Status},
-    synchronization_protocol_handler::SynchronizationProtocolHandler,
-    synchronization_state::SynchronizationState,
-    SharedSynchronizationGraph,
+use crate::{
+    channel::Channel,
+    sync::{
+        message::DynamicCapability,
+        state::{SnapshotChunkSync, Status},
+        synchronization_protocol_handler::SynchronizationProtocolHandler,
+        synchronization_state::SynchronizationState,
+        SharedSynchronizationGraph,
+    },
 };
 use cfx_internal_common::StateAvailabilityBoundary;
 use cfx_parameters::sync::CATCH_UP_EPOCH_LAG_THRESHOLD;
 use network::NetworkContext;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::{
     collections::HashMap,
     sync::{
@@ -42,6 +45,19 @@ pub enum SyncPhaseType {
     Normal = 5,
 }
 
+/// Synchronization progress, published when the phase changes.
+#[derive(Debug, Clone)]
+pub struct SyncPhaseProgress {
+    /// Name of the current phase.
+    pub phase: &'static str,
+    /// The best epoch reported by peers, `None` if there are no peers.
+    pub best_peer_epoch: Option<u64>,
+    pub local_best_epoch: u64,
+    /// The number of downloaded snapshot chunks and the total number of
+    /// chunks. Only set during state sync.
+    pub snapshot_chunks: Option<(usize, usize)>,
+}
+
 pub trait SynchronizationPhaseTrait: Send + Sync {
     fn name(&self) -> &'static str;
     fn phase_type(&self) -> SyncPhaseType;
@@ -102,6 +118,10 @@ impl SynchronizationPhaseManagerInner {
 
 pub struct SynchronizationPhaseManager {
     inner: RwLock<SynchronizationPhaseManagerInner>,
+    state_sync: Arc<SnapshotChunkSync>,
+    sync_progress: Arc<Channel<SyncPhaseProgress>>,
+    /// Snapshot chunk counts of the last published progress.
+    last_snapshot_chunks: Mutex<Option<(usize, usize)>>,
 }
 
 impl SynchronizationPhaseManager {
@@ -110,12 +130,16 @@ impl SynchronizationPhaseManager {
         sync_state: Arc<SynchronizationState>,
         sync_graph: SharedSynchronizationGraph,
         state_sync: Arc<SnapshotChunkSync>,
+        sync_progress: Arc<Channel<SyncPhaseProgress>>,
     ) -> Self
     {
         let sync_manager = SynchronizationPhaseManager {
             inner: RwLock::new(SynchronizationPhaseManagerInner::new(
                 initial_phase_type,
             )),
+            state_sync: state_sync.clone(),
+            sync_progress,
+            last_snapshot_chunks: Mutex::new(None),
         };
 
         sync_manager.register_phase(Arc::new(
@@ -163,6 +187,7 @@ impl SynchronizationPhaseManager {
         self.inner.write().change_phase_to(phase_type);
         let current_phase = self.get_current_phase();
         current_phase.start(io, sync_handler);
+        self.notify_progress(sync_handler);
     }
 
     pub fn try_initialize(
@@ -174,7 +199,42 @@ impl SynchronizationPhaseManager {
             // if not initialized
             let current_phase = self.get_current_phase();
             current_phase.start(io, sync_handler);
+            self.notify_progress(sync_handler);
+        }
+    }
+
+    /// Publish the progress if snapshot chunks were downloaded since the
+    /// last notification. Only needed when the phase does not change.
+    pub fn notify_state_sync_progress(
+        &self, sync_handler: &SynchronizationProtocolHandler,
+    ) {
+        if self.get_current_phase().phase_type()
+            != SyncPhaseType::CatchUpCheckpoint
+        {
+            return;
         }
+        if self.state_sync.chunk_progress() != *self.last_snapshot_chunks.lock()
+        {
+            self.notify_progress(sync_handler);
+        }
+    }
+
+    fn notify_progress(&self, sync_handler: &SynchronizationProtocolHandler) {
+        let current_phase = self.get_current_phase();
+        let snapshot_chunks =
+            if current_phase.phase_type() == SyncPhaseType::CatchUpCheckpoint {
+                self.state_sync.chunk_progress()
+            } else {
+                None
+            };
+        *self.last_snapshot_chunks.lock() = snapshot_chunks;
+
+        self.sync_progress.send(SyncPhaseProgress {
+            phase: current_phase.name(),
+            best_peer_epoch: sync_handler.syn.best_peer_epoch(),
+            local_best_epoch: sync_handler.graph.consensus.best_epoch_number(),
+            snapshot_chunks,
+        });
     }
 }
 
@@ -222,7 +282,7 @@ impl SynchronizationPhaseTrait for CatchUpRecoverBlockHeaderFromDbPhase {
         let recovered = self.recovered.clone();
         let graph = self.graph.clone();
         std::thread::spawn(move || {
-            graph.recover_graph_from_db();
+            graph.recover_graph_from_db(false /* light_node */);
             recovered.store(true, AtomicOrdering::SeqCst);
             info!("finish recover header graph from db");
         });
@@ -8,6 +8,7 @@ use super::{
 };
 use crate::{
     block_data_manager::BlockStatus,
+    channel::Notifications,
     light_protocol::Provider as LightProvider,
     message::{decode_msg, Message, MsgId},
     sync::{
@@ -421,7 +422,7 @@ impl SynchronizationProtocolHandler {
         state_sync_config: StateSyncConfiguration,
         initial_sync_phase: SyncPhaseType,
         sync_graph: SharedSynchronizationGraph,
-        light_provider: Arc<LightProvider>,
+        light_provider: Arc<LightProvider>, notifications: Arc<Notifications>,
     ) -> Self
     {
         let sync_state = Arc::new(SynchronizationState::new(
@@ -453,6 +454,7 @@ impl SynchronizationProtocolHandler {
                 sync_state.clone(),
                 sync_graph.clone(),
                 state_sync.clone(),
+                notifications.sync_progress.clone(),
             ),
             phase_manager_lock: Mutex::new(0),
             recover_public_queue,
@@ -1583,6 +1585,7 @@ impl SynchronizationProtocolHandler {
                         self,
                     );
                 } else {
+                    self.phase_manager.notify_state_sync_progress(self);
                     break;
                 }
             }
@@ -6,6 +6,7 @@ use super::{
     Error, SharedSynchronizationGraph, SynchronizationProtocolHandler,
 };
 use crate::{
+    channel::Notifications,
     light_protocol::Provider as LightProvider,
     sync::{
         request_manager::RequestManager, synchronization_phases::SyncPhaseType,
@@ -36,6 +37,7 @@ impl SynchronizationService {
         protocol_config: ProtocolConfiguration,
         state_sync_config: StateSyncConfiguration,
         initial_sync_phase: SyncPhaseType, light_provider: Arc<LightProvider>,
+        notifications: Arc<Notifications>,
     ) -> Self
     {
         let sync_handler = Arc::new(SynchronizationProtocolHandler::new(
@@ -45,6 +47,7 @@ impl SynchronizationService {
             initial_sync_phase,
             sync_graph.clone(),
             light_provider,
+            notifications,
         ));
 
         assert_eq!(sync_handler.is_consortium(), sync_graph.is_consortium());
@@ -3,15 +3,21 @@
 // See http://www.gnu.org/licenses/
 
 use crate::{
-    block_data_manager::DbType,
+    block_data_manager::{DbType, LightSyncProgress},
+    pow::PowComputer,
     sync::{
-        utils::{create_simple_block_impl, initialize_synchronization_graph},
+        utils::{
+            create_simple_block_impl, initialize_data_manager,
+            initialize_synchronization_graph,
+        },
         SynchronizationGraphNode,
     },
+    vm_factory::VmFactory,
 };
 use cfx_types::{BigEndianHash, H256, U256};
 use primitives::Block;
 use std::{
+    collections::HashSet,
     fs,
     sync::Arc,
     thread::sleep,
@@ -235,3 +241,95 @@ fn test_remove_expire_blocks() {
         sleep(Duration::from_millis(300));
     }
 }
+
+#[test]
+fn test_recover_light_sync_data() {
+    {
+        let (data_man, genesis_block) = initialize_data_manager(
+            "./test_light_sync.db/",
+            DbType::Rocksdb,
+            Arc::new(PowComputer::new(true)),
+            VmFactory::new(1024 * 32),
+        );
+
+        // store a chain of headers and the sync progress up to its tip
+        let mut hashes = vec![genesis_block.hash()];
+        for i in 1..5 {
+            let (hash, block) = create_simple_block_impl(
+                hashes[i - 1],
+                vec![],
+                i as u64,
+                U256::from(i),
+                U256::from(10),
+                1,
+                false,
+            );
+            data_man.insert_block_header(
+                hash,
+                Arc::new(block.block_header),
+                true, /* persistent */
+            );
+            hashes.push(hash);
+        }
+        data_man.insert_terminals_to_db(vec![hashes[4]]);
+        data_man.insert_light_sync_progress(&LightSyncProgress {
+            height: 4,
+            pivot_hash: hashes[4],
+        });
+
+        // resume from the stored data
+        {
+            let progress = data_man.light_sync_progress().unwrap();
+            assert_eq!(progress.height, 4);
+            assert_eq!(progress.pivot_hash, hashes[4]);
+
+            let mut recovered = HashSet::new();
+            let missed = data_man.traverse_headers_from_db(
+                &hashes[0],
+                0, /* genesis_seq_num */
+                |header| {
+                    recovered.insert(header.hash());
+                },
+            );
+            assert!(missed.is_empty());
+            assert_eq!(recovered, hashes[1..].iter().cloned().collect());
+        }
+
+        // fall back to syncing from peers if the stored data is corrupt
+        {
+            data_man.db_manager.corrupt_light_sync_data(&hashes[2]);
+            // the header is read from db after a restart
+            data_man
+                .remove_block_header(&hashes[2], false /* remove_db */);
+
+            assert!(data_man.light_sync_progress().is_none());
+            assert!(data_man
+                .db_manager
+                .light_sync_progress_from_db()
+                .unwrap()
+                .is_none());
+
+            let mut recovered = HashSet::new();
+            let missed = data_man.traverse_headers_from_db(
+                &hashes[0],
+                0, /* genesis_seq_num */
+                |header| {
+                    recovered.insert(header.hash());
+                },
+            );
+            assert_eq!(missed, std::iter::once(hashes[2]).collect());
+            assert_eq!(recovered, hashes[3..].iter().cloned().collect());
+        }
+    }
+
+    let mut retry = 3;
+    while let Err(e) = fs::remove_dir_all("./test_light_sync.db") {
+        println!(
+            "failed to remove directory test_light_sync.db, err = {:?}",
+            e
+        );
+        assert!(retry > 0);
+        retry -= 1;
+        sleep(Duration::from_millis(300));
+    }
+}
@@ -170,11 +170,13 @@ pub fn initialize_synchronization_graph_with_data_manager(
         machine.clone(),
     );
 
+    let notifications = Notifications::init();
     let txpool = Arc::new(TransactionPool::new(
         TxPoolConfig::default(),
         verification_config.clone(),
         data_man.clone(),
         machine.clone(),
+        notifications.clone(),
     ));
     let statistics = Arc::new(Statistics::new());
 
@@ -193,7 +195,6 @@ pub fn initialize_synchronization_graph_with_data_manager(
         enable_state_expose: false,
         is_consortium: false,
     };
-    let notifications = Notifications::init();
     let consensus = Arc::new(ConsensusGraph::new(
         ConsensusConfig {
             chain_id: ChainIdParamsInner::new_simple(0),
@@ -214,6 +215,7 @@ pub fn initialize_synchronization_graph_with_data_manager(
             referee_bound: REFEREE_DEFAULT_BOUND,
             get_logs_epoch_batch_size: 32,
             get_logs_filter_max_epoch_range: None,
+            gas_price_oracle: None,
             sync_state_starting_epoch: None,
             sync_state_epoch_gap: None,
         },
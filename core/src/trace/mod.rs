@@ -8,15 +8,35 @@ use crate::{
         Action, Call, CallResult, Create, CreateResult, ExecTrace,
         InternalTransferAction,
     },
-    vm::{ActionParams, Result as VmResult},
+    vm::{ActionParams, Error as VmError, Result as VmResult},
 };
 use cfx_types::{Address, U256};
 
 pub mod error_unwind;
+pub mod struct_log;
 pub mod trace;
 pub mod trace_filter;
 
 pub use error_unwind::ErrorUnwind;
+pub use struct_log::{StructLog, StructLogConfig, StructLogTracer};
+
+/// The interpreter state right before an opcode is executed.
+pub struct OpcodeStep<'a> {
+    /// Program counter of the opcode.
+    pub pc: usize,
+    /// Mnemonic name of the opcode.
+    pub op: &'static str,
+    /// Gas left before the opcode is executed.
+    pub gas: U256,
+    /// Gas charged for the opcode.
+    pub gas_cost: U256,
+    /// Call depth, starting from 1 for the transaction itself.
+    pub depth: usize,
+    pub stack: &'a [U256],
+    pub memory: &'a [u8],
+    /// The storage (key, value) written by the opcode, if it is a `SSTORE`.
+    pub store_written: Option<(U256, U256)>,
+}
 
 /// This trait is used by executive to build traces.
 pub trait Tracer: Send {
@@ -42,6 +62,23 @@ pub trait Tracer: Send {
         &mut self, from: Address, to: Address, value: U256,
     );
 
+    /// Returns true if the interpreter should report every executed opcode
+    /// to this tracer.
+    fn trace_opcodes(&self) -> bool { false }
+
+    /// Called before an opcode is executed. Only called if `trace_opcodes`
+    /// returns true.
+    fn trace_opcode_step(&mut self, _step: OpcodeStep) {}
+
+    /// Called after an opcode is executed with the items it pushed to the
+    /// stack. Only called if `trace_opcodes` returns true.
+    fn trace_opcode_executed(&mut self, _gas_left: U256, _stack_push: &[U256]) {
+    }
+
+    /// Called when the execution at `depth` stops with an error. Only called
+    /// if `trace_opcodes` returns true.
+    fn trace_opcode_failed(&mut self, _depth: usize, _error: &VmError) {}
+
     /// Consumes self and returns all traces.
     fn drain(self) -> Vec<Self::Output>;
 }
@@ -0,0 +1,165 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{OpcodeStep, Tracer};
+use crate::{
+    executive::ExecutiveResult,
+    trace::trace::ExecTrace,
+    vm::{ActionParams, Error as VmError, Result as VmResult},
+};
+use cfx_types::{Address, BigEndianHash, H256, U256};
+use std::collections::BTreeMap;
+
+/// Controls which parts of the interpreter state are captured in each
+/// `StructLog`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StructLogConfig {
+    pub disable_stack: bool,
+    pub disable_memory: bool,
+    pub disable_storage: bool,
+}
+
+/// The interpreter state of a single executed opcode.
+#[derive(Debug, Clone)]
+pub struct StructLog {
+    pub pc: usize,
+    pub op: &'static str,
+    pub gas: U256,
+    pub gas_cost: U256,
+    pub depth: usize,
+    /// The stack before the opcode is executed, bottom first.
+    pub stack: Option<Vec<U256>>,
+    /// The memory before the opcode is executed.
+    pub memory: Option<Vec<u8>>,
+    /// The storage slots of the current contract accessed so far. Only set
+    /// for `SLOAD` and `SSTORE`.
+    pub storage: Option<BTreeMap<H256, H256>>,
+    pub error: Option<String>,
+}
+
+/// Opcode-level tracer. It records a `StructLog` for every opcode executed
+/// by the interpreter.
+///
+/// `Tracer::drain` consumes the tracer, so the logs are written into a
+/// vector owned by the caller, which is still available after the
+/// transaction is executed.
+pub struct StructLogTracer<'a> {
+    config: StructLogConfig,
+    logs: &'a mut Vec<StructLog>,
+    /// Storage slots accessed by the contract at each call depth.
+    storage: Vec<BTreeMap<H256, H256>>,
+    /// Key of the `SLOAD` in progress, its value is known only after the
+    /// opcode is executed.
+    pending_sload: Option<H256>,
+}
+
+impl<'a> StructLogTracer<'a> {
+    pub fn new(config: StructLogConfig, logs: &'a mut Vec<StructLog>) -> Self {
+        StructLogTracer {
+            config,
+            logs,
+            storage: Vec::new(),
+            pending_sload: None,
+        }
+    }
+
+    fn storage_at(&mut self, depth: usize) -> &mut BTreeMap<H256, H256> {
+        // Storage of finished sub-calls is discarded, a new sub-call at the
+        // same depth may run in another contract.
+        self.storage.resize_with(depth, Default::default);
+        &mut self.storage[depth - 1]
+    }
+}
+
+impl<'a> Tracer for StructLogTracer<'a> {
+    type Output = ExecTrace;
+
+    fn prepare_trace_call(&mut self, _: &ActionParams) {}
+
+    fn prepare_trace_call_result(&mut self, _: &VmResult<ExecutiveResult>) {}
+
+    fn prepare_trace_create(&mut self, _: &ActionParams) {}
+
+    fn prepare_trace_create_result(&mut self, _: &VmResult<ExecutiveResult>) {}
+
+    fn prepare_internal_transfer_action(
+        &mut self, _: Address, _: Address, _: U256,
+    ) {
+    }
+
+    fn trace_opcodes(&self) -> bool { true }
+
+    fn trace_opcode_step(&mut self, step: OpcodeStep) {
+        let mut storage = None;
+        self.pending_sload = None;
+        if !self.config.disable_storage {
+            let accessed = self.storage_at(step.depth);
+            match step.op {
+                "SSTORE" => {
+                    if let Some((key, value)) = step.store_written {
+                        accessed.insert(
+                            H256::from_uint(&key),
+                            H256::from_uint(&value),
+                        );
+                    }
+                    storage = Some(accessed.clone());
+                }
+                "SLOAD" => {
+                    if let Some(key) = step.stack.last() {
+                        self.pending_sload = Some(H256::from_uint(key));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.logs.push(StructLog {
+            pc: step.pc,
+            op: step.op,
+            gas: step.gas,
+            gas_cost: step.gas_cost,
+            depth: step.depth,
+            stack: if self.config.disable_stack {
+                None
+            } else {
+                Some(step.stack.to_vec())
+            },
+            memory: if self.config.disable_memory {
+                None
+            } else {
+                Some(step.memory.to_vec())
+            },
+            storage,
+            error: None,
+        });
+    }
+
+    fn trace_opcode_executed(&mut self, _gas_left: U256, stack_push: &[U256]) {
+        if let (Some(key), Some(value)) =
+            (self.pending_sload.take(), stack_push.last())
+        {
+            let depth = match self.logs.last() {
+                Some(log) => log.depth,
+                None => return,
+            };
+            let accessed = self.storage_at(depth);
+            accessed.insert(key, H256::from_uint(value));
+            let accessed = accessed.clone();
+            if let Some(log) = self.logs.last_mut() {
+                log.storage = Some(accessed);
+            }
+        }
+    }
+
+    fn trace_opcode_failed(&mut self, depth: usize, error: &VmError) {
+        self.pending_sload = None;
+        if let Some(log) = self.logs.last_mut() {
+            if log.depth == depth && log.error.is_none() {
+                log.error = Some(format!("{}", error));
+            }
+        }
+    }
+
+    fn drain(self) -> Vec<ExecTrace> { vec![] }
+}
@@ -3,7 +3,7 @@ use cfx_types::H256;
 use primitives::EpochNumber;
 
 /// Log event Filter.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct TraceFilter {
     /// Search will be applied from this epoch number.
     pub from_epoch: EpochNumber,
@@ -14,10 +14,18 @@ mod transaction_pool_inner;
 
 extern crate rand;
 
-pub use self::{impls::TreapMap, transaction_pool_inner::TransactionStatus};
+pub use self::{
+    impls::TreapMap,
+    transaction_pool_inner::{PendingReason, TransactionStatus},
+};
 use crate::{
-    block_data_manager::BlockDataManager, consensus::BestInformation,
-    machine::Machine, state::State, verification::VerificationConfig,
+    block_data_manager::BlockDataManager,
+    channel::Notifications,
+    consensus::BestInformation,
+    executive::{ToRepackError, TxDropError},
+    machine::Machine,
+    state::State,
+    verification::VerificationConfig,
 };
 
 use crate::{
@@ -30,6 +38,7 @@ use cfx_parameters::block::DEFAULT_TARGET_BLOCK_GAS_LIMIT;
 use cfx_statedb::{Result as StateDbResult, StateDb};
 use cfx_storage::{StateIndex, StorageManagerTrait};
 use cfx_types::{Address, H256, U256};
+use lru_time_cache::LruCache;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use metrics::{
     register_meter_with_group, Gauge, GaugeUsize, Lock, Meter, MeterTimer,
@@ -37,6 +46,7 @@ use metrics::{
 };
 use parking_lot::{Mutex, RwLock};
 use primitives::{Account, SignedTransaction, TransactionWithSignature};
+use serde::Serialize;
 use std::{
     cmp::{max, min},
     collections::hash_map::HashMap,
@@ -82,6 +92,9 @@ lazy_static! {
         Lock::register("txpool_notify_modified_info");
 }
 
+/// The maximum number of skipped transactions remembered by the pool.
+const MAX_SKIPPED_TX_RECORDS: usize = 10_000;
+
 pub struct TxPoolConfig {
     pub capacity: usize,
     pub min_tx_price: u64,
@@ -112,6 +125,52 @@ impl Default for TxPoolConfig {
     }
 }
 
+/// Why a packed transaction is skipped in execution without bumping the
+/// sender nonce.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// The nonce does not match the sender nonce in the state.
+    InvalidNonce,
+    /// The sender or the sponsor cannot afford the transaction.
+    NotEnoughCash,
+    /// The epoch height of the transaction is out of the bound.
+    EpochHeightOutOfBound,
+    InvalidRecipientAddress,
+}
+
+impl From<&TxDropError> for SkipReason {
+    fn from(e: &TxDropError) -> Self {
+        match e {
+            TxDropError::OldNonce(..) => SkipReason::InvalidNonce,
+            TxDropError::InvalidRecipientAddress(_) => {
+                SkipReason::InvalidRecipientAddress
+            }
+        }
+    }
+}
+
+impl From<&ToRepackError> for SkipReason {
+    fn from(e: &ToRepackError) -> Self {
+        match e {
+            ToRepackError::InvalidNonce { .. } => SkipReason::InvalidNonce,
+            ToRepackError::EpochHeightOutOfBound { .. } => {
+                SkipReason::EpochHeightOutOfBound
+            }
+            ToRepackError::NotEnoughCashFromSponsor { .. }
+            | ToRepackError::SenderDoesNotExist => SkipReason::NotEnoughCash,
+        }
+    }
+}
+
+/// The last skipped execution of a transaction on the pivot chain.
+#[derive(Clone, Debug)]
+pub struct SkippedTransaction {
+    pub block_hash: H256,
+    pub epoch_number: u64,
+    pub reason: SkipReason,
+}
+
 pub struct TransactionPool {
     config: TxPoolConfig,
     verification_config: VerificationConfig,
@@ -122,7 +181,9 @@ pub struct TransactionPool {
     consensus_best_info: Mutex<Arc<BestInformation>>,
     set_tx_requests: Mutex<Vec<Arc<SignedTransaction>>>,
     recycle_tx_requests: Mutex<Vec<Arc<SignedTransaction>>>,
+    skipped_txs: Mutex<LruCache<H256, SkippedTransaction>>,
     machine: Arc<Machine>,
+    notifications: Arc<Notifications>,
 
     /// If it's `false`, operations on the tx pool will be ignored to save
     /// memory/CPU cost.
@@ -146,7 +207,7 @@ impl MallocSizeOf for TransactionPool {
             + consensus_best_info_size
             + set_tx_requests_size
             + recycle_tx_requests_size
-        // Does not count size_of machine
+        // Does not count size_of machine and skipped_txs
     }
 }
 
@@ -156,6 +217,7 @@ impl TransactionPool {
     pub fn new(
         config: TxPoolConfig, verification_config: VerificationConfig,
         data_man: Arc<BlockDataManager>, machine: Arc<Machine>,
+        notifications: Arc<Notifications>,
     ) -> Self
     {
         let genesis_hash = data_man.true_genesis.hash();
@@ -184,7 +246,11 @@ impl TransactionPool {
             consensus_best_info: Mutex::new(Arc::new(Default::default())),
             set_tx_requests: Mutex::new(Default::default()),
             recycle_tx_requests: Mutex::new(Default::default()),
+            skipped_txs: Mutex::new(LruCache::with_capacity(
+                MAX_SKIPPED_TX_RECORDS,
+            )),
             machine,
+            notifications,
             ready_for_mining: AtomicBool::new(false),
         }
     }
@@ -201,6 +267,27 @@ impl TransactionPool {
         self.inner.read().check_tx_packed_in_deferred_pool(tx_hash)
     }
 
+    /// Return the status of a transaction in the pool, or `None` if it is not
+    /// in the pool.
+    pub fn get_transaction_status(
+        &self, tx_hash: &H256,
+    ) -> Option<TransactionStatus> {
+        self.inner.read().get_transaction_status(tx_hash)
+    }
+
+    /// Return whether an unpacked transaction was recently removed from the
+    /// pool by garbage collection.
+    pub fn is_garbage_collected(&self, tx_hash: &H256) -> bool {
+        self.inner.read().is_garbage_collected(tx_hash)
+    }
+
+    /// Return the last skipped execution of a transaction if it is recent.
+    pub fn get_skipped_transaction(
+        &self, tx_hash: &H256,
+    ) -> Option<SkippedTransaction> {
+        self.skipped_txs.lock().get(tx_hash).cloned()
+    }
+
     pub fn get_local_account_info(&self, address: &Address) -> (U256, U256) {
         self.inner
             .read()
@@ -364,9 +451,22 @@ impl TransactionPool {
         INSERT_TXS_SUCCESS_TPS.mark(passed_transactions.len());
         INSERT_TXS_FAILURE_TPS.mark(failure.len());
 
+        self.notify_new_pending_transactions(&passed_transactions);
+
         (passed_transactions, failure)
     }
 
+    fn notify_new_pending_transactions(
+        &self, transactions: &Vec<Arc<SignedTransaction>>,
+    ) {
+        if transactions.is_empty() {
+            return;
+        }
+        self.notifications
+            .new_pending_transactions
+            .send(transactions.clone());
+    }
+
     /// Try to insert `signed_transaction` into transaction pool.
     ///
     /// If some tx is already in our tx_cache, it will be ignored and will not
@@ -475,6 +575,8 @@ impl TransactionPool {
         INSERT_TXS_SUCCESS_TPS.mark(passed_transactions.len());
         INSERT_TXS_FAILURE_TPS.mark(failure.len());
 
+        self.notify_new_pending_transactions(&passed_transactions);
+
         (passed_transactions, failure)
     }
 
@@ -585,6 +687,22 @@ impl TransactionPool {
         }
     }
 
+    /// Remember the transactions skipped in the execution of an epoch on the
+    /// pivot chain, including those executed before the pool is ready.
+    pub fn set_tx_skipped(
+        &self, transactions: Vec<(H256, SkippedTransaction)>,
+    ) {
+        if transactions.is_empty() {
+            // Fast return.
+            return;
+        }
+
+        let mut skipped_txs = self.skipped_txs.lock();
+        for (tx_hash, skipped) in transactions {
+            skipped_txs.insert(tx_hash, skipped);
+        }
+    }
+
     pub fn set_tx_packed(&self, transactions: &Vec<Arc<SignedTransaction>>) {
         if transactions.is_empty() || !self.ready_for_mining() {
             // Fast return.
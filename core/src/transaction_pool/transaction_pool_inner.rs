@@ -21,7 +21,7 @@ use primitives::{
 use rlp::*;
 use serde::Serialize;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -35,6 +35,9 @@ const FURTHEST_FUTURE_TRANSACTION_NONCE_OFFSET: u32 = 2000;
 // By default, the capacity of tx pool is 500K, so the maximum TPS is
 // 500K / 100 = 5K
 const TIME_WINDOW: u64 = 100;
+/// The maximum number of garbage-collected unpacked transactions remembered
+/// by the pool.
+const MAX_GARBAGE_COLLECTED_RECORDS: usize = 10_000;
 
 lazy_static! {
     static ref TX_POOL_RECALCULATE: Arc<dyn Meter> =
@@ -163,6 +166,20 @@ impl DeferredPool {
         }
     }
 
+    fn check_pending_reason(
+        &self, addr: &Address, local_nonce: &U256, local_balance: &U256,
+        tx: &SignedTransaction,
+    ) -> Option<PendingReason>
+    {
+        self.buckets.get(addr).and_then(|bucket| {
+            bucket.check_pending_reason_with_local_info(
+                *local_nonce,
+                *local_balance,
+                tx,
+            )
+        })
+    }
+
     fn check_tx_packed(&self, addr: Address, nonce: U256) -> bool {
         if let Some(bucket) = self.buckets.get(&addr) {
             if let Some(tx_with_ready_info) = bucket.get_tx_by_nonce(nonce) {
@@ -262,7 +279,7 @@ impl ReadyAccountPool {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionStatus {
     Packed,
@@ -270,11 +287,41 @@ pub enum TransactionStatus {
     Pending(PendingReason),
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum PendingReason {
     FutureNonce,
     NotEnoughCash,
+    /// The nonce is lower than the local nonce of the sender, so the
+    /// transaction can no longer be packed.
+    OldNonce,
+}
+
+// `TransactionStatus` is sent to light nodes as a single status code.
+impl Encodable for TransactionStatus {
+    fn rlp_append(&self, stream: &mut RlpStream) {
+        let code: u8 = match self {
+            TransactionStatus::Packed => 0,
+            TransactionStatus::Ready => 1,
+            TransactionStatus::Pending(PendingReason::FutureNonce) => 2,
+            TransactionStatus::Pending(PendingReason::NotEnoughCash) => 3,
+            TransactionStatus::Pending(PendingReason::OldNonce) => 4,
+        };
+        stream.append_internal(&code);
+    }
+}
+
+impl Decodable for TransactionStatus {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        match rlp.as_val::<u8>()? {
+            0 => Ok(TransactionStatus::Packed),
+            1 => Ok(TransactionStatus::Ready),
+            2 => Ok(TransactionStatus::Pending(PendingReason::FutureNonce)),
+            3 => Ok(TransactionStatus::Pending(PendingReason::NotEnoughCash)),
+            4 => Ok(TransactionStatus::Pending(PendingReason::OldNonce)),
+            _ => Err(DecoderError::Custom("Unknown transaction status")),
+        }
+    }
 }
 
 #[derive(DeriveMallocSizeOf)]
@@ -299,6 +346,10 @@ pub struct TransactionPoolInner {
     /// It should contain the same transaction set as `deferred_pool`.
     txs: HashMap<H256, Arc<SignedTransaction>>,
     tx_sponsored_gas_map: HashMap<H256, (U256, u64)>,
+    /// The hashes of the recent unpacked transactions removed by garbage
+    /// collection, in the order of removal.
+    garbage_collected_txs: VecDeque<H256>,
+    garbage_collected_tx_set: HashSet<H256>,
 }
 
 impl TransactionPoolInner {
@@ -318,6 +369,8 @@ impl TransactionPoolInner {
             garbage_collector: GarbageCollector::default(),
             txs: HashMap::new(),
             tx_sponsored_gas_map: HashMap::new(),
+            garbage_collected_txs: VecDeque::new(),
+            garbage_collected_tx_set: HashSet::new(),
         }
     }
 
@@ -327,6 +380,8 @@ impl TransactionPoolInner {
         self.ready_nonces_and_balances.clear();
         self.garbage_collector.clear();
         self.txs.clear();
+        self.garbage_collected_txs.clear();
+        self.garbage_collected_tx_set.clear();
         self.total_received_count = 0;
         self.unpacked_transaction_count = 0;
     }
@@ -401,10 +456,10 @@ impl TransactionPoolInner {
                 warn!("an unexecuted tx is garbage-collected.");
             }
 
-            if !self
+            let packed = self
                 .deferred_pool
-                .check_tx_packed(addr.clone(), lowest_nonce)
-            {
+                .check_tx_packed(addr.clone(), lowest_nonce);
+            if !packed {
                 self.unpacked_transaction_count -= 1;
             }
 
@@ -445,11 +500,31 @@ impl TransactionPoolInner {
             // maintain txs
             self.txs.remove(&removed_tx.hash());
             self.tx_sponsored_gas_map.remove(&removed_tx.hash());
+            if !packed {
+                self.record_garbage_collected(removed_tx.hash());
+            }
         }
 
         GC_METER.mark(count_before_gc - self.total_deferred());
     }
 
+    fn record_garbage_collected(&mut self, tx_hash: H256) {
+        if !self.garbage_collected_tx_set.insert(tx_hash) {
+            return;
+        }
+        self.garbage_collected_txs.push_back(tx_hash);
+        if self.garbage_collected_txs.len() > MAX_GARBAGE_COLLECTED_RECORDS {
+            let oldest = self.garbage_collected_txs.pop_front().unwrap();
+            self.garbage_collected_tx_set.remove(&oldest);
+        }
+    }
+
+    /// Return whether an unpacked transaction was recently removed by garbage
+    /// collection.
+    pub fn is_garbage_collected(&self, tx_hash: &H256) -> bool {
+        self.garbage_collected_tx_set.contains(tx_hash)
+    }
+
     /// Collect garbage and return the remaining quota of the pool to insert new
     /// transactions.
     pub fn remaining_quota(&mut self) -> usize {
@@ -616,6 +691,34 @@ impl TransactionPoolInner {
         )
     }
 
+    /// Return the status of a transaction in the pool, or `None` if it is not
+    /// in the pool or the local nonce of its sender is unknown.
+    pub fn get_transaction_status(
+        &self, tx_hash: &H256,
+    ) -> Option<TransactionStatus> {
+        let tx = self.txs.get(tx_hash)?;
+        let sender = tx.sender();
+        if self.deferred_pool.check_tx_packed(sender, tx.nonce) {
+            return Some(TransactionStatus::Packed);
+        }
+        let (local_nonce, local_balance) =
+            self.get_local_nonce_and_balance(&sender)?;
+        if tx.nonce < local_nonce {
+            return Some(TransactionStatus::Pending(PendingReason::OldNonce));
+        }
+        Some(
+            match self.deferred_pool.check_pending_reason(
+                &sender,
+                &local_nonce,
+                &local_balance,
+                tx,
+            ) {
+                None => TransactionStatus::Ready,
+                Some(reason) => TransactionStatus::Pending(reason),
+            },
+        )
+    }
+
     pub fn get_local_nonce_and_balance(
         &self, address: &Address,
     ) -> Option<(U256, U256)> {
@@ -989,7 +1092,10 @@ impl TransactionPoolInner {
 
 #[cfg(test)]
 mod test_transaction_pool_inner {
-    use super::{DeferredPool, InsertResult, TxWithReadyInfo};
+    use super::{
+        DeferredPool, InsertResult, PendingReason, TransactionStatus,
+        TxWithReadyInfo,
+    };
     use cfx_types::{Address, U256};
     use keylib::{Generator, KeyPair, Random};
     use primitives::{Action, SignedTransaction, Transaction};
@@ -1242,4 +1348,22 @@ mod test_transaction_pool_inner {
             None
         );
     }
+
+    #[test]
+    fn test_transaction_status_rlp() {
+        let statuses = vec![
+            TransactionStatus::Packed,
+            TransactionStatus::Ready,
+            TransactionStatus::Pending(PendingReason::FutureNonce),
+            TransactionStatus::Pending(PendingReason::NotEnoughCash),
+            TransactionStatus::Pending(PendingReason::OldNonce),
+        ];
+
+        for status in statuses {
+            let encoded = rlp::encode(&status);
+            assert_eq!(rlp::decode::<TransactionStatus>(&encoded), Ok(status));
+        }
+
+        assert!(rlp::decode::<TransactionStatus>(&rlp::encode(&5u8)).is_err());
+    }
 }
@@ -22,6 +22,7 @@
 
 use crate::{epoch::EpochNumber, log_entry::LogEntry};
 use cfx_types::{Address, Bloom, BloomInput, H256};
+use rlp_derive::{RlpDecodable, RlpEncodable};
 use std::{error, fmt};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -273,6 +274,27 @@ impl LogFilter {
     }
 }
 
+/// The position of the next entry to return when the results of a filter are
+/// paginated. Entries are returned in execution order, so new epochs do not
+/// change the position of the entries already returned.
+#[derive(Debug, PartialEq, Clone, RlpEncodable, RlpDecodable)]
+pub struct FilterCursor {
+    pub epoch_number: u64,
+    /// The pivot block of `epoch_number`, used to detect pivot chain reorgs
+    /// between pages.
+    pub epoch_hash: H256,
+    /// The index of the block in the epoch.
+    pub block_index: u64,
+    /// The index of the entry (e.g. log or trace) in the block.
+    pub entry_index: u64,
+}
+
+impl FilterCursor {
+    pub fn to_bytes(&self) -> Vec<u8> { rlp::encode(self) }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> { rlp::decode(bytes).ok() }
+}
+
 impl From<String> for FilterError {
     fn from(s: String) -> Self { FilterError::Custom(s) }
 }
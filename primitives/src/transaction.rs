@@ -440,6 +440,29 @@ impl TransactionWithSignature {
 
     pub fn hash(&self) -> H256 { self.hash }
 
+    /// The hash of an Ethereum like transaction encoded as a signed legacy
+    /// Ethereum transaction `[nonce, gasPrice, gas, to, value, data, v, r,
+    /// s]`, which is how Ethereum tools identify it. None for other
+    /// transactions.
+    pub fn eth_hash(&self) -> Option<H256> {
+        if self.transaction_type() != TransactionType::EthereumLike {
+            return None;
+        }
+
+        let mut s = RlpStream::new();
+        s.begin_list(9);
+        s.append(&self.nonce);
+        s.append(&self.gas_price);
+        s.append(&self.gas);
+        s.append(&self.action);
+        s.append(&self.value);
+        s.append(&self.data);
+        s.append(&(self.v as u64 + 35 + self.chain_id as u64 * 2));
+        s.append(&self.r);
+        s.append(&self.s);
+        Some(keccak(s.as_raw()))
+    }
+
     /// Recovers the public key of the sender.
     pub fn recover_public(&self) -> Result<Public, keylib::Error> {
         Ok(recover(&self.signature(), &self.unsigned.signature_hash())?)
@@ -3,7 +3,7 @@ from conflux.address import hex_to_b32_address
 
 class Filter():
     def __init__(self, from_epoch="earliest", to_epoch="latest_state", block_hashes = None, address = None, topics = [],
-                 offset = None, limit = None, encode_address=True):
+                 offset = None, limit = None, cursor = None, page_size = None, encode_address=True):
         if encode_address and address is not None:
             if isinstance(address, list):
                 base32_address = []
@@ -19,3 +19,5 @@ class Filter():
         self.topics = topics
         self.offset = offset
         self.limit = limit
+        self.cursor = cursor
+        self.pageSize = page_size
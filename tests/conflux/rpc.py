@@ -134,6 +134,33 @@ class RpcClient:
             convert_b32_address_field_to_hex(log, "address")
         return logs
 
+    def get_logs_page(self, filter: Filter) -> dict:
+        page = self.node.cfx_getLogs(filter.__dict__)
+        for log in page["logs"]:
+            convert_b32_address_field_to_hex(log, "address")
+        return page
+
+    def new_filter(self, filter: Filter) -> str:
+        return self.node.cfx_newFilter(filter.__dict__)
+
+    def new_block_filter(self) -> str:
+        return self.node.cfx_newBlockFilter()
+
+    def new_pending_transaction_filter(self) -> str:
+        return self.node.cfx_newPendingTransactionFilter()
+
+    def get_filter_changes(self, filter_id: str) -> list:
+        return self.node.cfx_getFilterChanges(filter_id)
+
+    def get_filter_logs(self, filter_id: str) -> list:
+        logs = self.node.cfx_getFilterLogs(filter_id)
+        for log in logs:
+            convert_b32_address_field_to_hex(log, "address")
+        return logs
+
+    def uninstall_filter(self, filter_id: str) -> bool:
+        return self.node.cfx_uninstallFilter(filter_id)
+
     def get_storage_at(self, addr: str, pos: str, epoch: str = None) -> str:
         assert_is_hash_string(addr, length=40)
         addr = hex_to_b32_address(addr)
@@ -169,6 +196,12 @@ class RpcClient:
     def gas_price(self) -> int:
         return int(self.node.cfx_gasPrice(), 0)
 
+    def fee_history(self, epoch_count: int, newest_epoch: str = "latest_state", reward_percentiles: list = None) -> dict:
+        if reward_percentiles is None:
+            return self.node.cfx_feeHistory(hex(epoch_count), newest_epoch)
+        else:
+            return self.node.cfx_feeHistory(hex(epoch_count), newest_epoch, reward_percentiles)
+
     def get_block_reward_info(self, epoch: str):
         reward = self.node.cfx_getBlockRewardInfo(epoch)
         convert_b32_address_field_to_hex(reward, "author")
@@ -391,6 +424,12 @@ class RpcClient:
         convert_b32_address_field_to_hex(r, "to")
         return r
 
+    def get_transactions_by_address(self, addr: str, skip: int = None, limit: int = None) -> dict:
+        addr = hex_to_b32_address(addr)
+        skip = None if skip is None else hex(skip)
+        limit = None if limit is None else hex(limit)
+        return self.node.cfx_getTransactionsByAddress(addr, skip, limit)
+
     def txpool_status(self) -> (int, int):
         status = self.node.txpool_status()
         return (status["deferred"], status["ready"])
@@ -436,13 +475,33 @@ class RpcClient:
         contract_addr = hex_to_b32_address(contract_addr)
         return self.node.cfx_checkBalanceAgainstTransaction(account_addr, contract_addr, hex(gas_limit), hex(gas_price), hex(storage_limit))
 
-    def call(self, contract_addr:str, data_hex:str, nonce=None, epoch:str=None) -> str:
+    def call(self, contract_addr:str, data_hex:str, nonce=None, epoch:str=None, state_override:dict=None) -> str:
         tx = self.new_tx_for_call(contract_addr, data_hex, nonce=nonce)
-        if epoch is None:
+        if state_override is not None:
+            state_override = {hex_to_b32_address(addr): account for addr, account in state_override.items()}
+            return self.node.cfx_call(tx, epoch, state_override)
+        elif epoch is None:
             return self.node.cfx_call(tx)
         else:
             return self.node.cfx_call(tx, epoch)
 
+    def call_many(self, txs: list, epoch: str = None, include_trace: bool = None) -> list:
+        if include_trace is not None:
+            return self.node.cfx_callMany(txs, epoch, include_trace)
+        elif epoch is None:
+            return self.node.cfx_callMany(txs)
+        else:
+            return self.node.cfx_callMany(txs, epoch)
+
+    def get_proof(self, addr: str, keys: list = [], epoch: str = None):
+        assert_is_hash_string(addr, length=40)
+        addr = hex_to_b32_address(addr)
+
+        if epoch is None:
+            return self.node.cfx_getProof(addr, keys)
+        else:
+            return self.node.cfx_getProof(addr, keys, epoch)
+
     def get_supply_info(self, epoch:str=None):
         if epoch is None:
             return self.node.cfx_getSupplyInfo()
@@ -476,11 +535,68 @@ class RpcClient:
     def get_status(self):
         return self.node.cfx_getStatus()
 
+    def get_transaction_status(self, tx_hash: str) -> dict:
+        return self.node.cfx_getTransactionStatus(tx_hash)
+
+    def get_epoch_summary(self, epoch: str) -> dict:
+        return self.node.cfx_getEpochSummary(epoch)
+
+    def get_epoch_summaries(self, from_epoch: str, to_epoch: str) -> list:
+        return self.node.cfx_getEpochSummaries(from_epoch, to_epoch)
+
+    def discover(self) -> dict:
+        return getattr(self.node, "rpc.discover")()
+
     def get_block_trace(self, block_hash: str):
         return self.node.trace_block(block_hash)
 
     def get_transaction_trace(self, tx_hash: str):
         return self.node.trace_transaction(tx_hash)
 
+    def trace_transaction_struct_logs(self, tx_hash: str, options: dict = None):
+        if options is None:
+            return self.node.debug_traceTransaction(tx_hash)
+        else:
+            return self.node.debug_traceTransaction(tx_hash, options)
+
     def filter_trace(self, filter: dict):
-        return self.node.trace_filter(filter)
\ No newline at end of file
+        return self.node.trace_filter(filter)
+
+    def trace_call(self, tx: dict, trace_types: list, epoch: str = None):
+        if epoch is None:
+            return self.node.trace_call(tx, trace_types)
+        else:
+            return self.node.trace_call(tx, trace_types, epoch)
+
+    def trace_replay_transaction(self, tx_hash: str, trace_types: list):
+        return self.node.trace_replayTransaction(tx_hash, trace_types)
+
+    def eth_chain_id(self) -> int:
+        return int(self.node.eth_chainId(), 0)
+
+    def eth_block_number(self) -> int:
+        return int(self.node.eth_blockNumber(), 0)
+
+    def eth_get_balance(self, addr: str, block: str = "latest") -> int:
+        return int(self.node.eth_getBalance(addr, block), 0)
+
+    def eth_get_transaction_count(self, addr: str, block: str = "latest") -> int:
+        return int(self.node.eth_getTransactionCount(addr, block), 0)
+
+    def eth_send_raw_transaction(self, raw_tx: str) -> str:
+        return self.node.eth_sendRawTransaction(raw_tx)
+
+    def eth_get_block_by_hash(self, block_hash: str, include_txs: bool = False) -> dict:
+        return self.node.eth_getBlockByHash(block_hash, include_txs)
+
+    def eth_get_block_by_number(self, block: str, include_txs: bool = False) -> dict:
+        return self.node.eth_getBlockByNumber(block, include_txs)
+
+    def eth_get_transaction_by_hash(self, tx_hash: str) -> dict:
+        return self.node.eth_getTransactionByHash(tx_hash)
+
+    def eth_get_transaction_receipt(self, tx_hash: str) -> dict:
+        return self.node.eth_getTransactionReceipt(tx_hash)
+
+    def eth_get_logs(self, filter: dict) -> list:
+        return self.node.eth_getLogs(filter)
\ No newline at end of file
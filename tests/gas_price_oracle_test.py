@@ -0,0 +1,43 @@
+#!/usr/bin/env python3
+from conflux.rpc import RpcClient
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import *
+
+MIN_PRICE = 3
+
+class GasPriceOracleTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+        self.conf_parameters["gas_price_oracle"] = "true"
+        self.conf_parameters["gas_price_oracle_percentile"] = "50"
+        self.conf_parameters["gas_price_oracle_epoch_window"] = "100"
+        self.conf_parameters["gas_price_oracle_min_price"] = str(MIN_PRICE)
+
+    def setup_network(self):
+        self.setup_nodes()
+
+    def run_test(self):
+        client = RpcClient(self.nodes[0])
+
+        # no transactions are executed yet
+        assert_equal(client.gas_price(), MIN_PRICE)
+
+        for gas_price in [10, 20]:
+            tx = client.new_tx(gas_price=gas_price)
+            assert_equal(client.send_tx(tx, True), tx.hash_hex())
+        client.generate_blocks_to_state()
+
+        # both transactions use the same gas
+        assert_equal(client.gas_price(), 10)
+
+        # the highest price is used if the pool backlog is large
+        nonce = client.get_nonce(client.GENESIS_ADDR)
+        for i in range(2):
+            tx = client.new_tx(gas_price=15, nonce=nonce + i)
+            assert_equal(client.send_tx(tx), tx.hash_hex())
+        assert_equal(client.gas_price(), 20)
+
+        self.log.info("Pass")
+
+if __name__ == "__main__":
+    GasPriceOracleTest().main()
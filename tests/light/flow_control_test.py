@@ -0,0 +1,121 @@
+#!/usr/bin/env python3
+
+# allow imports from parent directory
+# source: https://stackoverflow.com/a/11158224
+import os, sys, time
+sys.path.insert(1, os.path.join(sys.path[0], '..'))
+
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.mininode import *
+from test_framework.util import *
+
+FULLNODE = 0
+
+# light protocol message ids
+STATUS_PING_V2 = 0x18
+STATUS_PONG_V2 = 0x19
+STATUS_PONG_V3 = 0x20
+GET_BLOCK_HEADERS = 0x07
+BLOCK_HEADERS = 0x08
+THROTTLED = 0xfe
+
+NODE_TYPE_LIGHT = 2
+
+BUFFER_LIMIT = 5_000
+RECHARGE_RATE = 1
+
+# GetBlockHeaders costs 1,000 plus 100 per requested header
+NUM_REQUESTS = 10
+NUM_AFFORDABLE_REQUESTS = BUFFER_LIMIT // 1_100
+
+class StatusPingV2(rlp.Serializable):
+    fields = [
+        ("chain_id", ChainIdParams),
+        ("genesis_hash", hash32),
+        ("node_type", big_endian_int),
+    ]
+
+class LightPeer(P2PInterface):
+    def __init__(self, protocol_version):
+        super().__init__()
+        self.protocol = b'clp'
+        self.protocol_version = protocol_version
+
+    def read_protocol_msg(self, msg):
+        # message ids above 0x7f are encoded in two bytes
+        if msg[-1] & 0x80:
+            return ((msg[-2] << 7) | (msg[-1] & 0x7f), msg[:-2])
+        return (msg[-1], msg[:-1])
+
+    def send_light_msg(self, msg_id, msg):
+        self.send_protocol_packet(rlp.encode(msg) + bytes([msg_id]))
+
+    def send_status(self):
+        status = StatusPingV2(
+            ChainIdParams(self.chain_id),
+            self.genesis.block_header.hash,
+            NODE_TYPE_LIGHT)
+        self.send_light_msg(STATUS_PING_V2, status)
+
+    def on_protocol_packet(self, protocol, payload):
+        with mininode_lock:
+            assert(protocol == self.protocol)
+            packet_type, _ = self.read_protocol_msg(payload)
+            self.protocol_message_count[packet_type] += 1
+
+            if packet_type in [STATUS_PONG_V2, STATUS_PONG_V3]:
+                self.had_status = True
+
+    def count(self, packet_type):
+        with mininode_lock:
+            return self.protocol_message_count[packet_type]
+
+class FlowControlTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+        self.conf_parameters["light_flow_control_buffer_limit"] = str(BUFFER_LIMIT)
+        self.conf_parameters["light_flow_control_recharge_rate"] = str(RECHARGE_RATE)
+
+    def setup_network(self):
+        self.add_nodes(self.num_nodes)
+        self.start_node(FULLNODE, ["--archive"])
+
+    def connect_light_peer(self, protocol_version):
+        peer = self.nodes[FULLNODE].add_p2p_connection(LightPeer(protocol_version))
+        network_thread_start()
+        peer.wait_for_status()
+        return peer
+
+    def request_headers(self, peer):
+        for reqid in range(NUM_REQUESTS):
+            msg = GetBlockHeaders(hashes=[peer.genesis.block_header.hash], reqid=reqid)
+            peer.send_light_msg(GET_BLOCK_HEADERS, msg)
+
+        wait_until(lambda: peer.count(BLOCK_HEADERS) + peer.count(THROTTLED) == NUM_REQUESTS)
+
+    def run_test(self):
+        # ------------------------------------------------
+        self.log.info("Testing that requests exceeding the credits of V3 peers are throttled...")
+        peer = self.connect_light_peer(3)
+        assert_equal(peer.count(STATUS_PONG_V3), 1)
+
+        self.request_headers(peer)
+        assert_equal(peer.count(BLOCK_HEADERS), NUM_AFFORDABLE_REQUESTS)
+        assert_equal(peer.count(THROTTLED), NUM_REQUESTS - NUM_AFFORDABLE_REQUESTS)
+        self.log.info("Pass")
+
+        self.nodes[FULLNODE].disconnect_p2ps()
+        time.sleep(0.5)
+
+        # ------------------------------------------------
+        self.log.info("Testing that V2 peers are not charged...")
+        peer = self.connect_light_peer(2)
+        assert_equal(peer.count(STATUS_PONG_V2), 1)
+
+        self.request_headers(peer)
+        assert_equal(peer.count(BLOCK_HEADERS), NUM_REQUESTS)
+        assert_equal(peer.count(THROTTLED), 0)
+        self.log.info("Pass")
+
+if __name__ == "__main__":
+    FlowControlTest().main()
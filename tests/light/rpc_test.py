@@ -6,9 +6,10 @@ import os, sys, random, time, json
 sys.path.insert(1, os.path.join(sys.path[0], '..'))
 
 from eth_utils import decode_hex
+from conflux.address import hex_to_b32_address
 from conflux.rpc import RpcClient
 from test_framework.test_framework import ConfluxTestFramework
-from test_framework.util import assert_equal, assert_greater_than, assert_is_hex_string, assert_raises_rpc_error, connect_nodes, sync_blocks, get_contract_instance
+from test_framework.util import assert_equal, assert_greater_than, assert_is_hex_string, assert_raises_rpc_error, connect_nodes, sync_blocks, get_contract_instance, wait_until
 from web3 import Web3
 
 FULLNODE0 = 0
@@ -82,6 +83,8 @@ class LightRPCTest(ConfluxTestFramework):
         contract_dict = json.loads(open(os.path.join(file_path), "r").read())
         whitelist_control = get_contract_instance(contract_dict=contract_dict)
         whitelist_control_addr = "0x0888000000000000000000000000000000000001"
+        self.whitelist_control = whitelist_control
+        self.whitelist_control_addr = whitelist_control_addr
 
         tx_conf = {
             "from": Web3.toChecksumAddress(self.rpc[FULLNODE0].GENESIS_ADDR),
@@ -183,6 +186,17 @@ class LightRPCTest(ConfluxTestFramework):
 
         # --------------------------
 
+        self.log.info(f"Checking rpc.discover...")
+
+        methods = [m["name"] for m in self.rpc[LIGHTNODE].discover()["methods"]]
+        assert("cfx_getBalance" in methods)
+        assert("cfx_getProof" not in methods)
+        assert("cfx_newFilter" not in methods)
+
+        self.log.info(f"Pass -- rpc.discover")
+
+        # --------------------------
+
         self.log.info(f"Checking cfx_getConfirmationRiskByHash...")
 
         best = self.nodes[FULLNODE0].cfx_getBestBlockHash()
@@ -403,6 +417,45 @@ class LightRPCTest(ConfluxTestFramework):
 
         self.log.info(f"Pass -- cfx_checkBalanceAgainstTransaction")
 
+        # --------------------------
+
+        self.log.info(f"Checking cfx_call...")
+
+        tx_conf = {"to": Web3.toChecksumAddress(self.whitelist_control_addr), "gas": 3_000_000, "gasPrice": 1, "chainId": 0}
+        data = self.whitelist_control.functions.isWhitelisted(Web3.toChecksumAddress(self.contract), Web3.toChecksumAddress(self.sponsored_address)).buildTransaction(tx_conf)["data"]
+        full = self.rpc[FULLNODE0].call(self.whitelist_control_addr, data, epoch=latest_state)
+        light = self.rpc[LIGHTNODE].call(self.whitelist_control_addr, data, epoch=latest_state)
+        assert_equal(light, full)
+        assert_equal(int(light, 0), 1)
+
+        data = self.whitelist_control.functions.getSponsoredBalanceForGas(Web3.toChecksumAddress(self.contract)).buildTransaction(tx_conf)["data"]
+        full = self.rpc[FULLNODE0].call(self.whitelist_control_addr, data, epoch=latest_state)
+        light = self.rpc[LIGHTNODE].call(self.whitelist_control_addr, data, epoch=latest_state)
+        assert_equal(light, full)
+
+        self.log.info(f"Pass -- cfx_call")
+
+        # --------------------------
+
+        self.log.info(f"Checking cfx_estimateGasAndCollateral...")
+
+        tx = self.rpc[FULLNODE0].new_tx_for_call(self.whitelist_control_addr, data)
+        full = self.nodes[FULLNODE0].cfx_estimateGasAndCollateral(tx, latest_state)
+        light = self.nodes[LIGHTNODE].cfx_estimateGasAndCollateral(tx, latest_state)
+        assert_equal(light, full)
+
+        self.log.info(f"Pass -- cfx_estimateGasAndCollateral")
+
+        # --------------------------
+
+        self.log.info(f"Checking cfx_getSupplyInfo...")
+
+        full = self.rpc[FULLNODE0].get_supply_info(latest_state)
+        light = self.rpc[LIGHTNODE].get_supply_info(latest_state)
+        assert_equal(light, full)
+
+        self.log.info(f"Pass -- cfx_getSupplyInfo")
+
     def assert_blocks_equal(self, light_block, block):
         # light nodes do not retrieve receipts for block queries
         # so fields related to execution results are not filled
@@ -526,6 +579,18 @@ class LightRPCTest(ConfluxTestFramework):
 
         self.log.info(f"Pass -- cfx_getBlockByHashWithPivotAssumption")
 
+        # --------------------------
+
+        self.log.info(f"Checking cfx_getBlockRewardInfo...")
+
+        # NOTE: the rewards of recent epochs are not computed yet
+        full = self.rpc[FULLNODE0].get_block_reward_info(block_2_epoch)
+        light = self.rpc[LIGHTNODE].get_block_reward_info(block_2_epoch)
+        assert_greater_than(len(full), 0)
+        assert_equal(light, full)
+
+        self.log.info(f"Pass -- cfx_getBlockRewardInfo")
+
     def assert_txs_equal(self, light_tx, tx):
         # light nodes do not retrieve receipts for tx queries
         # so fields related to execution results are not filled
@@ -550,16 +615,38 @@ class LightRPCTest(ConfluxTestFramework):
         assert_equal(light, full)
         self.log.info(f"Pass -- cfx_getTransactionReceipt")
 
+        self.log.info(f"Checking cfx_getAccountPendingTransactions...")
+
+        # light nodes only serve the accounts that sent txs through them
+        user = hex_to_b32_address(self.user)
+        assert_raises_rpc_error(None, None, self.nodes[LIGHTNODE].cfx_getAccountPendingTransactions, user)
+
+        # leave a nonce gap so that the tx stays in the pool
+        nonce = self.rpc[FULLNODE0].get_nonce(self.user)
+        tx = self.rpc[FULLNODE0].new_tx(nonce=nonce + 1)
+        assert_equal(self.rpc[LIGHTNODE].send_tx(tx, wait_for_catchup=False), tx.hash_hex())
+
+        for node in [FULLNODE0, FULLNODE1]:
+            wait_until(lambda: self.nodes[node].cfx_getAccountPendingTransactions(user)["pendingCount"] == "0x1")
+
+        full = self.nodes[FULLNODE0].cfx_getAccountPendingTransactions(user)
+        light = self.nodes[LIGHTNODE].cfx_getAccountPendingTransactions(user)
+        assert_equal(light, full)
+        assert_equal(light["firstTxStatus"], {'pending': 'futureNonce'})
+
+        full = self.nodes[FULLNODE0].cfx_getAccountPendingTransactions(user, hex(nonce + 2))
+        light = self.nodes[LIGHTNODE].cfx_getAccountPendingTransactions(user, hex(nonce + 2))
+        assert_equal(light, full)
+        assert_equal(len(light["pendingTransactions"]), 0)
+
+        self.log.info(f"Pass -- cfx_getAccountPendingTransactions")
+
         # note: cfx_getLogs and cfx_sendRawTransaction have separate tests
 
     def test_not_supported(self):
         self.log.info(f"Checking not supported APIs...")
 
-        assert_raises_rpc_error(-32000, None, self.nodes[LIGHTNODE].cfx_call, {}, "latest_checkpoint")
-        assert_raises_rpc_error(-32000, None, self.nodes[LIGHTNODE].cfx_estimateGasAndCollateral, {}, "latest_checkpoint")
-        assert_raises_rpc_error(-32000, None, self.nodes[LIGHTNODE].cfx_getBlockRewardInfo, "latest_checkpoint")
         assert_raises_rpc_error(-32000, None, self.nodes[LIGHTNODE].cfx_getEpochReceipts, "latest_checkpoint")
-        assert_raises_rpc_error(-32000, None, self.nodes[LIGHTNODE].cfx_getSupplyInfo, "latest_checkpoint")
 
         self.log.info(f"Pass -- not supported APIs")
 
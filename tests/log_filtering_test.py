@@ -256,6 +256,24 @@ class LogFilteringTest(ConfluxTestFramework):
 
         assert_equal(collected_logs, all_logs)
 
+        # test cursor paging from the oldest log
+        collected_logs = []
+        cursor = None
+
+        while True:
+            filter = Filter(from_epoch = epoch_1, to_epoch = epoch_2, topics=[BAR_TOPIC], cursor = cursor, page_size = hex(BATCH_SIZE))
+            page = self.rpc.get_logs_page(filter)
+            assert(len(page["logs"]) <= BATCH_SIZE)
+            collected_logs += page["logs"]
+            cursor = page["cursor"]
+            if cursor is None: break
+
+        assert_equal(collected_logs, all_logs)
+
+        # cursor paging cannot be combined with offset and limit
+        filter = Filter(from_epoch = epoch_1, to_epoch = epoch_2, limit = hex(BATCH_SIZE), page_size = hex(BATCH_SIZE))
+        assert_raises_rpc_error(None, None, self.rpc.get_logs_page, filter)
+
         # get-logs-filter-max-epoch-range should limit the number of epochs queried.
         self.stop_node(0)
         self.start_node(0, ["--get-logs-filter-max-epoch-range", "16"])
@@ -0,0 +1,54 @@
+#!/usr/bin/env python3
+import os
+
+from conflux.filter import Filter
+from conflux.rpc import RpcClient
+from conflux.utils import sha3 as keccak
+from test_framework.blocktools import encode_hex_0x
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import *
+
+CONTRACT_PATH = "contracts/EventsTestContract_bytecode.dat"
+CONSTRUCTED_TOPIC = encode_hex_0x(keccak(b"Constructed(address,address)"))
+MAX_EPOCH_RANGE = 20
+
+class LogIndexTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+        self.conf_parameters["persist_log_index"] = "true"
+        self.conf_parameters["get_logs_filter_max_epoch_range"] = str(MAX_EPOCH_RANGE)
+
+    def setup_network(self):
+        self.setup_nodes()
+
+    def run_test(self):
+        self.rpc = RpcClient(self.nodes[0])
+
+        bytecode_file = os.path.join(os.path.dirname(os.path.realpath(__file__)), CONTRACT_PATH)
+        bytecode = open(bytecode_file).read()
+        tx = self.rpc.new_contract_tx(receiver="", data_hex=bytecode, storage_limit=512)
+        assert_equal(self.rpc.send_tx(tx, True), tx.hash_hex())
+        receipt = self.rpc.get_transaction_receipt(tx.hash_hex())
+
+        # make the queried range much longer than the allowed range
+        self.rpc.generate_empty_blocks(5 * MAX_EPOCH_RANGE)
+        self.rpc.generate_blocks_to_state()
+
+        # the indexed epochs that cannot match are skipped and not counted
+        filter = Filter(from_epoch="earliest", to_epoch="latest_state", topics=[CONSTRUCTED_TOPIC])
+        logs = self.rpc.get_logs(filter)
+        assert_equal(len(logs), 1)
+        assert_equal(logs[0]["transactionHash"], tx.hash_hex())
+        assert_equal(logs[0]["epochNumber"], receipt["epochNumber"])
+
+        filter = Filter(from_epoch="earliest", to_epoch="latest_state", topics=[encode_hex_0x(keccak(b"Unknown()"))])
+        assert_equal(self.rpc.get_logs(filter), [])
+
+        # every epoch may match a filter without addresses and topics
+        filter = Filter(from_epoch="earliest", to_epoch="latest_state")
+        assert_raises_rpc_error(None, None, self.rpc.get_logs, filter)
+
+        self.log.info("Pass")
+
+if __name__ == "__main__":
+    LogIndexTest().main()
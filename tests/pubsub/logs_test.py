@@ -20,34 +20,43 @@ from test_framework.util import assert_equal, assert_ne, assert_is_hex_string, c
 
 FULLNODE0 = 0
 FULLNODE1 = 1
+LIGHTNODE = 2
 
 CONTRACT_PATH = "../contracts/EventsTestContract_bytecode.dat"
 FOO_TOPIC = encode_hex_0x(keccak(b"foo()"))
 
 NUM_CALLS = 20
 
+# number of blocks to generate for the light node to verify the logs
+BLAME_CHECK_OFFSET = 30
+
 class PubSubTest(ConfluxTestFramework):
     def set_test_params(self):
-        self.num_nodes = 2
+        self.num_nodes = 3
 
     def setup_network(self):
         self.add_nodes(self.num_nodes)
 
         self.start_node(FULLNODE0, ["--archive"])
         self.start_node(FULLNODE1, ["--archive"])
+        self.start_node(LIGHTNODE, ["--light"], phase_to_wait=None)
 
         # set up RPC clients
         self.rpc = [None] * self.num_nodes
         self.rpc[FULLNODE0] = RpcClient(self.nodes[FULLNODE0])
         self.rpc[FULLNODE1] = RpcClient(self.nodes[FULLNODE1])
+        self.rpc[LIGHTNODE] = RpcClient(self.nodes[LIGHTNODE])
 
         # set up PubSub clients
         self.pubsub = [None] * self.num_nodes
         self.pubsub[FULLNODE0] = PubSubClient(self.nodes[FULLNODE0])
         self.pubsub[FULLNODE1] = PubSubClient(self.nodes[FULLNODE1])
+        self.pubsub[LIGHTNODE] = PubSubClient(self.nodes[LIGHTNODE])
 
         # connect nodes
         connect_nodes(self.nodes, FULLNODE0, FULLNODE1)
+        connect_nodes(self.nodes, LIGHTNODE, FULLNODE0)
+        connect_nodes(self.nodes, LIGHTNODE, FULLNODE1)
 
         # wait for phase changes to complete
         self.nodes[FULLNODE0].wait_for_phase(["NormalSyncPhase"])
@@ -66,7 +75,9 @@ class PubSubTest(ConfluxTestFramework):
 
         # subscribe
         sub_all = await self.pubsub[FULLNODE0].subscribe("logs")
+        sub_empty = await self.pubsub[FULLNODE0].subscribe("logs", {})
         sub_one = await self.pubsub[FULLNODE0].subscribe("logs", Filter(address=[contract2]).__dict__)
+        sub_light = await self.pubsub[LIGHTNODE].subscribe("logs", Filter(address=[contract2]).__dict__)
 
         # call contracts and collect receipts
         receipts = []
@@ -85,12 +96,31 @@ class PubSubTest(ConfluxTestFramework):
         # collect pub-sub notifications
         logs1 = [l async for l in sub_all.iter()]
         logs2 = [l async for l in sub_one.iter()]
+        logs3 = [l async for l in sub_empty.iter()]
 
         assert_equal(len(logs1), 2 * NUM_CALLS)
         assert_equal(len(logs2), NUM_CALLS)
 
+        # an empty filter matches all logs
+        assert_equal(logs3, logs1)
+        await sub_empty.unsubscribe()
+
         self.log.info(f"Pass -- retrieved logs with no fork")
 
+        # the light node publishes logs once their epochs are verified
+        self.rpc[FULLNODE0].generate_blocks(BLAME_CHECK_OFFSET)
+        sync_blocks(self.nodes)
+
+        logs_light = []
+
+        while len(logs_light) < NUM_CALLS:
+            logs_light.append(await sub_light.next(timeout=10))
+
+        assert_equal(logs_light, logs2)
+        await sub_light.unsubscribe()
+
+        self.log.info(f"Pass -- retrieved verified logs on light node")
+
         # create alternative fork
         old_tip = self.rpc[FULLNODE0].best_block_hash()
         old_tip_epoch = self.rpc[FULLNODE0].epoch_number()
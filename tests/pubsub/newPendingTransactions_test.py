@@ -0,0 +1,82 @@
+#!/usr/bin/env python3
+
+# allow imports from parent directory
+# source: https://stackoverflow.com/a/11158224
+import os, sys
+sys.path.insert(1, os.path.join(sys.path[0], '..'))
+
+import asyncio
+
+from conflux.address import hex_to_b32_address
+from conflux.rpc import RpcClient
+from conflux.pubsub import PubSubClient
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import assert_equal
+
+FULLNODE = 0
+
+NUM_TXS = 10
+
+class PubSubTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+
+    def setup_network(self):
+        self.add_nodes(self.num_nodes)
+        self.start_node(FULLNODE, ["--archive"])
+
+        # set up RPC and PubSub clients
+        self.rpc = RpcClient(self.nodes[FULLNODE])
+        self.pubsub = PubSubClient(self.nodes[FULLNODE])
+
+        # wait for phase changes to complete
+        self.nodes[FULLNODE].wait_for_phase(["NormalSyncPhase"])
+
+    async def run_async(self):
+        receiver1 = self.rpc.rand_addr()
+        receiver2 = self.rpc.rand_addr()
+
+        # subscribe
+        sub_hashes = await self.pubsub.subscribe("newPendingTransactions")
+        sub_full = await self.pubsub.subscribe("newPendingTransactions", {
+            "fullTransactions": True,
+            "to": hex_to_b32_address(receiver2),
+        })
+
+        # -------- 1. receive transaction hashes --------
+        nonce = self.rpc.get_nonce(self.rpc.GENESIS_ADDR)
+        hashes = []
+        hashes_to_receiver2 = []
+
+        for ii in range(NUM_TXS):
+            receiver = receiver1 if ii % 2 == 0 else receiver2
+            tx = self.rpc.new_tx(receiver=receiver, nonce=nonce + ii)
+            hashes.append(self.rpc.send_tx(tx))
+
+            if receiver == receiver2:
+                hashes_to_receiver2.append(tx.hash_hex())
+
+        # NOTE: notifications might be received out-of-order
+        received = [h async for h in sub_hashes.iter()]
+        assert_equal(sorted(received), sorted(hashes))
+
+        self.log.info("Pass -- 1")
+
+        # -------- 2. receive filtered full transactions --------
+        received = [tx async for tx in sub_full.iter()]
+        assert_equal(sorted(tx["hash"] for tx in received), sorted(hashes_to_receiver2))
+
+        for tx in received:
+            assert_equal(tx["to"], hex_to_b32_address(receiver2))
+            assert_equal(tx["blockHash"], None)
+
+        self.log.info("Pass -- 2")
+
+        await sub_hashes.unsubscribe()
+        await sub_full.unsubscribe()
+
+    def run_test(self):
+        asyncio.get_event_loop().run_until_complete(self.run_async())
+
+if __name__ == "__main__":
+    PubSubTest().main()
@@ -0,0 +1,78 @@
+#!/usr/bin/env python3
+
+# allow imports from parent directory
+# source: https://stackoverflow.com/a/11158224
+import os, sys
+sys.path.insert(1, os.path.join(sys.path[0], '..'))
+
+import asyncio
+
+from conflux.rpc import RpcClient
+from conflux.pubsub import PubSubClient
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import assert_equal, assert_greater_than_or_equal, connect_nodes
+
+FULLNODE0 = 0
+FULLNODE1 = 1
+
+NUM_BLOCKS = 100
+
+class PubSubTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 2
+
+    def setup_network(self):
+        self.add_nodes(self.num_nodes)
+        self.start_node(FULLNODE0, ["--archive"])
+
+        # set up RPC clients
+        self.rpc = [None] * self.num_nodes
+        self.rpc[FULLNODE0] = RpcClient(self.nodes[FULLNODE0])
+        self.rpc[FULLNODE1] = RpcClient(self.nodes[FULLNODE1])
+
+        # set up PubSub clients
+        self.pubsub = [None] * self.num_nodes
+        self.pubsub[FULLNODE1] = PubSubClient(self.nodes[FULLNODE1])
+
+    async def run_async(self):
+        self.rpc[FULLNODE0].generate_blocks(NUM_BLOCKS)
+        best_epoch = self.rpc[FULLNODE0].epoch_number()
+
+        # start the second node without peers, it cannot leave the catch-up
+        # phases until it is connected
+        self.start_node(FULLNODE1, ["--archive"], phase_to_wait=None)
+        sub = await self.pubsub[FULLNODE1].subscribe("syncing")
+
+        # the current progress is sent when the subscription starts
+        first = await sub.next(timeout=5)
+        assert first["phase"] != "NormalSyncPhase"
+
+        connect_nodes(self.nodes, FULLNODE1, FULLNODE0)
+
+        # collect progress until the node is caught up
+        events = [first]
+        while True:
+            event = await sub.next(timeout=30)
+            events.append(event)
+            if event["phase"] == "NormalSyncPhase":
+                break
+
+        self.log.info(f"Phases: {[e['phase'] for e in events]}")
+
+        # check the phase reported in the last event
+        assert_equal(self.rpc[FULLNODE1].current_sync_phase(), "NormalSyncPhase")
+
+        last = events[-1]
+        assert_greater_than_or_equal(int(last["bestPeerEpoch"], 16), best_epoch)
+        assert_greater_than_or_equal(int(last["localBestEpoch"], 16), 0)
+        assert("totalSnapshotChunks" not in last)
+
+        self.log.info("Pass")
+
+        await sub.unsubscribe()
+
+    def run_test(self):
+        asyncio.get_event_loop().run_until_complete(self.run_async())
+
+if __name__ == "__main__":
+    PubSubTest().main()
@@ -0,0 +1,93 @@
+#!/usr/bin/env python3
+
+# allow imports from parent directory
+# source: https://stackoverflow.com/a/11158224
+import os, sys
+sys.path.insert(1, os.path.join(sys.path[0], '..'))
+
+import asyncio
+
+from conflux.pubsub import PubSubClient
+from conflux.rpc import RpcClient
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import assert_equal
+
+FULLNODE = 0
+
+NUM_TXS = 10
+
+class PubSubTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+        self.conf_parameters["executive_trace"] = "true"
+
+    def setup_network(self):
+        self.add_nodes(self.num_nodes)
+        self.start_node(FULLNODE, ["--archive"])
+
+        # set up RPC and PubSub clients
+        self.rpc = RpcClient(self.nodes[FULLNODE])
+        self.pubsub = PubSubClient(self.nodes[FULLNODE])
+
+        # wait for phase changes to complete
+        self.nodes[FULLNODE].wait_for_phase(["NormalSyncPhase"])
+
+    async def run_async(self):
+        # subscribe
+        sub_all = await self.pubsub.subscribe("traces")
+        sub_calls = await self.pubsub.subscribe("traces", {"actionTypes": "call"})
+
+        # -------- 1. receive traces --------
+        receipts = []
+
+        for _ in range(NUM_TXS):
+            tx = self.rpc.new_tx(receiver=self.rpc.rand_addr())
+            assert_equal(self.rpc.send_tx(tx, wait_for_receipt=True), tx.hash_hex())
+            receipts.append(self.rpc.get_transaction_receipt(tx.hash_hex()))
+
+        # traces are published in pivot order
+        traces = [t async for t in sub_all.iter()]
+        hashes = [t["transactionHash"] for t in traces if t["type"] == "call"]
+        assert_equal(hashes, [r["transactionHash"] for r in receipts])
+
+        calls = [t async for t in sub_calls.iter()]
+        assert_equal(len(calls), NUM_TXS)
+
+        for call in calls:
+            assert_equal(call["type"], "call")
+
+        self.log.info("Pass -- 1")
+
+        # -------- 2. receive revert notice on pivot chain reorg --------
+        old_tip = self.rpc.best_block_hash()
+        fork_hash = receipts[NUM_TXS // 2]["blockHash"]
+        fork_epoch = int(receipts[NUM_TXS // 2]["epochNumber"], 16)
+        old_tip_epoch = self.rpc.epoch_number()
+
+        self.log.info(f"Creating fork at {fork_hash[:20]}... (#{fork_epoch})")
+
+        new_tip = fork_hash
+        for _ in range(2 * (old_tip_epoch - fork_epoch)):
+            new_tip = self.rpc.generate_block_with_parent(new_tip)
+        new_tip = self.rpc.generate_block_with_parent(new_tip, referee=[old_tip])
+        for _ in range(20):
+            new_tip = self.rpc.generate_block_with_parent(new_tip)
+
+        msg = await sub_calls.next(timeout=5)
+        assert_equal(int(msg["revertTo"], 16), fork_epoch)
+
+        # re-executed transactions are published again
+        num_to_reexecute = sum(1 for r in receipts if int(r["epochNumber"], 16) > fork_epoch)
+        calls = [t async for t in sub_calls.iter()]
+        assert_equal(len(calls), num_to_reexecute)
+
+        self.log.info("Pass -- 2")
+
+        await sub_all.unsubscribe()
+        await sub_calls.unsubscribe()
+
+    def run_test(self):
+        asyncio.get_event_loop().run_until_complete(self.run_async())
+
+if __name__ == "__main__":
+    PubSubTest().main()
@@ -0,0 +1,76 @@
+import sys
+
+sys.path.append("..")
+
+from conflux.address import hex_to_b32_address
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, assert_greater_than, assert_raises_rpc_error
+
+# Simple storage contract, `get()` returns the storage slot 0 (initially 5).
+SIMPLE_STORAGE_CODE = "0x608060405234801561001057600080fd5b50600560008190555060e6806100276000396000f3fe6080604052600436106043576000357c01000000000000000000000000000000000000000000000000000000009004806360fe47b11460485780636d4ce63c14607f575b600080fd5b348015605357600080fd5b50607d60048036036020811015606857600080fd5b810190808035906020019092919050505060a7565b005b348015608a57600080fd5b50609160b1565b6040518082815260200191505060405180910390f35b8060008190555050565b6000805490509056fea165627a7a72305820b5180d95fdc3813028ed47f62c7cdf708b76c0db094043f533b42a430d313e150029"
+GET_SELECTOR = "0x6d4ce63c"
+SET_SELECTOR = "0x60fe47b1"
+
+def encode_set(value: int) -> str:
+    return SET_SELECTOR + value.to_bytes(32, "big").hex()
+
+class TestCallMany(RpcClient):
+    def deploy_simple_storage(self) -> str:
+        tx = self.new_contract_tx("", SIMPLE_STORAGE_CODE, storage_limit=200000)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+        return self.get_tx(tx.hash_hex())["contractCreated"]
+
+    def test_sequential_calls(self):
+        contract_addr = self.deploy_simple_storage()
+        txs = [
+            self.new_tx_for_call(contract_addr, encode_set(7)),
+            self.new_tx_for_call(contract_addr, GET_SELECTOR),
+        ]
+        results = self.call_many(txs)
+        assert_equal(len(results), 2)
+        for result in results:
+            assert "error" not in result
+            assert "trace" not in result
+            assert_greater_than(int(result["gasUsed"], 0), 0)
+        # The second call sees the storage written by the first one.
+        assert_equal(int(results[1]["output"], 0), 7)
+
+        # Nothing is committed.
+        assert_equal(int(self.call(contract_addr, GET_SELECTOR), 0), 5)
+
+    def test_include_trace(self):
+        contract_addr = self.deploy_simple_storage()
+        txs = [self.new_tx_for_call(contract_addr, GET_SELECTOR)]
+        results = self.call_many(txs, "latest_state", True)
+        assert_equal(int(results[0]["output"], 0), 5)
+        assert_greater_than(len(results[0]["trace"]), 0)
+        assert_equal(results[0]["trace"][0]["type"], "call")
+
+    def test_failed_call(self):
+        contract_addr = self.deploy_simple_storage()
+        # Unknown selector, the contract reverts.
+        txs = [
+            self.new_tx_for_call(contract_addr, "0x12345678"),
+            self.new_tx_for_call(contract_addr, GET_SELECTOR),
+        ]
+        results = self.call_many(txs)
+        assert results[0]["error"].startswith("Vm reverted")
+        assert_equal(int(results[1]["output"], 0), 5)
+
+    def test_consecutive_creates(self):
+        # Without a nonce, the second create uses the nonce left by the first
+        # one, so the two contracts do not collide at the same address.
+        create = {
+            "from": hex_to_b32_address(self.GENESIS_ADDR),
+            "data": SIMPLE_STORAGE_CODE,
+            "storageLimit": hex(200000),
+        }
+        results = self.call_many([create, create])
+        assert_equal(len(results), 2)
+        for result in results:
+            assert "error" not in result
+
+    def test_too_many_calls(self):
+        contract_addr = self.deploy_simple_storage()
+        txs = [self.new_tx_for_call(contract_addr, GET_SELECTOR)] * 101
+        assert_raises_rpc_error(-32602, None, self.call_many, txs)
@@ -0,0 +1,47 @@
+import sys
+
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, assert_raises_rpc_error
+
+# PUSH1 0x01 PUSH1 0x00 SSTORE
+STORE_ONE_INIT_CODE = "0x6001600055"
+
+class TestDebugTrace(RpcClient):
+    def test_simple_transfer(self):
+        tx = self.new_tx()
+        tx_hash = self.send_tx(tx, True)
+        result = self.trace_transaction_struct_logs(tx_hash)
+        assert_equal(result["failed"], False)
+        assert_equal(result["gas"], hex(self.DEFAULT_TX_GAS))
+        assert_equal(result["returnValue"], "0x")
+        assert_equal(result["structLogs"], [])
+
+    def test_contract_create(self):
+        tx = self.new_contract_tx("", STORE_ONE_INIT_CODE, storage_limit=200000)
+        tx_hash = self.send_tx(tx, True)
+        result = self.trace_transaction_struct_logs(tx_hash)
+        assert_equal(result["failed"], False)
+
+        logs = result["structLogs"]
+        assert_equal([log["op"] for log in logs], ["PUSH1", "PUSH1", "SSTORE"])
+        assert_equal([log["pc"] for log in logs], [0, 2, 4])
+        assert_equal([log["depth"] for log in logs], [1, 1, 1])
+        assert_equal(logs[0]["stack"], [])
+        assert_equal(logs[2]["stack"], ["0x1", "0x0"])
+        assert_equal(logs[2]["storage"], {"0x" + "00" * 32: "0x" + "00" * 31 + "01"})
+        assert "storage" not in logs[0]
+
+        result = self.trace_transaction_struct_logs(tx_hash, {
+            "disableStack": True,
+            "disableMemory": True,
+            "disableStorage": True,
+        })
+        for log in result["structLogs"]:
+            assert "stack" not in log
+            assert "memory" not in log
+            assert "storage" not in log
+
+    def test_unknown_transaction(self):
+        assert_raises_rpc_error(None, None, self.trace_transaction_struct_logs, "0x" + "ab" * 32)
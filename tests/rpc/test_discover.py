@@ -0,0 +1,35 @@
+import sys
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal
+
+class TestDiscover(RpcClient):
+    def test_discover(self):
+        doc = self.discover()
+        assert_equal(doc["openrpc"], "1.2.6")
+        assert("cfx" in doc["info"]["x-enabled-apis"])
+
+        methods = {m["name"]: m for m in doc["methods"]}
+        assert("rpc.discover" not in methods)
+        balance = methods["cfx_getBalance"]
+        assert_equal([p["name"] for p in balance["params"]], ["addr", "epochNumber"])
+        assert_equal(balance["params"][0]["required"], True)
+        assert_equal(balance["params"][1]["required"], False)
+
+        # every referenced schema is defined in the components
+        schemas = doc["components"]["schemas"]
+        for ref in self._refs(doc):
+            assert(ref.startswith("#/components/schemas/"))
+            assert(ref[len("#/components/schemas/"):] in schemas)
+
+    def _refs(self, value):
+        if isinstance(value, dict):
+            for k, v in value.items():
+                if k == "$ref":
+                    yield v
+                else:
+                    yield from self._refs(v)
+        elif isinstance(value, list):
+            for v in value:
+                yield from self._refs(v)
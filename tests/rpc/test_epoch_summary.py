@@ -0,0 +1,33 @@
+import sys
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, assert_greater_than_or_equal, assert_raises_rpc_error
+
+class TestEpochSummary(RpcClient):
+    def test_executed_epoch(self):
+        tx = self.new_tx()
+        tx_hash = self.send_tx(tx, True)
+        receipt = self.get_transaction_receipt(tx_hash)
+        epoch = receipt["epochNumber"]
+
+        summary = self.get_epoch_summary(epoch)
+        assert_equal(summary["epochNumber"], epoch)
+        assert_equal(summary["pivotHash"], self.block_by_epoch(epoch)["hash"])
+        assert_greater_than_or_equal(int(summary["blockCount"], 0), 1)
+        assert_greater_than_or_equal(int(summary["transactionCount"], 0), 1)
+        assert_greater_than_or_equal(int(summary["gasUsed"], 0), int(receipt["gasUsed"], 0))
+        assert_greater_than_or_equal(int(summary["totalFee"], 0), int(receipt["gasFee"], 0))
+
+    def test_range(self):
+        self.generate_blocks_to_state(num_txs=1)
+        to_epoch = self.epoch_number(self.EPOCH_LATEST_STATE)
+        summaries = self.get_epoch_summaries(self.EPOCH_NUM(to_epoch - 2), self.EPOCH_NUM(to_epoch))
+        assert_equal(len(summaries), 3)
+        for (i, summary) in enumerate(summaries):
+            assert_equal(int(summary["epochNumber"], 0), to_epoch - 2 + i)
+
+    def test_invalid_range(self):
+        epoch = self.epoch_number(self.EPOCH_LATEST_STATE)
+        assert_raises_rpc_error(None, None, self.get_epoch_summaries, self.EPOCH_NUM(epoch), self.EPOCH_NUM(epoch - 1))
+        assert_raises_rpc_error(None, None, self.get_epoch_summary, self.EPOCH_NUM(epoch + 1000))
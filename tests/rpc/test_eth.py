@@ -0,0 +1,116 @@
+import os
+import sys
+
+sys.path.append("..")
+
+from eth_account import Account
+from web3 import Web3
+
+from conflux.config import DEFAULT_PY_TEST_CHAIN_ID
+from conflux.rpc import RpcClient
+from conflux.utils import priv_to_addr, sha3 as keccak
+from test_framework.blocktools import encode_hex_0x
+from test_framework.util import assert_equal, assert_raises_rpc_error, wait_until
+
+CONTRACT_PATH = "../contracts/EventsTestContract_bytecode.dat"
+CONSTRUCTED_TOPIC = encode_hex_0x(keccak(b"Constructed(address,address)"))
+
+# The address of this key starts with 0x1, as required for Ethereum like
+# transactions.
+ETH_KEY = "0x6195034b293444a42eb382550ab5649f3a094fb3e57c32d8597bbfc63f85abc8"
+
+class TestEth(RpcClient):
+    def test_chain_id(self):
+        assert_equal(self.eth_chain_id(), DEFAULT_PY_TEST_CHAIN_ID)
+
+    def test_block_number(self):
+        self.generate_blocks_to_state()
+        assert_equal(self.eth_block_number(), self.epoch_number(self.EPOCH_LATEST_STATE))
+
+    def test_get_balance(self):
+        assert_equal(self.eth_get_balance(self.GENESIS_ADDR), self.get_balance(self.GENESIS_ADDR))
+
+    def test_get_block(self):
+        epoch = self.epoch_number(self.EPOCH_LATEST_STATE)
+        pivot = self.block_by_epoch(hex(epoch))
+
+        # the block with number `n` is the pivot block of epoch `n`
+        block = self.eth_get_block_by_number(hex(epoch))
+        assert_equal(block["hash"], pivot["hash"])
+        assert_equal(block["parentHash"], pivot["parentHash"])
+        assert_equal(int(block["number"], 0), epoch)
+        assert_equal(block["uncles"], [])
+
+        assert_equal(self.eth_get_block_by_hash(pivot["hash"]), block)
+        assert_equal(self.eth_get_block_by_number(hex(epoch + 1000)), None)
+
+    def test_send_raw_transaction(self):
+        eth_addr = encode_hex_0x(priv_to_addr(ETH_KEY))
+        tx = self.new_tx(receiver=eth_addr, value=10 ** 18)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+
+        unsigned = {
+            "nonce": self.eth_get_transaction_count(eth_addr),
+            "gasPrice": 1,
+            "gas": 21000,
+            "to": Web3.toChecksumAddress(self.COINBASE_ADDR),
+            "value": 100,
+            "data": b"",
+        }
+
+        # only EIP-155 transactions are accepted
+        raw_tx = Account.sign_transaction(unsigned, ETH_KEY).rawTransaction.hex()
+        assert_raises_rpc_error(None, None, self.eth_send_raw_transaction, raw_tx)
+
+        unsigned["chainId"] = DEFAULT_PY_TEST_CHAIN_ID
+        raw_tx = Account.sign_transaction(unsigned, ETH_KEY).rawTransaction.hex()
+        tx_hash = self.eth_send_raw_transaction(raw_tx)
+
+        # transactions are identified by their Ethereum hash
+        assert_equal(tx_hash, encode_hex_0x(keccak(bytes.fromhex(raw_tx[2:]))))
+        assert_equal(self.eth_get_transaction_by_hash(tx_hash)["hash"], tx_hash)
+
+        self.generate_blocks_to_state()
+        def check_receipt():
+            self.generate_block()
+            return self.eth_get_transaction_receipt(tx_hash) is not None
+        wait_until(check_receipt, timeout=10)
+
+        receipt = self.eth_get_transaction_receipt(tx_hash)
+        assert_equal(receipt["transactionHash"], tx_hash)
+        assert_equal(receipt["status"], "0x1")
+        assert_equal(receipt["from"], eth_addr)
+        assert_equal(receipt["to"], self.COINBASE_ADDR)
+        assert_equal(int(receipt["gasUsed"], 0), 21000)
+
+        tx = self.eth_get_transaction_by_hash(tx_hash)
+        assert_equal(tx["hash"], tx_hash)
+        assert_equal(tx["blockHash"], receipt["blockHash"])
+        assert_equal(tx["transactionIndex"], receipt["transactionIndex"])
+        assert_equal(int(tx["chainId"], 0), DEFAULT_PY_TEST_CHAIN_ID)
+
+        block = self.eth_get_block_by_hash(receipt["blockHash"])
+        assert(tx_hash in block["transactions"])
+
+    def test_get_logs(self):
+        bytecode_file = os.path.join(os.path.dirname(os.path.realpath(__file__)), CONTRACT_PATH)
+        bytecode = open(bytecode_file).read()
+        tx = self.new_contract_tx(receiver="", data_hex=bytecode, storage_limit=512)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+
+        receipt = self.get_transaction_receipt(tx.hash_hex())
+        epoch = receipt["epochNumber"]
+
+        logs = self.eth_get_logs({"fromBlock": epoch, "toBlock": epoch, "topics": [CONSTRUCTED_TOPIC]})
+        assert_equal(len(logs), 1)
+        assert_equal(logs[0]["transactionHash"], tx.hash_hex())
+        assert_equal(logs[0]["address"], receipt["contractCreated"])
+        assert_equal(logs[0]["blockHash"], self.block_by_epoch(epoch)["hash"])
+
+        # `blockHash` selects the whole epoch of the pivot block
+        logs_by_hash = self.eth_get_logs({"blockHash": logs[0]["blockHash"], "topics": [CONSTRUCTED_TOPIC]})
+        assert_equal(logs_by_hash, logs)
+
+        eth_receipt = self.eth_get_transaction_receipt(tx.hash_hex())
+        assert_equal(eth_receipt["contractAddress"], receipt["contractCreated"])
+        assert_equal(eth_receipt["logs"], logs)
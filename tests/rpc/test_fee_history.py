@@ -0,0 +1,31 @@
+import sys
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, assert_greater_than, assert_raises_rpc_error
+
+class TestFeeHistory(RpcClient):
+    def test_fee_history(self):
+        tx = self.new_tx(gas_price=7)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+        receipt = self.get_transaction_receipt(tx.hash_hex())
+        epoch = int(receipt["epochNumber"], 0)
+
+        history = self.fee_history(2, hex(epoch), [0, 50, 100])
+        assert_equal(int(history["oldestEpoch"], 0), epoch - 1)
+        assert_equal(len(history["gasUsedRatio"]), 2)
+        assert_greater_than(history["gasUsedRatio"][1], 0)
+        assert_equal(len(history["reward"]), 2)
+        prices = [int(p, 0) for p in history["reward"][1]]
+        assert_equal(prices, sorted(prices))
+        assert(7 in prices)
+
+        # the rewards are not returned without percentiles
+        history = self.fee_history(1, hex(epoch))
+        assert("reward" not in history)
+
+    def test_invalid_params(self):
+        assert_raises_rpc_error(None, None, self.fee_history, 0)
+        assert_raises_rpc_error(None, None, self.fee_history, 1, "latest_state", [50, 10])
+        assert_raises_rpc_error(None, None, self.fee_history, 1, "latest_state", [101])
+        assert_raises_rpc_error(None, None, self.fee_history, 1, "latest_mined")
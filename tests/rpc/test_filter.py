@@ -0,0 +1,107 @@
+import os
+import sys
+
+sys.path.append("..")
+
+from conflux.filter import Filter
+from conflux.rpc import RpcClient
+from conflux.utils import sha3 as keccak
+from test_framework.blocktools import encode_hex_0x
+from test_framework.util import assert_equal, assert_raises_rpc_error
+
+CONTRACT_PATH = "../contracts/EventsTestContract_bytecode.dat"
+CONSTRUCTED_TOPIC = encode_hex_0x(keccak(b"Constructed(address,address)"))
+
+class TestFilter(RpcClient):
+    def test_block_filter(self):
+        filter_id = self.new_block_filter()
+        assert_equal(self.get_filter_changes(filter_id), [])
+
+        blocks = self.generate_blocks(3)
+        assert_equal(self.get_filter_changes(filter_id), blocks)
+
+        # changes are only reported once
+        assert_equal(self.get_filter_changes(filter_id), [])
+        assert_equal(self.uninstall_filter(filter_id), True)
+
+    def test_block_filter_reorg(self):
+        fork_point = self.best_block_hash()
+        fork_epoch = self.epoch_number()
+        filter_id = self.new_block_filter()
+
+        block = self.generate_block_with_parent(fork_point)
+        assert_equal(self.get_filter_changes(filter_id), [block])
+
+        # a longer branch from the fork point becomes the pivot chain
+        fork = [self.generate_block_with_parent(fork_point)]
+        fork.append(self.generate_block_with_parent(fork[-1]))
+        fork.append(self.generate_block_with_parent(fork[-1]))
+        assert_equal(self.best_block_hash(), fork[-1])
+
+        changes = self.get_filter_changes(filter_id)
+        assert_equal(changes, [{"revertTo": hex(fork_epoch)}] + fork)
+        assert_equal(self.uninstall_filter(filter_id), True)
+
+    def test_pending_transaction_filter(self):
+        filter_id = self.new_pending_transaction_filter()
+
+        tx = self.new_tx()
+        tx_hash = self.send_tx(tx)
+        assert_equal(self.get_filter_changes(filter_id), [tx_hash])
+        assert_equal(self.get_filter_changes(filter_id), [])
+
+        # pending transaction filters have no logs
+        assert_raises_rpc_error(None, None, self.get_filter_logs, filter_id)
+        assert_equal(self.uninstall_filter(filter_id), True)
+
+    def test_log_filter(self):
+        filter_id = self.new_filter(Filter(topics=[CONSTRUCTED_TOPIC]))
+        assert_equal(self.get_filter_changes(filter_id), [])
+
+        bytecode_file = os.path.join(os.path.dirname(os.path.realpath(__file__)), CONTRACT_PATH)
+        bytecode = open(bytecode_file).read()
+        tx = self.new_contract_tx(receiver="", data_hex=bytecode, storage_limit=512)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+        self.generate_blocks_to_state()
+
+        changes = self.get_filter_changes(filter_id)
+        assert_equal(len(changes), 1)
+        assert_equal(changes[0]["transactionHash"], tx.hash_hex())
+        assert_equal(changes[0]["topics"][0], CONSTRUCTED_TOPIC)
+        assert_equal(self.get_filter_changes(filter_id), [])
+
+        # `cfx_getFilterLogs` returns all matching logs in the filter range
+        logs = self.get_filter_logs(filter_id)
+        assert_equal(logs[-1]["transactionHash"], tx.hash_hex())
+
+        assert_equal(self.uninstall_filter(filter_id), True)
+
+    def test_log_filter_epoch_range(self):
+        # the filter range ends before the epochs executed from now on
+        to_epoch = self.epoch_number("latest_state")
+        filter_id = self.new_filter(Filter(to_epoch=hex(to_epoch), topics=[CONSTRUCTED_TOPIC]))
+
+        bytecode_file = os.path.join(os.path.dirname(os.path.realpath(__file__)), CONTRACT_PATH)
+        bytecode = open(bytecode_file).read()
+        tx = self.new_contract_tx(receiver="", data_hex=bytecode, storage_limit=512)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+        self.generate_blocks_to_state()
+
+        assert_equal(self.get_filter_changes(filter_id), [])
+        assert_equal(self.uninstall_filter(filter_id), True)
+
+    def test_uninstall_filter(self):
+        filter_id = self.new_block_filter()
+        assert_equal(self.uninstall_filter(filter_id), True)
+        assert_equal(self.uninstall_filter(filter_id), False)
+
+        # uninstalled filters cannot be polled
+        assert_raises_rpc_error(None, None, self.get_filter_changes, filter_id)
+
+    def test_filter_by_block_hashes(self):
+        filter = Filter(block_hashes=[self.best_block_hash()])
+        assert_raises_rpc_error(None, None, self.new_filter, filter)
+
+    def test_filter_with_pagination(self):
+        filter = Filter(page_size="0x10")
+        assert_raises_rpc_error(None, None, self.new_filter, filter)
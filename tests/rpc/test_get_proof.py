@@ -0,0 +1,34 @@
+import sys
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, assert_raises_rpc_error
+
+
+class TestGetProof(RpcClient):
+    def test_genesis_account(self):
+        self.generate_blocks_to_state()
+        proof = self.get_proof(self.GENESIS_ADDR)
+
+        assert proof["account"] is not None
+        assert proof["accountProof"]["snapshotProof"] is not None or \
+            proof["accountProof"]["deltaProof"] is not None
+        assert_equal(proof["storageProof"], [])
+
+    def test_address_not_exists(self):
+        addr = self.rand_addr()
+        proof = self.get_proof(addr)
+        assert_equal(proof["account"], None)
+
+    def test_storage_keys(self):
+        keys = [self.ZERO_HASH, "0x" + "01" * 32]
+        proof = self.get_proof(self.GENESIS_ADDR, keys)
+
+        assert_equal(len(proof["storageProof"]), 2)
+        for (key, entry) in zip(keys, proof["storageProof"]):
+            assert_equal(entry["key"], key)
+            assert_equal(entry["value"], None)
+
+    def test_epoch_not_executed(self):
+        epoch = self.EPOCH_NUM(self.epoch_number() + 100)
+        assert_raises_rpc_error(None, None, self.get_proof, self.GENESIS_ADDR, [], epoch)
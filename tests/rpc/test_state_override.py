@@ -0,0 +1,51 @@
+import sys
+
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, assert_raises_rpc_error
+
+# Simple storage contract, `get()` returns the storage slot 0 (initially 5).
+SIMPLE_STORAGE_CODE = "0x608060405234801561001057600080fd5b50600560008190555060e6806100276000396000f3fe6080604052600436106043576000357c01000000000000000000000000000000000000000000000000000000009004806360fe47b11460485780636d4ce63c14607f575b600080fd5b348015605357600080fd5b50607d60048036036020811015606857600080fd5b810190808035906020019092919050505060a7565b005b348015608a57600080fd5b50609160b1565b6040518082815260200191505060405180910390f35b8060008190555050565b6000805490509056fea165627a7a72305820b5180d95fdc3813028ed47f62c7cdf708b76c0db094043f533b42a430d313e150029"
+GET_SELECTOR = "0x6d4ce63c"
+# PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+RETURN_42_CODE = "0x602a60005260206000f3"
+
+SLOT_0 = "0x" + "00" * 32
+
+def to_word(value: int) -> str:
+    return "0x" + value.to_bytes(32, "big").hex()
+
+class TestStateOverride(RpcClient):
+    def deploy_simple_storage(self) -> str:
+        tx = self.new_contract_tx("", SIMPLE_STORAGE_CODE, storage_limit=200000)
+        assert_equal(self.send_tx(tx, True), tx.hash_hex())
+        return self.get_tx(tx.hash_hex())["contractCreated"]
+
+    def test_state_diff(self):
+        contract_addr = self.deploy_simple_storage()
+        assert_equal(int(self.call(contract_addr, GET_SELECTOR), 0), 5)
+
+        state_override = {contract_addr: {"stateDiff": {SLOT_0: to_word(7)}}}
+        result = self.call(contract_addr, GET_SELECTOR, state_override=state_override)
+        assert_equal(int(result, 0), 7)
+
+        # The override is never committed.
+        assert_equal(int(self.call(contract_addr, GET_SELECTOR), 0), 5)
+
+    def test_full_state_replacement(self):
+        contract_addr = self.deploy_simple_storage()
+        state_override = {contract_addr: {"state": {}}}
+        result = self.call(contract_addr, GET_SELECTOR, state_override=state_override)
+        assert_equal(int(result, 0), 0)
+
+    def test_code_override(self):
+        contract_addr = "0x8" + "0" * 38 + "1"
+        state_override = {contract_addr: {"code": RETURN_42_CODE}}
+        result = self.call(contract_addr, "0x", state_override=state_override)
+        assert_equal(int(result, 0), 42)
+
+    def test_state_and_state_diff_conflict(self):
+        contract_addr = self.deploy_simple_storage()
+        state_override = {contract_addr: {"state": {}, "stateDiff": {}}}
+        assert_raises_rpc_error(None, None, self.call, contract_addr, GET_SELECTOR, None, None, state_override)
@@ -4,6 +4,7 @@ from test_framework.blocktools import create_transaction
 
 sys.path.append("..")
 
+from conflux.address import hex_to_b32_address
 from conflux.rpc import RpcClient
 from test_framework.util import assert_equal, assert_raises_rpc_error
 
@@ -142,4 +143,87 @@ class TestTrace(RpcClient):
             "transactionPosition": "0x0",
             "type": "call_result"
         }]
-        assert_equal(trace, expected_trace)
\ No newline at end of file
+        assert_equal(trace, expected_trace)
+
+    def test_filter_trace_pagination(self):
+        receipts = []
+        for _ in range(2):
+            tx = self.new_tx()
+            assert_equal(self.send_tx(tx, True), tx.hash_hex())
+            receipts.append(self.get_transaction_receipt(tx.hash_hex()))
+        epoch_range = {"fromEpoch": receipts[0]["epochNumber"], "toEpoch": receipts[1]["epochNumber"]}
+        all_traces = self.filter_trace(epoch_range)
+        assert(len(all_traces) >= 4)
+
+        collected_traces = []
+        cursor = None
+        while True:
+            page = self.filter_trace({**epoch_range, "cursor": cursor, "pageSize": "0x1"})
+            assert(len(page["traces"]) <= 1)
+            collected_traces += page["traces"]
+            cursor = page["cursor"]
+            if cursor is None:
+                break
+        assert_equal(collected_traces, all_traces)
+
+        assert_raises_rpc_error(None, None, self.filter_trace, {**epoch_range, "cursor": "0x1234"})
+        assert_raises_rpc_error(None, None, self.filter_trace, {**epoch_range, "pageSize": "0x0"})
+
+    def test_trace_call(self):
+        receiver = self.rand_addr()
+        tx = self.new_tx_for_call(receiver, "0x")
+        tx["value"] = hex(100)
+
+        results = self.trace_call(tx, ["trace", "stateDiff"])
+        assert_equal(results["output"], "0x")
+        assert_equal([t["type"] for t in results["trace"]], ["call", "call_result"])
+        # the receiver is created with the transferred value
+        born = [d for d in results["stateDiff"].values() if is_born(d["balance"])]
+        assert_equal(len(born), 1)
+        assert_equal(int(born[0]["balance"]["+"], 0), 100)
+
+        results = self.trace_call(tx, ["trace"])
+        assert_equal(results["stateDiff"], None)
+
+        # nothing is committed
+        assert_equal(self.get_balance(receiver), 0)
+
+        # unsupported trace types are rejected
+        assert_raises_rpc_error(None, None, self.trace_call, tx, ["vmTrace"])
+
+    def test_trace_call_suicide(self):
+        # a contract that destroys itself during creation: CALLER SELFDESTRUCT
+        tx = {
+            "from": hex_to_b32_address(self.GENESIS_ADDR),
+            "data": "0x33ff",
+        }
+        results = self.trace_call(tx, ["stateDiff"])
+        # the contract is not left in the state
+        assert_equal([d for d in results["stateDiff"].values() if d["code"] != "="], [])
+        # the node is still alive
+        self.epoch_number()
+
+    def test_trace_replay_transaction(self):
+        tx = self.new_tx(receiver=self.rand_addr())
+        tx_hash = self.send_tx(tx)
+        self.wait_for_receipt(tx_hash)
+
+        results = self.trace_replay_transaction(tx_hash, ["trace", "stateDiff"])
+        assert_equal(results["transactionHash"], tx_hash)
+        assert_equal([t["type"] for t in results["trace"]], ["call", "call_result"])
+
+        diffs = results["stateDiff"].values()
+        born = [d for d in diffs if is_born(d["balance"])]
+        assert_equal(len(born), 1)
+        assert_equal(int(born[0]["balance"]["+"], 0), 100)
+        # only the nonce of the sender is bumped
+        nonces = [d["nonce"]["*"] for d in diffs if d["nonce"] != "=" and "*" in d["nonce"]]
+        assert_equal(len(nonces), 1)
+        assert_equal(int(nonces[0]["to"], 0) - int(nonces[0]["from"], 0), 1)
+
+        results = self.trace_replay_transaction(tx_hash, ["stateDiff"])
+        assert_equal(results["trace"], None)
+        assert_equal(len(results["stateDiff"]), len(diffs))
+
+def is_born(diff) -> bool:
+    return diff != "=" and "+" in diff
@@ -0,0 +1,32 @@
+import sys
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal
+
+class TestTransactionStatus(RpcClient):
+    def test_unknown(self):
+        status = self.get_transaction_status(self.rand_hash())
+        assert_equal(status, {"status": "unknown"})
+
+    def test_ready_and_executed(self):
+        tx = self.new_tx()
+        tx_hash = self.send_tx(tx)
+        assert_equal(self.get_transaction_status(tx_hash)["status"], "ready")
+
+        self.wait_for_receipt(tx_hash)
+        receipt = self.get_transaction_receipt(tx_hash)
+        status = self.get_transaction_status(tx_hash)
+        assert_equal(status["status"], "executed")
+        assert_equal(status["outcomeStatus"], "0x0")
+        assert_equal(status["blockHash"], receipt["blockHash"])
+        assert_equal(status["epochNumber"], receipt["epochNumber"])
+        assert("confirmationRisk" in status)
+
+    def test_pending(self):
+        nonce = self.get_nonce(self.GENESIS_ADDR)
+        tx = self.new_tx(nonce=nonce + 1000)
+        tx_hash = self.send_tx(tx)
+        status = self.get_transaction_status(tx_hash)
+        assert_equal(status["status"], "pending")
+        assert_equal(status["pendingReason"], "futureNonce")
@@ -0,0 +1,33 @@
+import sys
+sys.path.append("..")
+
+from conflux.rpc import RpcClient
+from test_framework.util import assert_equal
+
+class TestTransactionsByAddress(RpcClient):
+    def test_unknown_address(self):
+        result = self.get_transactions_by_address(self.rand_addr())
+        assert_equal(int(result["totalCount"], 0), 0)
+        assert_equal(result["transactions"], [])
+
+    def test_sender_and_receiver(self):
+        (addr, priv_key) = self.rand_account()
+        tx1 = self.new_tx(receiver=addr, value=10 ** 18)
+        assert_equal(self.send_tx(tx1, True), tx1.hash_hex())
+        tx2 = self.new_tx(sender=addr, priv_key=priv_key, receiver=self.COINBASE_ADDR)
+        assert_equal(self.send_tx(tx2, True), tx2.hash_hex())
+
+        result = self.get_transactions_by_address(addr)
+        assert_equal(int(result["totalCount"], 0), 2)
+
+        # the newest transaction comes first
+        txs = result["transactions"]
+        assert_equal([tx["transactionHash"] for tx in txs], [tx2.hash_hex(), tx1.hash_hex()])
+        receipt = self.get_transaction_receipt(tx1.hash_hex())
+        assert_equal(txs[1]["blockHash"], receipt["blockHash"])
+        assert_equal(int(txs[1]["transactionIndex"], 0), int(receipt["index"], 0))
+        assert_equal(int(txs[1]["epochNumber"], 0), int(receipt["epochNumber"], 0))
+
+        page = self.get_transactions_by_address(addr, skip=1, limit=1)
+        assert_equal(int(page["totalCount"], 0), 2)
+        assert_equal(page["transactions"], txs[1:])
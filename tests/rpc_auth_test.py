@@ -0,0 +1,92 @@
+#!/usr/bin/env python3
+import asyncio
+import base64
+import hashlib
+import hmac
+import http.client
+import json
+import time
+import websockets
+
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import *
+
+API_KEY = "test-api-key"
+JWT_SECRET = bytes(range(32))
+UNAUTHORIZED = -32078
+
+def b64(data: bytes) -> str:
+    return base64.urlsafe_b64encode(data).rstrip(b"=").decode()
+
+def jwt(claims: dict, secret: bytes = JWT_SECRET) -> str:
+    signed = b64(json.dumps({"alg": "HS256", "typ": "JWT"}).encode()) + "." + b64(json.dumps(claims).encode())
+    signature = hmac.new(secret, signed.encode(), hashlib.sha256).digest()
+    return signed + "." + b64(signature)
+
+class RpcAuthTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+        self.conf_parameters["public_rpc_apis"] = "\"cfx\""
+        self.conf_parameters["rpc_auth_api_keys_file"] = "\"api_keys.toml\""
+        self.conf_parameters["rpc_auth_jwt_secret_file"] = "\"jwt.hex\""
+        self.extra_conf_files = {
+            "api_keys.toml": f"\"{API_KEY}\" = \"cfx,test\"\n",
+            "jwt.hex": "0x" + JWT_SECRET.hex(),
+        }
+
+    def setup_network(self):
+        self.setup_nodes()
+
+    def http_call(self, method, headers={}):
+        conn = http.client.HTTPConnection(self.nodes[0].ip, remote_rpc_port(0))
+        body = json.dumps({"jsonrpc": "2.0", "method": method, "params": [], "id": 1})
+        conn.request("POST", "/", body, {"Content-Type": "application/json", **headers})
+        response = json.loads(conn.getresponse().read())
+        conn.close()
+        return response
+
+    def assert_allowed(self, method, headers={}):
+        response = self.http_call(method, headers)
+        assert "result" in response, response
+
+    def assert_rejected(self, method, headers={}):
+        response = self.http_call(method, headers)
+        assert_equal(response["error"]["code"], UNAUTHORIZED)
+
+    async def ws_call(self, method, token):
+        url = pubsub_url(0, self.nodes[0].ip, pubsub_port(0))
+        async with websockets.connect(url, subprotocols=[token]) as ws:
+            await ws.send(json.dumps({"jsonrpc": "2.0", "method": method, "params": [], "id": 1}))
+            return json.loads(await ws.recv())
+
+    def run_test(self):
+        # requests without credentials can only use `public_rpc_apis`
+        self.assert_allowed("cfx_epochNumber")
+        self.assert_rejected("getblockcount")
+        self.assert_rejected("txpool_status")
+
+        # API keys are limited to their API sets
+        self.assert_allowed("getblockcount", {"X-Api-Key": API_KEY})
+        self.assert_allowed("getblockcount", {"Authorization": "Bearer " + API_KEY})
+        self.assert_rejected("txpool_status", {"X-Api-Key": API_KEY})
+        self.assert_rejected("cfx_epochNumber", {"X-Api-Key": "unknown"})
+
+        # JWTs are limited to their `apis` claims
+        token = jwt({"apis": "cfx,debug"})
+        self.assert_allowed("txpool_status", {"Authorization": "Bearer " + token})
+        self.assert_rejected("getblockcount", {"Authorization": "Bearer " + token})
+        self.assert_allowed("getblockcount", {"Authorization": "Bearer " + jwt({})})
+        self.assert_rejected("cfx_epochNumber", {"Authorization": "Bearer " + jwt({}, b"wrong secret")})
+        self.assert_rejected("cfx_epochNumber", {"Authorization": "Bearer " + jwt({"exp": int(time.time()) - 10})})
+
+        # WebSocket connections send the credential as the subprotocol
+        loop = asyncio.get_event_loop()
+        response = loop.run_until_complete(self.ws_call("txpool_status", token))
+        assert "result" in response, response
+        response = loop.run_until_complete(self.ws_call("getblockcount", token))
+        assert_equal(response["error"]["code"], UNAUTHORIZED)
+
+        self.log.info("Pass")
+
+if __name__ == "__main__":
+    RpcAuthTest().main()
@@ -0,0 +1,54 @@
+#!/usr/bin/env python3
+import json
+import os
+import socket
+import stat
+
+from test_framework.test_framework import ConfluxTestFramework
+from test_framework.util import *
+
+IPC_FILE = "conflux.ipc"
+
+class RpcIpcTest(ConfluxTestFramework):
+    def set_test_params(self):
+        self.num_nodes = 1
+        self.conf_parameters["jsonrpc_ipc_path"] = f"\"{IPC_FILE}\""
+        self.conf_parameters["jsonrpc_ipc_permissions"] = "\"640\""
+
+    def setup_network(self):
+        self.setup_nodes()
+
+    def run_test(self):
+        path = os.path.join(get_datadir_path(self.options.tmpdir, 0), IPC_FILE)
+        assert stat.S_ISSOCK(os.stat(path).st_mode)
+        assert_equal(stat.S_IMODE(os.stat(path).st_mode), 0o640)
+
+        sock = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+        sock.connect(path)
+        reader = sock.makefile("r")
+        request_id = 0
+
+        def call(method, *params):
+            nonlocal request_id
+            request_id += 1
+            sock.sendall(json.dumps({"jsonrpc": "2.0", "method": method, "params": list(params), "id": request_id}).encode())
+            return json.loads(reader.readline())
+
+        # both the public and the debug APIs are available
+        assert_equal(call("cfx_getBestBlockHash")["result"], self.nodes[0].cfx_getBestBlockHash())
+        assert "result" in call("txpool_status")
+
+        # subscriptions are published to the connection
+        subscription = call("cfx_subscribe", "newHeads")["result"]
+        block_hash = self.nodes[0].generate_empty_blocks(1)[0]
+        notification = json.loads(reader.readline())
+        assert_equal(notification["method"], "cfx_subscription")
+        assert_equal(notification["params"]["subscription"], subscription)
+        assert_equal(notification["params"]["result"]["hash"], block_hash)
+        assert_equal(call("cfx_unsubscribe", subscription)["result"], True)
+
+        sock.close()
+        self.log.info("Pass")
+
+if __name__ == "__main__":
+    RpcIpcTest().main()
@@ -17,7 +17,8 @@ class RpcTest(ConfluxTestFramework):
         self.conf_parameters = {
             "log_level": "\"trace\"",
             "executive_trace": "true",
-            "public_rpc_apis": "\"cfx,debug,test,pubsub,trace\"",
+            "persist_address_tx_index": "true",
+            "public_rpc_apis": "\"cfx,debug,eth,test,pubsub,trace\"",
         }
 
     def setup_network(self):
@@ -1,17 +1,22 @@
 #!/usr/bin/env python3
 
+import http.client
+import json
 import os
 import time
 from jsonrpcclient.exceptions import ReceivedErrorResponseError
 
 from test_framework.test_framework import ConfluxTestFramework
 from conflux.rpc import RpcClient
+from test_framework.util import assert_equal, rpc_port
 
 class ThrottleRpcTests(ConfluxTestFramework):
     def set_test_params(self):
         self.num_nodes = 1
         self.conf_parameters = {
-            "throttling_conf": "\"../throttling.toml\""
+            "throttling_conf": "\"../throttling.toml\"",
+            # the forwarding headers of the requests from the test are trusted
+            "rpc_trusted_proxies": "\"127.0.0.1\"",
         }
 
     def setup_chain(self):
@@ -22,6 +27,8 @@ class ThrottleRpcTests(ConfluxTestFramework):
             fp.write("[rpc_local]\n")
             fp.write("cfx_epochNumber=\"300,200,1,100,1\"\n")
             fp.write("cfx_gasPrice=\"5,5,2,1,0\"\n")
+            fp.write("[rpc_local_client]\n")
+            fp.write("cfx_clientVersion=\"1,1,1,1,0\"\n")
             # In python tests, we will only call the local RPC interface,
             # just add the public rpc section as a placeholder.
             fp.write("[rpc]")
@@ -37,6 +44,7 @@ class ThrottleRpcTests(ConfluxTestFramework):
         client = RpcClient(self.nodes[0])
         self.test_throttled(client)
         self.test_recharged(client)
+        self.test_client_throttled()
 
     def test_throttled(self, client):
         # allow 2 times
@@ -103,5 +111,27 @@ class ThrottleRpcTests(ConfluxTestFramework):
             assert e.response.code == -32072
             assert e.response.data.startswith("throttled in ")
 
+    def client_version(self, forwarded_for):
+        conn = http.client.HTTPConnection(self.nodes[0].ip, rpc_port(0))
+        body = json.dumps({"jsonrpc": "2.0", "method": "cfx_clientVersion", "params": [], "id": 1})
+        conn.request("POST", "/", body, {"Content-Type": "application/json", "X-Forwarded-For": forwarded_for})
+        response = json.loads(conn.getresponse().read())
+        conn.close()
+        return response
+
+    def test_client_throttled(self):
+        # each client has its own bucket
+        assert "result" in self.client_version("10.0.0.1")
+        assert "result" in self.client_version("10.0.0.2")
+
+        response = self.client_version("10.0.0.1")
+        assert_equal(response["error"]["code"], -32072)
+        assert response["error"]["data"].startswith("throttled for the client in ")
+        assert "retry after " in response["error"]["data"]
+
+        # the address set by the nearest proxy is used
+        response = self.client_version("10.0.0.3, 10.0.0.2")
+        assert_equal(response["error"]["code"], -32072)
+
 if __name__ == "__main__":
     ThrottleRpcTests().main()
\ No newline at end of file
@@ -0,0 +1,610 @@
+// Copyright 2021 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+//! Derives the JSON schemas of the RPC types from their serde attributes, and
+//! the OpenRPC descriptions of the RPC methods from their trait definitions.
+//!
+//! The generated code refers to `crate::rpc::openrpc`, so it is only meant to
+//! be used in the client crate.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error,
+    Fields, FnArg, GenericArgument, ItemTrait, Lit, Meta, NestedMeta, Pat,
+    PathArguments, Result, ReturnType, TraitItem, Type,
+};
+
+/// Implements `RpcSchema` for a struct or an enum with the schema of its
+/// serde representation. The container attribute
+/// `#[rpc_schema(name = "...")]` sets the name of the schema in the OpenRPC
+/// components, which is the type name by default. The serde attributes that
+/// cannot be represented, e.g. `flatten`, are reported as compile errors.
+#[proc_macro_derive(RpcSchema, attributes(rpc_schema))]
+pub fn derive_rpc_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_rpc_schema(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand_rpc_schema(mut input: DeriveInput) -> Result<TokenStream2> {
+    let serde_attrs = SerdeAttrs::parse(&input.attrs)?;
+    let description = doc_description(&input.attrs);
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            fields_schema(&data.fields, &serde_attrs.rename_all, &description)?
+        }
+        Data::Enum(data) => {
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                let variant_attrs = SerdeAttrs::parse(&variant.attrs)?;
+                if variant_attrs.skip {
+                    continue;
+                }
+                let name = variant_attrs.rename.unwrap_or_else(|| {
+                    rename_variant(
+                        &variant.ident.to_string(),
+                        &serde_attrs.rename_all,
+                    )
+                });
+                let is_unit = matches!(variant.fields, Fields::Unit);
+                let schema = fields_schema(
+                    &variant.fields,
+                    &variant_attrs.rename_all,
+                    &doc_description(&variant.attrs),
+                )?;
+                variants.push((name, is_unit, schema));
+            }
+            if serde_attrs.untagged {
+                let schemas = variants.iter().map(|(_, _, schema)| schema);
+                quote! {
+                    crate::rpc::openrpc::one_of(vec![#(#schemas),*])
+                }
+            } else if variants.iter().all(|(_, is_unit, _)| *is_unit) {
+                let names = variants.iter().map(|(name, _, _)| name);
+                quote! {
+                    crate::rpc::openrpc::string_enum(vec![#(#names),*])
+                }
+            } else {
+                let schemas = variants.iter().map(|(name, is_unit, schema)| {
+                    if *is_unit {
+                        quote! { crate::rpc::openrpc::string_enum(vec![#name]) }
+                    } else {
+                        quote! { crate::rpc::openrpc::tagged(#name, #schema) }
+                    }
+                });
+                quote! {
+                    crate::rpc::openrpc::one_of(vec![#(#schemas),*])
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "RpcSchema cannot be derived for unions",
+            ));
+        }
+    };
+
+    let ident = &input.ident;
+    let is_generic = input.generics.type_params().next().is_some();
+    let type_params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: crate::rpc::openrpc::RpcSchema));
+    }
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    // The generic types are inlined, since their schemas depend on the type
+    // parameters.
+    let body = if is_generic {
+        body
+    } else {
+        let name = schema_name(&input.attrs)
+            .unwrap_or_else(|| input.ident.to_string());
+        quote! {
+            components.reference(#name, |components| #body)
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics crate::rpc::openrpc::RpcSchema
+            for #ident #ty_generics #where_clause
+        {
+            fn schema(
+                components: &mut crate::rpc::openrpc::SchemaComponents,
+            ) -> serde_json::Value {
+                #body
+            }
+        }
+    })
+}
+
+/// Generates `pub fn method_schemas`, which returns the OpenRPC descriptions
+/// of the methods of the annotated RPC trait. It should be placed before the
+/// `#[rpc]` attribute of the trait.
+#[proc_macro_attribute]
+pub fn openrpc(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item_trait = parse_macro_input!(item as ItemTrait);
+
+    let mut methods = Vec::new();
+    for item in &item_trait.items {
+        let method = match item {
+            TraitItem::Method(method) => method,
+            _ => continue,
+        };
+        let (name, is_subscribe) = match rpc_method_name(&method.attrs) {
+            Some(name) => name,
+            None => continue,
+        };
+        let summary = doc_description(&method.attrs).unwrap_or_default();
+
+        let mut params = Vec::new();
+        for input in &method.sig.inputs {
+            let arg = match input {
+                FnArg::Typed(arg) => arg,
+                FnArg::Receiver(_) => continue,
+            };
+            let ty = &*arg.ty;
+            // The metadata and the subscriber are not request parameters.
+            let ty_string = quote!(#ty).to_string();
+            if ty_string.contains("Metadata")
+                || ty_string.contains("Subscriber")
+            {
+                continue;
+            }
+            let (required, inner) = match option_inner(ty) {
+                Some(inner) => (false, inner),
+                None => (true, ty),
+            };
+            let param_name = match &*arg.pat {
+                Pat::Ident(pat) => {
+                    camel_case(pat.ident.to_string().trim_start_matches('_'))
+                }
+                _ => type_param_name(inner),
+            };
+            params.push(quote! {
+                crate::rpc::openrpc::Param {
+                    name: #param_name,
+                    schema: <#inner as crate::rpc::openrpc::RpcSchema>::schema(
+                        components,
+                    ),
+                    required: #required,
+                }
+            });
+        }
+
+        let result = match &method.sig.output {
+            ReturnType::Type(_, ty) => {
+                let ty = result_inner(ty);
+                quote! {
+                    <#ty as crate::rpc::openrpc::RpcSchema>::schema(components)
+                }
+            }
+            ReturnType::Default if is_subscribe => quote! {
+                <jsonrpc_pubsub::SubscriptionId
+                    as crate::rpc::openrpc::RpcSchema>::schema(components)
+            },
+            ReturnType::Default => quote! { serde_json::Value::Null },
+        };
+
+        methods.push(quote! {
+            crate::rpc::openrpc::method(
+                #name,
+                #summary,
+                vec![#(#params),*],
+                #result,
+            )
+        });
+    }
+
+    let doc = format!(
+        "Returns the OpenRPC descriptions of the methods of `{}`.",
+        item_trait.ident
+    );
+    let expanded = quote! {
+        #item_trait
+
+        #[doc = #doc]
+        pub fn method_schemas(
+            components: &mut crate::rpc::openrpc::SchemaComponents,
+        ) -> Vec<serde_json::Value> {
+            vec![#(#methods),*]
+        }
+    };
+    expanded.into()
+}
+
+/// The serde attributes that affect the schema.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    untagged: bool,
+    skip: bool,
+    /// Whether the field may be absent in the serialized object.
+    optional: bool,
+}
+
+impl SerdeAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut serde_attrs = SerdeAttrs::default();
+        for meta in nested_metas(attrs, "serde") {
+            match meta {
+                Meta::NameValue(nv) => {
+                    let value = match nv.lit {
+                        Lit::Str(s) => s.value(),
+                        _ => continue,
+                    };
+                    if nv.path.is_ident("rename") {
+                        serde_attrs.rename = Some(value);
+                    } else if nv.path.is_ident("rename_all") {
+                        serde_attrs.rename_all = Some(value);
+                    } else if nv.path.is_ident("skip_serializing_if")
+                        || nv.path.is_ident("default")
+                    {
+                        serde_attrs.optional = true;
+                    } else if nv.path.is_ident("tag")
+                        || nv.path.is_ident("content")
+                    {
+                        return Err(Error::new_spanned(
+                            nv.path,
+                            "RpcSchema only supports external and untagged \
+                             enum representations",
+                        ));
+                    }
+                }
+                Meta::Path(path) => {
+                    if path.is_ident("untagged") {
+                        serde_attrs.untagged = true;
+                    } else if path.is_ident("skip")
+                        || path.is_ident("skip_serializing")
+                    {
+                        serde_attrs.skip = true;
+                    } else if path.is_ident("default") {
+                        serde_attrs.optional = true;
+                    } else if path.is_ident("flatten") {
+                        return Err(Error::new_spanned(
+                            path,
+                            "RpcSchema does not support flattened fields",
+                        ));
+                    }
+                }
+                Meta::List(_) => {}
+            }
+        }
+        Ok(serde_attrs)
+    }
+}
+
+/// Returns the metas in the attributes like `#[name(meta, ...)]`.
+fn nested_metas(attrs: &[Attribute], name: &str) -> Vec<Meta> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        if !attr.path.is_ident(name) {
+            continue;
+        }
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            for nested in list.nested {
+                if let NestedMeta::Meta(meta) = nested {
+                    metas.push(meta);
+                }
+            }
+        }
+    }
+    metas
+}
+
+fn schema_name(attrs: &[Attribute]) -> Option<String> {
+    nested_metas(attrs, "rpc_schema")
+        .into_iter()
+        .find_map(|meta| match meta {
+            Meta::NameValue(nv) if nv.path.is_ident("name") => match nv.lit {
+                Lit::Str(s) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// Returns the RPC method name in `#[rpc(name = "...")]` or
+/// `#[pubsub(name = "...")]`, and whether the method is a subscription.
+fn rpc_method_name(attrs: &[Attribute]) -> Option<(String, bool)> {
+    for attr_name in &["rpc", "pubsub"] {
+        let metas = nested_metas(attrs, attr_name);
+        let is_subscribe = metas.iter().any(|meta| match meta {
+            Meta::Path(path) => path.is_ident("subscribe"),
+            _ => false,
+        });
+        for meta in metas {
+            if let Meta::NameValue(nv) = meta {
+                if let (true, Lit::Str(s)) = (nv.path.is_ident("name"), nv.lit)
+                {
+                    return Some((s.value(), is_subscribe));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Joins the lines of the doc comments.
+fn doc_description(attrs: &[Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if !attr.path.is_ident("doc") {
+            continue;
+        }
+        if let Ok(Meta::NameValue(nv)) = attr.parse_meta() {
+            if let Lit::Str(s) = nv.lit {
+                let line = s.value().trim().to_string();
+                if !line.is_empty() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+fn fields_schema(
+    fields: &Fields, rename_all: &Option<String>, description: &Option<String>,
+) -> Result<TokenStream2>
+{
+    let schema = match fields {
+        Fields::Named(named) => {
+            let mut properties = Vec::new();
+            for field in &named.named {
+                let attrs = SerdeAttrs::parse(&field.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                let ident = field.ident.as_ref().expect("named field");
+                let name = attrs.rename.unwrap_or_else(|| {
+                    rename_field(
+                        ident.to_string().trim_start_matches("r#"),
+                        rename_all,
+                    )
+                });
+                let ty = &field.ty;
+                let required = !attrs.optional && option_inner(ty).is_none();
+                let field_description =
+                    optional_str(&doc_description(&field.attrs));
+                properties.push(quote! {
+                    crate::rpc::openrpc::Field {
+                        name: #name,
+                        schema: <#ty as crate::rpc::openrpc::RpcSchema>::schema(
+                            components,
+                        ),
+                        required: #required,
+                        description: #field_description,
+                    }
+                });
+            }
+            let description = optional_str(description);
+            quote! {
+                crate::rpc::openrpc::object(
+                    vec![#(#properties),*],
+                    #description,
+                )
+            }
+        }
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let ty = &unnamed.unnamed[0].ty;
+            quote! {
+                <#ty as crate::rpc::openrpc::RpcSchema>::schema(components)
+            }
+        }
+        Fields::Unnamed(unnamed) => {
+            let items = unnamed.unnamed.iter().map(|field| {
+                let ty = &field.ty;
+                quote! {
+                    <#ty as crate::rpc::openrpc::RpcSchema>::schema(components)
+                }
+            });
+            quote! { crate::rpc::openrpc::tuple(vec![#(#items),*]) }
+        }
+        Fields::Unit => quote! { serde_json::json!({ "type": "null" }) },
+    };
+    Ok(schema)
+}
+
+fn optional_str(s: &Option<String>) -> TokenStream2 {
+    match s {
+        Some(s) => quote! { Some(#s) },
+        None => quote! { None },
+    }
+}
+
+/// Returns `T` if `ty` is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    first_type_argument(&segment.arguments)
+}
+
+/// Returns `T` if `ty` is a `BoxFuture<T>` or a `Result<T>`.
+fn result_inner(ty: &Type) -> &Type {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            let ident = segment.ident.to_string();
+            if ident == "BoxFuture" || ident.ends_with("Result") {
+                if let Some(inner) = first_type_argument(&segment.arguments) {
+                    return inner;
+                }
+            }
+        }
+    }
+    ty
+}
+
+fn first_type_argument(arguments: &PathArguments) -> Option<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(args) => {
+            args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The name of an unnamed parameter, derived from its type.
+fn type_param_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => {
+                let ident = segment.ident.to_string();
+                let mut chars = ident.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => ident,
+                }
+            }
+            None => "param".into(),
+        },
+        _ => "param".into(),
+    }
+}
+
+fn camel_case(snake: &str) -> String {
+    let mut result = String::new();
+    let mut upper = false;
+    for c in snake.chars() {
+        if c == '_' {
+            upper = !result.is_empty();
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Applies `rename_all` to a field name in snake case, like serde.
+fn rename_field(name: &str, rename_all: &Option<String>) -> String {
+    match rename_all.as_ref().map(String::as_str) {
+        Some("UPPERCASE") | Some("SCREAMING_SNAKE_CASE") => {
+            name.to_ascii_uppercase()
+        }
+        Some("camelCase") => camel_case(name),
+        Some("PascalCase") => {
+            let camel = camel_case(name);
+            let mut chars = camel.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => camel,
+            }
+        }
+        Some("kebab-case") => name.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => {
+            name.replace('_', "-").to_ascii_uppercase()
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Applies `rename_all` to a variant name in Pascal case, like serde.
+fn rename_variant(name: &str, rename_all: &Option<String>) -> String {
+    let snake = || {
+        let mut snake = String::new();
+        for (i, c) in name.char_indices() {
+            if c.is_uppercase() && i != 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        snake
+    };
+    match rename_all.as_ref().map(String::as_str) {
+        Some("lowercase") => name.to_ascii_lowercase(),
+        Some("UPPERCASE") => name.to_ascii_uppercase(),
+        Some("camelCase") => name[..1].to_ascii_lowercase() + &name[1..],
+        Some("snake_case") => snake(),
+        Some("SCREAMING_SNAKE_CASE") => snake().to_ascii_uppercase(),
+        Some("kebab-case") => snake().replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => {
+            snake().replace('_', "-").to_ascii_uppercase()
+        }
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_rpc_schema, rename_field, rename_variant};
+    use syn::parse_quote;
+
+    #[test]
+    fn test_rename() {
+        let rename_all = |s: &str| Some(s.to_string());
+        assert_eq!(
+            rename_field("block_hash", &rename_all("camelCase")),
+            "blockHash"
+        );
+        assert_eq!(
+            rename_field("block_hash", &rename_all("kebab-case")),
+            "block-hash"
+        );
+        assert_eq!(rename_field("block_hash", &None), "block_hash");
+        assert_eq!(
+            rename_variant("LatestState", &rename_all("snake_case")),
+            "latest_state"
+        );
+        assert_eq!(
+            rename_variant("NewHeads", &rename_all("camelCase")),
+            "newHeads"
+        );
+        assert_eq!(rename_variant("CApi", &rename_all("kebab-case")), "c-api");
+    }
+
+    #[test]
+    fn test_unsupported_attributes() {
+        assert!(expand_rpc_schema(parse_quote! {
+            struct Log {
+                #[serde(rename = "blockHash")]
+                block_hash: H256,
+            }
+        })
+        .is_ok());
+        assert!(expand_rpc_schema(parse_quote! {
+            struct Log {
+                #[serde(flatten)]
+                inner: LogInner,
+            }
+        })
+        .is_err());
+        assert!(expand_rpc_schema(parse_quote! {
+            #[serde(tag = "type")]
+            enum Action {
+                Call(Call),
+            }
+        })
+        .is_err());
+    }
+}
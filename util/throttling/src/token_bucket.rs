@@ -128,6 +128,17 @@ impl TokenBucket {
         self.max_throttled_counter = max_throttled_counter;
     }
 
+    /// Return true if the bucket is recharged to full and not throttled, i.e.
+    /// it is the same as a newly created full bucket.
+    pub fn is_full(&mut self) -> bool {
+        let now = Instant::now();
+        self.refresh(now);
+        self.cpu_tokens.cur_tokens == self.cpu_tokens.max_tokens
+            && self.message_size_tokens.cur_tokens
+                == self.message_size_tokens.max_tokens
+            && self.throttled_until.map_or(true, |until| until <= now)
+    }
+
     fn refresh(&mut self, now: Instant) {
         let elapsed_secs = (now - self.last_update).as_secs();
         if elapsed_secs == 0 {
@@ -277,39 +288,165 @@ impl TokenBucketManager {
     pub fn load(
         toml_file: &str, section: Option<&str>,
     ) -> Result<Self, String> {
-        let content = read_to_string(toml_file)
-            .map_err(|e| format!("failed to read toml file: {:?}", e))?;
-        let toml_val = content
-            .parse::<toml::Value>()
-            .map_err(|e| format!("failed to parse toml file: {:?}", e))?;
-
-        let val = match section {
-            Some(section) => match toml_val.get(section) {
-                Some(val) => val,
-                None => return Err(format!("section [{}] not found", section)),
-            },
-            None => &toml_val,
+        let configs = match load_bucket_configs(toml_file, section)? {
+            Some(configs) => configs,
+            None => {
+                return Err(format!(
+                    "section [{}] not found",
+                    section.unwrap_or_default()
+                ))
+            }
         };
-        let table = val.as_table().expect("not table value");
 
         let mut manager = TokenBucketManager::default();
 
-        for (k, v) in table.iter() {
-            let v = match v.as_str() {
-                Some(v) => v,
-                None => {
-                    return Err(format!(
-                        "invalid value type {:?}, string type required",
-                        v.type_str()
-                    ))
-                }
-            };
+        for (k, v) in configs {
+            let bucket = TokenBucket::from_str(&v)?;
+            manager.register(k, bucket);
+        }
+
+        Ok(manager)
+    }
+}
+
+/// Load the token bucket configurations by name from a section of a toml
+/// file, or from the whole file if `section` is `None`. Return `None` if the
+/// section does not exist.
+fn load_bucket_configs(
+    toml_file: &str, section: Option<&str>,
+) -> Result<Option<Vec<(String, String)>>, String> {
+    let content = read_to_string(toml_file)
+        .map_err(|e| format!("failed to read toml file: {:?}", e))?;
+    let toml_val = content
+        .parse::<toml::Value>()
+        .map_err(|e| format!("failed to parse toml file: {:?}", e))?;
+
+    let val = match section {
+        Some(section) => match toml_val.get(section) {
+            Some(val) => val,
+            None => return Ok(None),
+        },
+        None => &toml_val,
+    };
+    let table = val.as_table().expect("not table value");
+
+    let mut configs = Vec::new();
+
+    for (k, v) in table.iter() {
+        let v = match v.as_str() {
+            Some(v) => v,
+            None => {
+                return Err(format!(
+                    "invalid value type {:?}, string type required",
+                    v.type_str()
+                ))
+            }
+        };
+
+        configs.push((k.clone(), v.to_string()));
+    }
+
+    Ok(Some(configs))
+}
+
+/// The number of buckets in a `KeyedTokenBucketManager` to start removing the
+/// full buckets.
+const KEYED_BUCKETS_CLEANUP_THRESHOLD: usize = 10_000;
+
+/// The maximum number of buckets in a `KeyedTokenBucketManager`. The requests
+/// of new keys are throttled once it is reached and no bucket is full.
+const KEYED_BUCKETS_MAX: usize = 100_000;
+
+/// Manages token buckets separately for each key, e.g. each RPC client. The
+/// buckets of a key are created on demand with the configurations by name,
+/// and are removed once they are recharged to full. The number of buckets is
+/// capped.
+pub struct KeyedTokenBucketManager<K: Eq + Hash> {
+    // bucket configurations by name
+    configs: HashMap<String, String>,
+    max_buckets: usize,
+    inner: Mutex<KeyedBuckets<K>>,
+}
+
+struct KeyedBuckets<K: Eq + Hash> {
+    buckets: HashMap<(K, String), TokenBucket>,
+    // remove the full buckets when the number of buckets reaches it
+    next_cleanup: usize,
+}
+
+impl<K: Eq + Hash> Default for KeyedTokenBucketManager<K> {
+    fn default() -> Self {
+        KeyedTokenBucketManager {
+            configs: HashMap::new(),
+            max_buckets: KEYED_BUCKETS_MAX,
+            inner: Mutex::new(KeyedBuckets {
+                buckets: HashMap::new(),
+                next_cleanup: KEYED_BUCKETS_CLEANUP_THRESHOLD,
+            }),
+        }
+    }
+}
+
+impl<K: Eq + Hash> KeyedTokenBucketManager<K> {
+    pub fn register(
+        &mut self, name: String, config: &str,
+    ) -> Result<(), String> {
+        if self.configs.contains_key(&name) {
+            panic!("token bucket {:?} already registered", name);
+        }
+
+        // validate the configuration
+        TokenBucket::from_str(config)?;
+        self.configs.insert(name, config.into());
+        Ok(())
+    }
+
+    /// Load the bucket configurations like `TokenBucketManager::load`, except
+    /// that a missing section is treated as an empty one.
+    pub fn load(
+        toml_file: &str, section: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut manager = KeyedTokenBucketManager::default();
 
-            manager.register(k.into(), TokenBucket::from_str(v)?);
+        for (k, v) in
+            load_bucket_configs(toml_file, section)?.unwrap_or_default()
+        {
+            manager.register(k, &v)?;
         }
 
         Ok(manager)
     }
+
+    /// Throttle with the default cost of the bucket `name` of `key`. Return
+    /// `None` if the bucket is not configured.
+    pub fn throttle_default(
+        &self, key: K, name: &str,
+    ) -> Option<ThrottleResult> {
+        let config = self.configs.get(name)?;
+        let mut inner = self.inner.lock();
+        let key = (key, name.to_string());
+
+        if inner.buckets.len() >= inner.next_cleanup {
+            inner.buckets.retain(|_, bucket| !bucket.is_full());
+            inner.next_cleanup = min(
+                max(KEYED_BUCKETS_CLEANUP_THRESHOLD, inner.buckets.len() * 2),
+                self.max_buckets,
+            );
+        }
+
+        // Too many keys are throttled, so the new keys cannot be tracked.
+        if inner.buckets.len() >= self.max_buckets
+            && !inner.buckets.contains_key(&key)
+        {
+            return Some(ThrottleResult::AlreadyThrottled);
+        }
+
+        let bucket = inner.buckets.entry(key).or_insert_with(|| {
+            TokenBucket::from_str(config).expect("validated config")
+        });
+
+        Some(bucket.throttle_default())
+    }
 }
 
 #[derive(Default, DeriveMallocSizeOf)]
@@ -343,7 +480,9 @@ impl<K: Eq + Hash + MallocSizeOf> ThrottledManager<K> {
 
 #[cfg(test)]
 mod tests {
-    use crate::token_bucket::{ThrottleResult, TokenBucket};
+    use crate::token_bucket::{
+        KeyedTokenBucketManager, ThrottleResult, TokenBucket,
+    };
     use std::{thread::sleep, time::Duration};
 
     #[test]
@@ -443,4 +582,39 @@ mod tests {
         // already throttled
         assert_eq!(bucket.throttle(1, 1), ThrottleResult::AlreadyThrottled);
     }
+
+    #[test]
+    fn test_keyed_buckets() {
+        let mut manager = KeyedTokenBucketManager::default();
+        manager.register("foo".into(), "1,1,1,1,0").unwrap();
+        assert!(manager.register("bar".into(), "1,1").is_err());
+
+        // each key has its own bucket
+        assert_eq!(
+            manager.throttle_default(1, "foo"),
+            Some(ThrottleResult::Success)
+        );
+        assert_throttled(
+            manager.throttle_default(1, "foo").unwrap(),
+            Duration::from_secs(1),
+        );
+        assert_eq!(
+            manager.throttle_default(2, "foo"),
+            Some(ThrottleResult::Success)
+        );
+
+        // not configured
+        assert_eq!(manager.throttle_default(1, "bar"), None);
+
+        // new keys are throttled when the buckets are capped
+        manager.max_buckets = 2;
+        assert_eq!(
+            manager.throttle_default(3, "foo"),
+            Some(ThrottleResult::AlreadyThrottled)
+        );
+        assert_throttled(
+            manager.throttle_default(2, "foo").unwrap(),
+            Duration::from_secs(1),
+        );
+    }
 }